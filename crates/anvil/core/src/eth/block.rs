@@ -1,4 +1,4 @@
-use crate::eth::{receipt::TypedReceipt, transaction::TransactionInfo, trie};
+use crate::eth::{receipt::TypedReceipt, transaction::TransactionInfo, trie, utils::trie_value};
 use ethers_core::{
     types::{Address, Bloom, Bytes, H256, H64, U256},
     utils::{
@@ -49,8 +49,7 @@ impl Block {
     {
         let transactions: Vec<_> = transactions.into_iter().map(Into::into).collect();
         let ommers_hash = H256::from_slice(keccak256(&rlp::encode_list(&ommers)[..]).as_slice());
-        let transactions_root =
-            trie::ordered_trie_root(transactions.iter().map(|r| rlp::encode(r).freeze()));
+        let transactions_root = trie::ordered_trie_root(transactions.iter().map(trie_value));
 
         Self {
             header: Header::new(partial_header, ommers_hash, transactions_root),
@@ -97,6 +96,14 @@ pub struct Header {
     pub nonce: H64,
     /// BaseFee was added by EIP-1559 and is ignored in legacy headers.
     pub base_fee_per_gas: Option<U256>,
+    /// The total blob gas consumed by the transactions of the block, added by EIP-4844
+    ///
+    /// Note: this is not part of the rlp encoding of the header
+    pub blob_gas_used: Option<u64>,
+    /// The running total of blob gas consumed in excess of the target, added by EIP-4844
+    ///
+    /// Note: this is not part of the rlp encoding of the header
+    pub excess_blob_gas: Option<u64>,
 }
 
 // == impl Header ==
@@ -120,6 +127,8 @@ impl Header {
             mix_hash: partial_header.mix_hash,
             nonce: partial_header.nonce,
             base_fee_per_gas: partial_header.base_fee,
+            blob_gas_used: partial_header.blob_gas_used,
+            excess_blob_gas: partial_header.excess_blob_gas,
         }
     }
 
@@ -206,6 +215,8 @@ impl rlp::Decodable for Header {
             } else {
                 None
             },
+            blob_gas_used: None,
+            excess_blob_gas: None,
         };
         Ok(result)
     }
@@ -276,6 +287,8 @@ impl open_fastrlp::Decodable for Header {
             } else {
                 None
             },
+            blob_gas_used: None,
+            excess_blob_gas: None,
         })
     }
 }
@@ -297,6 +310,8 @@ pub struct PartialHeader {
     pub mix_hash: H256,
     pub nonce: H64,
     pub base_fee: Option<U256>,
+    pub blob_gas_used: Option<u64>,
+    pub excess_blob_gas: Option<u64>,
 }
 
 impl From<Header> for PartialHeader {
@@ -316,6 +331,8 @@ impl From<Header> for PartialHeader {
             mix_hash: header.mix_hash,
             nonce: header.nonce,
             base_fee: header.base_fee_per_gas,
+            blob_gas_used: header.blob_gas_used,
+            excess_blob_gas: header.excess_blob_gas,
         }
     }
 }
@@ -323,6 +340,7 @@ impl From<Header> for PartialHeader {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::eth::transaction::{EIP2930Transaction, TransactionKind, TypedTransaction};
     use ethers_core::{
         types::H160,
         utils::{hex, hex::FromHex},
@@ -348,6 +366,8 @@ mod tests {
             mix_hash: Default::default(),
             nonce: 99u64.to_be_bytes().into(),
             base_fee_per_gas: None,
+            blob_gas_used: None,
+            excess_blob_gas: None,
         };
 
         let encoded = rlp::encode(&header);
@@ -381,6 +401,8 @@ mod tests {
             mix_hash: Default::default(),
             nonce: H64::from_low_u64_be(99u64),
             base_fee_per_gas: None,
+            blob_gas_used: None,
+            excess_blob_gas: None,
         };
 
         let mut encoded = vec![];
@@ -423,6 +445,8 @@ mod tests {
             mix_hash: H256::from_str("0000000000000000000000000000000000000000000000000000000000000000").unwrap(),
             nonce: H64::from_low_u64_be(0x0),
             base_fee_per_gas: None,
+            blob_gas_used: None,
+            excess_blob_gas: None,
         };
         header.encode(&mut data);
         assert_eq!(hex::encode(&data), hex::encode(expected));
@@ -452,6 +476,8 @@ mod tests {
             mix_hash: H256::from_str("0000000000000000000000000000000000000000000000000000000000000000").unwrap(),
             nonce: H64::from_low_u64_be(0x0),
             base_fee_per_gas: Some(0x036b.into()),
+            blob_gas_used: None,
+            excess_blob_gas: None,
         };
         assert_eq!(header.hash(), expected_hash);
     }
@@ -478,6 +504,8 @@ mod tests {
             mix_hash: H256::from_str("0000000000000000000000000000000000000000000000000000000000000000").unwrap(),
             nonce: H64::from_low_u64_be(0x0),
             base_fee_per_gas: None,
+            blob_gas_used: None,
+            excess_blob_gas: None,
         };
         let header = <Header as open_fastrlp::Decodable>::decode(&mut data.as_slice()).unwrap();
         assert_eq!(header, expected);
@@ -501,4 +529,28 @@ mod tests {
         // check that length of encoding is the same as the output of `length`
         assert_eq!(block.length(), encoded.len());
     }
+
+    #[test]
+    fn transactions_root_commits_to_typed_transactions() {
+        let tx = TypedTransaction::EIP2930(EIP2930Transaction {
+            chain_id: 1,
+            nonce: 0.into(),
+            gas_price: 1.into(),
+            gas_limit: 21000.into(),
+            kind: TransactionKind::Call(Address::random()),
+            value: 1.into(),
+            input: Default::default(),
+            access_list: Default::default(),
+            odd_y_parity: false,
+            r: H256::random(),
+            s: H256::random(),
+        });
+        let block = Block::new(PartialHeader::default(), vec![tx.clone()], vec![]);
+
+        // typed transactions are stored as `type || rlp(tx)`
+        let value = trie_value(&tx);
+        assert_eq!(value[0], 1);
+        assert_eq!(H256::from_slice(keccak256(&value).as_slice()), tx.hash());
+        assert_eq!(block.header.transactions_root, trie::ordered_trie_root([value]));
+    }
 }
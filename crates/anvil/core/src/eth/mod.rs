@@ -1,12 +1,16 @@
 use crate::{
-    eth::subscription::SubscriptionId,
-    types::{EvmMineOptions, Forking, Index},
+    eth::subscription::{AnvilSubscriptionKind, AnvilSubscriptionParams, SubscriptionId},
+    types::{
+        BaseFeeParamsOptions, BlockOverrides, DumpStateOptions, EvmMineOptions, Forking, Index,
+        StorageBatch, TraceCallOptions, TraceFilter, TraceTransactionOptions,
+    },
 };
+use alloy_json_abi::JsonAbi;
 use alloy_primitives::{Address, Bytes, TxHash, B256, B64, U256};
 use alloy_rpc_types::{
     pubsub::{Params as SubscriptionParams, SubscriptionKind},
     state::StateOverride,
-    trace::{GethDebugTracingOptions, GethDefaultTracingOptions},
+    trace::GethDebugTracingOptions,
     BlockId, BlockNumberOrTag as BlockNumber, CallRequest, Filter,
 };
 use ethers_core::types::transaction::eip712::TypedData;
@@ -72,6 +76,9 @@ pub enum EthRequest {
     )]
     EthMaxPriorityFeePerGas(()),
 
+    #[cfg_attr(feature = "serde", serde(rename = "eth_blobBaseFee", with = "empty_params"))]
+    EthBlobBaseFee(()),
+
     #[cfg_attr(
         feature = "serde",
         serde(rename = "eth_accounts", alias = "eth_requestAccounts", with = "empty_params")
@@ -171,6 +178,7 @@ pub enum EthRequest {
         CallRequest,
         #[cfg_attr(feature = "serde", serde(default))] Option<BlockId>,
         #[cfg_attr(feature = "serde", serde(default))] Option<StateOverride>,
+        #[cfg_attr(feature = "serde", serde(default))] Option<BlockOverrides>,
     ),
 
     #[cfg_attr(feature = "serde", serde(rename = "eth_createAccessList"))]
@@ -261,6 +269,20 @@ pub enum EthRequest {
     /// geth's `debug_traceTransaction`  endpoint
     #[cfg_attr(feature = "serde", serde(rename = "debug_traceTransaction"))]
     DebugTraceTransaction(
+        B256,
+        #[cfg_attr(feature = "serde", serde(default))] TraceTransactionOptions,
+    ),
+
+    /// geth's `debug_traceBlockByNumber` endpoint
+    #[cfg_attr(feature = "serde", serde(rename = "debug_traceBlockByNumber"))]
+    DebugTraceBlockByNumber(
+        BlockNumber,
+        #[cfg_attr(feature = "serde", serde(default))] GethDebugTracingOptions,
+    ),
+
+    /// geth's `debug_traceBlockByHash` endpoint
+    #[cfg_attr(feature = "serde", serde(rename = "debug_traceBlockByHash"))]
+    DebugTraceBlockByHash(
         B256,
         #[cfg_attr(feature = "serde", serde(default))] GethDebugTracingOptions,
     ),
@@ -270,7 +292,7 @@ pub enum EthRequest {
     DebugTraceCall(
         CallRequest,
         #[cfg_attr(feature = "serde", serde(default))] Option<BlockId>,
-        #[cfg_attr(feature = "serde", serde(default))] GethDefaultTracingOptions,
+        #[cfg_attr(feature = "serde", serde(default))] TraceCallOptions,
     ),
 
     /// Trace transaction endpoint for parity's `trace_transaction`
@@ -287,6 +309,10 @@ pub enum EthRequest {
     )]
     TraceBlock(BlockNumber),
 
+    /// Trace endpoint for parity's `trace_filter`
+    #[cfg_attr(feature = "serde", serde(rename = "trace_filter", with = "sequence"))]
+    TraceFilter(TraceFilter),
+
     // Custom endpoints, they're not extracted to a separate type out of serde convenience
     /// send transactions impersonating specific account and contract addresses.
     #[cfg_attr(
@@ -308,6 +334,21 @@ pub enum EthRequest {
         )
     )]
     StopImpersonatingAccount(Address),
+    /// send transactions impersonating all of the given account and contract addresses.
+    #[cfg_attr(feature = "serde", serde(rename = "anvil_impersonateAccounts", with = "sequence"))]
+    ImpersonateAccounts(Vec<Address>),
+    /// Stops impersonating all of the given accounts
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename = "anvil_stopImpersonatingAccounts", with = "sequence")
+    )]
+    StopImpersonatingAccounts(Vec<Address>),
+    /// Stops impersonating all accounts that were impersonated explicitly
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename = "anvil_stopImpersonatingAll", with = "empty_params")
+    )]
+    StopImpersonatingAll(()),
     /// Will make every account impersonated
     #[cfg_attr(
         feature = "serde",
@@ -318,12 +359,19 @@ pub enum EthRequest {
         )
     )]
     AutoImpersonateAccount(bool),
-    /// Returns true if automatic mining is enabled, and false.
+    /// Derives the next dev account from the configured mnemonic
+    #[cfg_attr(feature = "serde", serde(rename = "anvil_addAccount", with = "empty_params"))]
+    AddAccount(()),
+    /// Returns true if automatic mining is enabled, and false otherwise, including in interval
+    /// mining mode.
     #[cfg_attr(
         feature = "serde",
         serde(rename = "anvil_getAutomine", alias = "hardhat_getAutomine", with = "empty_params")
     )]
     GetAutoMine(()),
+    /// Returns the current mining mode
+    #[cfg_attr(feature = "serde", serde(rename = "anvil_getMiningMode", with = "empty_params"))]
+    GetMiningMode(()),
     /// Mines a series of blocks
     #[cfg_attr(feature = "serde", serde(rename = "anvil_mine", alias = "hardhat_mine"))]
     Mine(
@@ -337,6 +385,13 @@ pub enum EthRequest {
         Option<U256>,
     ),
 
+    /// Mines blocks until the chain reaches the given block number
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename = "anvil_mineTo", deserialize_with = "deserialize_number_seq")
+    )]
+    MineTo(U256),
+
     /// Enables or disables, based on the single boolean argument, the automatic mining of new
     /// blocks with each new transaction submitted to the network.
     #[cfg_attr(
@@ -367,6 +422,49 @@ pub enum EthRequest {
     )]
     DropTransaction(B256),
 
+    /// Removes all transactions from the pool
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename = "anvil_dropAllTransactions", with = "empty_params")
+    )]
+    DropAllTransactions(()),
+
+    /// Sets the order in which pool transactions are mined, `fees` or `fifo`
+    #[cfg_attr(feature = "serde", serde(rename = "anvil_setTxOrdering", with = "sequence"))]
+    SetTxOrdering(String),
+
+    /// Pins the order of the given pool transactions in the next block, ahead of all other
+    /// transactions
+    #[cfg_attr(feature = "serde", serde(rename = "anvil_setNextBlockOrder", with = "sequence"))]
+    SetNextBlockOrder(Vec<B256>),
+
+    /// Sets the percentage by which a replacement transaction must raise the gas price
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename = "anvil_setMinFeeBumpPercent", deserialize_with = "deserialize_number_seq")
+    )]
+    SetMinFeeBumpPercent(U256),
+
+    /// Removes the given number of blocks from the chain and mines the same number of new blocks,
+    /// the first of which includes the given raw signed transactions
+    #[cfg_attr(feature = "serde", serde(rename = "anvil_reorg"))]
+    Reorg(
+        /// The number of blocks to remove
+        #[cfg_attr(feature = "serde", serde(deserialize_with = "deserialize_number"))]
+        U256,
+        /// The raw signed transactions to include in the first new block
+        #[cfg_attr(feature = "serde", serde(default))]
+        Vec<Bytes>,
+    ),
+
+    /// Removes the given number of blocks from the chain and returns their transactions to the
+    /// pool
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename = "anvil_rollback", deserialize_with = "deserialize_number_seq")
+    )]
+    Rollback(U256),
+
     /// Reset the fork to a fresh forked state, and optionally update the fork config
     #[cfg_attr(feature = "serde", serde(rename = "anvil_reset", alias = "hardhat_reset"))]
     Reset(#[cfg_attr(feature = "serde", serde(default))] Option<Params<Option<Forking>>>),
@@ -385,9 +483,13 @@ pub enum EthRequest {
         #[cfg_attr(feature = "serde", serde(deserialize_with = "deserialize_number"))] U256,
     ),
 
-    /// Sets the code of a contract
+    /// Sets the code of a contract, optionally rejecting code that halts immediately
     #[cfg_attr(feature = "serde", serde(rename = "anvil_setCode", alias = "hardhat_setCode"))]
-    SetCode(Address, Bytes),
+    SetCode(
+        Address,
+        #[cfg_attr(feature = "serde", serde(deserialize_with = "bytecode::deserialize"))] Bytes,
+        #[cfg_attr(feature = "serde", serde(default))] Option<bool>,
+    ),
 
     /// Sets the nonce of an address
     #[cfg_attr(
@@ -403,6 +505,10 @@ pub enum EthRequest {
         #[cfg_attr(feature = "serde", serde(deserialize_with = "deserialize_number"))] U256,
     ),
 
+    /// Returns the next nonce of an account, including its pending transactions
+    #[cfg_attr(feature = "serde", serde(rename = "anvil_getNonce", with = "sequence"))]
+    GetNonce(Address),
+
     /// Writes a single slot of the account's storage
     #[cfg_attr(
         feature = "serde",
@@ -416,6 +522,10 @@ pub enum EthRequest {
         B256,
     ),
 
+    /// Writes multiple slots of multiple accounts at once, either all or none are written
+    #[cfg_attr(feature = "serde", serde(rename = "anvil_setStorageBatch", with = "sequence"))]
+    SetStorageBatch(StorageBatch),
+
     /// Sets the coinbase address
     #[cfg_attr(
         feature = "serde",
@@ -427,6 +537,10 @@ pub enum EthRequest {
     #[cfg_attr(feature = "serde", serde(rename = "anvil_setChainId", with = "sequence"))]
     SetChainId(u64),
 
+    /// Sets the hardfork of all following blocks, like `shanghai`
+    #[cfg_attr(feature = "serde", serde(rename = "anvil_setHardfork", with = "sequence"))]
+    SetHardfork(String),
+
     /// Enable or disable logging
     #[cfg_attr(
         feature = "serde",
@@ -438,6 +552,19 @@ pub enum EthRequest {
     )]
     SetLogging(bool),
 
+    /// Returns the given number of the most recent node log lines
+    #[cfg_attr(feature = "serde", serde(rename = "anvil_getNodeLogs", with = "sequence"))]
+    GetNodeLogs(u64),
+
+    /// Enable or disable tracing the steps of all mined transactions
+    #[cfg_attr(feature = "serde", serde(rename = "anvil_setTracingEnabled", with = "sequence"))]
+    SetTracingEnabled(bool),
+
+    /// Registers the ABI of a contract to decode its calls and events, or the fallback ABI of all
+    /// contracts if the address is `null`
+    #[cfg_attr(feature = "serde", serde(rename = "anvil_registerAbi"))]
+    RegisterAbi(Option<Address>, JsonAbi),
+
     /// Set the minimum gas price for the node
     #[cfg_attr(
         feature = "serde",
@@ -460,9 +587,37 @@ pub enum EthRequest {
     )]
     SetNextBlockBaseFeePerGas(U256),
 
-    /// Sets the specific timestamp
-    /// Accepts timestamp (Unix epoch) with millisecond precision and returns the number of seconds
-    /// between the given timestamp and the current time.
+    /// Sets the parameters of the base fee calculation of all following blocks
+    #[cfg_attr(feature = "serde", serde(rename = "anvil_setBaseFeeParams", with = "sequence"))]
+    SetBaseFeeParams(BaseFeeParamsOptions),
+
+    /// Sets the prevrandao of all following blocks, which is the difficulty before the merge
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            rename = "anvil_setPrevRandao",
+            alias = "hardhat_setPrevRandao",
+            alias = "anvil_setDifficulty",
+            deserialize_with = "deserialize_number_seq"
+        )
+    )]
+    SetPrevRandao(U256),
+
+    /// Sets the prevrandao of the next block only
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            rename = "anvil_setNextBlockPrevRandao",
+            deserialize_with = "deserialize_number_seq"
+        )
+    )]
+    SetNextBlockPrevRandao(U256),
+
+    /// Sets the timestamp (Unix epoch seconds) of the next block and returns the number of seconds
+    /// the clock was moved by.
+    ///
+    /// This sets an absolute base for the timestamps of the following blocks, offsets of previous
+    /// `evm_increaseTime` calls no longer apply.
     #[cfg_attr(
         feature = "serde",
         serde(
@@ -475,11 +630,10 @@ pub enum EthRequest {
 
     /// Serializes the current state (including contracts code, contract's storage, accounts
     /// properties, etc.) into a savable data blob
-    #[cfg_attr(
-        feature = "serde",
-        serde(rename = "anvil_dumpState", alias = "hardhat_dumpState", with = "empty_params")
-    )]
-    DumpState(()),
+    #[cfg_attr(feature = "serde", serde(rename = "anvil_dumpState", alias = "hardhat_dumpState"))]
+    DumpState(
+        #[cfg_attr(feature = "serde", serde(default))] Option<Params<Option<DumpStateOptions>>>,
+    ),
 
     /// Adds state previously dumped with `DumpState` to the current chain
     #[cfg_attr(
@@ -499,6 +653,39 @@ pub enum EthRequest {
     )]
     AnvilMetadata(()),
 
+    /// Executes the call and returns the gas spent per opcode, the hottest opcodes first
+    #[cfg_attr(feature = "serde", serde(rename = "anvil_gasProfile"))]
+    AnvilGasProfile(CallRequest, #[cfg_attr(feature = "serde", serde(default))] Option<BlockId>),
+
+    /// Executes the call without committing it and returns its output, events, `console.sol`
+    /// logs and call trace
+    #[cfg_attr(feature = "serde", serde(rename = "anvil_simulate"))]
+    AnvilSimulate(
+        CallRequest,
+        #[cfg_attr(feature = "serde", serde(default))] Option<BlockId>,
+        #[cfg_attr(feature = "serde", serde(default))] Option<BlockOverrides>,
+    ),
+
+    /// Executes the calls in order without committing them and returns the simulation of every
+    /// call, optionally stopping at the first failed call
+    #[cfg_attr(feature = "serde", serde(rename = "anvil_simulateBundle"))]
+    AnvilSimulateBundle(
+        Vec<CallRequest>,
+        #[cfg_attr(feature = "serde", serde(default))] Option<BlockOverrides>,
+        /// stop on revert
+        #[cfg_attr(feature = "serde", serde(default))]
+        Option<bool>,
+    ),
+
+    /// Re-executes the mined transaction and returns its `console.sol` logs, the gas used by its
+    /// calls and the revert reason
+    #[cfg_attr(feature = "serde", serde(rename = "anvil_inspectTransaction", with = "sequence"))]
+    AnvilInspectTransaction(B256),
+
+    /// Returns the gas used by every call frame of the mined transaction
+    #[cfg_attr(feature = "serde", serde(rename = "anvil_traceGasSummary", with = "sequence"))]
+    AnvilTraceGasSummary(B256),
+
     // Ganache compatible calls
     /// Snapshot the state of the blockchain at the current block.
     ///
@@ -509,6 +696,24 @@ pub enum EthRequest {
     )]
     EvmSnapshot(()),
 
+    /// Snapshot the state of the blockchain and the transactions in the mempool at the current
+    /// block, reverting the snapshot also restores the mempool
+    #[cfg_attr(feature = "serde", serde(rename = "anvil_snapshotFull", with = "empty_params"))]
+    SnapshotFull(()),
+
+    /// Snapshot the state of the blockchain at the current block, the snapshot can be reverted by
+    /// the given name
+    #[cfg_attr(feature = "serde", serde(rename = "anvil_snapshotNamed", with = "sequence"))]
+    SnapshotNamed(String),
+
+    /// Revert the state of the blockchain to the snapshot with the given name
+    #[cfg_attr(feature = "serde", serde(rename = "anvil_revertNamed", with = "sequence"))]
+    RevertNamed(String),
+
+    /// Returns the names of all active named snapshots and their ids
+    #[cfg_attr(feature = "serde", serde(rename = "anvil_listSnapshots", with = "empty_params"))]
+    ListSnapshots(()),
+
     /// Revert the state of the blockchain to a previous snapshot.
     /// Takes a single parameter, which is the snapshot id to revert to.
     ///
@@ -524,6 +729,9 @@ pub enum EthRequest {
     EvmRevert(U256),
 
     /// Jump forward in time by the given amount of time, in seconds.
+    ///
+    /// The offset applies to the timestamps of all following blocks, returns the timestamp the
+    /// next block will use.
     #[cfg_attr(
         feature = "serde",
         serde(
@@ -572,6 +780,10 @@ pub enum EthRequest {
     )]
     EvmRemoveBlockTimeStampInterval(()),
 
+    /// Returns the offset in seconds of the block timestamps to the current time
+    #[cfg_attr(feature = "serde", serde(rename = "anvil_getTimeOffset", with = "empty_params"))]
+    GetTimeOffset(()),
+
     /// Mine a single block
     #[cfg_attr(feature = "serde", serde(rename = "evm_mine"))]
     EvmMine(#[cfg_attr(feature = "serde", serde(default))] Option<Params<Option<EvmMineOptions>>>),
@@ -615,7 +827,10 @@ pub enum EthRequest {
     /// Returns the details of all transactions currently pending for inclusion in the next
     /// block(s), as well as the ones that are being scheduled for future execution only.
     /// Ref: [Here](https://geth.ethereum.org/docs/rpc/ns-txpool#txpool_content)
-    #[cfg_attr(feature = "serde", serde(rename = "txpool_content", with = "empty_params"))]
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename = "txpool_content", alias = "anvil_getMempool", with = "empty_params")
+    )]
     TxPoolContent(()),
 
     /// Otterscan's `ots_getApiLevel` endpoint
@@ -724,12 +939,25 @@ pub enum EthPubSub {
     #[cfg_attr(feature = "serde", serde(rename = "eth_subscribe"))]
     EthSubscribe(
         SubscriptionKind,
-        #[cfg_attr(feature = "serde", serde(default))] Box<SubscriptionParams>,
+        #[cfg_attr(
+            feature = "serde",
+            serde(default, deserialize_with = "subscription_params::deserialize")
+        )]
+        Box<SubscriptionParams>,
     ),
 
     /// Unsubscribe from an eth subscription
     #[cfg_attr(feature = "serde", serde(rename = "eth_unsubscribe", with = "sequence"))]
     EthUnSubscribe(SubscriptionId),
+
+    /// Subscribe to an Anvil specific subscription, like `blocks` which yields entire blocks
+    ///
+    /// These are cancelled with `eth_unsubscribe`.
+    #[cfg_attr(feature = "serde", serde(rename = "anvil_subscribe"))]
+    AnvilSubscribe(
+        AnvilSubscriptionKind,
+        #[cfg_attr(feature = "serde", serde(default))] AnvilSubscriptionParams,
+    ),
 }
 
 /// Container type for either a request or a pub sub
@@ -744,6 +972,8 @@ pub enum EthRpcCall {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::types::MiningModeInfo;
+    use alloy_primitives::U64;
 
     #[test]
     fn test_web3_client_version() {
@@ -801,6 +1031,13 @@ mod tests {
         let _req = serde_json::from_value::<EthRequest>(value).unwrap();
     }
 
+    #[test]
+    fn test_eth_blob_base_fee() {
+        let s = r#"{"method": "eth_blobBaseFee", "params":[]}"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let _req = serde_json::from_value::<EthRequest>(value).unwrap();
+    }
+
     #[test]
     fn test_eth_max_priority_fee() {
         let s = r#"{"method": "eth_maxPriorityFeePerGas", "params":[]}"#;
@@ -838,6 +1075,66 @@ mod tests {
         let _req = serde_json::from_value::<EthRequest>(value).unwrap();
     }
 
+    #[test]
+    fn test_custom_mine_to() {
+        let s = r#"{"method": "anvil_mineTo", "params": ["0x64"]}"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let req = serde_json::from_value::<EthRequest>(value).unwrap();
+        assert_eq!(req, EthRequest::MineTo(U256::from(100)));
+
+        let s = r#"{"method": "anvil_mineTo", "params": [100]}"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let req = serde_json::from_value::<EthRequest>(value).unwrap();
+        assert_eq!(req, EthRequest::MineTo(U256::from(100)));
+    }
+
+    #[test]
+    fn test_custom_get_node_logs() {
+        let s = r#"{"method": "anvil_getNodeLogs", "params": [10]}"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let req = serde_json::from_value::<EthRequest>(value).unwrap();
+        assert_eq!(req, EthRequest::GetNodeLogs(10));
+    }
+
+    #[test]
+    fn test_custom_set_hardfork() {
+        let s = r#"{"method": "anvil_setHardfork", "params": ["cancun"]}"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let req = serde_json::from_value::<EthRequest>(value).unwrap();
+        assert_eq!(req, EthRequest::SetHardfork("cancun".to_string()));
+    }
+
+    #[test]
+    fn test_serde_anvil_inspect_transaction() {
+        let s = r#"{"method": "anvil_inspectTransaction", "params":
+["0x4a3b0fce2cb9707b0baa68640cf2fe858c8bb4121b2a8cb904ff369d38a560ff"]}"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let req = serde_json::from_value::<EthRequest>(value).unwrap();
+        assert!(matches!(req, EthRequest::AnvilInspectTransaction(_)));
+    }
+
+    #[test]
+    fn test_serde_anvil_trace_gas_summary() {
+        let s = r#"{"method": "anvil_traceGasSummary", "params":
+["0x4a3b0fce2cb9707b0baa68640cf2fe858c8bb4121b2a8cb904ff369d38a560ff"]}"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let req = serde_json::from_value::<EthRequest>(value).unwrap();
+        assert!(matches!(req, EthRequest::AnvilTraceGasSummary(_)));
+    }
+
+    #[test]
+    fn test_custom_get_mining_mode() {
+        let s = r#"{"method": "anvil_getMiningMode", "params": []}"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let req = serde_json::from_value::<EthRequest>(value).unwrap();
+        assert_eq!(req, EthRequest::GetMiningMode(()));
+
+        let mode: MiningModeInfo =
+            serde_json::from_str(r#"{"mode": "interval", "blockTime": 12}"#).unwrap();
+        assert_eq!(mode, MiningModeInfo::Interval { block_time: 12 });
+        assert_eq!(serde_json::to_string(&MiningModeInfo::Manual).unwrap(), r#"{"mode":"manual"}"#);
+    }
+
     #[test]
     fn test_custom_get_automine() {
         let s = r#"{"method": "anvil_getAutomine", "params": []}"#;
@@ -845,6 +1142,14 @@ mod tests {
         let _req = serde_json::from_value::<EthRequest>(value).unwrap();
     }
 
+    #[test]
+    fn test_custom_get_mempool() {
+        let s = r#"{"method": "anvil_getMempool", "params": []}"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let req = serde_json::from_value::<EthRequest>(value).unwrap();
+        assert_eq!(req, EthRequest::TxPoolContent(()));
+    }
+
     #[test]
     fn test_custom_mine() {
         let s = r#"{"method": "anvil_mine", "params": []}"#;
@@ -908,6 +1213,14 @@ mod tests {
         let _req = serde_json::from_value::<EthRequest>(value).unwrap();
     }
 
+    #[test]
+    fn test_custom_drop_all_txs() {
+        let s = r#"{"method": "anvil_dropAllTransactions", "params": []}"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let req = serde_json::from_value::<EthRequest>(value).unwrap();
+        assert_eq!(req, EthRequest::DropAllTransactions(()));
+    }
+
     #[test]
     fn test_custom_reset() {
         let s = r#"{"method": "anvil_reset", "params": [{"forking": {"jsonRpcUrl": "https://ethereumpublicnode.com",
@@ -923,7 +1236,8 @@ mod tests {
                     forking,
                     Some(Forking {
                         json_rpc_url: Some("https://ethereumpublicnode.com".into()),
-                        block_number: Some(18441649)
+                        block_number: Some(18441649),
+                        ..Default::default()
                     })
                 )
             }
@@ -945,7 +1259,8 @@ mod tests {
                         json_rpc_url: Some(
                             "https://eth-mainnet.alchemyapi.io/v2/<key>".to_string()
                         ),
-                        block_number: Some(11095000)
+                        block_number: Some(11095000),
+                        ..Default::default()
                     })
                 )
             }
@@ -966,7 +1281,8 @@ mod tests {
                         json_rpc_url: Some(
                             "https://eth-mainnet.alchemyapi.io/v2/<key>".to_string()
                         ),
-                        block_number: None
+                        block_number: None,
+                        ..Default::default()
                     })
                 )
             }
@@ -983,7 +1299,8 @@ mod tests {
                     forking,
                     Some(Forking {
                         json_rpc_url: Some("http://localhost:8545".to_string()),
-                        block_number: Some(14000000)
+                        block_number: Some(14000000),
+                        ..Default::default()
                     })
                 )
             }
@@ -998,7 +1315,11 @@ mod tests {
                 let forking = forking.and_then(|f| f.params);
                 assert_eq!(
                     forking,
-                    Some(Forking { json_rpc_url: None, block_number: Some(14000000) })
+                    Some(Forking {
+                        json_rpc_url: None,
+                        block_number: Some(14000000),
+                        ..Default::default()
+                    })
                 )
             }
             _ => unreachable!(),
@@ -1012,7 +1333,11 @@ mod tests {
                 let forking = forking.and_then(|f| f.params);
                 assert_eq!(
                     forking,
-                    Some(Forking { json_rpc_url: None, block_number: Some(14000000) })
+                    Some(Forking {
+                        json_rpc_url: None,
+                        block_number: Some(14000000),
+                        ..Default::default()
+                    })
                 )
             }
             _ => unreachable!(),
@@ -1028,7 +1353,8 @@ mod tests {
                     forking,
                     Some(Forking {
                         json_rpc_url: Some("http://localhost:8545".to_string()),
-                        block_number: None
+                        block_number: None,
+                        ..Default::default()
                     })
                 )
             }
@@ -1046,6 +1372,52 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_custom_impersonate_accounts() {
+        let s = r#"{"method": "anvil_impersonateAccounts", "params":
+[["0xd84de507f3fada7df80908082d3239466db55a71", "0xe84de507f3fada7df80908082d3239466db55a71"]]}"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let req = serde_json::from_value::<EthRequest>(value).unwrap();
+        match req {
+            EthRequest::ImpersonateAccounts(addresses) => assert_eq!(addresses.len(), 2),
+            _ => unreachable!(),
+        }
+
+        let s = r#"{"method": "anvil_stopImpersonatingAccounts", "params":
+[["0xd84de507f3fada7df80908082d3239466db55a71"]]}"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let _req = serde_json::from_value::<EthRequest>(value).unwrap();
+
+        let s = r#"{"method": "anvil_stopImpersonatingAll", "params": []}"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let _req = serde_json::from_value::<EthRequest>(value).unwrap();
+    }
+
+    #[test]
+    fn test_custom_set_base_fee_params() {
+        let s = r#"{"method": "anvil_setBaseFeeParams", "params": [{"gasTarget": "0x5208", "maxChangeDenominator": 50}]}"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let req = serde_json::from_value::<EthRequest>(value).unwrap();
+        match req {
+            EthRequest::SetBaseFeeParams(opts) => assert_eq!(
+                opts,
+                BaseFeeParamsOptions { gas_target: Some(21000), max_change_denominator: Some(50) }
+            ),
+            _ => unreachable!(),
+        }
+
+        let s = r#"{"method": "anvil_setBaseFeeParams", "params": [{}]}"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let _req = serde_json::from_value::<EthRequest>(value).unwrap();
+    }
+
+    #[test]
+    fn test_custom_add_account() {
+        let s = r#"{"method": "anvil_addAccount", "params": []}"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let _req = serde_json::from_value::<EthRequest>(value).unwrap();
+    }
+
     #[test]
     fn test_custom_set_balance() {
         let s = r#"{"method": "anvil_setBalance", "params":
@@ -1075,6 +1447,18 @@ mod tests {
 ["0xd84de507f3fada7df80908082d3239466db55a71", ""]}"#;
         let value: serde_json::Value = serde_json::from_str(s).unwrap();
         let _req = serde_json::from_value::<EthRequest>(value).unwrap();
+
+        let s = r#"{"method": "anvil_setCode", "params":
+["0xd84de507f3fada7df80908082d3239466db55a71", "0x6080", true]}"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let req = serde_json::from_value::<EthRequest>(value).unwrap();
+        assert!(matches!(req, EthRequest::SetCode(_, _, Some(true))));
+
+        let s = r#"{"method": "anvil_setCode", "params":
+["0xd84de507f3fada7df80908082d3239466db55a71", "0x608"]}"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let err = serde_json::from_value::<EthRequest>(value).unwrap_err();
+        assert!(err.to_string().contains("even number of hex digits"), "{err}");
     }
 
     #[test]
@@ -1093,6 +1477,14 @@ mod tests {
         let _req = serde_json::from_value::<EthRequest>(value).unwrap();
     }
 
+    #[test]
+    fn test_custom_get_nonce() {
+        let s = r#"{"method": "anvil_getNonce", "params":
+["0xd84de507f3fada7df80908082d3239466db55a71"]}"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let _req = serde_json::from_value::<EthRequest>(value).unwrap();
+    }
+
     #[test]
     fn test_serde_custom_set_storage_at() {
         let s = r#"{"method": "anvil_setStorageAt", "params":
@@ -1109,6 +1501,81 @@ mod tests {
         let _req = serde_json::from_value::<EthRequest>(value).unwrap();
     }
 
+    #[test]
+    fn test_serde_custom_set_storage_batch() {
+        let s = r#"{"method": "anvil_setStorageBatch", "params":
+[{"0x295a70b2de5e3953354a6a8344e616ed314d7251": {
+    "0x0": "0x0000000000000000000000000000000000000000000000000000000000003039",
+    "0x1": "0x0000000000000000000000000000000000000000000000000000000000000001"
+}}]}"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let req = serde_json::from_value::<EthRequest>(value).unwrap();
+        match req {
+            EthRequest::SetStorageBatch(storage) => {
+                assert_eq!(storage.values().next().unwrap().len(), 2);
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn test_serde_custom_set_tx_ordering() {
+        let s = r#"{"method": "anvil_setTxOrdering", "params": ["fifo"]}"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let req = serde_json::from_value::<EthRequest>(value).unwrap();
+        assert_eq!(req, EthRequest::SetTxOrdering("fifo".to_string()));
+    }
+
+    #[test]
+    fn test_serde_custom_set_next_block_order() {
+        let s = r#"{"method": "anvil_setNextBlockOrder", "params":
+[["0x4a3b0fce2cb9707b0baa68640cf2fe858c8bb4121b2a8cb904ff369d38a560ff"]]}"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let req = serde_json::from_value::<EthRequest>(value).unwrap();
+        match req {
+            EthRequest::SetNextBlockOrder(hashes) => assert_eq!(hashes.len(), 1),
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn test_serde_custom_reorg() {
+        let s = r#"{"method": "anvil_reorg", "params": ["0x2"]}"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let req = serde_json::from_value::<EthRequest>(value).unwrap();
+        assert_eq!(req, EthRequest::Reorg(U256::from(2), vec![]));
+
+        let s = r#"{"method": "anvil_reorg", "params": [2, ["0x1234"]]}"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let req = serde_json::from_value::<EthRequest>(value).unwrap();
+        assert_eq!(req, EthRequest::Reorg(U256::from(2), vec![Bytes::from(vec![0x12, 0x34])]));
+    }
+
+    #[test]
+    fn test_serde_custom_rollback() {
+        let s = r#"{"method": "anvil_rollback", "params": ["0x2"]}"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let req = serde_json::from_value::<EthRequest>(value).unwrap();
+        assert_eq!(req, EthRequest::Rollback(U256::from(2)));
+
+        let s = r#"{"method": "anvil_rollback", "params": [1]}"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let req = serde_json::from_value::<EthRequest>(value).unwrap();
+        assert_eq!(req, EthRequest::Rollback(U256::from(1)));
+    }
+
+    #[test]
+    fn test_serde_custom_set_min_fee_bump_percent() {
+        let s = r#"{"method": "anvil_setMinFeeBumpPercent", "params": ["0x14"]}"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let req = serde_json::from_value::<EthRequest>(value).unwrap();
+        assert_eq!(req, EthRequest::SetMinFeeBumpPercent(U256::from(20)));
+
+        let s = r#"{"method": "anvil_setMinFeeBumpPercent", "params": [20]}"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let _req = serde_json::from_value::<EthRequest>(value).unwrap();
+    }
+
     #[test]
     fn test_serde_custom_coinbase() {
         let s = r#"{"method": "anvil_setCoinbase", "params":
@@ -1124,6 +1591,28 @@ mod tests {
         let _req = serde_json::from_value::<EthRequest>(value).unwrap();
     }
 
+    #[test]
+    fn test_serde_custom_tracing_enabled() {
+        let s = r#"{"method": "anvil_setTracingEnabled", "params": [true]}"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let req = serde_json::from_value::<EthRequest>(value).unwrap();
+        assert_eq!(req, EthRequest::SetTracingEnabled(true));
+    }
+
+    #[test]
+    fn test_serde_custom_register_abi() {
+        let s = r#"{"method": "anvil_registerAbi", "params": ["0xd84de507f3fada7df80908082d3239466db55a71", [{"type":"event","name":"Transfer","inputs":[{"name":"from","type":"address","indexed":true},{"name":"to","type":"address","indexed":true},{"name":"value","type":"uint256","indexed":false}],"anonymous":false}]]}"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let req = serde_json::from_value::<EthRequest>(value).unwrap();
+        let EthRequest::RegisterAbi(Some(_), abi) = req else { panic!("unexpected request") };
+        assert!(abi.events.contains_key("Transfer"));
+
+        let s = r#"{"method": "anvil_registerAbi", "params": [null, []]}"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let req = serde_json::from_value::<EthRequest>(value).unwrap();
+        assert!(matches!(req, EthRequest::RegisterAbi(None, _)));
+    }
+
     #[test]
     fn test_serde_custom_min_gas_price() {
         let s = r#"{"method": "anvil_setMinGasPrice", "params": ["0x0"]}"#;
@@ -1138,6 +1627,24 @@ mod tests {
         let _req = serde_json::from_value::<EthRequest>(value).unwrap();
     }
 
+    #[test]
+    fn test_serde_custom_set_prevrandao() {
+        let s = r#"{"method": "anvil_setPrevRandao", "params": ["0x000000000000000000000000000000000000000000000000000000000000002a"]}"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let req = serde_json::from_value::<EthRequest>(value).unwrap();
+        assert_eq!(req, EthRequest::SetPrevRandao(U256::from(42)));
+
+        let s = r#"{"method": "anvil_setDifficulty", "params": [42]}"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let req = serde_json::from_value::<EthRequest>(value).unwrap();
+        assert_eq!(req, EthRequest::SetPrevRandao(U256::from(42)));
+
+        let s = r#"{"method": "anvil_setNextBlockPrevRandao", "params": ["0x2a"]}"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let req = serde_json::from_value::<EthRequest>(value).unwrap();
+        assert_eq!(req, EthRequest::SetNextBlockPrevRandao(U256::from(42)));
+    }
+
     #[test]
     fn test_serde_set_time() {
         let s = r#"{"method": "anvil_setTime", "params": ["0x0"]}"#;
@@ -1154,6 +1661,16 @@ mod tests {
         let s = r#"{"method": "anvil_dumpState", "params": [] }"#;
         let value: serde_json::Value = serde_json::from_str(s).unwrap();
         let _req = serde_json::from_value::<EthRequest>(value).unwrap();
+
+        let s = r#"{"method": "anvil_dumpState", "params": [{"diff": true}] }"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let req = serde_json::from_value::<EthRequest>(value).unwrap();
+        match req {
+            EthRequest::DumpState(opts) => {
+                assert_eq!(opts.unwrap().params, Some(DumpStateOptions { diff: true }));
+            }
+            _ => unreachable!(),
+        }
     }
 
     #[test]
@@ -1174,6 +1691,32 @@ mod tests {
         let _req = serde_json::from_value::<EthRequest>(value).unwrap();
     }
 
+    #[test]
+    fn test_serde_custom_snapshot_full() {
+        let s = r#"{"method": "anvil_snapshotFull", "params": []}"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let req = serde_json::from_value::<EthRequest>(value).unwrap();
+        assert_eq!(req, EthRequest::SnapshotFull(()));
+    }
+
+    #[test]
+    fn test_serde_custom_named_snapshots() {
+        let s = r#"{"method": "anvil_snapshotNamed", "params": ["setup"]}"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let req = serde_json::from_value::<EthRequest>(value).unwrap();
+        assert_eq!(req, EthRequest::SnapshotNamed("setup".to_string()));
+
+        let s = r#"{"method": "anvil_revertNamed", "params": ["setup"]}"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let req = serde_json::from_value::<EthRequest>(value).unwrap();
+        assert_eq!(req, EthRequest::RevertNamed("setup".to_string()));
+
+        let s = r#"{"method": "anvil_listSnapshots", "params": []}"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let req = serde_json::from_value::<EthRequest>(value).unwrap();
+        assert_eq!(req, EthRequest::ListSnapshots(()));
+    }
+
     #[test]
     fn test_serde_custom_revert() {
         let s = r#"{"method": "anvil_revert", "params": ["0x0"]}"#;
@@ -1228,6 +1771,13 @@ mod tests {
         let _req = serde_json::from_value::<EthRequest>(value).unwrap();
     }
 
+    #[test]
+    fn test_serde_custom_get_time_offset() {
+        let s = r#"{"method": "anvil_getTimeOffset", "params": []}"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let _req = serde_json::from_value::<EthRequest>(value).unwrap();
+    }
+
     #[test]
     fn test_serde_custom_remove_timestamp_interval() {
         let s = r#"{"method": "anvil_removeBlockTimestampInterval", "params": []}"#;
@@ -1390,11 +1940,56 @@ mod tests {
         let value: serde_json::Value = serde_json::from_str(s).unwrap();
         let _req = serde_json::from_value::<EthPubSub>(value).unwrap();
 
+        let s = r#"{"id": 1, "method": "eth_subscribe", "params": ["newPendingTransactions", {"fullTransactions": true}]}"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let req = serde_json::from_value::<EthPubSub>(value).unwrap();
+        assert_eq!(
+            req,
+            EthPubSub::EthSubscribe(
+                SubscriptionKind::NewPendingTransactions,
+                Box::new(SubscriptionParams::Bool(true))
+            )
+        );
+
+        let s =
+            r#"{"id": 1, "method": "eth_subscribe", "params": ["newPendingTransactions", true]}"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let req = serde_json::from_value::<EthPubSub>(value).unwrap();
+        assert_eq!(
+            req,
+            EthPubSub::EthSubscribe(
+                SubscriptionKind::NewPendingTransactions,
+                Box::new(SubscriptionParams::Bool(true))
+            )
+        );
+
         let s = r#"{"id": 1, "method": "eth_subscribe", "params": ["syncing"]}"#;
         let value: serde_json::Value = serde_json::from_str(s).unwrap();
         let _req = serde_json::from_value::<EthPubSub>(value).unwrap();
     }
 
+    #[test]
+    fn test_serde_anvil_subscribe() {
+        let s = r#"{"id": 1, "method": "anvil_subscribe", "params": ["blocks", {"fullTransactions": true}]}"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let req = serde_json::from_value::<EthPubSub>(value).unwrap();
+        assert_eq!(
+            req,
+            EthPubSub::AnvilSubscribe(
+                AnvilSubscriptionKind::Blocks,
+                AnvilSubscriptionParams { full_transactions: true }
+            )
+        );
+
+        let s = r#"{"id": 1, "method": "anvil_subscribe", "params": ["blocks"]}"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let req = serde_json::from_value::<EthPubSub>(value).unwrap();
+        assert_eq!(
+            req,
+            EthPubSub::AnvilSubscribe(AnvilSubscriptionKind::Blocks, Default::default())
+        );
+    }
+
     #[test]
     fn test_serde_debug_trace_transaction() {
         let s = r#"{"method": "debug_traceTransaction", "params":
@@ -1412,6 +2007,63 @@ mod tests {
 true}]}"#;
         let value: serde_json::Value = serde_json::from_str(s).unwrap();
         let _req = serde_json::from_value::<EthRequest>(value).unwrap();
+
+        let s = r#"{"method": "debug_traceTransaction", "params":
+["0x4a3b0fce2cb9707b0baa68640cf2fe858c8bb4121b2a8cb904ff369d38a560ff", {"tracer":
+"callTracer", "decode": true}]}"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let req = serde_json::from_value::<EthRequest>(value).unwrap();
+        match req {
+            EthRequest::DebugTraceTransaction(_, opts) => {
+                assert!(opts.decode);
+                assert!(opts.tracing_options.tracer.is_some());
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn test_serde_anvil_gas_profile() {
+        let s = r#"{"method": "anvil_gasProfile", "params": [{"data":"0xcfae3217","from":"0xd84de507f3fada7df80908082d3239466db55a71","to":"0xcbe828fdc46e3b1c351ec90b1a5e7d9742c0398d"}]}"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let _req = serde_json::from_value::<EthRequest>(value).unwrap();
+
+        let s = r#"{"method": "anvil_gasProfile", "params": [{"data":"0xcfae3217","from":"0xd84de507f3fada7df80908082d3239466db55a71","to":"0xcbe828fdc46e3b1c351ec90b1a5e7d9742c0398d"}, "latest"]}"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let _req = serde_json::from_value::<EthRequest>(value).unwrap();
+    }
+
+    #[test]
+    fn test_serde_anvil_simulate() {
+        let s = r#"{"method": "anvil_simulate", "params": [{"data":"0xcfae3217","from":"0xd84de507f3fada7df80908082d3239466db55a71","to":"0xcbe828fdc46e3b1c351ec90b1a5e7d9742c0398d"}]}"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let _req = serde_json::from_value::<EthRequest>(value).unwrap();
+
+        let s = r#"{"method": "anvil_simulate", "params": [{"data":"0xcfae3217","from":"0xd84de507f3fada7df80908082d3239466db55a71","to":"0xcbe828fdc46e3b1c351ec90b1a5e7d9742c0398d"}, "latest"]}"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let _req = serde_json::from_value::<EthRequest>(value).unwrap();
+    }
+
+    #[test]
+    fn test_serde_anvil_simulate_bundle() {
+        let s = r#"{"method": "anvil_simulateBundle", "params": [[{"data":"0xcfae3217","to":"0xcbe828fdc46e3b1c351ec90b1a5e7d9742c0398d"}]]}"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let req = serde_json::from_value::<EthRequest>(value).unwrap();
+        match req {
+            EthRequest::AnvilSimulateBundle(calls, None, None) => assert_eq!(calls.len(), 1),
+            _ => unreachable!(),
+        }
+
+        let s = r#"{"method": "anvil_simulateBundle", "params": [[{"data":"0xcfae3217","to":"0xcbe828fdc46e3b1c351ec90b1a5e7d9742c0398d"}], {"number": "0x10", "baseFee": "0x1"}, true]}"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let req = serde_json::from_value::<EthRequest>(value).unwrap();
+        match req {
+            EthRequest::AnvilSimulateBundle(_, Some(overrides), Some(true)) => {
+                assert_eq!(overrides.number, Some(U64::from(16)));
+                assert_eq!(overrides.base_fee, Some(U256::from(1)));
+            }
+            _ => unreachable!(),
+        }
     }
 
     #[test]
@@ -1435,6 +2087,47 @@ true}]}"#;
         let s = r#"{"method": "debug_traceCall", "params": [{"data":"0xcfae3217","from":"0xd84de507f3fada7df80908082d3239466db55a71","to":"0xcbe828fdc46e3b1c351ec90b1a5e7d9742c0398d"}, { "blockNumber": "0x0" }, {"disableStorage": true}]}"#;
         let value: serde_json::Value = serde_json::from_str(s).unwrap();
         let _req = serde_json::from_value::<EthRequest>(value).unwrap();
+
+        let s = r#"{"method": "debug_traceCall", "params": [{"data":"0xcfae3217","from":"0xd84de507f3fada7df80908082d3239466db55a71","to":"0xcbe828fdc46e3b1c351ec90b1a5e7d9742c0398d"}, "latest", {"disableStorage": true, "stateOverrides": {"0xcbe828fdc46e3b1c351ec90b1a5e7d9742c0398d": {"balance": "0x1", "stateDiff": {"0x0000000000000000000000000000000000000000000000000000000000000000": "0x0000000000000000000000000000000000000000000000000000000000000001"}}}}]}"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let req = serde_json::from_value::<EthRequest>(value).unwrap();
+        match req {
+            EthRequest::DebugTraceCall(_, _, opts) => {
+                assert_eq!(opts.tracing_options.disable_storage, Some(true));
+                assert_eq!(opts.state_overrides.unwrap().len(), 1);
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn test_serde_debug_trace_block() {
+        let s = r#"{"method": "debug_traceBlockByNumber", "params": ["0x1"]}"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let _req = serde_json::from_value::<EthRequest>(value).unwrap();
+
+        let s = r#"{"method": "debug_traceBlockByNumber", "params": ["latest", {"tracer": "callTracer", "tracerConfig": {"onlyTopCall": true}}]}"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let _req = serde_json::from_value::<EthRequest>(value).unwrap();
+
+        let s = r#"{"method": "debug_traceBlockByHash", "params": ["0xd4e56740f876aef8c010b86a40d5f56745a118d0906a34e69aec8c0db1cb8fa3", {"disableStorage": true}]}"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let _req = serde_json::from_value::<EthRequest>(value).unwrap();
+    }
+
+    #[test]
+    fn test_serde_trace_filter() {
+        let s = r#"{"method": "trace_filter", "params": [{"fromBlock": "0x0", "fromAddress": ["0xd84de507f3fada7df80908082d3239466db55a71"], "count": 10}]}"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let req = serde_json::from_value::<EthRequest>(value).unwrap();
+        match req {
+            EthRequest::TraceFilter(filter) => {
+                assert_eq!(filter.from_block, Some(BlockNumber::Number(0)));
+                assert_eq!(filter.from_address.len(), 1);
+                assert_eq!(filter.count, Some(10));
+            }
+            _ => unreachable!(),
+        }
     }
 
     #[test]
@@ -1464,6 +2157,15 @@ true}]}"#;
 
         let s = r#"{"method": "eth_call", "params":[{"data":"0xcfae3217","from":"0xd84de507f3fada7df80908082d3239466db55a71","to":"0xcbe828fdc46e3b1c351ec90b1a5e7d9742c0398d"}, { "blockHash":"0xd4e56740f876aef8c010b86a40d5f56745a118d0906a34e69aec8c0db1cb8fa3" }]}"#;
         let _req = serde_json::from_str::<EthRequest>(s).unwrap();
+
+        let s = r#"{"method": "eth_call", "params":[{"data":"0xcfae3217","to":"0xcbe828fdc46e3b1c351ec90b1a5e7d9742c0398d"}, "latest", {}, {"time": "0x64", "coinbase": "0xd84de507f3fada7df80908082d3239466db55a71", "random": "0xd4e56740f876aef8c010b86a40d5f56745a118d0906a34e69aec8c0db1cb8fa3"}]}"#;
+        match serde_json::from_str::<EthRequest>(s).unwrap() {
+            EthRequest::EthCall(_, _, _, Some(overrides)) => {
+                assert_eq!(overrides.time, Some(U64::from(100)));
+                assert!(overrides.prev_randao.is_some());
+            }
+            _ => unreachable!(),
+        }
     }
 
     #[test]
@@ -94,6 +94,8 @@ impl Decodable for EIP658Receipt {
 // same underlying data structure
 pub type EIP2930Receipt = EIP658Receipt;
 pub type EIP1559Receipt = EIP658Receipt;
+pub type EIP4844Receipt = EIP658Receipt;
+pub type EIP7702Receipt = EIP658Receipt;
 pub type DepositReceipt = EIP658Receipt;
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -105,6 +107,10 @@ pub enum TypedReceipt {
     EIP2930(EIP2930Receipt),
     /// EIP-1559 receipt
     EIP1559(EIP1559Receipt),
+    /// EIP-4844 receipt
+    EIP4844(EIP4844Receipt),
+    /// EIP-7702 receipt
+    EIP7702(EIP7702Receipt),
     /// op-stack deposit receipt
     Deposit(DepositReceipt),
 }
@@ -118,16 +124,35 @@ impl TypedReceipt {
             TypedReceipt::Legacy(r) |
             TypedReceipt::EIP2930(r) |
             TypedReceipt::EIP1559(r) |
+            TypedReceipt::EIP4844(r) |
+            TypedReceipt::EIP7702(r) |
             TypedReceipt::Deposit(r) => r.gas_used,
         }
     }
 
+    /// Returns the receipt with the gas used replaced by the given gas used by all transactions
+    /// of the block up to and including this one, as it's committed to by the receipts root
+    pub fn with_cumulative_gas_used(&self, cumulative_gas_used: U256) -> Self {
+        let mut receipt = self.clone();
+        match &mut receipt {
+            TypedReceipt::Legacy(r) |
+            TypedReceipt::EIP2930(r) |
+            TypedReceipt::EIP1559(r) |
+            TypedReceipt::EIP4844(r) |
+            TypedReceipt::EIP7702(r) |
+            TypedReceipt::Deposit(r) => r.gas_used = cumulative_gas_used,
+        }
+        receipt
+    }
+
     /// Returns the gas used by the transactions
     pub fn logs_bloom(&self) -> &Bloom {
         match self {
             TypedReceipt::Legacy(r) |
             TypedReceipt::EIP2930(r) |
             TypedReceipt::EIP1559(r) |
+            TypedReceipt::EIP4844(r) |
+            TypedReceipt::EIP7702(r) |
             TypedReceipt::Deposit(r) => &r.logs_bloom,
         }
     }
@@ -139,6 +164,8 @@ impl Encodable for TypedReceipt {
             TypedReceipt::Legacy(r) => r.rlp_append(s),
             TypedReceipt::EIP2930(r) => enveloped(1, r, s),
             TypedReceipt::EIP1559(r) => enveloped(2, r, s),
+            TypedReceipt::EIP4844(r) => enveloped(3, r, s),
+            TypedReceipt::EIP7702(r) => enveloped(4, r, s),
             TypedReceipt::Deposit(r) => enveloped(0x7E, r, s),
         }
     }
@@ -164,6 +191,14 @@ impl Decodable for TypedReceipt {
             return rlp::decode(s).map(TypedReceipt::EIP1559)
         }
 
+        if first == 0x03 {
+            return rlp::decode(s).map(TypedReceipt::EIP4844)
+        }
+
+        if first == 0x04 {
+            return rlp::decode(s).map(TypedReceipt::EIP7702)
+        }
+
         if first == 0x7E {
             return rlp::decode(s).map(TypedReceipt::Deposit)
         }
@@ -181,6 +216,8 @@ impl open_fastrlp::Encodable for TypedReceipt {
                 let payload_len = match receipt {
                     TypedReceipt::EIP2930(r) => r.length() + 1,
                     TypedReceipt::EIP1559(r) => r.length() + 1,
+                    TypedReceipt::EIP4844(r) => r.length() + 1,
+                    TypedReceipt::EIP7702(r) => r.length() + 1,
                     TypedReceipt::Deposit(r) => r.length() + 1,
                     _ => unreachable!("receipt already matched"),
                 };
@@ -199,6 +236,8 @@ impl open_fastrlp::Encodable for TypedReceipt {
                 let payload_len = match receipt {
                     TypedReceipt::EIP2930(r) => r.length() + 1,
                     TypedReceipt::EIP1559(r) => r.length() + 1,
+                    TypedReceipt::EIP4844(r) => r.length() + 1,
+                    TypedReceipt::EIP7702(r) => r.length() + 1,
                     TypedReceipt::Deposit(r) => r.length() + 1,
                     _ => unreachable!("receipt already matched"),
                 };
@@ -220,6 +259,22 @@ impl open_fastrlp::Encodable for TypedReceipt {
                         out.put_u8(0x02);
                         r.encode(out);
                     }
+                    TypedReceipt::EIP4844(r) => {
+                        let receipt_string_header =
+                            Header { list: false, payload_length: payload_len };
+
+                        receipt_string_header.encode(out);
+                        out.put_u8(0x03);
+                        r.encode(out);
+                    }
+                    TypedReceipt::EIP7702(r) => {
+                        let receipt_string_header =
+                            Header { list: false, payload_length: payload_len };
+
+                        receipt_string_header.encode(out);
+                        out.put_u8(0x04);
+                        r.encode(out);
+                    }
                     TypedReceipt::Deposit(r) => {
                         let receipt_string_header =
                             Header { list: false, payload_length: payload_len };
@@ -264,6 +319,14 @@ impl open_fastrlp::Decodable for TypedReceipt {
                     buf.advance(1);
                     <EIP1559Receipt as open_fastrlp::Decodable>::decode(buf)
                         .map(TypedReceipt::EIP1559)
+                } else if receipt_type == 0x03 {
+                    buf.advance(1);
+                    <EIP4844Receipt as open_fastrlp::Decodable>::decode(buf)
+                        .map(TypedReceipt::EIP4844)
+                } else if receipt_type == 0x04 {
+                    buf.advance(1);
+                    <EIP7702Receipt as open_fastrlp::Decodable>::decode(buf)
+                        .map(TypedReceipt::EIP7702)
                 } else if receipt_type == 0x7E {
                     buf.advance(1);
                     <DepositReceipt as open_fastrlp::Decodable>::decode(buf)
@@ -288,6 +351,8 @@ impl From<TypedReceipt> for EIP658Receipt {
             TypedReceipt::Legacy(receipt) => receipt,
             TypedReceipt::EIP2930(receipt) => receipt,
             TypedReceipt::EIP1559(receipt) => receipt,
+            TypedReceipt::EIP4844(receipt) => receipt,
+            TypedReceipt::EIP7702(receipt) => receipt,
             TypedReceipt::Deposit(receipt) => receipt,
         }
     }
@@ -52,6 +52,29 @@ pub mod empty_params {
     }
 }
 
+/// A module that deserializes hex encoded bytecode, with a descriptive error for odd-length hex
+pub mod bytecode {
+    use alloy_primitives::{hex, Bytes};
+    use serde::{Deserialize, Deserializer};
+
+    pub fn deserialize<'de, D>(d: D) -> Result<Bytes, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let code = String::deserialize(d)?;
+        let digits = code.strip_prefix("0x").unwrap_or(&code);
+        if digits.len() % 2 != 0 {
+            return Err(serde::de::Error::custom(format!(
+                "invalid bytecode, expected an even number of hex digits but got {}",
+                digits.len()
+            )))
+        }
+        hex::decode(digits)
+            .map(Into::into)
+            .map_err(|err| serde::de::Error::custom(format!("invalid bytecode: {err}")))
+    }
+}
+
 /// A module that deserializes either a BlockNumberOrTag, or a simple number.
 pub mod lenient_block_number {
     use alloy_rpc_types::BlockNumberOrTag;
@@ -108,3 +131,26 @@ pub mod lenient_block_number {
         }
     }
 }
+
+/// A module that deserializes the params of `eth_subscribe`
+pub mod subscription_params {
+    use alloy_rpc_types::pubsub::Params;
+    use serde::{Deserialize, Deserializer};
+
+    /// Deserializes the params like [Params], but additionally accepts geth's
+    /// `{"fullTransactions": bool}` option of `newPendingTransactions` subscriptions, which is
+    /// returned as [Params::Bool]
+    pub fn deserialize<'de, D>(d: D) -> Result<Box<Params>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let value = serde_json::Value::deserialize(d)?;
+        if let Some(full) = value.as_object().and_then(|obj| obj.get("fullTransactions")) {
+            let full = full
+                .as_bool()
+                .ok_or_else(|| serde::de::Error::custom("fullTransactions must be a boolean"))?;
+            return Ok(Box::new(Params::Bool(full)))
+        }
+        serde_json::from_value(value).map(Box::new).map_err(serde::de::Error::custom)
+    }
+}
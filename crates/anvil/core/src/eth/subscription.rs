@@ -73,6 +73,24 @@ pub enum SubscriptionKind {
     Syncing,
 }
 
+/// Kind of an Anvil specific `anvil_subscribe` subscription
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub enum AnvilSubscriptionKind {
+    /// subscribe to new blocks
+    Blocks,
+}
+
+/// Params of an `anvil_subscribe` subscription
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default, deny_unknown_fields, rename_all = "camelCase"))]
+pub struct AnvilSubscriptionParams {
+    /// Whether to include the entire transactions instead of their hashes
+    pub full_transactions: bool,
+}
+
 /// Unique subscription id
 #[derive(Clone, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
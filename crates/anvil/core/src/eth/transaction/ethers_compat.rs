@@ -6,8 +6,8 @@ use crate::eth::{
     state::{AccountOverride, StateOverride as EthStateOverride},
     transaction::{
         DepositTransactionRequest, EIP1559TransactionRequest, EIP2930TransactionRequest,
-        LegacyTransactionRequest, MaybeImpersonatedTransaction, TypedTransaction,
-        TypedTransactionRequest,
+        LegacyTransactionRequest, MaybeImpersonatedTransaction, SignedAuthorization,
+        TypedTransaction, TypedTransactionRequest,
     },
 };
 use alloy_primitives::{U128 as rU128, U256 as rU256, U64 as rU64};
@@ -354,6 +354,57 @@ fn to_ethers_transaction_with_hash_and_sender(
             is_system_tx: false,
             other: Default::default(),
         },
+        TypedTransaction::EIP4844(t) => EthersTransaction {
+            hash,
+            nonce: t.nonce,
+            block_hash: None,
+            block_number: None,
+            transaction_index: None,
+            from,
+            to: None,
+            value: t.value,
+            gas_price: None,
+            max_fee_per_gas: Some(t.max_fee_per_gas),
+            max_priority_fee_per_gas: Some(t.max_priority_fee_per_gas),
+            gas: t.gas_limit,
+            input: t.input.clone(),
+            chain_id: Some(t.chain_id.into()),
+            v: U64::from(t.odd_y_parity as u8),
+            r: U256::from(t.r.as_bytes()),
+            s: U256::from(t.s.as_bytes()),
+            access_list: Some(t.access_list),
+            transaction_type: Some(3u64.into()),
+            source_hash: H256::zero(),
+            mint: None,
+            is_system_tx: false,
+            other: Default::default(),
+        },
+        TypedTransaction::EIP7702(t) => EthersTransaction {
+            hash,
+            nonce: t.nonce,
+            block_hash: None,
+            block_number: None,
+            transaction_index: None,
+            from,
+            to: None,
+            value: t.value,
+            gas_price: None,
+            max_fee_per_gas: Some(t.max_fee_per_gas),
+            max_priority_fee_per_gas: Some(t.max_priority_fee_per_gas),
+            gas: t.gas_limit,
+            input: t.input.clone(),
+            chain_id: Some(t.chain_id.into()),
+            v: U64::from(t.odd_y_parity as u8),
+            r: U256::from(t.r.as_bytes()),
+            s: U256::from(t.s.as_bytes()),
+            access_list: Some(t.access_list),
+            transaction_type: Some(4u64.into()),
+            source_hash: H256::zero(),
+            mint: None,
+            is_system_tx: false,
+            other: serde_json::from_value(authorization_list_fields(&t.authorization_list))
+                .unwrap_or_default(),
+        },
         TypedTransaction::Deposit(t) => EthersTransaction {
             hash,
             nonce: t.nonce,
@@ -469,6 +520,61 @@ fn to_alloy_transaction_with_hash_and_sender(
             blob_versioned_hashes: vec![],
             other: Default::default(),
         },
+        TypedTransaction::EIP4844(t) => AlloyTransaction {
+            hash: hash.to_alloy(),
+            nonce: t.nonce.to_alloy().to::<rU64>(),
+            block_hash: None,
+            block_number: None,
+            transaction_index: None,
+            from: from.to_alloy(),
+            to: None,
+            value: t.value.to_alloy(),
+            gas_price: None,
+            max_fee_per_gas: Some(t.max_fee_per_gas.to_alloy().to::<rU128>()),
+            max_priority_fee_per_gas: Some(t.max_priority_fee_per_gas.to_alloy().to::<rU128>()),
+            gas: t.gas_limit.to_alloy(),
+            input: t.input.clone().0.into(),
+            chain_id: Some(rU64::from(t.chain_id)),
+            signature: Some(Signature {
+                r: rU256::from_be_bytes(t.r.to_alloy().0),
+                s: rU256::from_be_bytes(t.s.to_alloy().0),
+                v: rU256::from(t.odd_y_parity as u8),
+                y_parity: Some(t.odd_y_parity.into()),
+            }),
+            access_list: Some(from_ethers_access_list(t.access_list).0),
+            transaction_type: Some(rU64::from(3)),
+            max_fee_per_blob_gas: Some(t.max_fee_per_blob_gas.to_alloy().to::<rU128>()),
+            blob_versioned_hashes: t.blob_versioned_hashes.iter().map(|h| h.to_alloy()).collect(),
+            other: Default::default(),
+        },
+        TypedTransaction::EIP7702(t) => AlloyTransaction {
+            hash: hash.to_alloy(),
+            nonce: t.nonce.to_alloy().to::<rU64>(),
+            block_hash: None,
+            block_number: None,
+            transaction_index: None,
+            from: from.to_alloy(),
+            to: None,
+            value: t.value.to_alloy(),
+            gas_price: None,
+            max_fee_per_gas: Some(t.max_fee_per_gas.to_alloy().to::<rU128>()),
+            max_priority_fee_per_gas: Some(t.max_priority_fee_per_gas.to_alloy().to::<rU128>()),
+            gas: t.gas_limit.to_alloy(),
+            input: t.input.clone().0.into(),
+            chain_id: Some(rU64::from(t.chain_id)),
+            signature: Some(Signature {
+                r: rU256::from_be_bytes(t.r.to_alloy().0),
+                s: rU256::from_be_bytes(t.s.to_alloy().0),
+                v: rU256::from(t.odd_y_parity as u8),
+                y_parity: Some(t.odd_y_parity.into()),
+            }),
+            access_list: Some(from_ethers_access_list(t.access_list).0),
+            transaction_type: Some(rU64::from(4)),
+            max_fee_per_blob_gas: None,
+            blob_versioned_hashes: vec![],
+            other: serde_json::from_value(authorization_list_fields(&t.authorization_list))
+                .unwrap_or_default(),
+        },
         TypedTransaction::Deposit(t) => AlloyTransaction {
             hash: hash.to_alloy(),
             nonce: t.nonce.to_alloy().to::<rU64>(),
@@ -494,6 +600,24 @@ fn to_alloy_transaction_with_hash_and_sender(
     }
 }
 
+/// Returns the `authorizationList` of an EIP7702 transaction as additional JSON fields
+fn authorization_list_fields(authorization_list: &[SignedAuthorization]) -> serde_json::Value {
+    let list = authorization_list
+        .iter()
+        .map(|auth| {
+            serde_json::json!({
+                "chainId": U64::from(auth.chain_id),
+                "address": auth.address,
+                "nonce": U64::from(auth.nonce),
+                "yParity": U64::from(auth.y_parity as u8),
+                "r": U256::from_big_endian(&auth.r[..]),
+                "s": U256::from_big_endian(&auth.s[..]),
+            })
+        })
+        .collect::<Vec<_>>();
+    serde_json::json!({ "authorizationList": list })
+}
+
 impl From<TypedTransaction> for EthersTransaction {
     fn from(transaction: TypedTransaction) -> Self {
         let hash = transaction.hash();
@@ -1,8 +1,11 @@
 //! transaction related data
 
-use crate::eth::{
-    receipt::Log,
-    utils::{enveloped, to_revm_access_list},
+use crate::{
+    eth::{
+        receipt::Log,
+        utils::{enveloped, to_revm_access_list, trie_value},
+    },
+    types::{PreState, StorageDiff},
 };
 use ethers_core::{
     types::{
@@ -18,7 +21,7 @@ use foundry_common::types::ToAlloy;
 use foundry_evm::traces::CallTraceNode;
 use revm::{
     interpreter::InstructionResult,
-    primitives::{CreateScheme, OptimismFields, TransactTo, TxEnv},
+    primitives::{CreateScheme, OptimismFields, TransactTo, TxEnv, GAS_PER_BLOB},
 };
 use std::ops::Deref;
 
@@ -339,6 +342,127 @@ impl Encodable for EIP2930TransactionRequest {
     }
 }
 
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EIP4844TransactionRequest {
+    pub chain_id: u64,
+    pub nonce: U256,
+    pub max_priority_fee_per_gas: U256,
+    pub max_fee_per_gas: U256,
+    pub gas_limit: U256,
+    pub kind: TransactionKind,
+    pub value: U256,
+    pub input: Bytes,
+    pub access_list: Vec<AccessListItem>,
+    pub max_fee_per_blob_gas: U256,
+    pub blob_versioned_hashes: Vec<H256>,
+}
+
+// == impl EIP4844TransactionRequest ==
+
+impl EIP4844TransactionRequest {
+    pub fn hash(&self) -> H256 {
+        let encoded = rlp::encode(self);
+        let mut out = vec![0; 1 + encoded.len()];
+        out[0] = 3;
+        out[1..].copy_from_slice(&encoded);
+        H256::from_slice(keccak256(&out).as_slice())
+    }
+}
+
+impl From<EIP4844Transaction> for EIP4844TransactionRequest {
+    fn from(t: EIP4844Transaction) -> Self {
+        Self {
+            chain_id: t.chain_id,
+            nonce: t.nonce,
+            max_priority_fee_per_gas: t.max_priority_fee_per_gas,
+            max_fee_per_gas: t.max_fee_per_gas,
+            gas_limit: t.gas_limit,
+            kind: t.kind,
+            value: t.value,
+            input: t.input,
+            access_list: t.access_list.0,
+            max_fee_per_blob_gas: t.max_fee_per_blob_gas,
+            blob_versioned_hashes: t.blob_versioned_hashes,
+        }
+    }
+}
+
+impl Encodable for EIP4844TransactionRequest {
+    fn rlp_append(&self, s: &mut RlpStream) {
+        s.begin_list(11);
+        s.append(&self.chain_id);
+        s.append(&self.nonce);
+        s.append(&self.max_priority_fee_per_gas);
+        s.append(&self.max_fee_per_gas);
+        s.append(&self.gas_limit);
+        s.append(&self.kind);
+        s.append(&self.value);
+        s.append(&self.input.as_ref());
+        s.append_list(&self.access_list);
+        s.append(&self.max_fee_per_blob_gas);
+        s.append_list(&self.blob_versioned_hashes);
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EIP7702TransactionRequest {
+    pub chain_id: u64,
+    pub nonce: U256,
+    pub max_priority_fee_per_gas: U256,
+    pub max_fee_per_gas: U256,
+    pub gas_limit: U256,
+    pub kind: TransactionKind,
+    pub value: U256,
+    pub input: Bytes,
+    pub access_list: Vec<AccessListItem>,
+    pub authorization_list: Vec<SignedAuthorization>,
+}
+
+// == impl EIP7702TransactionRequest ==
+
+impl EIP7702TransactionRequest {
+    pub fn hash(&self) -> H256 {
+        let encoded = rlp::encode(self);
+        let mut out = vec![0; 1 + encoded.len()];
+        out[0] = 4;
+        out[1..].copy_from_slice(&encoded);
+        H256::from_slice(keccak256(&out).as_slice())
+    }
+}
+
+impl From<EIP7702Transaction> for EIP7702TransactionRequest {
+    fn from(t: EIP7702Transaction) -> Self {
+        Self {
+            chain_id: t.chain_id,
+            nonce: t.nonce,
+            max_priority_fee_per_gas: t.max_priority_fee_per_gas,
+            max_fee_per_gas: t.max_fee_per_gas,
+            gas_limit: t.gas_limit,
+            kind: t.kind,
+            value: t.value,
+            input: t.input,
+            access_list: t.access_list.0,
+            authorization_list: t.authorization_list,
+        }
+    }
+}
+
+impl Encodable for EIP7702TransactionRequest {
+    fn rlp_append(&self, s: &mut RlpStream) {
+        s.begin_list(10);
+        s.append(&self.chain_id);
+        s.append(&self.nonce);
+        s.append(&self.max_priority_fee_per_gas);
+        s.append(&self.max_fee_per_gas);
+        s.append(&self.gas_limit);
+        s.append(&self.kind);
+        s.append(&self.value);
+        s.append(&self.input.as_ref());
+        s.append_list(&self.access_list);
+        s.append_list(&self.authorization_list);
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct LegacyTransactionRequest {
     pub nonce: U256,
@@ -622,6 +746,10 @@ pub enum TypedTransaction {
     EIP2930(EIP2930Transaction),
     /// EIP-1559 transaction
     EIP1559(EIP1559Transaction),
+    /// EIP-4844 blob transaction
+    EIP4844(EIP4844Transaction),
+    /// EIP-7702 set code transaction
+    EIP7702(EIP7702Transaction),
     /// op-stack deposit transaction
     Deposit(DepositTransaction),
 }
@@ -629,9 +757,14 @@ pub enum TypedTransaction {
 // == impl TypedTransaction ==
 
 impl TypedTransaction {
-    /// Returns true if the transaction uses dynamic fees: EIP1559
+    /// Returns true if the transaction uses dynamic fees: EIP1559, EIP4844, EIP7702
     pub fn is_dynamic_fee(&self) -> bool {
-        matches!(self, TypedTransaction::EIP1559(_))
+        matches!(
+            self,
+            TypedTransaction::EIP1559(_) |
+                TypedTransaction::EIP4844(_) |
+                TypedTransaction::EIP7702(_)
+        )
     }
 
     pub fn gas_price(&self) -> U256 {
@@ -639,6 +772,35 @@ impl TypedTransaction {
             TypedTransaction::Legacy(tx) => tx.gas_price,
             TypedTransaction::EIP2930(tx) => tx.gas_price,
             TypedTransaction::EIP1559(tx) => tx.max_fee_per_gas,
+            TypedTransaction::EIP4844(tx) => tx.max_fee_per_gas,
+            TypedTransaction::EIP7702(tx) => tx.max_fee_per_gas,
+            TypedTransaction::Deposit(_) => U256::from(0),
+        }
+    }
+
+    /// Returns the price per gas the transaction pays in a block with the given base fee
+    ///
+    /// For dynamic fee transactions this is `base_fee + min(max_priority_fee_per_gas,
+    /// max_fee_per_gas - base_fee)`.
+    pub fn effective_gas_price(&self, base_fee: Option<U256>) -> U256 {
+        let base_fee = base_fee.unwrap_or_default();
+        let dynamic = |max_fee_per_gas: U256, max_priority_fee_per_gas: U256| {
+            base_fee.saturating_add(
+                max_priority_fee_per_gas.min(max_fee_per_gas.saturating_sub(base_fee)),
+            )
+        };
+        match self {
+            TypedTransaction::Legacy(tx) => tx.gas_price,
+            TypedTransaction::EIP2930(tx) => tx.gas_price,
+            TypedTransaction::EIP1559(tx) => {
+                dynamic(tx.max_fee_per_gas, tx.max_priority_fee_per_gas)
+            }
+            TypedTransaction::EIP4844(tx) => {
+                dynamic(tx.max_fee_per_gas, tx.max_priority_fee_per_gas)
+            }
+            TypedTransaction::EIP7702(tx) => {
+                dynamic(tx.max_fee_per_gas, tx.max_priority_fee_per_gas)
+            }
             TypedTransaction::Deposit(_) => U256::from(0),
         }
     }
@@ -648,6 +810,8 @@ impl TypedTransaction {
             TypedTransaction::Legacy(tx) => tx.gas_limit,
             TypedTransaction::EIP2930(tx) => tx.gas_limit,
             TypedTransaction::EIP1559(tx) => tx.gas_limit,
+            TypedTransaction::EIP4844(tx) => tx.gas_limit,
+            TypedTransaction::EIP7702(tx) => tx.gas_limit,
             TypedTransaction::Deposit(tx) => tx.gas_limit,
         }
     }
@@ -657,6 +821,8 @@ impl TypedTransaction {
             TypedTransaction::Legacy(tx) => tx.value,
             TypedTransaction::EIP2930(tx) => tx.value,
             TypedTransaction::EIP1559(tx) => tx.value,
+            TypedTransaction::EIP4844(tx) => tx.value,
+            TypedTransaction::EIP7702(tx) => tx.value,
             TypedTransaction::Deposit(tx) => tx.value,
         }
     }
@@ -666,6 +832,8 @@ impl TypedTransaction {
             TypedTransaction::Legacy(tx) => &tx.input,
             TypedTransaction::EIP2930(tx) => &tx.input,
             TypedTransaction::EIP1559(tx) => &tx.input,
+            TypedTransaction::EIP4844(tx) => &tx.input,
+            TypedTransaction::EIP7702(tx) => &tx.input,
             TypedTransaction::Deposit(tx) => &tx.input,
         }
     }
@@ -676,13 +844,46 @@ impl TypedTransaction {
             TypedTransaction::Legacy(_) => None,
             TypedTransaction::EIP2930(_) => Some(1),
             TypedTransaction::EIP1559(_) => Some(2),
+            TypedTransaction::EIP4844(_) => Some(3),
+            TypedTransaction::EIP7702(_) => Some(4),
             TypedTransaction::Deposit(_) => Some(0x7E),
         }
     }
 
-    /// Max cost of the transaction
+    /// Max cost of the transaction, including the blob gas
     pub fn max_cost(&self) -> U256 {
-        self.gas_limit().saturating_mul(self.gas_price())
+        let blob_cost = U256::from(self.blob_gas())
+            .saturating_mul(self.max_fee_per_blob_gas().unwrap_or_default());
+        self.gas_limit().saturating_mul(self.gas_price()).saturating_add(blob_cost)
+    }
+
+    /// Returns the versioned hashes of the blobs of an EIP4844 transaction
+    pub fn blob_versioned_hashes(&self) -> &[H256] {
+        match self {
+            TypedTransaction::EIP4844(tx) => &tx.blob_versioned_hashes,
+            _ => &[],
+        }
+    }
+
+    /// Returns the max fee per blob gas of an EIP4844 transaction
+    pub fn max_fee_per_blob_gas(&self) -> Option<U256> {
+        match self {
+            TypedTransaction::EIP4844(tx) => Some(tx.max_fee_per_blob_gas),
+            _ => None,
+        }
+    }
+
+    /// Returns the blob gas the transaction consumes, this is zero for non EIP4844 transactions
+    pub fn blob_gas(&self) -> u64 {
+        self.blob_versioned_hashes().len() as u64 * GAS_PER_BLOB
+    }
+
+    /// Returns the authorizations of an EIP7702 transaction
+    pub fn authorization_list(&self) -> &[SignedAuthorization] {
+        match self {
+            TypedTransaction::EIP7702(tx) => &tx.authorization_list,
+            _ => &[],
+        }
     }
 
     /// Returns a helper type that contains commonly used values as fields
@@ -724,6 +925,30 @@ impl TypedTransaction {
                 chain_id: Some(t.chain_id),
                 access_list: t.access_list.clone(),
             },
+            TypedTransaction::EIP4844(t) => TransactionEssentials {
+                kind: t.kind,
+                input: t.input.clone(),
+                nonce: t.nonce,
+                gas_limit: t.gas_limit,
+                gas_price: None,
+                max_fee_per_gas: Some(t.max_fee_per_gas),
+                max_priority_fee_per_gas: Some(t.max_priority_fee_per_gas),
+                value: t.value,
+                chain_id: Some(t.chain_id),
+                access_list: t.access_list.clone(),
+            },
+            TypedTransaction::EIP7702(t) => TransactionEssentials {
+                kind: t.kind,
+                input: t.input.clone(),
+                nonce: t.nonce,
+                gas_limit: t.gas_limit,
+                gas_price: None,
+                max_fee_per_gas: Some(t.max_fee_per_gas),
+                max_priority_fee_per_gas: Some(t.max_priority_fee_per_gas),
+                value: t.value,
+                chain_id: Some(t.chain_id),
+                access_list: t.access_list.clone(),
+            },
             TypedTransaction::Deposit(t) => TransactionEssentials {
                 kind: t.kind,
                 input: t.input.clone(),
@@ -744,6 +969,8 @@ impl TypedTransaction {
             TypedTransaction::Legacy(t) => t.nonce(),
             TypedTransaction::EIP2930(t) => t.nonce(),
             TypedTransaction::EIP1559(t) => t.nonce(),
+            TypedTransaction::EIP4844(t) => t.nonce(),
+            TypedTransaction::EIP7702(t) => t.nonce(),
             TypedTransaction::Deposit(t) => t.nonce(),
         }
     }
@@ -753,6 +980,8 @@ impl TypedTransaction {
             TypedTransaction::Legacy(t) => t.chain_id(),
             TypedTransaction::EIP2930(t) => Some(t.chain_id),
             TypedTransaction::EIP1559(t) => Some(t.chain_id),
+            TypedTransaction::EIP4844(t) => Some(t.chain_id),
+            TypedTransaction::EIP7702(t) => Some(t.chain_id),
             TypedTransaction::Deposit(t) => t.chain_id(),
         }
     }
@@ -783,10 +1012,53 @@ impl TypedTransaction {
             TypedTransaction::Legacy(t) => t.hash(),
             TypedTransaction::EIP2930(t) => t.hash(),
             TypedTransaction::EIP1559(t) => t.hash(),
+            TypedTransaction::EIP4844(t) => t.hash(),
+            TypedTransaction::EIP7702(t) => t.hash(),
             TypedTransaction::Deposit(t) => t.hash(),
         }
     }
 
+    /// Decodes a raw signed transaction, as it's sent via `eth_sendRawTransaction`
+    ///
+    /// This is either the RLP list of a legacy transaction or the `type || payload` envelope of a
+    /// typed transaction, see [EIP-2718](https://eips.ethereum.org/EIPS/eip-2718). Inputs with
+    /// trailing bytes or a non-canonical encoding are rejected, so the [hash](Self::hash) of the
+    /// transaction is the keccak of the input.
+    ///
+    /// The only exception are blob transactions in their network encoding, which also contains the
+    /// blobs: their hash is the keccak of `0x03 || rlp(tx)`, without the blobs.
+    pub fn decode_raw(data: &[u8]) -> Result<Self, DecoderError> {
+        let [first, payload @ ..] = data else { return Err(DecoderError::RlpIsTooShort) };
+        let tx = if *first > 0x7f {
+            let rlp = Rlp::new(data);
+            ensure_consumed(&rlp, data)?;
+            rlp.as_val().map(TypedTransaction::Legacy)?
+        } else {
+            ensure_consumed(&Rlp::new(payload), payload)?;
+            Self::decode_typed(*first, payload)?
+        };
+        ensure_canonical(&tx, data)?;
+        Ok(tx)
+    }
+
+    /// Decodes the payload of a typed transaction of the given type
+    fn decode_typed(ty: u8, payload: &[u8]) -> Result<Self, DecoderError> {
+        match ty {
+            0x01 => rlp::decode(payload).map(TypedTransaction::EIP2930),
+            0x02 => rlp::decode(payload).map(TypedTransaction::EIP1559),
+            0x03 => {
+                // the network encoding wraps the transaction together with its blobs, commitments
+                // and proofs, only the transaction itself is kept
+                let rlp = Rlp::new(payload);
+                let tx = if rlp.at(0)?.is_list() { rlp.val_at(0) } else { rlp.as_val() };
+                tx.map(TypedTransaction::EIP4844)
+            }
+            0x04 => rlp::decode(payload).map(TypedTransaction::EIP7702),
+            0x7E => rlp::decode(payload).map(TypedTransaction::Deposit),
+            _ => Err(DecoderError::Custom("invalid tx type")),
+        }
+    }
+
     /// Returns true if the transaction was impersonated (using the impersonate Signature)
     #[cfg(feature = "impersonated-tx")]
     pub fn is_impersonated(&self) -> bool {
@@ -809,6 +1081,8 @@ impl TypedTransaction {
             TypedTransaction::Legacy(tx) => tx.recover(),
             TypedTransaction::EIP2930(tx) => tx.recover(),
             TypedTransaction::EIP1559(tx) => tx.recover(),
+            TypedTransaction::EIP4844(tx) => tx.recover(),
+            TypedTransaction::EIP7702(tx) => tx.recover(),
             TypedTransaction::Deposit(tx) => tx.recover(),
         }
     }
@@ -819,6 +1093,8 @@ impl TypedTransaction {
             TypedTransaction::Legacy(tx) => &tx.kind,
             TypedTransaction::EIP2930(tx) => &tx.kind,
             TypedTransaction::EIP1559(tx) => &tx.kind,
+            TypedTransaction::EIP4844(tx) => &tx.kind,
+            TypedTransaction::EIP7702(tx) => &tx.kind,
             TypedTransaction::Deposit(tx) => &tx.kind,
         }
     }
@@ -844,6 +1120,18 @@ impl TypedTransaction {
                 let s = U256::from_big_endian(&tx.s[..]);
                 Signature { r, s, v: v.into() }
             }
+            TypedTransaction::EIP4844(tx) => {
+                let v = tx.odd_y_parity as u8;
+                let r = U256::from_big_endian(&tx.r[..]);
+                let s = U256::from_big_endian(&tx.s[..]);
+                Signature { r, s, v: v.into() }
+            }
+            TypedTransaction::EIP7702(tx) => {
+                let v = tx.odd_y_parity as u8;
+                let r = U256::from_big_endian(&tx.r[..]);
+                let s = U256::from_big_endian(&tx.s[..]);
+                Signature { r, s, v: v.into() }
+            }
             TypedTransaction::Deposit(_) => Signature { r: U256::zero(), s: U256::zero(), v: 0 },
         }
     }
@@ -855,6 +1143,8 @@ impl Encodable for TypedTransaction {
             TypedTransaction::Legacy(tx) => tx.rlp_append(s),
             TypedTransaction::EIP2930(tx) => enveloped(1, tx, s),
             TypedTransaction::EIP1559(tx) => enveloped(2, tx, s),
+            TypedTransaction::EIP4844(tx) => enveloped(3, tx, s),
+            TypedTransaction::EIP7702(tx) => enveloped(4, tx, s),
             TypedTransaction::Deposit(tx) => enveloped(0x7E, tx, s),
         }
     }
@@ -871,13 +1161,33 @@ impl Decodable for TypedTransaction {
         // "advance" the header, see comments in fastrlp impl below
         let s = if s.is_empty() { &rlp.as_raw()[1..] } else { s };
 
-        match *first {
-            0x01 => rlp::decode(s).map(TypedTransaction::EIP2930),
-            0x02 => rlp::decode(s).map(TypedTransaction::EIP1559),
-            0x7E => rlp::decode(s).map(TypedTransaction::Deposit),
-            _ => Err(DecoderError::Custom("invalid tx type")),
+        Self::decode_typed(*first, s)
+    }
+}
+
+/// Ensures that the RLP item spans the entire input
+fn ensure_consumed(rlp: &Rlp, data: &[u8]) -> Result<(), DecoderError> {
+    if rlp.payload_info()?.total() != data.len() {
+        return Err(DecoderError::RlpInconsistentLengthAndData)
+    }
+    Ok(())
+}
+
+/// Ensures that the transaction encodes to the raw input it was decoded from
+fn ensure_canonical(tx: &TypedTransaction, data: &[u8]) -> Result<(), DecoderError> {
+    let encoded = trie_value(tx);
+    let canonical = match tx {
+        // the network encoding wraps the transaction, see `decode_typed`
+        TypedTransaction::EIP4844(_) if encoded.len() != data.len() => {
+            let item = Rlp::new(&data[1..]).at(0)?;
+            item.is_list() && item.as_raw() == &encoded[1..]
         }
+        _ => encoded == data,
+    };
+    if !canonical {
+        return Err(DecoderError::Custom("non-canonical transaction encoding"))
     }
+    Ok(())
 }
 
 #[cfg(feature = "fastrlp")]
@@ -889,6 +1199,8 @@ impl open_fastrlp::Encodable for TypedTransaction {
                 let payload_len = match tx {
                     TypedTransaction::EIP2930(tx) => tx.length() + 1,
                     TypedTransaction::EIP1559(tx) => tx.length() + 1,
+                    TypedTransaction::EIP4844(tx) => tx.length() + 1,
+                    TypedTransaction::EIP7702(tx) => tx.length() + 1,
                     TypedTransaction::Deposit(tx) => tx.length() + 1,
                     _ => unreachable!("legacy tx length already matched"),
                 };
@@ -910,6 +1222,22 @@ impl open_fastrlp::Encodable for TypedTransaction {
                         out.put_u8(0x02);
                         tx.encode(out);
                     }
+                    TypedTransaction::EIP4844(tx) => {
+                        let tx_string_header =
+                            open_fastrlp::Header { list: false, payload_length: payload_len };
+
+                        tx_string_header.encode(out);
+                        out.put_u8(0x03);
+                        tx.encode(out);
+                    }
+                    TypedTransaction::EIP7702(tx) => {
+                        let tx_string_header =
+                            open_fastrlp::Header { list: false, payload_length: payload_len };
+
+                        tx_string_header.encode(out);
+                        out.put_u8(0x04);
+                        tx.encode(out);
+                    }
                     TypedTransaction::Deposit(tx) => {
                         let tx_string_header =
                             open_fastrlp::Header { list: false, payload_length: payload_len };
@@ -930,6 +1258,8 @@ impl open_fastrlp::Encodable for TypedTransaction {
                 let payload_len = match tx {
                     TypedTransaction::EIP2930(tx) => tx.length() + 1,
                     TypedTransaction::EIP1559(tx) => tx.length() + 1,
+                    TypedTransaction::EIP4844(tx) => tx.length() + 1,
+                    TypedTransaction::EIP7702(tx) => tx.length() + 1,
                     TypedTransaction::Deposit(tx) => tx.length() + 1,
                     _ => unreachable!("legacy tx length already matched"),
                 };
@@ -976,6 +1306,14 @@ impl open_fastrlp::Decodable for TypedTransaction {
                     buf.advance(1);
                     <EIP1559Transaction as open_fastrlp::Decodable>::decode(buf)
                         .map(TypedTransaction::EIP1559)
+                } else if tx_type == 0x03 {
+                    buf.advance(1);
+                    <EIP4844Transaction as open_fastrlp::Decodable>::decode(buf)
+                        .map(TypedTransaction::EIP4844)
+                } else if tx_type == 0x04 {
+                    buf.advance(1);
+                    <EIP7702Transaction as open_fastrlp::Decodable>::decode(buf)
+                        .map(TypedTransaction::EIP7702)
                 } else if tx_type == 0x7E {
                     buf.advance(1);
                     <DepositTransaction as open_fastrlp::Decodable>::decode(buf)
@@ -1255,6 +1593,278 @@ impl Decodable for EIP1559Transaction {
     }
 }
 
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "fastrlp", derive(open_fastrlp::RlpEncodable, open_fastrlp::RlpDecodable))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct EIP4844Transaction {
+    pub chain_id: u64,
+    pub nonce: U256,
+    pub max_priority_fee_per_gas: U256,
+    pub max_fee_per_gas: U256,
+    pub gas_limit: U256,
+    pub kind: TransactionKind,
+    pub value: U256,
+    pub input: Bytes,
+    pub access_list: AccessList,
+    pub max_fee_per_blob_gas: U256,
+    pub blob_versioned_hashes: Vec<H256>,
+    pub odd_y_parity: bool,
+    pub r: H256,
+    pub s: H256,
+}
+
+impl EIP4844Transaction {
+    pub fn nonce(&self) -> &U256 {
+        &self.nonce
+    }
+
+    pub fn hash(&self) -> H256 {
+        let encoded = rlp::encode(self);
+        let mut out = vec![0; 1 + encoded.len()];
+        out[0] = 3;
+        out[1..].copy_from_slice(&encoded);
+        H256::from_slice(keccak256(&out).as_slice())
+    }
+
+    /// Recovers the Ethereum address which was used to sign the transaction.
+    pub fn recover(&self) -> Result<Address, SignatureError> {
+        let mut sig = [0u8; 65];
+        sig[0..32].copy_from_slice(&self.r[..]);
+        sig[32..64].copy_from_slice(&self.s[..]);
+        sig[64] = self.odd_y_parity as u8;
+        let signature = Signature::try_from(&sig[..])?;
+        signature.recover(EIP4844TransactionRequest::from(self.clone()).hash())
+    }
+}
+
+impl Encodable for EIP4844Transaction {
+    fn rlp_append(&self, s: &mut RlpStream) {
+        s.begin_list(14);
+        s.append(&self.chain_id);
+        s.append(&self.nonce);
+        s.append(&self.max_priority_fee_per_gas);
+        s.append(&self.max_fee_per_gas);
+        s.append(&self.gas_limit);
+        s.append(&self.kind);
+        s.append(&self.value);
+        s.append(&self.input.as_ref());
+        s.append(&self.access_list);
+        s.append(&self.max_fee_per_blob_gas);
+        s.append_list(&self.blob_versioned_hashes);
+        s.append(&self.odd_y_parity);
+        s.append(&U256::from_big_endian(&self.r[..]));
+        s.append(&U256::from_big_endian(&self.s[..]));
+    }
+}
+
+impl Decodable for EIP4844Transaction {
+    fn decode(rlp: &Rlp) -> Result<Self, DecoderError> {
+        if rlp.item_count()? != 14 {
+            return Err(DecoderError::RlpIncorrectListLen)
+        }
+
+        Ok(Self {
+            chain_id: rlp.val_at(0)?,
+            nonce: rlp.val_at(1)?,
+            max_priority_fee_per_gas: rlp.val_at(2)?,
+            max_fee_per_gas: rlp.val_at(3)?,
+            gas_limit: rlp.val_at(4)?,
+            kind: rlp.val_at(5)?,
+            value: rlp.val_at(6)?,
+            input: rlp.val_at::<Vec<u8>>(7)?.into(),
+            access_list: rlp.val_at(8)?,
+            max_fee_per_blob_gas: rlp.val_at(9)?,
+            blob_versioned_hashes: rlp.list_at(10)?,
+            odd_y_parity: rlp.val_at(11)?,
+            r: {
+                let mut rarr = [0u8; 32];
+                rlp.val_at::<U256>(12)?.to_big_endian(&mut rarr);
+                H256::from(rarr)
+            },
+            s: {
+                let mut sarr = [0u8; 32];
+                rlp.val_at::<U256>(13)?.to_big_endian(&mut sarr);
+                H256::from(sarr)
+            },
+        })
+    }
+}
+
+/// The magic byte that prefixes the signing payload of an authorization
+pub const AUTHORIZATION_MAGIC: u8 = 0x05;
+
+/// An authorization of an EIP-7702 transaction, signed by the authority whose account delegates
+/// to the code of `address`
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "fastrlp", derive(open_fastrlp::RlpEncodable, open_fastrlp::RlpDecodable))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct SignedAuthorization {
+    /// The chain the authorization is valid on, `0` for all chains
+    pub chain_id: u64,
+    /// The address whose code the authority delegates to
+    pub address: Address,
+    /// The nonce of the authority
+    pub nonce: u64,
+    pub y_parity: bool,
+    pub r: H256,
+    pub s: H256,
+}
+
+impl SignedAuthorization {
+    /// Returns the hash the authority signs: `keccak256(0x05 || rlp([chain_id, address, nonce]))`
+    pub fn signature_hash(&self) -> H256 {
+        let mut s = RlpStream::new_list(3);
+        s.append(&self.chain_id);
+        s.append(&self.address);
+        s.append(&self.nonce);
+        let mut out = vec![AUTHORIZATION_MAGIC];
+        out.extend_from_slice(&s.out());
+        H256::from_slice(keccak256(&out).as_slice())
+    }
+
+    /// Recovers the address of the authority which signed the authorization.
+    pub fn recover_authority(&self) -> Result<Address, SignatureError> {
+        let mut sig = [0u8; 65];
+        sig[0..32].copy_from_slice(&self.r[..]);
+        sig[32..64].copy_from_slice(&self.s[..]);
+        sig[64] = self.y_parity as u8;
+        let signature = Signature::try_from(&sig[..])?;
+        signature.recover(self.signature_hash())
+    }
+}
+
+impl Encodable for SignedAuthorization {
+    fn rlp_append(&self, s: &mut RlpStream) {
+        s.begin_list(6);
+        s.append(&self.chain_id);
+        s.append(&self.address);
+        s.append(&self.nonce);
+        s.append(&self.y_parity);
+        s.append(&U256::from_big_endian(&self.r[..]));
+        s.append(&U256::from_big_endian(&self.s[..]));
+    }
+}
+
+impl Decodable for SignedAuthorization {
+    fn decode(rlp: &Rlp) -> Result<Self, DecoderError> {
+        if rlp.item_count()? != 6 {
+            return Err(DecoderError::RlpIncorrectListLen)
+        }
+
+        Ok(Self {
+            chain_id: rlp.val_at(0)?,
+            address: rlp.val_at(1)?,
+            nonce: rlp.val_at(2)?,
+            y_parity: rlp.val_at(3)?,
+            r: {
+                let mut rarr = [0u8; 32];
+                rlp.val_at::<U256>(4)?.to_big_endian(&mut rarr);
+                H256::from(rarr)
+            },
+            s: {
+                let mut sarr = [0u8; 32];
+                rlp.val_at::<U256>(5)?.to_big_endian(&mut sarr);
+                H256::from(sarr)
+            },
+        })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "fastrlp", derive(open_fastrlp::RlpEncodable, open_fastrlp::RlpDecodable))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct EIP7702Transaction {
+    pub chain_id: u64,
+    pub nonce: U256,
+    pub max_priority_fee_per_gas: U256,
+    pub max_fee_per_gas: U256,
+    pub gas_limit: U256,
+    pub kind: TransactionKind,
+    pub value: U256,
+    pub input: Bytes,
+    pub access_list: AccessList,
+    pub authorization_list: Vec<SignedAuthorization>,
+    pub odd_y_parity: bool,
+    pub r: H256,
+    pub s: H256,
+}
+
+impl EIP7702Transaction {
+    pub fn nonce(&self) -> &U256 {
+        &self.nonce
+    }
+
+    pub fn hash(&self) -> H256 {
+        let encoded = rlp::encode(self);
+        let mut out = vec![0; 1 + encoded.len()];
+        out[0] = 4;
+        out[1..].copy_from_slice(&encoded);
+        H256::from_slice(keccak256(&out).as_slice())
+    }
+
+    /// Recovers the Ethereum address which was used to sign the transaction.
+    pub fn recover(&self) -> Result<Address, SignatureError> {
+        let mut sig = [0u8; 65];
+        sig[0..32].copy_from_slice(&self.r[..]);
+        sig[32..64].copy_from_slice(&self.s[..]);
+        sig[64] = self.odd_y_parity as u8;
+        let signature = Signature::try_from(&sig[..])?;
+        signature.recover(EIP7702TransactionRequest::from(self.clone()).hash())
+    }
+}
+
+impl Encodable for EIP7702Transaction {
+    fn rlp_append(&self, s: &mut RlpStream) {
+        s.begin_list(13);
+        s.append(&self.chain_id);
+        s.append(&self.nonce);
+        s.append(&self.max_priority_fee_per_gas);
+        s.append(&self.max_fee_per_gas);
+        s.append(&self.gas_limit);
+        s.append(&self.kind);
+        s.append(&self.value);
+        s.append(&self.input.as_ref());
+        s.append(&self.access_list);
+        s.append_list(&self.authorization_list);
+        s.append(&self.odd_y_parity);
+        s.append(&U256::from_big_endian(&self.r[..]));
+        s.append(&U256::from_big_endian(&self.s[..]));
+    }
+}
+
+impl Decodable for EIP7702Transaction {
+    fn decode(rlp: &Rlp) -> Result<Self, DecoderError> {
+        if rlp.item_count()? != 13 {
+            return Err(DecoderError::RlpIncorrectListLen)
+        }
+
+        Ok(Self {
+            chain_id: rlp.val_at(0)?,
+            nonce: rlp.val_at(1)?,
+            max_priority_fee_per_gas: rlp.val_at(2)?,
+            max_fee_per_gas: rlp.val_at(3)?,
+            gas_limit: rlp.val_at(4)?,
+            kind: rlp.val_at(5)?,
+            value: rlp.val_at(6)?,
+            input: rlp.val_at::<Vec<u8>>(7)?.into(),
+            access_list: rlp.val_at(8)?,
+            authorization_list: rlp.list_at(9)?,
+            odd_y_parity: rlp.val_at(10)?,
+            r: {
+                let mut rarr = [0u8; 32];
+                rlp.val_at::<U256>(11)?.to_big_endian(&mut rarr);
+                H256::from(rarr)
+            },
+            s: {
+                let mut sarr = [0u8; 32];
+                rlp.val_at::<U256>(12)?.to_big_endian(&mut sarr);
+                H256::from(sarr)
+            },
+        })
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "fastrlp", derive(open_fastrlp::RlpEncodable, open_fastrlp::RlpDecodable))]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -1276,8 +1886,16 @@ impl DepositTransaction {
         &self.nonce
     }
 
+    /// Returns the hash of the transaction: `keccak256(0x7E || rlp(tx))`
+    ///
+    /// Like the hash of other typed transactions, this is the keccak of the EIP-2718 envelope, see
+    /// <https://specs.optimism.io/protocol/deposits.html#the-deposited-transaction-type>
     pub fn hash(&self) -> H256 {
-        H256::from_slice(keccak256(&rlp::encode(self)).as_slice())
+        let encoded = rlp::encode(self);
+        let mut out = vec![0; 1 + encoded.len()];
+        out[0] = 0x7E;
+        out[1..].copy_from_slice(&encoded);
+        H256::from_slice(keccak256(&out).as_slice())
     }
 
     /// Recovers the Ethereum address which was used to sign the transaction.
@@ -1466,6 +2084,64 @@ impl PendingTransaction {
                     ..Default::default()
                 }
             }
+            TypedTransaction::EIP4844(tx) => {
+                let EIP4844Transaction {
+                    chain_id,
+                    nonce,
+                    max_priority_fee_per_gas,
+                    max_fee_per_gas,
+                    gas_limit,
+                    kind,
+                    value,
+                    input,
+                    access_list,
+                    max_fee_per_blob_gas,
+                    blob_versioned_hashes,
+                    ..
+                } = tx;
+                TxEnv {
+                    caller: (caller).to_alloy(),
+                    transact_to: transact_to(kind),
+                    data: alloy_primitives::Bytes(input.0.clone()),
+                    chain_id: Some(*chain_id),
+                    nonce: Some(nonce.as_u64()),
+                    value: (*value).to_alloy(),
+                    gas_price: (*max_fee_per_gas).to_alloy(),
+                    gas_priority_fee: Some((*max_priority_fee_per_gas).to_alloy()),
+                    gas_limit: gas_limit.as_u64(),
+                    access_list: to_revm_access_list(access_list.0.clone()),
+                    blob_hashes: blob_versioned_hashes.iter().map(|hash| hash.to_alloy()).collect(),
+                    max_fee_per_blob_gas: Some((*max_fee_per_blob_gas).to_alloy()),
+                    ..Default::default()
+                }
+            }
+            TypedTransaction::EIP7702(tx) => {
+                let EIP7702Transaction {
+                    chain_id,
+                    nonce,
+                    max_priority_fee_per_gas,
+                    max_fee_per_gas,
+                    gas_limit,
+                    kind,
+                    value,
+                    input,
+                    access_list,
+                    ..
+                } = tx;
+                TxEnv {
+                    caller: (caller).to_alloy(),
+                    transact_to: transact_to(kind),
+                    data: alloy_primitives::Bytes(input.0.clone()),
+                    chain_id: Some(*chain_id),
+                    nonce: Some(nonce.as_u64()),
+                    value: (*value).to_alloy(),
+                    gas_price: (*max_fee_per_gas).to_alloy(),
+                    gas_priority_fee: Some((*max_priority_fee_per_gas).to_alloy()),
+                    gas_limit: gas_limit.as_u64(),
+                    access_list: to_revm_access_list(access_list.0.clone()),
+                    ..Default::default()
+                }
+            }
             TypedTransaction::Deposit(tx) => {
                 let chain_id = tx.chain_id();
                 let DepositTransaction {
@@ -1517,6 +2193,12 @@ pub struct TransactionInfo {
     pub exit: InstructionResult,
     pub out: Option<Bytes>,
     pub nonce: u64,
+    /// The storage slots the transaction accessed
+    pub storage_diff: StorageDiff,
+    /// The state of the accounts the transaction accessed, before the transaction
+    pub pre_state: PreState,
+    /// The decoded revert reason, if the transaction failed
+    pub revert_reason: Option<String>,
 }
 
 #[cfg(test)]
@@ -1567,6 +2249,85 @@ mod tests {
         );
     }
 
+    #[test]
+    fn decodes_raw_transactions() {
+        let legacy = hex::decode("f85f800182520894095e7baea6a6c7c4c2dfeb977efac326af552d870a801ba048b55bfa915ac795c431978d8a6a992b628d557da5ff759b307d495a36649353a0efffd310ac743f371de3b9f7f9cb56c0b28ad43601b4ab949f53faa07bd2c804").unwrap();
+        let tx = TypedTransaction::decode_raw(&legacy).unwrap();
+        assert!(matches!(tx, TypedTransaction::Legacy(_)));
+        assert_eq!(
+            tx.recover().unwrap(),
+            "0f65fe9276bc9a24ae7083ae28e2660ef72df99e".parse().unwrap()
+        );
+
+        let eip1559 = hex::decode("02f872018307910d808507204d2cb1827d0094388c818ca8b9251b393131c08a736a67ccb19297880320d04823e2701c80c001a0cf024f4815304df2867a1a74e9d2707b6abda0337d2d54a4438d453f4160f190a07ac0e6b3bc9395b5b9c8b9e6d77204a236577a5b18467b9175c01de4faa208d9").unwrap();
+        let tx = TypedTransaction::decode_raw(&eip1559).unwrap();
+        assert!(tx.is_eip1559());
+        assert_eq!(
+            tx.hash(),
+            "0x86718885c4b4218c6af87d3d0b0d83e3cc465df2a05c048aa4db9f1a6f9de91f".parse().unwrap()
+        );
+
+        let eip2930 = TypedTransaction::EIP2930(EIP2930Transaction {
+            chain_id: 1,
+            nonce: 1.into(),
+            gas_price: 1.into(),
+            gas_limit: 30_000.into(),
+            kind: TransactionKind::Call(Address::repeat_byte(0x11)),
+            value: 0.into(),
+            input: Bytes::default(),
+            access_list: AccessList(vec![AccessListItem {
+                address: Address::repeat_byte(0x22),
+                storage_keys: vec![H256::repeat_byte(0x33)],
+            }]),
+            odd_y_parity: true,
+            r: H256::repeat_byte(0x44),
+            s: H256::repeat_byte(0x55),
+        });
+        let envelope = trie_value(&eip2930);
+        assert_eq!(TypedTransaction::decode_raw(&envelope).unwrap(), eip2930);
+        // the rlp encoding wraps the envelope in an rlp string
+        let raw = rlp::encode(&eip2930);
+
+        // trailing bytes, the wrapped envelope and empty inputs are rejected
+        assert!(TypedTransaction::decode_raw(&[&legacy[..], &[0x00]].concat()).is_err());
+        assert!(TypedTransaction::decode_raw(&[&eip1559[..], &[0x00]].concat()).is_err());
+        assert!(TypedTransaction::decode_raw(&raw).is_err());
+        assert!(TypedTransaction::decode_raw(&[]).is_err());
+    }
+
+    #[test]
+    fn raw_transaction_hash_is_keccak_of_input() {
+        let legacy = hex::decode("f86b02843b9aca00830186a094d3e8763675e4c425df46cc3b5c0f6cbdac39604687038d7ea4c68000802ba00eb96ca19e8a77102767a41fc85a36afd5c61ccb09911cec5d3e86e193d9c5aea03a456401896b1b6055311536bf00a718568c744d8c1f9df59879e8350220ca18").unwrap();
+        // random mainnet tx: https://etherscan.io/tx/0x86718885c4b4218c6af87d3d0b0d83e3cc465df2a05c048aa4db9f1a6f9de91f
+        let eip1559 = hex::decode("02f872018307910d808507204d2cb1827d0094388c818ca8b9251b393131c08a736a67ccb19297880320d04823e2701c80c001a0cf024f4815304df2867a1a74e9d2707b6abda0337d2d54a4438d453f4160f190a07ac0e6b3bc9395b5b9c8b9e6d77204a236577a5b18467b9175c01de4faa208d9").unwrap();
+        let deposit = trie_value(&TypedTransaction::Deposit(DepositTransaction {
+            nonce: 0.into(),
+            source_hash: H256::repeat_byte(0x11),
+            from: Address::repeat_byte(0x22),
+            kind: TransactionKind::Call(Address::repeat_byte(0x33)),
+            mint: 1.into(),
+            value: 1.into(),
+            gas_limit: 21_000.into(),
+            is_system_tx: false,
+            input: Bytes::default(),
+        }));
+        assert_eq!(deposit[0], 0x7E);
+
+        for raw in [legacy, eip1559.clone(), deposit] {
+            let tx = TypedTransaction::decode_raw(&raw).unwrap();
+            assert_eq!(tx.hash(), H256::from(keccak256(&raw)));
+        }
+        assert_eq!(
+            TypedTransaction::decode_raw(&eip1559).unwrap().hash(),
+            "0x86718885c4b4218c6af87d3d0b0d83e3cc465df2a05c048aa4db9f1a6f9de91f".parse().unwrap()
+        );
+
+        // the chain id as a single byte string instead of a single byte
+        let mut non_canonical = hex::decode("02f8738101").unwrap();
+        non_canonical.extend_from_slice(&eip1559[4..]);
+        assert!(TypedTransaction::decode_raw(&non_canonical).is_err());
+    }
+
     #[test]
     #[cfg(feature = "fastrlp")]
     fn test_decode_fastrlp_create() {
@@ -1820,4 +2581,44 @@ mod tests {
         let expected: Address = "0xa12e1462d0ced572f396f58b6e2d03894cd7c8a4".parse().unwrap();
         assert_eq!(expected, recovered);
     }
+
+    #[test]
+    fn decode_blob_transaction_network_encoding() {
+        let tx = EIP4844Transaction {
+            chain_id: 1,
+            nonce: 2.into(),
+            max_priority_fee_per_gas: 3.into(),
+            max_fee_per_gas: 4.into(),
+            gas_limit: 21000.into(),
+            kind: TransactionKind::Call(Address::random()),
+            value: 5.into(),
+            input: Bytes::from(vec![1, 2, 3]),
+            access_list: Default::default(),
+            max_fee_per_blob_gas: 6.into(),
+            blob_versioned_hashes: vec![H256::random(), H256::random()],
+            odd_y_parity: true,
+            r: H256::random(),
+            s: H256::random(),
+        };
+        let typed = TypedTransaction::EIP4844(tx.clone());
+        assert_eq!(typed.blob_gas(), 2 * GAS_PER_BLOB);
+
+        let encoded = rlp::encode(&typed);
+        let decoded: TypedTransaction = rlp::decode(&encoded).unwrap();
+        assert_eq!(decoded, typed);
+
+        // the network encoding appends the blobs, commitments and proofs
+        let mut stream = RlpStream::new_list(4);
+        stream.append(&tx);
+        for item in [vec![0u8; 32], vec![1u8; 48], vec![2u8; 48]] {
+            stream.begin_list(2);
+            stream.append(&item);
+            stream.append(&item);
+        }
+        let mut network = vec![0x03];
+        network.extend_from_slice(&stream.out());
+        let decoded: TypedTransaction = rlp::decode(&rlp::encode(&network)).unwrap();
+        assert_eq!(decoded, typed);
+        assert_eq!(decoded.hash(), typed.hash());
+    }
 }
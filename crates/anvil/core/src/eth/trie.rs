@@ -1,5 +1,9 @@
 //! Utility functions for Ethereum adapted from https://github.dev/rust-blockchain/ethereum/blob/755dffaa4903fbec1269f50cde9863cf86269a14/src/util.rs
-use ethers_core::types::H256;
+use ethers_core::{
+    types::H256,
+    utils::{keccak256, rlp::RlpStream},
+};
+use std::collections::BTreeMap;
 
 pub use keccak_hasher::KeccakHasher;
 
@@ -40,3 +44,163 @@ where
 {
     H256::from(triehash::ordered_trie_root::<KeccakHasher, I>(input))
 }
+
+/// Generates a key-hashed (secure) trie root hash for a vector of key-value tuples and the proof
+/// of the given `key`, see [trie_root_with_proof]
+pub fn sec_trie_root_with_proof<I, K, V>(input: I, key: &[u8]) -> (H256, Vec<Vec<u8>>)
+where
+    I: IntoIterator<Item = (K, V)>,
+    K: AsRef<[u8]>,
+    V: AsRef<[u8]>,
+{
+    trie_root_with_proof(input.into_iter().map(|(k, v)| (keccak256(k), v)), &keccak256(key))
+}
+
+/// Generates a trie root hash for a vector of key-value tuples and the merkle proof of the given
+/// `key`, as returned by `eth_getProof`
+///
+/// The proof contains the RLP encoded nodes on the path to the key, starting with the root node.
+/// Nodes that are embedded in their parent are not included. If the trie doesn't contain the key,
+/// the proof proves its absence.
+pub fn trie_root_with_proof<I, K, V>(input: I, key: &[u8]) -> (H256, Vec<Vec<u8>>)
+where
+    I: IntoIterator<Item = (K, V)>,
+    K: AsRef<[u8]>,
+    V: AsRef<[u8]>,
+{
+    // sorted and deduplicated, the last value of a key wins
+    let entries = input
+        .into_iter()
+        .map(|(k, v)| (to_nibbles(k.as_ref()), v))
+        .collect::<BTreeMap<_, _>>()
+        .into_iter()
+        .collect::<Vec<_>>();
+    if entries.is_empty() {
+        return (KECCAK_NULL_RLP, Vec::new())
+    }
+
+    let mut proof = Vec::new();
+    let root = encode_node(&entries, 0, Some(to_nibbles(key).as_slice()), &mut proof);
+    // the nodes are added bottom up
+    proof.reverse();
+    (H256(keccak256(root)), proof)
+}
+
+fn to_nibbles(key: &[u8]) -> Vec<u8> {
+    key.iter().flat_map(|b| [b >> 4, b & 0x0f]).collect()
+}
+
+/// Returns the hex prefix encoding of the nibbles of a leaf or extension node
+fn hex_prefix(nibbles: &[u8], leaf: bool) -> Vec<u8> {
+    let flag = if leaf { 2 } else { 0 };
+    let mut out = Vec::with_capacity(nibbles.len() / 2 + 1);
+    let rest = if nibbles.len() % 2 == 1 {
+        out.push(((flag + 1) << 4) | nibbles[0]);
+        &nibbles[1..]
+    } else {
+        out.push(flag << 4);
+        nibbles
+    };
+    out.extend(rest.chunks(2).map(|pair| (pair[0] << 4) | pair[1]));
+    out
+}
+
+/// Appends the reference to a child node, nodes shorter than 32 bytes are embedded
+fn append_child(stream: &mut RlpStream, node: &[u8]) {
+    if node.len() < 32 {
+        stream.append_raw(node, 1);
+    } else {
+        stream.append(&keccak256(node).as_slice());
+    }
+}
+
+/// Returns the RLP encoding of the node of the sorted `entries` that share their first `depth`
+/// nibbles
+///
+/// `path` is the key to prove, if the node is on its path, in which case the node is added to the
+/// `proof`.
+fn encode_node<V: AsRef<[u8]>>(
+    entries: &[(Vec<u8>, V)],
+    depth: usize,
+    path: Option<&[u8]>,
+    proof: &mut Vec<Vec<u8>>,
+) -> Vec<u8> {
+    let first = &entries[0].0;
+    let last = &entries[entries.len() - 1].0;
+
+    let node = if entries.len() == 1 {
+        let mut stream = RlpStream::new_list(2);
+        stream.append(&hex_prefix(&first[depth..], true));
+        stream.append(&entries[0].1.as_ref());
+        stream.out().to_vec()
+    } else {
+        let shared = first[depth..].iter().zip(&last[depth..]).take_while(|(a, b)| a == b).count();
+        if shared > 0 {
+            let prefix = &first[depth..depth + shared];
+            let child_path = path.filter(|path| path.get(depth..depth + shared) == Some(prefix));
+            let child = encode_node(entries, depth + shared, child_path, proof);
+            let mut stream = RlpStream::new_list(2);
+            stream.append(&hex_prefix(prefix, false));
+            append_child(&mut stream, &child);
+            stream.out().to_vec()
+        } else {
+            let mut stream = RlpStream::new_list(17);
+            // a key that ends at this node is sorted first
+            let (value, mut rest) = if first.len() == depth {
+                (Some(entries[0].1.as_ref()), &entries[1..])
+            } else {
+                (None, entries)
+            };
+            for nibble in 0..16u8 {
+                let end = rest.iter().position(|(k, _)| k[depth] != nibble).unwrap_or(rest.len());
+                let (children, others) = rest.split_at(end);
+                rest = others;
+                if children.is_empty() {
+                    stream.append_empty_data();
+                    continue
+                }
+                let child_path = path.filter(|path| path.get(depth) == Some(&nibble));
+                let child = encode_node(children, depth + 1, child_path, proof);
+                append_child(&mut stream, &child);
+            }
+            match value {
+                Some(value) => stream.append(&value),
+                None => stream.append_empty_data(),
+            };
+            stream.out().to_vec()
+        }
+    };
+
+    if path.is_some() && (depth == 0 || node.len() >= 32) {
+        proof.push(node.clone());
+    }
+    node
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers_core::utils::rlp;
+
+    #[test]
+    fn proof_root_matches_trie_root() {
+        for n in [1u64, 2, 3, 17, 100, 500] {
+            let entries = (0..n)
+                .map(|i| (i.to_be_bytes().to_vec(), rlp::encode(&(i + 1)).to_vec()))
+                .collect::<Vec<_>>();
+            let (root, proof) = sec_trie_root_with_proof(entries.clone(), &1u64.to_be_bytes());
+            assert_eq!(root, sec_trie_root(entries.clone()));
+            assert_eq!(H256(keccak256(&proof[0])), root);
+
+            // absent keys are proven as well
+            let (root, proof) = sec_trie_root_with_proof(entries.clone(), b"missing");
+            assert_eq!(root, sec_trie_root(entries));
+            assert!(!proof.is_empty());
+        }
+
+        assert_eq!(
+            sec_trie_root_with_proof(Vec::<(Vec<u8>, Vec<u8>)>::new(), b"key").0,
+            KECCAK_NULL_RLP
+        );
+    }
+}
@@ -3,7 +3,7 @@ use ethers_core::{
     types::transaction::eip2930::AccessListItem,
     utils::{
         rlp,
-        rlp::{Encodable, RlpStream},
+        rlp::{Encodable, Rlp, RlpStream},
     },
 };
 use foundry_common::types::ToAlloy;
@@ -16,6 +16,21 @@ pub fn enveloped<T: Encodable>(id: u8, v: &T, s: &mut RlpStream) {
     out.rlp_append(s)
 }
 
+/// Returns the encoding of a transaction or receipt as it is stored in the transactions or
+/// receipts trie of a block
+///
+/// Typed transactions and receipts are stored as `type || rlp(payload)`, without the RLP string
+/// header of their [enveloped] encoding.
+pub fn trie_value<T: Encodable>(v: &T) -> Vec<u8> {
+    let encoded = rlp::encode(v);
+    let item = Rlp::new(&encoded);
+    if item.is_list() {
+        encoded.to_vec()
+    } else {
+        item.data().map(<[u8]>::to_vec).unwrap_or_default()
+    }
+}
+
 pub fn to_revm_access_list(list: Vec<AccessListItem>) -> Vec<(Address, Vec<U256>)> {
     list.into_iter()
         .map(|item| {
@@ -1,4 +1,13 @@
-use alloy_primitives::{TxHash, B256, U256, U64};
+use alloy_primitives::{Address, Bytes, Selector, TxHash, B256, U256, U64};
+use alloy_rpc_types::{
+    state::StateOverride,
+    trace::{
+        geth::CallFrame,
+        parity::{Action, TraceOutput},
+        GethDebugTracingOptions, GethDefaultTracingOptions, GethTrace, LocalizedTransactionTrace,
+    },
+    BlockNumberOrTag,
+};
 use revm::primitives::SpecId;
 use std::collections::BTreeMap;
 
@@ -12,6 +21,8 @@ use serde::{de::Error, Deserializer, Serializer};
 pub struct Forking {
     pub json_rpc_url: Option<String>,
     pub block_number: Option<u64>,
+    /// Whether accounts impersonated via `anvil_impersonateAccount` remain impersonated
+    pub keep_impersonated_accounts: bool,
 }
 
 #[cfg(feature = "serde")]
@@ -29,6 +40,8 @@ impl<'de> serde::Deserialize<'de> for Forking {
                 deserialize_with = "ethers_core::types::serde_helpers::deserialize_stringified_u64_opt"
             )]
             pub block_number: Option<u64>,
+            #[serde(default)]
+            pub keep_impersonated_accounts: bool,
         }
 
         #[derive(serde::Deserialize)]
@@ -42,13 +55,13 @@ impl<'de> serde::Deserialize<'de> for Forking {
             Fork(ForkOpts),
         }
         let f = match ForkingVariants::deserialize(deserializer)? {
-            ForkingVariants::Fork(ForkOpts { json_rpc_url, block_number }) => {
-                Forking { json_rpc_url, block_number }
+            ForkingVariants::Fork(opts) | ForkingVariants::Tagged(Tagged { forking: opts }) => {
+                Forking {
+                    json_rpc_url: opts.json_rpc_url,
+                    block_number: opts.block_number,
+                    keep_impersonated_accounts: opts.keep_impersonated_accounts,
+                }
             }
-            ForkingVariants::Tagged(f) => Forking {
-                json_rpc_url: f.forking.json_rpc_url,
-                block_number: f.forking.block_number,
-            },
         };
         Ok(f)
     }
@@ -87,6 +100,135 @@ impl Default for EvmMineOptions {
     }
 }
 
+/// Additional `anvil_dumpState` options
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize))]
+pub struct DumpStateOptions {
+    /// Only dump the accounts and storage slots that were modified since forking
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub diff: bool,
+}
+
+/// The `debug_traceCall` options, geth's tracing options and the state overrides of the call
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct TraceCallOptions {
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub tracing_options: GethDefaultTracingOptions,
+    /// The state overrides that are applied before the call is traced
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub state_overrides: Option<StateOverride>,
+}
+
+/// The `debug_traceTransaction` options, geth's tracing options and whether to decode the calls
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct TraceTransactionOptions {
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub tracing_options: GethDebugTracingOptions,
+    /// Whether to attach the resolved function signatures and arguments to the frames of the
+    /// `callTracer`, this is ignored by other tracers
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub decode: bool,
+}
+
+impl From<GethDebugTracingOptions> for TraceTransactionOptions {
+    fn from(tracing_options: GethDebugTracingOptions) -> Self {
+        Self { tracing_options, decode: false }
+    }
+}
+
+/// The `trace_filter` options
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct TraceFilter {
+    /// The first block to include, defaults to the earliest block
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub from_block: Option<BlockNumberOrTag>,
+    /// The last block to include, defaults to the latest block
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub to_block: Option<BlockNumberOrTag>,
+    /// Only include traces with any of these senders, all senders if empty
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub from_address: Vec<Address>,
+    /// Only include traces with any of these recipients, all recipients if empty
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub to_address: Vec<Address>,
+    /// The number of matching traces to skip
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            default,
+            deserialize_with = "ethers_core::types::serde_helpers::deserialize_stringified_u64_opt"
+        )
+    )]
+    pub after: Option<u64>,
+    /// The maximum number of traces to return
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            default,
+            deserialize_with = "ethers_core::types::serde_helpers::deserialize_stringified_u64_opt"
+        )
+    )]
+    pub count: Option<u64>,
+}
+
+impl TraceFilter {
+    /// Returns true if the sender and the recipient of the trace match the filter.
+    ///
+    /// The recipient of a create trace is the created contract, the sender of a selfdestruct trace
+    /// is the destroyed contract.
+    pub fn matches(&self, trace: &LocalizedTransactionTrace) -> bool {
+        let (from, to) = match &trace.trace.action {
+            Action::Call(call) => (Some(call.from), Some(call.to)),
+            Action::Create(create) => {
+                let to = match &trace.trace.result {
+                    Some(TraceOutput::Create(output)) => Some(output.address),
+                    _ => None,
+                };
+                (Some(create.from), to)
+            }
+            Action::Selfdestruct(selfdestruct) => {
+                (Some(selfdestruct.address), Some(selfdestruct.refund_address))
+            }
+            Action::Reward(reward) => (None, Some(reward.author)),
+        };
+        let matches = |addresses: &[Address], address: Option<Address>| {
+            addresses.is_empty() || address.map_or(false, |address| addresses.contains(&address))
+        };
+        matches(&self.from_address, from) && matches(&self.to_address, to)
+    }
+}
+
+/// The `anvil_setBaseFeeParams` options, parameters that are not set remain unchanged
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct BaseFeeParamsOptions {
+    /// The gas used by a block at which the base fee stays the same
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            default,
+            deserialize_with = "ethers_core::types::serde_helpers::deserialize_stringified_u64_opt"
+        )
+    )]
+    pub gas_target: Option<u64>,
+    /// Bounds the amount the base fee can change between blocks
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            default,
+            deserialize_with = "ethers_core::types::serde_helpers::deserialize_stringified_u64_opt"
+        )
+    )]
+    pub max_change_denominator: Option<u64>,
+}
+
 /// Represents the result of `eth_getWork`
 /// This may or may not include the block number
 #[derive(Debug, PartialEq, Eq, Default)]
@@ -173,15 +315,54 @@ impl<'a> serde::Deserialize<'a> for Index {
     }
 }
 
+/// The mode the miner currently operates in, returned by `anvil_getMiningMode`
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "mode", rename_all = "camelCase"))]
+pub enum MiningModeInfo {
+    /// A block is mined for every ready transaction
+    Auto,
+    /// A block is mined every `block_time` seconds
+    Interval {
+        #[cfg_attr(feature = "serde", serde(rename = "blockTime"))]
+        block_time: u64,
+    },
+    /// Blocks are only mined via `anvil_mine` or `evm_mine`
+    Manual,
+}
+
+/// The result of `txpool_status`, the number of transactions in the pool as well as its size and
+/// limits
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct PoolStatus {
+    /// The number of transactions that are ready to be mined
+    pub pending: U64,
+    /// The number of transactions that are scheduled for future execution
+    pub queued: U64,
+    /// The total size of the encoded transactions in bytes
+    pub bytes: U64,
+    /// The max number of transactions, `None` if unlimited
+    pub max_count: Option<U64>,
+    /// The max total size of the transactions in bytes, `None` if unlimited
+    pub max_bytes: Option<U64>,
+}
+
 #[derive(Debug, Clone, Eq, PartialEq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
 pub struct NodeInfo {
+    /// The version of the node, like `anvil/v0.2.0`
+    pub client_version: String,
     pub current_block_number: U64,
     pub current_block_timestamp: u64,
     pub current_block_hash: B256,
     pub hard_fork: SpecId,
     pub transaction_order: String,
+    pub mining_mode: MiningModeInfo,
+    /// The number of accounts the node can sign transactions for
+    pub accounts_count: u64,
     pub environment: NodeEnvironment,
     pub fork_config: NodeForkConfig,
 }
@@ -200,6 +381,7 @@ pub struct NodeEnvironment {
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
 pub struct NodeForkConfig {
+    /// The url of the forked node, with its credentials masked
     pub fork_url: Option<String>,
     pub fork_block_number: Option<u64>,
     pub fork_retry_backoff: Option<u128>,
@@ -214,13 +396,26 @@ pub struct NodeForkConfig {
 pub struct AnvilMetadata {
     pub client_version: &'static str,
     pub chain_id: u64,
+    /// A random id of the running node, it changes when the node restarts or the fork is reset
     pub instance_id: B256,
     pub latest_block_number: u64,
     pub latest_block_hash: B256,
+    /// The network the node is forked off, if any
     pub forked_network: Option<ForkedNetwork>,
     pub snapshots: BTreeMap<U256, (u64, B256)>,
 }
 
+/// A line of the node logs, returned by `anvil_getNodeLogs`
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct NodeLog {
+    /// The unix timestamp of the log in milliseconds
+    pub timestamp: u64,
+    pub level: String,
+    pub message: String,
+}
+
 /// Information about the forked network.
 /// See <https://hardhat.org/hardhat-network/docs/reference#hardhat_metadata>
 #[derive(Debug, Clone, Eq, PartialEq)]
@@ -232,6 +427,177 @@ pub struct ForkedNetwork {
     pub fork_block_hash: TxHash,
 }
 
+/// The gas spent by all invocations of an opcode during a call.
+///
+/// Returned by `anvil_gasProfile`.
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct OpcodeGas {
+    /// The name of the opcode, like `SSTORE`
+    pub opcode: String,
+    /// The gas spent by all invocations of the opcode, excluding the gas spent in nested calls
+    pub gas: u64,
+    /// The number of times the opcode was executed
+    pub count: u64,
+}
+
+/// The report of a re-executed mined transaction
+///
+/// Returned by `anvil_inspectTransaction`.
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct TransactionInspection {
+    pub transaction_hash: B256,
+    /// Whether the transaction succeeded
+    pub success: bool,
+    /// The gas used by the entire transaction
+    pub gas_used: u64,
+    /// The calls made by the outermost call of the transaction, in order
+    pub calls: Vec<CallGas>,
+    /// The messages of the `console.sol` logs, in the order they were emitted
+    pub console_logs: Vec<String>,
+    /// The decoded revert reason, if the transaction failed
+    pub revert_reason: Option<String>,
+}
+
+/// The gas used by a call, see [TransactionInspection]
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct CallGas {
+    pub from: Address,
+    /// The called contract, or the created contract
+    pub to: Address,
+    pub input: Bytes,
+    /// The gas used by the call, including the gas used by the calls it made
+    pub gas_used: u64,
+    pub success: bool,
+}
+
+/// The gas used by a call frame of a transaction, without the calls it made
+///
+/// Returned by `anvil_traceGasSummary`, one per call frame in the order the calls were made.
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct CallGasSummary {
+    /// The called contract, or the created contract
+    pub address: Address,
+    /// The function selector of the call, `None` for creations and calls without one
+    pub selector: Option<Selector>,
+    /// The gas used by the call, including the gas used by the calls it made
+    pub gas_used: u64,
+    /// The gas used by the call itself, excluding the gas used by the calls it made
+    pub self_gas_used: u64,
+}
+
+/// The outcome of a call that was executed without committing its changes
+///
+/// Returned by `anvil_simulate`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct Simulation {
+    /// Whether the call succeeded
+    pub success: bool,
+    pub gas_used: u64,
+    /// The output of the call, or the revert data if it reverted
+    pub return_data: Bytes,
+    /// The decoded revert reason, if the call failed
+    pub revert_reason: Option<String>,
+    /// The messages of the `console.sol` logs, in the order they were emitted
+    pub console_logs: Vec<String>,
+    /// The events the call emitted, empty if it failed
+    pub logs: Vec<SimulatedLog>,
+    /// The call trace in the format of geth's `callTracer`, including the events of every call
+    pub trace: CallFrame,
+}
+
+/// An event emitted by a simulated call, see [Simulation]
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct SimulatedLog {
+    pub address: Address,
+    pub topics: Vec<B256>,
+    pub data: Bytes,
+    /// The event decoded with the ABI registered via `anvil_registerAbi`, if any
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub decoded: Option<DecodedEvent>,
+}
+
+/// An event decoded with a registered ABI
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct DecodedEvent {
+    /// The signature of the event, like `Transfer(address,address,uint256)`
+    pub signature: String,
+    /// The parameters of the event in the order of the signature, formatted so that they can be
+    /// parsed again
+    pub params: Vec<String>,
+    /// The names of the parameters, empty for unnamed parameters
+    pub names: Vec<String>,
+}
+
+/// Overrides of the block environment a call is executed in, accepted by `eth_call`,
+/// `anvil_simulate` and `anvil_simulateBundle`
+///
+/// Only the environment of the call is changed, it is still executed on top of the state of the
+/// requested block. The fields that are not set keep the values of that block.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase", deny_unknown_fields))]
+pub struct BlockOverrides {
+    pub number: Option<U64>,
+    /// The timestamp of the block
+    pub time: Option<U64>,
+    pub coinbase: Option<Address>,
+    /// The `prevrandao` of the block, `random` in geth's overrides
+    #[cfg_attr(feature = "serde", serde(alias = "random"))]
+    pub prev_randao: Option<B256>,
+    pub base_fee: Option<U256>,
+    pub gas_limit: Option<U64>,
+}
+
+/// The trace of a transaction of a block, as returned by `debug_traceBlockByNumber` and
+/// `debug_traceBlockByHash`
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct TxTraceResult {
+    pub tx_hash: B256,
+    pub result: GethTrace,
+}
+
+/// The name of the custom `debug_traceTransaction` tracer that returns the [StorageDiff] of the
+/// transaction
+pub const STORAGE_DIFF_TRACER: &str = "storageDiffTracer";
+
+/// The storage slots a transaction read or wrote, per account.
+///
+/// Every slot maps to its value before and after the transaction, `(old, new)`. Writes of
+/// reverted calls are not included.
+pub type StorageDiff = BTreeMap<Address, BTreeMap<U256, (U256, U256)>>;
+
+/// The state of an account before a transaction, for the `prestateTracer`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AccountPreState {
+    pub balance: U256,
+    pub nonce: u64,
+    pub code: Bytes,
+    /// The storage slots the transaction accessed
+    pub storage: BTreeMap<U256, U256>,
+}
+
+/// The state of all accounts a transaction accessed, as it was before the transaction
+pub type PreState = BTreeMap<Address, AccountPreState>;
+
+/// The storage slots to set with `anvil_setStorageBatch`, `address -> {slot: value}`
+pub type StorageBatch = BTreeMap<Address, BTreeMap<U256, B256>>;
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -247,8 +613,33 @@ mod tests {
             f,
             Forking {
                 json_rpc_url: Some("https://ethereumpublicnode.com".into()),
-                block_number: Some(18441649)
+                block_number: Some(18441649),
+                keep_impersonated_accounts: false,
             }
         );
+
+        let s =
+            r#"{"jsonRpcUrl": "https://ethereumpublicnode.com", "keepImpersonatedAccounts": true}"#;
+        let f: Forking = serde_json::from_str(s).unwrap();
+        assert_eq!(
+            f,
+            Forking {
+                json_rpc_url: Some("https://ethereumpublicnode.com".into()),
+                block_number: None,
+                keep_impersonated_accounts: true,
+            }
+        );
+    }
+
+    #[test]
+    fn serde_trace_filter() {
+        let s = r#"{"fromBlock": "0x1", "toBlock": "latest", "toAddress": ["0x8ba1f109551bd432803012645ac136ddd64dba72"], "after": 1, "count": "0x2"}"#;
+        let filter: TraceFilter = serde_json::from_str(s).unwrap();
+        assert_eq!(filter.from_block, Some(BlockNumberOrTag::Number(1)));
+        assert_eq!(filter.to_block, Some(BlockNumberOrTag::Latest));
+        assert!(filter.from_address.is_empty());
+        assert_eq!(filter.to_address.len(), 1);
+        assert_eq!(filter.after, Some(1));
+        assert_eq!(filter.count, Some(2));
     }
 }
@@ -51,6 +51,17 @@ pub enum RpcCall {
     },
 }
 
+impl RpcCall {
+    /// Returns the name of the method to invoke, if this is a valid call
+    pub fn method(&self) -> Option<&str> {
+        match self {
+            RpcCall::MethodCall(call) => Some(&call.method),
+            RpcCall::Notification(notification) => Some(&notification.method),
+            RpcCall::Invalid { .. } => None,
+        }
+    }
+}
+
 /// Represents a JSON-RPC request.
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(deny_unknown_fields)]
@@ -62,6 +73,16 @@ pub enum Request {
     Batch(Vec<RpcCall>),
 }
 
+impl Request {
+    /// Returns all the calls of this request
+    pub fn calls(&self) -> &[RpcCall] {
+        match self {
+            Request::Single(call) => std::slice::from_ref(call),
+            Request::Batch(calls) => calls,
+        }
+    }
+}
+
 /// Request parameters
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(untagged, deny_unknown_fields)]
@@ -30,6 +30,16 @@ impl RpcResponse {
     pub fn invalid_request(id: Id) -> Self {
         Self::new(id, RpcError::invalid_request())
     }
+
+    /// Returns the id of the call this is the response to
+    pub fn id(&self) -> Option<&Id> {
+        self.id.as_ref()
+    }
+
+    /// Returns the result of the call
+    pub fn result(&self) -> &ResponseResult {
+        &self.result
+    }
 }
 
 /// Represents the result of a call either success or error
@@ -53,6 +63,14 @@ impl ResponseResult {
     pub fn error(error: RpcError) -> Self {
         ResponseResult::Error(error)
     }
+
+    /// Returns the error, if the call failed
+    pub fn as_error(&self) -> Option<&RpcError> {
+        match self {
+            ResponseResult::Success(_) => None,
+            ResponseResult::Error(err) => Some(err),
+        }
+    }
 }
 
 impl From<RpcError> for ResponseResult {
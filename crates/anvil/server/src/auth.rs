@@ -0,0 +1,126 @@
+//! Bearer token authentication for admin methods
+
+use crate::{is_admin_method, limit};
+use anvil_rpc::{
+    error::{ErrorCode, RpcError},
+    request::{Request as RpcRequest, RpcCall},
+    response::{self, Response as RpcResponse},
+};
+use axum::{
+    body::{to_bytes, Body},
+    extract::{Request, State},
+    http::{header, HeaderMap, Method},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+};
+use std::sync::Arc;
+
+/// The JSON-RPC error code returned for unauthorized calls to admin methods
+pub(crate) const UNAUTHORIZED_ERROR_CODE: i64 = -32001;
+
+/// The token that is required to call admin methods
+#[derive(Clone)]
+pub(crate) struct BearerAuth {
+    token: Arc<str>,
+}
+
+impl BearerAuth {
+    pub fn new(token: impl Into<Arc<str>>) -> Self {
+        Self { token: token.into() }
+    }
+
+    /// Returns true if the `Authorization` header carries the configured bearer token
+    pub fn is_authorized(&self, headers: &HeaderMap) -> bool {
+        headers
+            .get(header::AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.split_once(' '))
+            .filter(|(scheme, _)| scheme.eq_ignore_ascii_case("bearer"))
+            .map_or(false, |(_, token)| constant_time_eq(token.trim(), &self.token))
+    }
+}
+
+/// Whether the request carried a valid bearer token
+///
+/// This is attached to the request so that the websocket connection can reject admin calls of
+/// unauthorized clients.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct Authorized(pub bool);
+
+/// The error returned for calls to admin methods without a valid bearer token
+pub(crate) fn unauthorized() -> RpcError {
+    RpcError {
+        code: ErrorCode::ServerError(UNAUTHORIZED_ERROR_CODE),
+        message: "Unauthorized, admin methods require a valid bearer token".into(),
+        data: None,
+    }
+}
+
+/// Returns the response that rejects an unauthorized request.
+///
+/// Every call of a batch is rejected with its own id. Like the handler, notifications are not
+/// answered.
+fn unauthorized_response(request: RpcRequest) -> RpcResponse {
+    let reject = |call| match call {
+        RpcCall::MethodCall(call) => Some(response::RpcResponse::new(call.id, unauthorized())),
+        RpcCall::Notification(_) => None,
+        RpcCall::Invalid { id } => Some(response::RpcResponse::invalid_request(id)),
+    };
+    let responses = match request {
+        RpcRequest::Single(call) => reject(call).map(RpcResponse::Single),
+        RpcRequest::Batch(calls) => {
+            let batch: Vec<_> = calls.into_iter().filter_map(reject).collect();
+            (!batch.is_empty()).then_some(RpcResponse::Batch(batch))
+        }
+    };
+    responses.unwrap_or_else(|| RpcResponse::error(unauthorized()))
+}
+
+/// Returns true if the request calls any of the admin methods
+pub(crate) fn calls_admin_method(request: &RpcRequest) -> bool {
+    request.calls().iter().filter_map(|call| call.method()).any(is_admin_method)
+}
+
+/// Rejects HTTP requests that call admin methods without a valid bearer token.
+///
+/// Standard methods are not affected. Websocket upgrade requests are always let through, the
+/// result of the token check is attached to the request as [Authorized] instead.
+pub(crate) async fn require_admin_auth(
+    State(auth): State<BearerAuth>,
+    mut request: Request,
+    next: Next,
+) -> Response {
+    let authorized = auth.is_authorized(request.headers());
+    if authorized || request.method() != Method::POST {
+        request.extensions_mut().insert(Authorized(authorized));
+        return next.run(request).await
+    }
+
+    // the body is already bounded by the body limit layer
+    let (parts, body) = request.into_parts();
+    let body = match to_bytes(body, usize::MAX).await {
+        Ok(body) => body,
+        Err(err) => {
+            trace!(target: "rpc::auth", ?err, "failed to read request body");
+            return limit::payload_too_large()
+        }
+    };
+
+    // invalid requests are rejected by the handler
+    if let Ok(req) = serde_json::from_slice::<RpcRequest>(&body) {
+        if calls_admin_method(&req) {
+            warn!(target: "rpc::auth", "rejecting unauthorized call to admin method");
+            return Json(unauthorized_response(req)).into_response()
+        }
+    }
+
+    let mut request = Request::from_parts(parts, Body::from(body));
+    request.extensions_mut().insert(Authorized(false));
+    next.run(request).await
+}
+
+/// Compares the strings in constant time to not leak the token via timing
+fn constant_time_eq(a: &str, b: &str) -> bool {
+    a.len() == b.len() && a.bytes().zip(b.bytes()).fold(0, |acc, (a, b)| acc | (a ^ b)) == 0
+}
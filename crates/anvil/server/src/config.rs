@@ -1,37 +1,210 @@
-use crate::HeaderValue;
-use serde::{Deserialize, Deserializer, Serialize, Serializer};
-use std::str::FromStr;
+use crate::limit::RequestLimits;
+use serde::{Deserialize, Serialize};
+use std::{path::PathBuf, time::Duration};
+
+/// The default time to wait for a websocket pong before the connection is considered dead
+pub const DEFAULT_WS_PING_TIMEOUT: Duration = Duration::from_secs(20);
+
+/// The default maximum size of a request in bytes: 10 MiB
+pub const DEFAULT_MAX_REQUEST_SIZE: usize = 10 * 1024 * 1024;
+
+/// The default maximum size of a request in bytes that only calls `anvil_`, `hardhat_` or `evm_`
+/// methods: 100 MiB
+///
+/// This is larger than [DEFAULT_MAX_REQUEST_SIZE] because methods like `anvil_loadState` can
+/// carry the entire state of the chain.
+pub const DEFAULT_MAX_ADMIN_REQUEST_SIZE: usize = 100 * 1024 * 1024;
 
 /// Additional server options.
 #[derive(Clone, Debug, Serialize, Deserialize)]
 #[cfg_attr(feature = "clap", derive(clap::Parser), clap(next_help_heading = "Server options"))]
 pub struct ServerConfig {
-    /// The cors `allow_origin` header
+    /// The origins that are allowed to access the server, `*` allows all origins
     #[cfg_attr(
         feature = "clap",
         clap(
-            long,
-            help = "Set the CORS allow_origin",
+            long = "allow-origin",
+            help = "Comma separated list of origins allowed by CORS, `*` allows all origins",
             default_value = "*",
             name = "allow-origin",
-            value_name = "ALLOW_ORIGIN"
+            value_name = "ALLOW_ORIGIN",
+            value_delimiter = ','
         )
     )]
-    pub allow_origin: HeaderValueWrapper,
+    #[serde(default = "default_allowed_origins")]
+    pub allowed_origins: Vec<String>,
     /// Whether to enable CORS
     #[cfg_attr(
         feature = "clap",
         clap(long, help = "Disable CORS", conflicts_with = "allow-origin")
     )]
     pub no_cors: bool,
+    /// Whether to disable response compression
+    #[cfg_attr(
+        feature = "clap",
+        clap(long, help = "Disable gzip and brotli response compression")
+    )]
+    #[serde(default)]
+    pub no_compression: bool,
+    /// Whether to serve over TLS
+    #[cfg_attr(
+        feature = "clap",
+        clap(
+            long,
+            help = "Serve over TLS, using a self-signed certificate unless `--tls-cert` is set"
+        )
+    )]
+    #[serde(default)]
+    pub tls: bool,
+    /// The PEM encoded certificate chain to use for TLS
+    #[cfg_attr(
+        feature = "clap",
+        clap(
+            long,
+            help = "PEM encoded certificate chain to serve over TLS",
+            requires = "tls_key",
+            value_name = "PATH"
+        )
+    )]
+    pub tls_cert: Option<PathBuf>,
+    /// The PEM encoded private key to use for TLS
+    #[cfg_attr(
+        feature = "clap",
+        clap(
+            long,
+            help = "PEM encoded private key to serve over TLS",
+            requires = "tls_cert",
+            value_name = "PATH"
+        )
+    )]
+    pub tls_key: Option<PathBuf>,
+    /// The interval in seconds at which websocket pings are sent to the client
+    #[cfg_attr(
+        feature = "clap",
+        clap(
+            long,
+            help = "Send websocket pings every SECONDS and drop unresponsive connections",
+            value_name = "SECONDS"
+        )
+    )]
+    pub ws_ping_interval: Option<u64>,
+    /// The time in seconds to wait for a pong before closing the websocket connection
+    #[cfg_attr(
+        feature = "clap",
+        clap(
+            long,
+            help = "Seconds to wait for a websocket pong [default: 20]",
+            requires = "ws_ping_interval",
+            value_name = "SECONDS"
+        )
+    )]
+    pub ws_ping_timeout: Option<u64>,
+    /// The maximum size of a request in bytes
+    #[cfg_attr(
+        feature = "clap",
+        clap(
+            long,
+            help = "The maximum size of a request in bytes",
+            default_value_t = DEFAULT_MAX_REQUEST_SIZE,
+            value_name = "BYTES"
+        )
+    )]
+    #[serde(default = "default_max_request_size")]
+    pub max_request_size: usize,
+    /// The maximum size of a request in bytes that only calls admin methods
+    #[cfg_attr(
+        feature = "clap",
+        clap(
+            long,
+            help = "The maximum size of a request in bytes that only calls admin methods",
+            default_value_t = DEFAULT_MAX_ADMIN_REQUEST_SIZE,
+            value_name = "BYTES"
+        )
+    )]
+    #[serde(default = "default_max_admin_request_size")]
+    pub max_admin_request_size: usize,
+    /// The maximum number of calls in a batch request
+    #[cfg_attr(
+        feature = "clap",
+        clap(long, help = "The maximum number of calls in a batch request", value_name = "NUM")
+    )]
+    pub max_batch_size: Option<usize>,
+    /// The maximum number of calls that are executed concurrently
+    #[cfg_attr(
+        feature = "clap",
+        clap(
+            long,
+            help = "The maximum number of calls that are executed concurrently",
+            value_name = "NUM"
+        )
+    )]
+    pub max_concurrent_requests: Option<usize>,
+    /// The bearer token required to call `anvil_`, `hardhat_` and `evm_` methods
+    #[cfg_attr(
+        feature = "clap",
+        clap(
+            long,
+            help = "Require this bearer token for `anvil_`, `hardhat_` and `evm_` methods",
+            env = "ANVIL_AUTH_TOKEN",
+            value_name = "TOKEN"
+        )
+    )]
+    #[serde(default, skip_serializing)]
+    pub auth_token: Option<String>,
+    /// Whether to serve prometheus metrics at `/metrics`
+    #[cfg_attr(feature = "clap", clap(long, help = "Serve prometheus metrics at `/metrics`"))]
+    #[serde(default)]
+    pub metrics: bool,
+    /// The number of requests per second a single client IP is allowed to make
+    #[cfg_attr(
+        feature = "clap",
+        clap(
+            long,
+            help = "Limit the number of requests per second of a single client IP",
+            value_name = "REQUESTS_PER_SECOND"
+        )
+    )]
+    pub ip_rate_limit: Option<u32>,
+    /// The number of requests a single client IP is allowed to make in a burst
+    #[cfg_attr(
+        feature = "clap",
+        clap(
+            long,
+            help = "The burst size of the client IP rate limit [default: the rate limit]",
+            requires = "ip_rate_limit",
+            value_name = "REQUESTS"
+        )
+    )]
+    pub ip_rate_limit_burst: Option<u32>,
+    /// Whether to identify clients by the `X-Forwarded-For` header
+    #[cfg_attr(
+        feature = "clap",
+        clap(
+            long,
+            help = "Identify rate limited clients by `X-Forwarded-For`, only use behind a proxy",
+            requires = "ip_rate_limit"
+        )
+    )]
+    #[serde(default)]
+    pub trust_forwarded_for: bool,
+    /// Whether to include the params of calls in the request log
+    #[cfg_attr(
+        feature = "clap",
+        clap(long, help = "Include call params in the `rpc::request_log` log, may be sensitive")
+    )]
+    #[serde(default)]
+    pub log_request_params: bool,
 }
 
 // === impl ServerConfig ===
 
 impl ServerConfig {
-    /// Sets the "allow origin" header for cors
-    pub fn with_allow_origin(mut self, allow_origin: impl Into<HeaderValueWrapper>) -> Self {
-        self.allow_origin = allow_origin.into();
+    /// Sets the origins that are allowed by cors, `*` allows all origins
+    pub fn with_allowed_origins<T: Into<String>>(
+        mut self,
+        origins: impl IntoIterator<Item = T>,
+    ) -> Self {
+        self.allowed_origins = origins.into_iter().map(Into::into).collect();
         self
     }
 
@@ -40,60 +213,145 @@ impl ServerConfig {
         self.no_cors = cors;
         self
     }
-}
 
-impl Default for ServerConfig {
-    fn default() -> Self {
-        Self { allow_origin: "*".parse::<HeaderValue>().unwrap().into(), no_cors: false }
+    /// Whether to compress responses
+    pub fn set_compression(mut self, compression: bool) -> Self {
+        self.no_compression = !compression;
+        self
     }
-}
 
-#[derive(Clone, Debug)]
-pub struct HeaderValueWrapper(pub HeaderValue);
+    /// Whether to serve over TLS
+    pub fn set_tls(mut self, tls: bool) -> Self {
+        self.tls = tls;
+        self
+    }
 
-impl FromStr for HeaderValueWrapper {
-    type Err = <HeaderValue as FromStr>::Err;
+    /// Sets the PEM encoded certificate chain and private key to serve over TLS
+    pub fn with_tls_cert(mut self, cert: impl Into<PathBuf>, key: impl Into<PathBuf>) -> Self {
+        self.tls_cert = Some(cert.into());
+        self.tls_key = Some(key.into());
+        self
+    }
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        Ok(HeaderValueWrapper(s.parse()?))
+    /// Returns true if the server should terminate TLS
+    pub fn is_tls_enabled(&self) -> bool {
+        self.tls || self.tls_cert.is_some()
     }
-}
 
-impl Serialize for HeaderValueWrapper {
-    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
-    where
-        S: Serializer,
-    {
-        serializer.serialize_str(self.0.to_str().map_err(serde::ser::Error::custom)?)
+    /// Sets the interval at which websocket pings are sent and how long to wait for the pong
+    pub fn with_ws_keepalive(mut self, interval: Duration, timeout: Duration) -> Self {
+        self.ws_ping_interval = Some(interval.as_secs());
+        self.ws_ping_timeout = Some(timeout.as_secs());
+        self
     }
-}
 
-impl<'de> Deserialize<'de> for HeaderValueWrapper {
-    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
-    where
-        D: Deserializer<'de>,
-    {
-        let s = String::deserialize(deserializer)?;
-        Ok(Self(s.parse().map_err(serde::de::Error::custom)?))
+    /// Returns the websocket ping `(interval, timeout)`, if enabled
+    pub fn ws_keepalive(&self) -> Option<(Duration, Duration)> {
+        let interval = self.ws_ping_interval.filter(|secs| *secs > 0)?;
+        let timeout =
+            self.ws_ping_timeout.map(Duration::from_secs).unwrap_or(DEFAULT_WS_PING_TIMEOUT);
+        Some((Duration::from_secs(interval), timeout))
     }
-}
 
-impl std::ops::Deref for HeaderValueWrapper {
-    type Target = HeaderValue;
+    /// Sets the maximum size of a request in bytes
+    pub fn with_max_request_size(mut self, max_request_size: usize) -> Self {
+        self.max_request_size = max_request_size;
+        self
+    }
 
-    fn deref(&self) -> &Self::Target {
-        &self.0
+    /// Sets the maximum size of a request in bytes that only calls admin methods
+    pub fn with_max_admin_request_size(mut self, max_admin_request_size: usize) -> Self {
+        self.max_admin_request_size = max_admin_request_size;
+        self
+    }
+
+    /// Sets the maximum number of calls in a batch request
+    pub fn with_max_batch_size(mut self, max_batch_size: Option<usize>) -> Self {
+        self.max_batch_size = max_batch_size;
+        self
+    }
+
+    /// Sets the maximum number of calls that are executed concurrently
+    pub fn with_max_concurrent_requests(mut self, max_concurrent_requests: Option<usize>) -> Self {
+        self.max_concurrent_requests = max_concurrent_requests;
+        self
+    }
+
+    /// Sets the bearer token that is required to call admin methods
+    pub fn with_auth_token(mut self, token: Option<String>) -> Self {
+        self.auth_token = token;
+        self
+    }
+
+    /// Whether to serve prometheus metrics at `/metrics`
+    pub fn set_metrics(mut self, metrics: bool) -> Self {
+        self.metrics = metrics;
+        self
+    }
+
+    /// Limits the number of requests per second of a single client IP, allowing bursts of up to
+    /// `burst` requests
+    pub fn with_ip_rate_limit(mut self, requests_per_second: u32, burst: Option<u32>) -> Self {
+        self.ip_rate_limit = Some(requests_per_second);
+        self.ip_rate_limit_burst = burst;
+        self
+    }
+
+    /// Whether to identify rate limited clients by the `X-Forwarded-For` header
+    pub fn set_trust_forwarded_for(mut self, trust_forwarded_for: bool) -> Self {
+        self.trust_forwarded_for = trust_forwarded_for;
+        self
+    }
+
+    /// Whether to include the params of calls in the request log
+    pub fn set_log_request_params(mut self, log_request_params: bool) -> Self {
+        self.log_request_params = log_request_params;
+        self
     }
-}
 
-impl From<HeaderValueWrapper> for HeaderValue {
-    fn from(wrapper: HeaderValueWrapper) -> Self {
-        wrapper.0
+    /// Returns the size limits for incoming requests
+    pub(crate) fn request_limits(&self) -> RequestLimits {
+        RequestLimits {
+            max_request_size: self.max_request_size,
+            max_admin_request_size: self.max_admin_request_size,
+            max_batch_size: self.max_batch_size,
+        }
     }
 }
 
-impl From<HeaderValue> for HeaderValueWrapper {
-    fn from(header: HeaderValue) -> Self {
-        HeaderValueWrapper(header)
+impl Default for ServerConfig {
+    fn default() -> Self {
+        Self {
+            allowed_origins: default_allowed_origins(),
+            no_cors: false,
+            no_compression: false,
+            tls: false,
+            tls_cert: None,
+            tls_key: None,
+            ws_ping_interval: None,
+            ws_ping_timeout: None,
+            max_request_size: DEFAULT_MAX_REQUEST_SIZE,
+            max_admin_request_size: DEFAULT_MAX_ADMIN_REQUEST_SIZE,
+            max_batch_size: None,
+            max_concurrent_requests: None,
+            auth_token: None,
+            metrics: false,
+            ip_rate_limit: None,
+            ip_rate_limit_burst: None,
+            trust_forwarded_for: false,
+            log_request_params: false,
+        }
     }
 }
+
+fn default_allowed_origins() -> Vec<String> {
+    vec!["*".to_string()]
+}
+
+fn default_max_request_size() -> usize {
+    DEFAULT_MAX_REQUEST_SIZE
+}
+
+fn default_max_admin_request_size() -> usize {
+    DEFAULT_MAX_ADMIN_REQUEST_SIZE
+}
@@ -0,0 +1,86 @@
+//! Restricts browser access to the configured origins
+
+use axum::{
+    extract::{Request, State},
+    http::{header, HeaderValue, Method, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use std::sync::Arc;
+use tower_http::cors::{AllowOrigin, CorsLayer};
+
+/// The origins that are allowed to access the server
+#[derive(Clone, Debug)]
+pub(crate) enum AllowedOrigins {
+    /// All origins are allowed
+    Any,
+    /// Only the listed origins are allowed
+    List(Arc<Vec<HeaderValue>>),
+}
+
+impl AllowedOrigins {
+    /// Creates the allowlist from the configured origins, `*` allows all origins
+    pub fn new(origins: &[String]) -> Self {
+        if origins.iter().any(|origin| origin.trim() == "*") {
+            return Self::Any
+        }
+        let origins = origins
+            .iter()
+            .filter_map(|origin| {
+                let origin = origin.trim().trim_end_matches('/');
+                match HeaderValue::from_str(origin) {
+                    Ok(origin) => Some(origin),
+                    Err(err) => {
+                        warn!(target: "rpc::cors", ?origin, ?err, "ignoring invalid origin");
+                        None
+                    }
+                }
+            })
+            .collect();
+        Self::List(Arc::new(origins))
+    }
+
+    /// Returns true if the origin is allowed to access the server
+    pub fn is_allowed(&self, origin: &HeaderValue) -> bool {
+        match self {
+            Self::Any => true,
+            Self::List(origins) => origins
+                .iter()
+                .any(|allowed| allowed.as_bytes().eq_ignore_ascii_case(origin.as_bytes())),
+        }
+    }
+
+    /// Returns the [CorsLayer] that answers preflight requests and sets the cors headers
+    pub fn layer(&self) -> CorsLayer {
+        // see https://docs.rs/tower-http/latest/tower_http/cors/index.html
+        // for more details
+        let allow_origin = match self {
+            Self::Any => AllowOrigin::any(),
+            Self::List(_) => {
+                let origins = self.clone();
+                AllowOrigin::predicate(move |origin, _| origins.is_allowed(origin))
+            }
+        };
+        CorsLayer::new()
+            .allow_origin(allow_origin)
+            .allow_headers([header::CONTENT_TYPE])
+            .allow_methods([Method::GET, Method::POST])
+    }
+}
+
+/// Rejects requests from origins that are not allowed with `403 Forbidden`.
+///
+/// Requests without an `Origin` header, like those made by non-browser clients, are not affected.
+pub(crate) async fn reject_disallowed_origin(
+    State(origins): State<AllowedOrigins>,
+    request: Request,
+    next: Next,
+) -> Response {
+    if let Some(origin) = request.headers().get(header::ORIGIN) {
+        if !origins.is_allowed(origin) {
+            trace!(target: "rpc::cors", ?origin, "rejecting request from disallowed origin");
+            return StatusCode::FORBIDDEN.into_response()
+        }
+    }
+    next.run(request).await
+}
@@ -9,6 +9,9 @@ pub enum RequestError {
     Serde(#[from] serde_json::Error),
     #[error(transparent)]
     Io(#[from] std::io::Error),
+    /// The request was rejected before it was handled, e.g. because it exceeds a limit
+    #[error("Request rejected: {0}")]
+    Rejected(anvil_rpc::error::RpcError),
     #[error("Disconnect")]
     Disconnect,
 }
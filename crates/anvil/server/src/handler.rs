@@ -1,30 +1,81 @@
-use crate::RpcHandler;
+use crate::{limit, limit::RequestLimits, RpcHandler};
 use anvil_rpc::{
     error::RpcError,
     request::{Request, RpcCall},
     response::{Response, RpcResponse},
 };
 use axum::{
-    extract::{rejection::JsonRejection, State},
+    body::Bytes,
+    extract::{rejection::BytesRejection, State},
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response as HttpResponse},
     Json,
 };
 use futures::{future, FutureExt};
 
 /// Handles incoming JSON-RPC Request.
-// NOTE: `handler` must come first because the `request` extractor consumes the request body.
-pub async fn handle<Http: RpcHandler, Ws>(
+///
+/// Requests that exceed the size limit for the methods they call are rejected with
+/// `413 Payload Too Large`.
+// NOTE: `handler` must come first because the `body` extractor consumes the request body.
+pub(crate) async fn handle<Http: RpcHandler, Ws>(
     State((handler, _)): State<(Http, Ws)>,
-    request: Result<Json<Request>, JsonRejection>,
-) -> Json<Response> {
-    Json(match request {
-        Ok(Json(req)) => handle_request(req, handler)
-            .await
-            .unwrap_or_else(|| Response::error(RpcError::invalid_request())),
+    headers: HeaderMap,
+    body: Result<Bytes, BytesRejection>,
+    limits: RequestLimits,
+) -> HttpResponse {
+    let body = match body {
+        Ok(body) => body,
+        Err(err) if err.status() == StatusCode::PAYLOAD_TOO_LARGE => {
+            return limit::payload_too_large()
+        }
+        Err(err) => {
+            warn!(target: "rpc", ?err, "invalid request");
+            return Json(Response::error(RpcError::invalid_request())).into_response()
+        }
+    };
+
+    if !is_json_content_type(&headers) {
+        warn!(target: "rpc", "invalid request, missing json content type");
+        return Json(Response::error(RpcError::invalid_request())).into_response()
+    }
+
+    let req = match serde_json::from_slice::<Request>(&body) {
+        Ok(req) => req,
         Err(err) => {
             warn!(target: "rpc", ?err, "invalid request");
-            Response::error(RpcError::invalid_request())
+            return Json(Response::error(RpcError::invalid_request())).into_response()
         }
-    })
+    };
+
+    if limits.exceeds(body.len(), &req) {
+        warn!(target: "rpc", len = body.len(), "request too large");
+        return limit::payload_too_large()
+    }
+
+    if let Err(err) = limits.check_batch_size(&req) {
+        warn!(target: "rpc", ?err, "batch too large");
+        return Json(Response::error(err)).into_response()
+    }
+
+    Json(
+        handle_request(req, handler)
+            .await
+            .unwrap_or_else(|| Response::error(RpcError::invalid_request())),
+    )
+    .into_response()
+}
+
+/// Returns true if the `Content-Type` header is set to `application/json`
+fn is_json_content_type(headers: &HeaderMap) -> bool {
+    headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|content_type| content_type.to_str().ok())
+        .and_then(|content_type| content_type.parse::<mime::Mime>().ok())
+        .map_or(false, |mime| {
+            mime.type_() == "application" &&
+                (mime.subtype() == "json" || mime.suffix().map_or(false, |name| name == "json"))
+        })
 }
 
 /// Handle the JSON-RPC [Request]
@@ -24,26 +24,32 @@ pub struct IpcEndpoint<Handler> {
 
 impl<Handler: PubSubRpcHandler> IpcEndpoint<Handler> {
     /// Creates a new endpoint with the given handler
+    ///
+    /// On Windows the endpoint is a named pipe, see [endpoint_path].
     pub fn new(handler: Handler, endpoint: String) -> Self {
-        Self { handler, endpoint: Endpoint::new(endpoint) }
+        Self { handler, endpoint: Endpoint::new(endpoint_path(endpoint)) }
     }
 
     /// Returns a stream of incoming connection handlers
     ///
     /// This establishes the ipc endpoint, converts the incoming connections into handled eth
     /// connections, See [`PubSubConnection`] that should be spawned
+    ///
+    /// On Unix, an existing socket file is removed if no server is listening on it anymore, if a
+    /// server is still listening this fails with [`io::ErrorKind::AddrInUse`]. The socket file is
+    /// removed again once the returned stream is dropped.
     #[instrument(target = "ipc", skip_all)]
     pub fn incoming(self) -> io::Result<impl Stream<Item = impl Future<Output = ()>>> {
         let IpcEndpoint { handler, endpoint } = self;
         trace!(endpoint=?endpoint.path(), "starting IPC server" );
 
-        if cfg!(unix) {
-            // ensure the file does not exist
-            if std::fs::remove_file(endpoint.path()).is_ok() {
-                warn!(endpoint=?endpoint.path(), "removed existing file");
-            }
+        #[cfg(unix)]
+        if let Err(err) = remove_stale_socket(endpoint.path()) {
+            error!(%err, "Failed to create IPC listener");
+            return Err(err)
         }
 
+        let socket = SocketFile::new(endpoint.path());
         let connections = match endpoint.incoming() {
             Ok(connections) => connections,
             Err(err) => {
@@ -66,10 +72,97 @@ impl<Handler: PubSubRpcHandler> IpcEndpoint<Handler> {
                 }
             })
         });
-        Ok(connections)
+        Ok(Incoming { connections, _socket: socket })
+    }
+}
+
+/// Checks whether a server is listening at the Unix socket and removes the file if not.
+///
+/// Socket files are left behind if the server did not shut down gracefully and would prevent
+/// binding a new listener.
+#[cfg(unix)]
+fn remove_stale_socket(path: &str) -> io::Result<()> {
+    match std::os::unix::net::UnixStream::connect(path) {
+        Ok(_) => Err(io::Error::new(
+            io::ErrorKind::AddrInUse,
+            format!("IPC endpoint {path} is already in use by another server"),
+        )),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(()),
+        Err(_) => {
+            std::fs::remove_file(path)?;
+            warn!(endpoint=?path, "removed stale socket file");
+            Ok(())
+        }
+    }
+}
+
+/// The socket file of a bound Unix socket, removed on drop.
+///
+/// This is a no-op on Windows where the endpoint is a named pipe.
+struct SocketFile(Option<String>);
+
+impl SocketFile {
+    fn new(path: &str) -> Self {
+        Self(cfg!(unix).then(|| path.to_string()))
+    }
+}
+
+impl Drop for SocketFile {
+    fn drop(&mut self) {
+        if let Some(path) = self.0.take() {
+            if std::fs::remove_file(&path).is_ok() {
+                trace!(endpoint=?path, "removed socket file");
+            }
+        }
+    }
+}
+
+/// The stream of incoming connections, removes the socket file once dropped
+#[pin_project::pin_project]
+struct Incoming<S> {
+    #[pin]
+    connections: S,
+    _socket: SocketFile,
+}
+
+impl<S: Stream> Stream for Incoming<S> {
+    type Item = S::Item;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.project().connections.poll_next(cx)
     }
 }
 
+/// The prefix of named pipe paths on Windows
+#[cfg(windows)]
+const PIPE_PREFIX: &str = r"\\.\pipe\";
+
+/// Returns the path of the IPC endpoint for the given `path`.
+///
+/// On Windows the endpoint is a named pipe: paths that are not already in the `\\.\pipe\`
+/// namespace are mapped to a pipe named after the file name of the path, so `C:\tmp\anvil.ipc`
+/// becomes `\\.\pipe\anvil.ipc`.
+#[cfg(windows)]
+pub fn endpoint_path(path: impl Into<String>) -> String {
+    let path = path.into();
+    if path.get(..PIPE_PREFIX.len()).map_or(false, |p| p.eq_ignore_ascii_case(PIPE_PREFIX)) {
+        return path
+    }
+    let name = std::path::Path::new(&path)
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "anvil.ipc".to_string());
+    format!("{PIPE_PREFIX}{name}")
+}
+
+/// Returns the path of the IPC endpoint for the given `path`.
+///
+/// This is the path itself on all platforms but Windows, where the endpoint is a named pipe.
+#[cfg(not(windows))]
+pub fn endpoint_path(path: impl Into<String>) -> String {
+    path.into()
+}
+
 #[pin_project::pin_project]
 struct IpcConn<T>(#[pin] T);
 
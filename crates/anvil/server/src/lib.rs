@@ -10,36 +10,84 @@ use anvil_rpc::{
     request::RpcMethodCall,
     response::{ResponseResult, RpcResponse},
 };
+use auth::{Authorized, BearerAuth};
 use axum::{
-    http::{header, HeaderValue, Method},
+    body::Bytes,
+    extract::{rejection::BytesRejection, DefaultBodyLimit, State, WebSocketUpgrade},
+    http::HeaderMap,
+    middleware,
     routing::{post, MethodRouter},
-    Router,
+    Extension, Router,
 };
+use cors::AllowedOrigins;
+use limit::RequestLimits;
+use rate_limit::IpRateLimiter;
+use request_log::RequestLog;
 use serde::de::DeserializeOwned;
-use std::fmt;
-use tower_http::{cors::CorsLayer, trace::TraceLayer};
+use std::{
+    fmt,
+    time::{Duration, Instant},
+};
+use tower_http::{
+    compression::{
+        predicate::{DefaultPredicate, Predicate, SizeAbove},
+        CompressionLayer,
+    },
+    limit::RequestBodyLimitLayer,
+    trace::TraceLayer,
+};
 
+mod auth;
 mod config;
-pub use config::ServerConfig;
+mod cors;
+pub use config::{
+    ServerConfig, DEFAULT_MAX_ADMIN_REQUEST_SIZE, DEFAULT_MAX_REQUEST_SIZE, DEFAULT_WS_PING_TIMEOUT,
+};
 
 mod error;
 mod handler;
+mod limit;
 
 mod pubsub;
 pub use pubsub::{PubSubContext, PubSubRpcHandler};
 
+mod rate_limit;
+
+mod request_log;
+pub use request_log::REQUEST_LOG_TARGET;
+
 mod ws;
 
 #[cfg(feature = "ipc")]
 pub mod ipc;
 
+/// Responses smaller than this many bytes are not compressed
+const COMPRESSION_MIN_SIZE: u16 = 1024;
+
+/// Method namespaces of admin methods that mutate the state of the node
+const ADMIN_NAMESPACES: [&str; 3] = ["anvil_", "hardhat_", "evm_"];
+
+/// Returns true if the method belongs to one of the admin namespaces
+pub(crate) fn is_admin_method(method: &str) -> bool {
+    ADMIN_NAMESPACES.iter().any(|namespace| method.starts_with(namespace))
+}
+
 /// Configures an [`axum::Router`] that handles JSON-RPC calls via both HTTP and WS.
 pub fn http_ws_router<Http, Ws>(config: ServerConfig, http: Http, ws: Ws) -> Router
 where
     Http: RpcHandler,
     Ws: PubSubRpcHandler,
 {
-    router_inner(config, post(handler::handle).get(ws::handle_ws), (http, ws))
+    let keepalive = config.ws_keepalive();
+    let limits = config.request_limits();
+    let ws_handler = move |ws: WebSocketUpgrade,
+                           state: State<(Http, Ws)>,
+                           authorized: Option<Extension<Authorized>>| {
+        // the extension is only set if authentication is enabled
+        let authorized = authorized.map_or(true, |Extension(Authorized(authorized))| authorized);
+        ws::handle_ws(ws, state, keepalive, limits, authorized)
+    };
+    router_inner(config, http_method_router(limits).get(ws_handler), (http, ws))
 }
 
 /// Configures an [`axum::Router`] that handles JSON-RPC calls via HTTP.
@@ -47,7 +95,21 @@ pub fn http_router<Http>(config: ServerConfig, http: Http) -> Router
 where
     Http: RpcHandler,
 {
-    router_inner(config, post(handler::handle), (http, ()))
+    let limits = config.request_limits();
+    router_inner(config, http_method_router(limits), (http, ()))
+}
+
+/// Returns the [`MethodRouter`] that handles JSON-RPC calls via HTTP POST.
+fn http_method_router<Http, Ws>(limits: RequestLimits) -> MethodRouter<(Http, Ws)>
+where
+    Http: RpcHandler,
+    Ws: Clone + Send + Sync + 'static,
+{
+    post(
+        move |state: State<(Http, Ws)>, headers: HeaderMap, body: Result<Bytes, BytesRejection>| {
+            handler::handle(state, headers, body, limits)
+        },
+    )
 }
 
 fn router_inner<S: Clone + Send + Sync + 'static>(
@@ -55,22 +117,63 @@ fn router_inner<S: Clone + Send + Sync + 'static>(
     root_method_router: MethodRouter<S>,
     state: S,
 ) -> Router {
-    let ServerConfig { allow_origin, no_cors } = config;
+    let limits = config.request_limits();
+    let ServerConfig {
+        allowed_origins,
+        no_cors,
+        no_compression,
+        auth_token,
+        ip_rate_limit,
+        ip_rate_limit_burst,
+        trust_forwarded_for,
+        log_request_params,
+        ..
+    } = config;
 
     let mut router = Router::new()
         .route("/", root_method_router)
         .with_state(state)
-        .layer(TraceLayer::new_for_http());
-    if !no_cors {
+        // the body limit is enforced by the `RequestBodyLimitLayer` instead
+        .layer(DefaultBodyLimit::disable());
+    if let Some(token) = auth_token {
+        // this buffers the request body, so it must be wrapped by the body limit layer
+        router = router.layer(middleware::from_fn_with_state(
+            BearerAuth::new(token),
+            auth::require_admin_auth,
+        ));
+    }
+    // this buffers the request body, so it must be wrapped by the body limit layer
+    router = router.layer(middleware::from_fn_with_state(
+        RequestLog { log_params: log_request_params },
+        request_log::log_requests,
+    ));
+    router = router
+        .layer(RequestBodyLimitLayer::new(limits.body_limit()))
+        .layer(middleware::map_response(limit::map_payload_too_large));
+    if !no_compression {
+        // only applies to HTTP responses, websocket messages are sent over the upgraded connection
         router = router.layer(
-            // see https://docs.rs/tower-http/latest/tower_http/cors/index.html
-            // for more details
-            CorsLayer::new()
-                .allow_origin(allow_origin.0)
-                .allow_headers([header::CONTENT_TYPE])
-                .allow_methods([Method::GET, Method::POST]),
+            CompressionLayer::new()
+                .gzip(true)
+                .br(true)
+                .compress_when(DefaultPredicate::new().and(SizeAbove::new(COMPRESSION_MIN_SIZE))),
         );
     }
+    router = router.layer(TraceLayer::new_for_http());
+    if let Some(requests_per_second) = ip_rate_limit {
+        let burst = ip_rate_limit_burst.unwrap_or(requests_per_second);
+        let limiter = IpRateLimiter::new(requests_per_second, burst, trust_forwarded_for);
+        router = router.layer(middleware::from_fn_with_state(limiter, rate_limit::limit_by_ip));
+    }
+    if !no_cors {
+        let origins = AllowedOrigins::new(&allowed_origins);
+        router = router.layer(origins.layer());
+        if let AllowedOrigins::List(_) = origins {
+            // this must wrap the cors layer so that preflight requests are rejected as well
+            router = router
+                .layer(middleware::from_fn_with_state(origins, cors::reject_disallowed_origin));
+        }
+    }
     router
 }
 
@@ -93,6 +196,7 @@ pub trait RpcHandler: Clone + Send + Sync + 'static {
     /// "<name>", "params": "<params>" }`
     async fn on_call(&self, call: RpcMethodCall) -> RpcResponse {
         trace!(target: "rpc",  id = ?call.id , method = ?call.method, "received method call");
+        let start = Instant::now();
         let RpcMethodCall { method, params, id, .. } = call;
 
         let params: serde_json::Value = params.into();
@@ -101,7 +205,7 @@ pub trait RpcHandler: Clone + Send + Sync + 'static {
             "params": params
         });
 
-        match serde_json::from_value::<Self::Request>(call) {
+        let response = match serde_json::from_value::<Self::Request>(call) {
             Ok(req) => {
                 let result = self.on_request(req).await;
                 RpcResponse::new(id, result)
@@ -116,6 +220,13 @@ pub trait RpcHandler: Clone + Send + Sync + 'static {
                     RpcResponse::new(id, RpcError::invalid_params(err))
                 }
             }
-        }
+        };
+        self.on_call_complete(&method, start.elapsed(), &response);
+        response
     }
+
+    /// Invoked after a method call was handled, `elapsed` is the time it took to handle the call.
+    ///
+    /// This can be used to collect metrics, the default implementation does nothing.
+    fn on_call_complete(&self, _method: &str, _elapsed: Duration, _response: &RpcResponse) {}
 }
@@ -0,0 +1,87 @@
+//! Limits for the size of incoming requests
+
+use crate::is_admin_method;
+use anvil_rpc::{
+    error::{ErrorCode, RpcError},
+    request::Request,
+    response::Response,
+};
+use axum::{
+    http::{header, StatusCode},
+    response::{IntoResponse, Response as HttpResponse},
+    Json,
+};
+
+/// The size limits for incoming requests
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct RequestLimits {
+    /// The maximum size of a request in bytes
+    pub max_request_size: usize,
+    /// The maximum size of a request in bytes that only calls admin methods
+    pub max_admin_request_size: usize,
+    /// The maximum number of calls in a batch request
+    pub max_batch_size: Option<usize>,
+}
+
+impl RequestLimits {
+    /// Returns the limit that is enforced before the request is parsed
+    pub fn body_limit(&self) -> usize {
+        self.max_request_size.max(self.max_admin_request_size)
+    }
+
+    /// Returns true if the request, `len` bytes in size, exceeds the limit for the methods it
+    /// calls
+    pub fn exceeds(&self, len: usize, request: &Request) -> bool {
+        if len <= self.max_request_size {
+            return false
+        }
+        len > self.max_admin_request_size ||
+            !request.calls().iter().all(|call| call.method().map_or(false, is_admin_method))
+    }
+
+    /// Returns an error if the request is a batch with more calls than allowed
+    pub fn check_batch_size(&self, request: &Request) -> Result<(), RpcError> {
+        match (request, self.max_batch_size) {
+            (Request::Batch(calls), Some(max)) if calls.len() > max => {
+                Err(batch_too_large(calls.len(), max))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// The error returned for requests that exceed the configured size limit
+pub(crate) fn request_too_large() -> RpcError {
+    RpcError {
+        code: ErrorCode::InvalidRequest,
+        message: "Request exceeds the maximum allowed size".into(),
+        data: None,
+    }
+}
+
+/// The error returned for batch requests with more calls than allowed
+pub(crate) fn batch_too_large(len: usize, max: usize) -> RpcError {
+    RpcError {
+        code: ErrorCode::InvalidRequest,
+        message: format!("Batch of {len} calls exceeds the maximum batch size of {max}").into(),
+        data: None,
+    }
+}
+
+/// Returns the `413 Payload Too Large` response with a JSON-RPC error body
+pub(crate) fn payload_too_large() -> HttpResponse {
+    (StatusCode::PAYLOAD_TOO_LARGE, Json(Response::error(request_too_large()))).into_response()
+}
+
+/// Replaces the plain text body of `413` responses produced by the body limit layer with a
+/// JSON-RPC error
+pub(crate) async fn map_payload_too_large(response: HttpResponse) -> HttpResponse {
+    let is_json = response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .map_or(false, |content_type| content_type.as_bytes().starts_with(b"application/json"));
+    if response.status() == StatusCode::PAYLOAD_TOO_LARGE && !is_json {
+        return payload_too_large()
+    }
+    response
+}
@@ -2,7 +2,7 @@ use crate::{error::RequestError, handler::handle_request, RpcHandler};
 use anvil_rpc::{
     error::RpcError,
     request::Request,
-    response::{Response, ResponseResult},
+    response::{Response, ResponseResult, RpcResponse},
 };
 
 use futures::{FutureExt, Sink, SinkExt, Stream, StreamExt};
@@ -16,6 +16,7 @@ use std::{
     pin::Pin,
     sync::Arc,
     task::{Context, Poll},
+    time::Duration,
 };
 
 /// The general purpose trait for handling RPC requests and subscriptions
@@ -30,6 +31,9 @@ pub trait PubSubRpcHandler: Clone + Send + Sync + Unpin + 'static {
 
     /// Invoked when the request was received
     async fn on_request(&self, request: Self::Request, cx: PubSubContext<Self>) -> ResponseResult;
+
+    /// Invoked after a method call was handled, see [RpcHandler::on_call_complete]
+    fn on_call_complete(&self, _method: &str, _elapsed: Duration, _response: &RpcResponse) {}
 }
 
 type Subscriptions<SubscriptionId, Subscription> = Arc<Mutex<Vec<(SubscriptionId, Subscription)>>>;
@@ -107,6 +111,10 @@ impl<Handler: PubSubRpcHandler> RpcHandler for ContextAwareHandler<Handler> {
     async fn on_request(&self, request: Self::Request) -> ResponseResult {
         self.handler.on_request(request, self.context.clone()).await
     }
+
+    fn on_call_complete(&self, method: &str, elapsed: Duration, response: &RpcResponse) {
+        self.handler.on_call_complete(method, elapsed, response)
+    }
 }
 
 /// Represents a connection to a client via websocket
@@ -143,17 +151,14 @@ impl<Handler: PubSubRpcHandler, Connection> PubSubConnection<Handler, Connection
         ContextAwareHandler { handler: self.handler.clone(), context: self.context.clone() }
     }
 
-    fn process_request(&mut self, req: serde_json::Result<Request>) {
+    fn process_request(&mut self, req: Result<Request, RpcError>) {
         let handler = self.compat_helper();
         self.processing.push(Box::pin(async move {
             match req {
                 Ok(req) => handle_request(req, handler)
                     .await
                     .unwrap_or_else(|| Response::error(RpcError::invalid_request())),
-                Err(err) => {
-                    error!(target: "rpc", ?err, "invalid request");
-                    Response::error(RpcError::invalid_request())
-                }
+                Err(err) => Response::error(err),
             }
         }));
     }
@@ -206,6 +211,11 @@ where
                                 return Poll::Ready(())
                             }
                             RequestError::Serde(err) => {
+                                error!(target: "rpc", ?err, "invalid request");
+                                pin.process_request(Err(RpcError::invalid_request()));
+                            }
+                            RequestError::Rejected(err) => {
+                                warn!(target: "rpc", ?err, "rejected request");
                                 pin.process_request(Err(err));
                             }
                             RequestError::Disconnect => {
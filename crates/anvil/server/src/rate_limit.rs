@@ -0,0 +1,198 @@
+//! Per client IP rate limiting
+
+use anvil_rpc::{
+    error::{ErrorCode, RpcError},
+    response::Response as RpcResponse,
+};
+use axum::{
+    extract::{ConnectInfo, Request, State},
+    http::{header, HeaderMap, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+};
+use parking_lot::Mutex;
+use std::{
+    collections::HashMap,
+    net::{IpAddr, SocketAddr},
+    sync::Arc,
+    time::Instant,
+};
+
+/// The JSON-RPC error code returned for rate limited requests
+pub(crate) const RATE_LIMITED_ERROR_CODE: i64 = -32005;
+
+/// The max number of tracked clients, once reached buckets of idle clients are evicted
+const MAX_TRACKED_CLIENTS: usize = 10_000;
+
+/// A token bucket rate limiter, keyed by the IP address of the client.
+#[derive(Clone, Debug)]
+pub(crate) struct IpRateLimiter {
+    inner: Arc<RateLimiterInner>,
+}
+
+#[derive(Debug)]
+struct RateLimiterInner {
+    /// The number of tokens that are added to a bucket per second
+    requests_per_second: f64,
+    /// The capacity of a bucket
+    burst: f64,
+    /// Whether to identify clients by the `X-Forwarded-For` header
+    trust_forwarded_for: bool,
+    buckets: Mutex<HashMap<IpAddr, Bucket>>,
+}
+
+#[derive(Debug)]
+struct Bucket {
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl IpRateLimiter {
+    /// Creates a new rate limiter that allows `requests_per_second` requests per client, with
+    /// bursts of up to `burst` requests.
+    pub fn new(requests_per_second: u32, burst: u32, trust_forwarded_for: bool) -> Self {
+        Self {
+            inner: Arc::new(RateLimiterInner {
+                requests_per_second: requests_per_second as f64,
+                burst: burst.max(1) as f64,
+                trust_forwarded_for,
+                buckets: Default::default(),
+            }),
+        }
+    }
+
+    /// Takes a token from the bucket of the client, returns false if the bucket is empty
+    fn try_acquire(&self, ip: IpAddr) -> bool {
+        let RateLimiterInner { requests_per_second, burst, .. } = *self.inner;
+        let now = Instant::now();
+        let mut buckets = self.inner.buckets.lock();
+
+        if buckets.len() >= MAX_TRACKED_CLIENTS && !buckets.contains_key(&ip) {
+            // evict clients whose buckets have been refilled completely, those are idle
+            buckets.retain(|_, bucket| {
+                let elapsed = now.duration_since(bucket.last_refill).as_secs_f64();
+                bucket.tokens + elapsed * requests_per_second < burst
+            });
+            // if all clients are active, the least recently seen one is evicted
+            if buckets.len() >= MAX_TRACKED_CLIENTS {
+                let oldest = buckets.iter().min_by_key(|(_, bucket)| bucket.last_refill);
+                if let Some(ip) = oldest.map(|(ip, _)| *ip) {
+                    buckets.remove(&ip);
+                }
+            }
+        }
+
+        let bucket = buckets.entry(ip).or_insert(Bucket { tokens: burst, last_refill: now });
+        let elapsed = now.duration_since(bucket.last_refill).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * requests_per_second).min(burst);
+        bucket.last_refill = now;
+        if bucket.tokens < 1.0 {
+            return false
+        }
+        bucket.tokens -= 1.0;
+        true
+    }
+
+    /// Returns the IP address that identifies the client of the request
+    fn client_ip(&self, request: &Request) -> Option<IpAddr> {
+        if self.inner.trust_forwarded_for {
+            if let Some(ip) = forwarded_for(request.headers()) {
+                return Some(ip)
+            }
+        }
+        request.extensions().get::<ConnectInfo<SocketAddr>>().map(|ConnectInfo(addr)| addr.ip())
+    }
+}
+
+/// Returns the client IP address appended by the closest proxy to the `X-Forwarded-For` header.
+///
+/// The last entry is used because all previous entries are provided by the client and can't be
+/// trusted.
+fn forwarded_for(headers: &HeaderMap) -> Option<IpAddr> {
+    headers
+        .get_all("x-forwarded-for")
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .last()
+        .and_then(|ip| ip.trim().parse().ok())
+}
+
+/// The error returned for requests of clients that exceeded the rate limit
+pub(crate) fn rate_limited() -> RpcError {
+    RpcError {
+        code: ErrorCode::ServerError(RATE_LIMITED_ERROR_CODE),
+        message: "Rate limit exceeded, please slow down".into(),
+        data: None,
+    }
+}
+
+/// Rejects requests of clients that exceeded the rate limit with `429 Too Many Requests`.
+///
+/// Requests whose client can't be identified are not limited.
+pub(crate) async fn limit_by_ip(
+    State(limiter): State<IpRateLimiter>,
+    request: Request,
+    next: Next,
+) -> Response {
+    if let Some(ip) = limiter.client_ip(&request) {
+        if !limiter.try_acquire(ip) {
+            trace!(target: "rpc::rate_limit", %ip, "rate limit exceeded");
+            return (
+                StatusCode::TOO_MANY_REQUESTS,
+                [(header::RETRY_AFTER, "1")],
+                Json(RpcResponse::error(rate_limited())),
+            )
+                .into_response()
+        }
+    }
+    next.run(request).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn refills_bucket() {
+        let limiter = IpRateLimiter::new(1, 2, false);
+        let ip = IpAddr::from([127, 0, 0, 1]);
+        assert!(limiter.try_acquire(ip));
+        assert!(limiter.try_acquire(ip));
+        assert!(!limiter.try_acquire(ip));
+
+        // other clients have their own bucket
+        assert!(limiter.try_acquire(IpAddr::from([127, 0, 0, 2])));
+
+        limiter.inner.buckets.lock().get_mut(&ip).unwrap().last_refill -=
+            std::time::Duration::from_secs(1);
+        assert!(limiter.try_acquire(ip));
+        assert!(!limiter.try_acquire(ip));
+    }
+
+    #[test]
+    fn caps_tracked_clients() {
+        let limiter = IpRateLimiter::new(1, 1, false);
+        let first = IpAddr::from(0u32.to_be_bytes());
+        // every client is active, so none of the buckets is refilled completely
+        for i in 0..MAX_TRACKED_CLIENTS as u32 {
+            assert!(limiter.try_acquire(IpAddr::from(i.to_be_bytes())));
+        }
+        limiter.inner.buckets.lock().get_mut(&first).unwrap().last_refill -=
+            std::time::Duration::from_millis(100);
+
+        // the least recently seen client makes room for the new one
+        assert!(limiter.try_acquire(IpAddr::from((MAX_TRACKED_CLIENTS as u32).to_be_bytes())));
+        let buckets = limiter.inner.buckets.lock();
+        assert_eq!(buckets.len(), MAX_TRACKED_CLIENTS);
+        assert!(!buckets.contains_key(&first));
+    }
+
+    #[test]
+    fn uses_last_forwarded_for() {
+        let mut headers = HeaderMap::new();
+        headers.insert("x-forwarded-for", "1.1.1.1, 2.2.2.2".parse().unwrap());
+        assert_eq!(forwarded_for(&headers), Some(IpAddr::from([2, 2, 2, 2])));
+    }
+}
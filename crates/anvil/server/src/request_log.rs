@@ -0,0 +1,163 @@
+//! Structured logging of HTTP JSON-RPC requests
+
+use crate::limit;
+use anvil_rpc::{
+    request::{Id, Request as RpcRequest, RequestParams, RpcCall},
+    response::{Response as RpcResponse, ResponseResult, RpcResponse as RpcCallResponse},
+};
+use axum::{
+    body::{to_bytes, Body, Bytes},
+    extract::{Request, State},
+    http::{Method, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use serde::Serialize;
+use std::time::{Duration, Instant};
+
+/// The target of the request log events, every call is logged as a single JSON line
+pub const REQUEST_LOG_TARGET: &str = "rpc::request_log";
+
+/// Configures the request log
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct RequestLog {
+    /// Whether to include the params of the calls, these may contain sensitive data
+    pub log_params: bool,
+}
+
+/// A single line of the request log
+#[derive(Debug, Serialize)]
+struct CallLog<'a> {
+    /// The method of the call, `None` for invalid calls
+    method: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    id: Option<&'a Id>,
+    /// The size of the JSON encoded params in bytes
+    params_size: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    params: Option<&'a RequestParams>,
+    /// The number of calls of the batch this call was part of
+    #[serde(skip_serializing_if = "Option::is_none")]
+    batch_size: Option<usize>,
+    /// The time it took to handle the entire request, including all calls of a batch
+    duration_ms: f64,
+    /// The size of the JSON encoded result in bytes, `None` if the call failed
+    result_size: Option<usize>,
+    error_code: Option<i64>,
+}
+
+impl RequestLog {
+    /// Logs every call of the request
+    fn log(&self, request: &RpcRequest, response: Option<&RpcResponse>, elapsed: Duration) {
+        let batch_size = match request {
+            RpcRequest::Single(_) => None,
+            RpcRequest::Batch(calls) => Some(calls.len()),
+        };
+        let duration_ms = elapsed.as_secs_f64() * 1000.0;
+
+        for call in request.calls() {
+            let (id, params) = match call {
+                RpcCall::MethodCall(call) => (Some(&call.id), Some(&call.params)),
+                RpcCall::Notification(notification) => (None, Some(&notification.params)),
+                RpcCall::Invalid { id } => (Some(id), None),
+            };
+            let result = response.and_then(|response| response_of(response, id));
+            let (result_size, error_code) = match result.map(|response| response.result()) {
+                Some(ResponseResult::Success(value)) => (Some(json_size(value)), None),
+                Some(ResponseResult::Error(err)) => (None, Some(err.code.code())),
+                None => (None, None),
+            };
+            let line = CallLog {
+                method: call.method(),
+                id,
+                params_size: params.map_or(0, json_size),
+                params: params.filter(|_| self.log_params),
+                batch_size,
+                duration_ms,
+                result_size,
+                error_code,
+            };
+            if let Ok(line) = serde_json::to_string(&line) {
+                info!(target: REQUEST_LOG_TARGET, "{line}");
+            }
+        }
+    }
+}
+
+/// Returns the response to the call with the given id, notifications don't have a response
+fn response_of<'a>(response: &'a RpcResponse, id: Option<&Id>) -> Option<&'a RpcCallResponse> {
+    let id = id?;
+    match response {
+        RpcResponse::Single(response) => Some(response),
+        RpcResponse::Batch(responses) => {
+            responses.iter().find(|response| response.id() == Some(id))
+        }
+    }
+}
+
+fn json_size(value: &impl Serialize) -> usize {
+    serde_json::to_vec(value).map_or(0, |value| value.len())
+}
+
+/// Logs every call of HTTP JSON-RPC requests to the [REQUEST_LOG_TARGET].
+///
+/// The request and response bodies are only buffered if the target is enabled. Calls made over
+/// websocket connections are not logged.
+pub(crate) async fn log_requests(
+    State(log): State<RequestLog>,
+    request: Request,
+    next: Next,
+) -> Response {
+    if request.method() != Method::POST ||
+        !tracing::enabled!(target: REQUEST_LOG_TARGET, tracing::Level::INFO)
+    {
+        return next.run(request).await
+    }
+
+    let started = Instant::now();
+    // the body is already bounded by the body limit layer
+    let (parts, body) = request.into_parts();
+    let body = match to_bytes(body, usize::MAX).await {
+        Ok(body) => body,
+        Err(err) => {
+            trace!(target: "rpc", ?err, "failed to read request body");
+            return limit::payload_too_large()
+        }
+    };
+    // invalid requests are rejected by the handler and not logged
+    let rpc_request = serde_json::from_slice::<RpcRequest>(&body).ok();
+
+    let response = next.run(Request::from_parts(parts, Body::from(body))).await;
+    let Some(rpc_request) = rpc_request else { return response };
+
+    let (parts, body) = response.into_parts();
+    let body: Bytes = match to_bytes(body, usize::MAX).await {
+        Ok(body) => body,
+        Err(err) => {
+            error!(target: "rpc", ?err, "failed to read response body");
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
+    };
+    let rpc_response = serde_json::from_slice::<RpcResponse>(&body).ok();
+    log.log(&rpc_request, rpc_response.as_ref(), started.elapsed());
+
+    Response::from_parts(parts, Body::from(body))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_response_of_batched_call() {
+        let response: RpcResponse = serde_json::from_str(
+            r#"[{"jsonrpc":"2.0","id":1,"result":"0x1"},{"jsonrpc":"2.0","id":2,"error":{"code":-32601,"message":"Method not found"}}]"#,
+        )
+        .unwrap();
+
+        let result = response_of(&response, Some(&Id::Number(2))).unwrap();
+        assert_eq!(result.result().as_error().unwrap().code.code(), -32601);
+        assert!(response_of(&response, Some(&Id::Number(3))).is_none());
+        assert!(response_of(&response, None).is_none());
+    }
+}
@@ -1,4 +1,10 @@
-use crate::{error::RequestError, pubsub::PubSubConnection, PubSubRpcHandler};
+use crate::{
+    auth,
+    error::RequestError,
+    limit::{self, RequestLimits},
+    pubsub::PubSubConnection,
+    PubSubRpcHandler,
+};
 use anvil_rpc::request::Request;
 use axum::{
     extract::{
@@ -9,29 +15,138 @@ use axum::{
 };
 use futures::{ready, Sink, Stream};
 use std::{
+    future::Future,
     pin::Pin,
     task::{Context, Poll},
+    time::Duration,
 };
+use tokio::time::{Instant, Interval, MissedTickBehavior, Sleep};
 
 /// Handles incoming Websocket upgrade
 ///
 /// This is the entrypoint invoked by the axum server for a websocket request
-pub async fn handle_ws<Http, Ws: PubSubRpcHandler>(
+///
+/// If `keepalive` is set to `(interval, timeout)`, a ping is sent every `interval` and the
+/// connection is closed if the client does not respond within `timeout`.
+///
+/// If the client is not `authorized`, calls to admin methods are rejected.
+pub(crate) async fn handle_ws<Http, Ws: PubSubRpcHandler>(
     ws: WebSocketUpgrade,
     State((_, handler)): State<(Http, Ws)>,
+    keepalive: Option<(Duration, Duration)>,
+    limits: RequestLimits,
+    authorized: bool,
 ) -> Response {
-    ws.on_upgrade(|socket| PubSubConnection::new(SocketConn(socket), handler))
+    ws.max_message_size(limits.body_limit()).on_upgrade(move |socket| {
+        PubSubConnection::new(SocketConn::new(socket, keepalive, limits, authorized), handler)
+    })
 }
 
 #[pin_project::pin_project]
-struct SocketConn(#[pin] WebSocket);
+struct SocketConn {
+    #[pin]
+    socket: WebSocket,
+    /// Tracks the liveness of the connection, if enabled
+    keepalive: Option<KeepAlive>,
+    /// The size limits for incoming messages
+    limits: RequestLimits,
+    /// Whether the client is allowed to call admin methods
+    authorized: bool,
+}
+
+impl SocketConn {
+    fn new(
+        socket: WebSocket,
+        keepalive: Option<(Duration, Duration)>,
+        limits: RequestLimits,
+        authorized: bool,
+    ) -> Self {
+        Self {
+            socket,
+            keepalive: keepalive.map(|(interval, timeout)| KeepAlive::new(interval, timeout)),
+            limits,
+            authorized,
+        }
+    }
+}
+
+/// Sends periodic pings and tracks whether the client is still responsive
+struct KeepAlive {
+    /// Fires whenever a new ping should be sent
+    interval: Interval,
+    /// How long to wait for any message after a ping was sent
+    timeout: Duration,
+    /// Whether a ping should be sent as soon as the socket is ready
+    ping_due: bool,
+    /// Deadline for the client to respond to the outstanding ping
+    deadline: Option<Pin<Box<Sleep>>>,
+}
+
+impl KeepAlive {
+    fn new(interval: Duration, timeout: Duration) -> Self {
+        let mut interval = tokio::time::interval_at(Instant::now() + interval, interval);
+        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        Self { interval, timeout, ping_due: false, deadline: None }
+    }
+
+    /// Returns true if the client did not respond to the outstanding ping in time
+    fn poll_expired(&mut self, cx: &mut Context<'_>) -> bool {
+        self.deadline
+            .as_mut()
+            .map(|deadline| deadline.as_mut().poll(cx).is_ready())
+            .unwrap_or(false)
+    }
+
+    /// Invoked when a ping was sent
+    fn on_ping(&mut self) {
+        self.ping_due = false;
+        if self.deadline.is_none() {
+            self.deadline = Some(Box::pin(tokio::time::sleep(self.timeout)));
+        }
+    }
+
+    /// Invoked for every incoming message, any message, not just pongs, proves that the client is
+    /// still alive
+    fn on_message(&mut self) {
+        self.deadline = None;
+    }
+}
 
 impl Stream for SocketConn {
     type Item = Result<Option<Request>, RequestError>;
 
     fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
-        match ready!(self.project().0.poll_next(cx)) {
-            Some(msg) => Poll::Ready(Some(on_message(msg))),
+        let mut this = self.project();
+
+        if let Some(keepalive) = this.keepalive.as_mut() {
+            if keepalive.poll_expired(cx) {
+                trace!(target: "rpc::ws", "ws client did not respond to ping");
+                return Poll::Ready(Some(Err(RequestError::Disconnect)))
+            }
+
+            if keepalive.interval.poll_tick(cx).is_ready() {
+                keepalive.ping_due = true;
+            }
+
+            if keepalive.ping_due {
+                if let Poll::Ready(Ok(())) = this.socket.as_mut().poll_ready(cx) {
+                    if this.socket.as_mut().start_send(Message::Ping(Vec::new())).is_ok() {
+                        trace!(target: "rpc::ws", "sent ping");
+                        keepalive.on_ping();
+                        // errors are surfaced by the next read or flush of the connection
+                        let _ = this.socket.as_mut().poll_flush(cx);
+                    }
+                }
+            }
+        }
+
+        match ready!(this.socket.poll_next(cx)) {
+            Some(msg) => {
+                if let Some(keepalive) = this.keepalive.as_mut() {
+                    keepalive.on_message();
+                }
+                Poll::Ready(Some(on_message(msg, this.limits, *this.authorized)))
+            }
             _ => Poll::Ready(None),
         }
     }
@@ -41,29 +156,44 @@ impl Sink<String> for SocketConn {
     type Error = axum::Error;
 
     fn poll_ready(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
-        self.project().0.poll_ready(cx)
+        self.project().socket.poll_ready(cx)
     }
 
     fn start_send(self: Pin<&mut Self>, item: String) -> Result<(), Self::Error> {
-        self.project().0.start_send(Message::Text(item))
+        self.project().socket.start_send(Message::Text(item))
     }
 
     fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
-        self.project().0.poll_flush(cx)
+        self.project().socket.poll_flush(cx)
     }
 
     fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
-        self.project().0.poll_close(cx)
+        self.project().socket.poll_close(cx)
     }
 }
 
-fn on_message(msg: Result<Message, axum::Error>) -> Result<Option<Request>, RequestError> {
+fn on_message(
+    msg: Result<Message, axum::Error>,
+    limits: &RequestLimits,
+    authorized: bool,
+) -> Result<Option<Request>, RequestError> {
+    let parse = |data: &[u8]| -> Result<Option<Request>, RequestError> {
+        let req: Request = serde_json::from_slice(data)?;
+        if limits.exceeds(data.len(), &req) {
+            return Err(RequestError::Rejected(limit::request_too_large()))
+        }
+        limits.check_batch_size(&req).map_err(RequestError::Rejected)?;
+        if !authorized && auth::calls_admin_method(&req) {
+            return Err(RequestError::Rejected(auth::unauthorized()))
+        }
+        Ok(Some(req))
+    };
     match msg? {
-        Message::Text(text) => Ok(Some(serde_json::from_str(&text)?)),
+        Message::Text(text) => parse(text.as_bytes()),
         Message::Binary(data) => {
             // the binary payload type is the request as-is but as bytes, if this is a valid
             // `Request` then we can deserialize the Json from the data Vec
-            Ok(Some(serde_json::from_slice(&data)?))
+            parse(&data)
         }
         Message::Close(_) => {
             trace!(target: "rpc::ws", "ws client disconnected");
@@ -4,15 +4,19 @@ use crate::{
             db::{Db, SerializableState},
             fork::{ClientFork, ClientForkConfig},
             genesis::GenesisConfig,
-            mem::fork_db::ForkedDatabase,
+            mem::{fork_db::ForkedDatabase, DEFAULT_STEPS_TRACING_CACHE_SIZE},
             time::duration_since_unix_epoch,
         },
-        fees::{INITIAL_BASE_FEE, INITIAL_GAS_PRICE},
-        pool::transactions::TransactionOrder,
+        fees::{BaseFeeParams, GasPriceOracle, INITIAL_BASE_FEE, INITIAL_GAS_PRICE},
+        pool::transactions::{TransactionOrder, DEFAULT_MIN_FEE_BUMP_PERCENT},
+        signatures::SignatureDatabase,
     },
+    filter::ACTIVE_FILTER_TIMEOUT_SECS,
     genesis::Genesis,
+    logging::DEFAULT_NODE_LOG_BUFFER_SIZE,
     mem,
     mem::in_memory_db::MemDb,
+    server::error::{NodeError, NodeResult},
     FeeManager, Hardfork,
 };
 use alloy_primitives::{hex, U256};
@@ -30,11 +34,11 @@ use ethers::{
     utils::WEI_IN_ETHER,
 };
 use foundry_common::{
-    provider::alloy::ProviderBuilder,
+    provider::alloy::{ProviderBuilder, RetryProvider},
     types::{ToAlloy, ToEthers},
     ALCHEMY_FREE_TIER_CUPS, NON_ARCHIVE_NODE_WARNING, REQUEST_TIMEOUT,
 };
-use foundry_config::Config;
+use foundry_config::{Chain, Config};
 use foundry_evm::{
     constants::DEFAULT_CREATE2_DEPLOYER,
     fork::{BlockchainDb, BlockchainDbMeta, SharedBackend},
@@ -102,6 +106,8 @@ pub struct NodeConfig {
     pub gas_price: Option<U256>,
     /// Default base fee
     pub base_fee: Option<U256>,
+    /// The parameters of the base fee calculation
+    pub base_fee_params: BaseFeeParams,
     /// The hardfork to use
     pub hardfork: Option<Hardfork>,
     /// Signer accounts that will be initialised with `genesis_balance` in the genesis block
@@ -114,6 +120,8 @@ pub struct NodeConfig {
     pub signer_accounts: Vec<Wallet<SigningKey>>,
     /// Configured block time for the EVM chain. Use `None` to mine a new block for every tx
     pub block_time: Option<Duration>,
+    /// The maximum deviation of the interval between blocks from the `block_time`
+    pub block_time_jitter: Option<Duration>,
     /// Disable auto, interval mining mode uns use `MiningMode::None` instead
     pub no_mining: bool,
     /// port to use for the server
@@ -122,6 +130,8 @@ pub struct NodeConfig {
     pub max_transactions: usize,
     /// don't print anything on startup
     pub silent: bool,
+    /// The number of recent node log lines that are kept for `anvil_getNodeLogs`, `0` disables it
+    pub node_log_buffer_size: usize,
     /// url of the rpc server that should be used for any rpc calls
     pub eth_rpc_url: Option<String>,
     /// pins the block number for the state fork
@@ -136,16 +146,26 @@ pub struct NodeConfig {
     pub enable_tracing: bool,
     /// Explicitly disables the use of RPC caching.
     pub no_storage_caching: bool,
+    /// The directory of the on-disk fork cache, defaults to `~/.foundry/cache/rpc`
+    pub fork_cache_dir: Option<PathBuf>,
     /// How to configure the server
     pub server_config: ServerConfig,
     /// The host the server will listen on
     pub host: Vec<IpAddr>,
     /// How transactions are sorted in the mempool
     pub transaction_order: TransactionOrder,
+    /// The percentage by which a replacement transaction must raise the gas price
+    pub min_fee_bump_percent: u64,
+    /// The max number of transactions in the mempool
+    pub txpool_max_count: Option<usize>,
+    /// The max total size of the transactions in the mempool in bytes
+    pub txpool_max_bytes: Option<usize>,
     /// Filename to write anvil output as json
     pub config_out: Option<String>,
     /// The genesis to use to initialize the node
     pub genesis: Option<Genesis>,
+    /// The function signatures used to decode traces
+    pub signatures: SignatureDatabase,
     /// Timeout in for requests sent to remote JSON-RPC server in forking mode
     pub fork_request_timeout: Duration,
     /// Number of request retries for spurious networks
@@ -158,10 +178,14 @@ pub struct NodeConfig {
     pub ipc_path: Option<Option<String>>,
     /// Enable transaction/call steps tracing for debug calls returning geth-style traces
     pub enable_steps_tracing: bool,
+    /// The max number of mined transactions whose traced steps are kept
+    pub steps_tracing_cache_size: usize,
     /// Enable auto impersonation of accounts on startup
     pub enable_auto_impersonate: bool,
     /// Configure the code size limit
     pub code_size_limit: Option<usize>,
+    /// The max depth of nested calls, deeper calls are reverted
+    pub max_call_depth: Option<u64>,
     /// Configures how to remove historic state.
     ///
     /// If set to `Some(num)` keep latest num state in memory only.
@@ -172,8 +196,22 @@ pub struct NodeConfig {
     pub transaction_block_keeper: Option<usize>,
     /// Disable the default CREATE2 deployer
     pub disable_default_create2_deployer: bool,
+    /// The maximum number of logs `eth_getLogs` returns, queries that match more logs fail
+    pub max_logs_returned: Option<usize>,
+    /// The maximum number of blocks an `eth_getLogs` query that reaches into the forked chain
+    /// may span
+    pub max_logs_block_range: Option<u64>,
+    /// How long a filter is kept after it was last polled
+    pub filter_timeout: Duration,
+    /// Whether `evm_setNextBlockTimestamp` accepts timestamps that aren't greater than the
+    /// timestamp of the last block
+    pub allow_non_increasing_timestamps: bool,
+    /// How `eth_gasPrice` is computed, ignored if a `gas_price` is set
+    pub gas_price_oracle: GasPriceOracle,
     /// Enable Optimism deposit transaction
     pub enable_optimism: bool,
+    /// Additional chains that are served under `/<alias>`, each with its own node
+    pub chains: Vec<(String, NodeConfig)>,
 }
 
 impl NodeConfig {
@@ -289,7 +327,7 @@ Gas Limit
 ==================
 {}
 "#,
-            Paint::green(format!("\n{}", self.gas_limit))
+            Paint::green(format!("\n{}", self.get_gas_limit()))
         );
 
         let _ = write!(
@@ -302,6 +340,18 @@ Genesis Timestamp
             Paint::green(format!("\n{}", self.get_genesis_timestamp()))
         );
 
+        if self.preloads_create2_deployer() {
+            let _ = write!(
+                config_string,
+                r#"
+CREATE2 Deployer
+==================
+{} (disable with --no-create2-deployer)
+"#,
+                Paint::green(format!("\n{DEFAULT_CREATE2_DEPLOYER}"))
+            );
+        }
+
         config_string
     }
 
@@ -334,7 +384,7 @@ Genesis Timestamp
               "wallet": wallet_description,
               "base_fee": format!("{}", self.get_base_fee()),
               "gas_price": format!("{}", self.get_gas_price()),
-              "gas_limit": format!("{}", self.gas_limit),
+              "gas_limit": format!("{}", self.get_gas_limit()),
             })
         } else {
             json!({
@@ -343,7 +393,7 @@ Genesis Timestamp
               "wallet": wallet_description,
               "base_fee": format!("{}", self.get_base_fee()),
               "gas_price": format!("{}", self.get_gas_price()),
-              "gas_limit": format!("{}", self.gas_limit),
+              "gas_limit": format!("{}", self.get_gas_limit()),
               "genesis_timestamp": format!("{}", self.get_genesis_timestamp()),
             })
         }
@@ -377,24 +427,33 @@ impl Default for NodeConfig {
             // 100ETH default balance
             genesis_balance: WEI_IN_ETHER.to_alloy().saturating_mul(U256::from(100u64)),
             block_time: None,
+            block_time_jitter: None,
             no_mining: false,
             port: NODE_PORT,
             // TODO make this something dependent on block capacity
             max_transactions: 1_000,
             silent: false,
+            node_log_buffer_size: DEFAULT_NODE_LOG_BUFFER_SIZE,
             eth_rpc_url: None,
             fork_block_number: None,
             account_generator: None,
             base_fee: None,
+            base_fee_params: Default::default(),
             enable_tracing: true,
             enable_steps_tracing: false,
+            steps_tracing_cache_size: DEFAULT_STEPS_TRACING_CACHE_SIZE,
             enable_auto_impersonate: false,
             no_storage_caching: false,
+            fork_cache_dir: None,
             server_config: Default::default(),
             host: vec![IpAddr::V4(Ipv4Addr::LOCALHOST)],
             transaction_order: Default::default(),
+            min_fee_bump_percent: DEFAULT_MIN_FEE_BUMP_PERCENT,
+            txpool_max_count: None,
+            txpool_max_bytes: None,
             config_out: None,
             genesis: None,
+            signatures: Default::default(),
             fork_request_timeout: REQUEST_TIMEOUT,
             fork_headers: vec![],
             fork_request_retries: 5,
@@ -404,11 +463,18 @@ impl Default for NodeConfig {
             compute_units_per_second: ALCHEMY_FREE_TIER_CUPS,
             ipc_path: None,
             code_size_limit: None,
+            max_call_depth: None,
             prune_history: Default::default(),
             init_state: None,
             transaction_block_keeper: None,
             disable_default_create2_deployer: false,
+            max_logs_returned: None,
+            max_logs_block_range: None,
+            filter_timeout: Duration::from_secs(ACTIVE_FILTER_TIMEOUT_SECS),
+            allow_non_increasing_timestamps: false,
+            gas_price_oracle: Default::default(),
             enable_optimism: false,
+            chains: Vec::new(),
         }
     }
 }
@@ -421,6 +487,15 @@ impl NodeConfig {
             .unwrap_or_else(|| U256::from(INITIAL_BASE_FEE))
     }
 
+    /// Returns the gas limit of the blocks, the `gasLimit` of the genesis takes precedence
+    pub fn get_gas_limit(&self) -> U256 {
+        self.genesis
+            .as_ref()
+            .map(|g| U256::from(g.gas_limit))
+            .filter(|gas_limit| !gas_limit.is_zero())
+            .unwrap_or(self.gas_limit)
+    }
+
     /// Returns the base fee to use
     pub fn get_gas_price(&self) -> U256 {
         self.gas_price.unwrap_or_else(|| U256::from(INITIAL_GAS_PRICE))
@@ -438,6 +513,13 @@ impl NodeConfig {
         self
     }
 
+    /// Sets the max depth of nested calls, this is a testing aid below the EVM's own limit
+    #[must_use]
+    pub fn with_max_call_depth(mut self, max_call_depth: Option<u64>) -> Self {
+        self.max_call_depth = max_call_depth;
+        self
+    }
+
     /// Sets a custom code size limit
     #[must_use]
     pub fn with_init_state(mut self, init_state: Option<SerializableState>) -> Self {
@@ -513,6 +595,57 @@ impl NodeConfig {
         self
     }
 
+    /// Sets the maximum number of logs `eth_getLogs` returns
+    #[must_use]
+    pub fn with_max_logs_returned(mut self, max_logs_returned: Option<usize>) -> Self {
+        self.max_logs_returned = max_logs_returned;
+        self
+    }
+
+    /// Sets the maximum number of blocks an `eth_getLogs` query on a fork may span
+    #[must_use]
+    pub fn with_max_logs_block_range(mut self, max_logs_block_range: Option<u64>) -> Self {
+        self.max_logs_block_range = max_logs_block_range;
+        self
+    }
+
+    /// Sets how long a filter is kept after it was last polled
+    #[must_use]
+    pub fn with_filter_timeout(mut self, filter_timeout: Option<Duration>) -> Self {
+        if let Some(filter_timeout) = filter_timeout {
+            self.filter_timeout = filter_timeout;
+        }
+        self
+    }
+
+    /// Sets whether `evm_setNextBlockTimestamp` accepts timestamps that aren't greater than the
+    /// timestamp of the last block
+    #[must_use]
+    pub fn with_allow_non_increasing_timestamps(mut self, allow: bool) -> Self {
+        self.allow_non_increasing_timestamps = allow;
+        self
+    }
+
+    /// Sets how `eth_gasPrice` is computed if no gas price is set
+    #[must_use]
+    pub fn with_gas_price_oracle(mut self, gas_price_oracle: GasPriceOracle) -> Self {
+        self.gas_price_oracle = gas_price_oracle;
+        self
+    }
+
+    /// Sets whether the default CREATE2 deployer is not preloaded at genesis
+    #[must_use]
+    pub fn with_disable_default_create2_deployer(mut self, disable: bool) -> Self {
+        self.disable_default_create2_deployer = disable;
+        self
+    }
+
+    /// Returns true if the default CREATE2 deployer is preloaded at genesis, forked state is left
+    /// as is
+    pub fn preloads_create2_deployer(&self) -> bool {
+        !self.disable_default_create2_deployer && self.eth_rpc_url.is_none()
+    }
+
     /// Sets the base fee
     #[must_use]
     pub fn with_base_fee(mut self, base_fee: Option<U256>) -> Self {
@@ -520,6 +653,22 @@ impl NodeConfig {
         self
     }
 
+    /// Sets the gas used by a block at which the base fee stays the same
+    #[must_use]
+    pub fn with_gas_target(mut self, gas_target: Option<u64>) -> Self {
+        self.base_fee_params.gas_target = gas_target;
+        self
+    }
+
+    /// Sets the denominator that bounds the amount the base fee can change between blocks
+    #[must_use]
+    pub fn with_base_fee_change_denominator(mut self, denominator: Option<u64>) -> Self {
+        if let Some(denominator) = denominator {
+            self.base_fee_params.max_change_denominator = denominator;
+        }
+        self
+    }
+
     /// Sets the init genesis (genesis.json)
     #[must_use]
     pub fn with_genesis(mut self, genesis: Option<Genesis>) -> Self {
@@ -527,6 +676,15 @@ impl NodeConfig {
         self
     }
 
+    /// Sets the function signatures used to decode traces, in addition to the common signatures
+    #[must_use]
+    pub fn with_signatures(mut self, signatures: Option<SignatureDatabase>) -> Self {
+        if let Some(signatures) = signatures {
+            self.signatures = signatures;
+        }
+        self
+    }
+
     /// Returns the genesis timestamp to use
     pub fn get_genesis_timestamp(&self) -> u64 {
         self.genesis_timestamp
@@ -573,6 +731,20 @@ impl NodeConfig {
         self.with_signer_accounts(accounts.clone()).with_genesis_accounts(accounts)
     }
 
+    /// Derives the next account from the mnemonic of the [AccountGenerator] and adds it to the
+    /// genesis and signer accounts
+    ///
+    /// Returns `None` if no account generator is configured.
+    pub fn derive_next_account(&mut self) -> Option<Wallet<SigningKey>> {
+        let chain_id = self.get_chain_id();
+        let gen = self.account_generator.as_mut()?;
+        let wallet = gen.gen_at(gen.amount).with_chain_id(chain_id);
+        gen.amount += 1;
+        self.genesis_accounts.push(wallet.clone());
+        self.signer_accounts.push(wallet.clone());
+        Some(wallet)
+    }
+
     /// Sets the balance of the genesis accounts in the genesis block
     #[must_use]
     pub fn with_genesis_balance<U: Into<U256>>(mut self, balance: U) -> Self {
@@ -587,6 +759,13 @@ impl NodeConfig {
         self
     }
 
+    /// Sets the maximum deviation of the interval between blocks from the block time
+    #[must_use]
+    pub fn with_block_time_jitter<D: Into<Duration>>(mut self, jitter: Option<D>) -> Self {
+        self.block_time_jitter = jitter.map(Into::into);
+        self
+    }
+
     /// If set to `true` auto mining will be disabled
     #[must_use]
     pub fn with_no_mining(mut self, no_mining: bool) -> Self {
@@ -613,6 +792,13 @@ impl NodeConfig {
         self
     }
 
+    /// Sets the number of recent node log lines that are kept for `anvil_getNodeLogs`
+    #[must_use]
+    pub fn with_node_log_buffer_size(mut self, size: usize) -> Self {
+        self.node_log_buffer_size = size;
+        self
+    }
+
     /// Sets the ipc path to use
     ///
     /// Note: this is a double Option for
@@ -644,6 +830,13 @@ impl NodeConfig {
         self
     }
 
+    /// Sets the directory of the on-disk fork cache
+    #[must_use]
+    pub fn with_fork_cache_dir(mut self, fork_cache_dir: Option<PathBuf>) -> Self {
+        self.fork_cache_dir = fork_cache_dir;
+        self
+    }
+
     /// Sets the `eth_rpc_url` to use when forking
     #[must_use]
     pub fn with_eth_rpc_url<U: Into<String>>(mut self, eth_rpc_url: Option<U>) -> Self {
@@ -724,6 +917,13 @@ impl NodeConfig {
         self
     }
 
+    /// Sets the max number of mined transactions whose traced steps are kept
+    #[must_use]
+    pub fn with_steps_tracing_cache_size(mut self, size: usize) -> Self {
+        self.steps_tracing_cache_size = size;
+        self
+    }
+
     /// Sets whether to enable autoImpersonate
     #[must_use]
     pub fn with_auto_impersonate(mut self, enable_auto_impersonate: bool) -> Self {
@@ -750,10 +950,36 @@ impl NodeConfig {
         self
     }
 
+    /// Sets the percentage by which a replacement transaction must raise the gas price
+    #[must_use]
+    pub fn with_min_fee_bump_percent(mut self, min_fee_bump_percent: u64) -> Self {
+        self.min_fee_bump_percent = min_fee_bump_percent;
+        self
+    }
+
+    /// Sets the max number of transactions in the mempool
+    #[must_use]
+    pub fn with_txpool_max_count(mut self, txpool_max_count: Option<usize>) -> Self {
+        self.txpool_max_count = txpool_max_count;
+        self
+    }
+
+    /// Sets the max total size of the transactions in the mempool in bytes
+    #[must_use]
+    pub fn with_txpool_max_bytes(mut self, txpool_max_bytes: Option<usize>) -> Self {
+        self.txpool_max_bytes = txpool_max_bytes;
+        self
+    }
+
     /// Returns the ipc path for the ipc endpoint if any
+    ///
+    /// On Windows this is the path of the named pipe, see [anvil_server::ipc::endpoint_path].
     pub fn get_ipc_path(&self) -> Option<String> {
         match self.ipc_path.as_ref() {
-            Some(path) => path.clone().or_else(|| Some(DEFAULT_IPC_ENDPOINT.to_string())),
+            Some(path) => path
+                .clone()
+                .or_else(|| Some(DEFAULT_IPC_ENDPOINT.to_string()))
+                .map(anvil_server::ipc::endpoint_path),
             None => None,
         }
     }
@@ -777,13 +1003,21 @@ impl NodeConfig {
 
     /// Returns the path where the cache file should be stored
     ///
-    /// See also [ Config::foundry_block_cache_file()]
+    /// The cache is keyed by the chain id and the block number, so changing the fork block never
+    /// reads the data of another block. See also [ Config::foundry_block_cache_file()]
     pub fn block_cache_path(&self, block: u64) -> Option<PathBuf> {
         if self.no_storage_caching || self.eth_rpc_url.is_none() {
             return None;
         }
         let chain_id = self.get_chain_id();
 
+        if let Some(dir) = &self.fork_cache_dir {
+            return Some(
+                dir.join(Chain::from(chain_id).to_string())
+                    .join(block.to_string())
+                    .join("storage.json"),
+            )
+        }
         Config::foundry_block_cache_file(chain_id, block)
     }
 
@@ -794,11 +1028,24 @@ impl NodeConfig {
         self
     }
 
+    /// Adds a chain that is served under `/<alias>` by the server of this node.
+    ///
+    /// The chain runs as a separate node with its own state, mempool and block height. Only the
+    /// node related settings of its config are used, the server is configured by this config.
+    #[must_use]
+    pub fn with_chain(mut self, alias: impl Into<String>, config: NodeConfig) -> Self {
+        self.chains.push((alias.into(), config));
+        self
+    }
+
     /// Configures everything related to env, backend and database and returns the
     /// [Backend](mem::Backend)
     ///
+    /// Returns an error if the initial state can't be loaded, for example because it was dumped
+    /// from another chain or fork.
+    ///
     /// *Note*: only memory based backend for now
-    pub(crate) async fn setup(&mut self) -> mem::Backend {
+    pub(crate) async fn setup(&mut self) -> NodeResult<mem::Backend> {
         // configure the revm environment
 
         let mut cfg = CfgEnv::default();
@@ -815,7 +1062,7 @@ impl NodeConfig {
         let mut env = revm::primitives::Env {
             cfg,
             block: BlockEnv {
-                gas_limit: self.gas_limit,
+                gas_limit: self.get_gas_limit(),
                 basefee: self.get_base_fee(),
                 ..Default::default()
             },
@@ -826,6 +1073,11 @@ impl NodeConfig {
             self.get_base_fee().to_ethers(),
             self.get_gas_price().to_ethers(),
         );
+        fees.set_base_fee_params(self.base_fee_params);
+        // an explicit gas price pins `eth_gasPrice`
+        if self.gas_price.is_none() {
+            fees.set_gas_price_oracle(self.gas_price_oracle);
+        }
 
         let (db, fork): (Arc<tokio::sync::RwLock<Box<dyn Db>>>, Option<ClientFork>) =
             if let Some(eth_rpc_url) = self.eth_rpc_url.clone() {
@@ -855,6 +1107,8 @@ impl NodeConfig {
             fees,
             Arc::new(RwLock::new(fork)),
             self.enable_steps_tracing,
+            self.steps_tracing_cache_size,
+            self.max_call_depth,
             self.prune_history,
             self.transaction_block_keeper,
             self.block_time,
@@ -864,7 +1118,7 @@ impl NodeConfig {
 
         // Writes the default create2 deployer to the backend,
         // if the option is not disabled and we are not forking.
-        if !self.disable_default_create2_deployer && self.eth_rpc_url.is_none() {
+        if self.preloads_create2_deployer() {
             backend
                 .set_create2_deployer(DEFAULT_CREATE2_DEPLOYER)
                 .await
@@ -872,15 +1126,13 @@ impl NodeConfig {
         }
 
         if let Some(ref state) = self.init_state {
-            backend
-                .get_db()
-                .write()
-                .await
-                .load_state(state.clone())
-                .expect("Failed to load init state");
+            backend.load_serialized_state(state.clone()).await.map_err(NodeError::LoadState)?;
         }
 
-        backend
+        // the genesis block commits to the entire initial state
+        backend.seal_genesis_state_root().await;
+
+        Ok(backend)
     }
 
     /// Configures everything related to forking based on the passed `eth_rpc_url`:
@@ -905,6 +1157,18 @@ impl NodeConfig {
         (db, Some(fork))
     }
 
+    /// Returns a provider for the fork endpoint with the configured request settings
+    pub fn fork_provider(&self, eth_rpc_url: &str) -> eyre::Result<RetryProvider> {
+        ProviderBuilder::new(eth_rpc_url)
+            .timeout(self.fork_request_timeout)
+            .timeout_retry(self.fork_request_retries)
+            .max_retry(self.fork_request_retries)
+            .initial_backoff(self.fork_retry_backoff.as_millis() as u64)
+            .compute_units_per_second(self.compute_units_per_second)
+            .headers(self.fork_headers.clone())
+            .build()
+    }
+
     /// Configures everything related to forking based on the passed `eth_rpc_url`:
     ///  - returning a tuple of a [ForkedDatabase](ForkedDatabase) and
     ///    [ClientForkConfig](ClientForkConfig) which can be used to build a
@@ -919,16 +1183,7 @@ impl NodeConfig {
     ) -> (ForkedDatabase, ClientForkConfig) {
         // TODO make provider agnostic
         let provider = Arc::new(
-            ProviderBuilder::new(&eth_rpc_url)
-                .timeout(self.fork_request_timeout)
-                .timeout_retry(self.fork_request_retries)
-                .initial_backoff(self.fork_retry_backoff.as_millis() as u64)
-                .compute_units_per_second(self.compute_units_per_second)
-                .max_retry(10)
-                .initial_backoff(1000)
-                .headers(self.fork_headers.clone())
-                .build()
-                .expect("Failed to establish provider to fork url"),
+            self.fork_provider(&eth_rpc_url).expect("Failed to establish provider to fork url"),
         );
 
         let (fork_block_number, fork_chain_id) = if let Some(fork_block_number) =
@@ -1163,20 +1418,19 @@ impl AccountGenerator {
 
 impl AccountGenerator {
     pub fn gen(&self) -> Vec<Wallet<SigningKey>> {
-        let builder = MnemonicBuilder::<English>::default().phrase(self.phrase.as_str());
+        (0..self.amount).map(|idx| self.gen_at(idx)).collect()
+    }
 
-        // use the
+    /// Derives the account at the given index of the derivation path
+    pub fn gen_at(&self, idx: usize) -> Wallet<SigningKey> {
         let derivation_path = self.get_derivation_path();
-
-        let mut wallets = Vec::with_capacity(self.amount);
-
-        for idx in 0..self.amount {
-            let builder =
-                builder.clone().derivation_path(&format!("{derivation_path}{idx}")).unwrap();
-            let wallet = builder.build().unwrap().with_chain_id(self.chain_id);
-            wallets.push(wallet)
-        }
-        wallets
+        MnemonicBuilder::<English>::default()
+            .phrase(self.phrase.as_str())
+            .derivation_path(&format!("{derivation_path}{idx}"))
+            .unwrap()
+            .build()
+            .unwrap()
+            .with_chain_id(self.chain_id)
     }
 }
 
@@ -1225,4 +1479,47 @@ mod tests {
         let config = PruneStateHistoryConfig::from_args(Some(Some(10)));
         assert!(config.is_state_history_supported());
     }
+
+    #[test]
+    fn test_block_cache_path() {
+        let config = NodeConfig::test()
+            .with_eth_rpc_url(Some("http://localhost:8545"))
+            .with_chain_id(Some(1u64))
+            .with_fork_cache_dir(Some(PathBuf::from("/tmp/anvil-cache")));
+        assert_eq!(
+            config.block_cache_path(100),
+            Some(PathBuf::from("/tmp/anvil-cache/mainnet/100/storage.json"))
+        );
+        assert_eq!(config.no_storage_caching().block_cache_path(100), None);
+    }
+
+    #[test]
+    fn test_derive_accounts() {
+        let gen = AccountGenerator::new(2).phrase(DEFAULT_MNEMONIC);
+        let wallets = gen.gen();
+        assert_eq!(
+            hex::encode(wallets[0].signer().to_bytes()),
+            "ac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80"
+        );
+
+        let mut config = NodeConfig::test().with_account_generator(gen);
+        let wallet = config.derive_next_account().unwrap();
+        assert_eq!(
+            wallet.address(),
+            AccountGenerator::new(3).phrase(DEFAULT_MNEMONIC).gen()[2].address()
+        );
+        assert_eq!(config.genesis_accounts.len(), 3);
+        assert_eq!(config.signer_accounts.len(), 3);
+
+        // custom derivation paths are derived like any other wallet
+        let gen =
+            AccountGenerator::new(1).phrase(DEFAULT_MNEMONIC).derivation_path("m/44'/60'/1'/0");
+        let expected = MnemonicBuilder::<English>::default()
+            .phrase(DEFAULT_MNEMONIC)
+            .derivation_path("m/44'/60'/1'/0/0")
+            .unwrap()
+            .build()
+            .unwrap();
+        assert_eq!(gen.gen()[0].address(), expected.address());
+    }
 }
@@ -12,7 +12,7 @@ use crate::{
             decode_revert_reason, BlockchainError, FeeHistoryError, InvalidTransactionError,
             Result, ToRpcResponseResult,
         },
-        fees::{FeeDetails, FeeHistoryCache},
+        fees::{recent_priority_fee, FeeDetails, FeeHistoryCache, GasPriceOracle},
         macros::node_info,
         miner::FixedBlockTimeMiner,
         pool::{
@@ -22,24 +22,24 @@ use crate::{
             Pool,
         },
         sign,
-        sign::Signer,
+        sign::{DevSigner, Signer},
+        util::{redact_url, validate_runtime_code},
     },
     filter::{EthFilter, Filters, LogsFilter},
+    logging,
     mem::transaction_build,
     revm::primitives::Output,
-    ClientFork, LoggingManager, Miner, MiningMode, StorageInfo,
+    ClientFork, Hardfork, LoggingManager, Miner, MiningMode, StorageInfo,
 };
+use alloy_json_abi::JsonAbi;
 use alloy_primitives::{Address, Bytes, TxHash, B256, B64, U256, U64};
 use alloy_rpc_types::{
     state::StateOverride,
-    trace::{
-        DefaultFrame, GethDebugTracingOptions, GethDefaultTracingOptions, GethTrace,
-        LocalizedTransactionTrace,
-    },
+    trace::{DefaultFrame, GethDebugTracingOptions, GethTrace, LocalizedTransactionTrace},
     AccessList, AccessListWithGasUsed, Block, BlockId, BlockNumberOrTag as BlockNumber,
     BlockTransactions, CallRequest, EIP1186AccountProofResponse, FeeHistory, Filter,
     FilteredParams, Log, Transaction, TransactionReceipt, TxpoolContent, TxpoolInspect,
-    TxpoolInspectSummary, TxpoolStatus,
+    TxpoolInspectSummary,
 };
 use alloy_transport::TransportErrorKind;
 use anvil_core::{
@@ -47,18 +47,21 @@ use anvil_core::{
         block::BlockInfo,
         transaction::{
             call_to_internal_tx_request, to_alloy_proof, to_ethers_access_list,
-            EthTransactionRequest, LegacyTransaction, PendingTransaction, TransactionKind,
-            TypedTransaction, TypedTransactionRequest,
+            EthTransactionRequest, PendingTransaction, TransactionKind, TypedTransaction,
+            TypedTransactionRequest,
         },
         EthRequest,
     },
     types::{
-        AnvilMetadata, EvmMineOptions, ForkedNetwork, Forking, Index, NodeEnvironment,
-        NodeForkConfig, NodeInfo, Work,
+        AnvilMetadata, BaseFeeParamsOptions, BlockOverrides, CallGasSummary, DumpStateOptions,
+        EvmMineOptions, ForkedNetwork, Forking, Index, MiningModeInfo, NodeEnvironment,
+        NodeForkConfig, NodeInfo, NodeLog, OpcodeGas, PoolStatus, Simulation, StorageBatch,
+        TraceCallOptions, TraceFilter, TraceTransactionOptions, TransactionInspection,
+        TxTraceResult, Work,
     },
 };
 use anvil_rpc::{error::RpcError, response::ResponseResult};
-use ethers::{types::transaction::eip712::TypedData, utils::rlp};
+use ethers::types::transaction::eip712::TypedData;
 use foundry_common::{
     provider::alloy::ProviderBuilder,
     types::{ToAlloy, ToEthers},
@@ -73,7 +76,13 @@ use foundry_evm::{
 };
 use futures::channel::{mpsc::Receiver, oneshot};
 use parking_lot::RwLock;
-use std::{collections::HashSet, future::Future, sync::Arc, time::Duration};
+use std::{
+    collections::{BTreeMap, HashSet},
+    future::Future,
+    str::FromStr,
+    sync::Arc,
+    time::Duration,
+};
 
 /// The client version: `anvil/v{major}.{minor}.{patch}`
 pub const CLIENT_VERSION: &str = concat!("anvil/v", env!("CARGO_PKG_VERSION"));
@@ -91,7 +100,7 @@ pub struct EthApi {
     /// Whether this node is mining
     is_mining: bool,
     /// available signers
-    signers: Arc<Vec<Box<dyn Signer>>>,
+    signers: Arc<RwLock<Vec<Arc<dyn Signer>>>>,
     /// data required for `eth_feeHistory`
     fee_history_cache: FeeHistoryCache,
     /// max number of items kept in fee cache
@@ -121,7 +130,7 @@ impl EthApi {
     pub fn new(
         pool: Arc<Pool>,
         backend: Arc<backend::mem::Backend>,
-        signers: Arc<Vec<Box<dyn Signer>>>,
+        signers: Vec<Arc<dyn Signer>>,
         fee_history_cache: FeeHistoryCache,
         fee_history_limit: u64,
         miner: Miner,
@@ -133,7 +142,7 @@ impl EthApi {
             pool,
             backend,
             is_mining: true,
-            signers,
+            signers: Arc::new(RwLock::new(signers)),
             fee_history_cache,
             fee_history_limit,
             miner,
@@ -167,6 +176,7 @@ impl EthApi {
             EthRequest::EthMaxPriorityFeePerGas(_) => {
                 self.gas_max_priority_fee_per_gas().to_rpc_result()
             }
+            EthRequest::EthBlobBaseFee(_) => self.blob_base_fee().to_rpc_result(),
             EthRequest::EthAccounts(_) => self.accounts().to_rpc_result(),
             EthRequest::EthBlockNumber(_) => self.block_number().to_rpc_result(),
             EthRequest::EthGetStorageAt(addr, slot, block) => {
@@ -223,8 +233,8 @@ impl EthApi {
             EthRequest::EthSendRawTransaction(tx) => {
                 self.send_raw_transaction(tx).await.to_rpc_result()
             }
-            EthRequest::EthCall(call, block, overrides) => {
-                self.call(call, block, overrides).await.to_rpc_result()
+            EthRequest::EthCall(call, block, overrides, block_overrides) => {
+                self.call(call, block, overrides, block_overrides).await.to_rpc_result()
             }
             EthRequest::EthCreateAccessList(call, block) => {
                 self.create_access_list(call, block).await.to_rpc_result()
@@ -264,25 +274,46 @@ impl EthApi {
             EthRequest::DebugTraceTransaction(tx, opts) => {
                 self.debug_trace_transaction(tx, opts).await.to_rpc_result()
             }
+            EthRequest::DebugTraceBlockByNumber(number, opts) => {
+                self.debug_trace_block_by_number(number, opts).await.to_rpc_result()
+            }
+            EthRequest::DebugTraceBlockByHash(hash, opts) => {
+                self.debug_trace_block_by_hash(hash, opts).await.to_rpc_result()
+            }
             // non eth-standard rpc calls
             EthRequest::DebugTraceCall(tx, block, opts) => {
                 self.debug_trace_call(tx, block, opts).await.to_rpc_result()
             }
             EthRequest::TraceTransaction(tx) => self.trace_transaction(tx).await.to_rpc_result(),
             EthRequest::TraceBlock(block) => self.trace_block(block).await.to_rpc_result(),
+            EthRequest::TraceFilter(filter) => self.trace_filter(filter).to_rpc_result(),
             EthRequest::ImpersonateAccount(addr) => {
                 self.anvil_impersonate_account(addr).await.to_rpc_result()
             }
             EthRequest::StopImpersonatingAccount(addr) => {
                 self.anvil_stop_impersonating_account(addr).await.to_rpc_result()
             }
+            EthRequest::ImpersonateAccounts(addrs) => {
+                self.anvil_impersonate_accounts(addrs).await.to_rpc_result()
+            }
+            EthRequest::StopImpersonatingAccounts(addrs) => {
+                self.anvil_stop_impersonating_accounts(addrs).await.to_rpc_result()
+            }
+            EthRequest::StopImpersonatingAll(()) => {
+                self.anvil_stop_impersonating_all().await.to_rpc_result()
+            }
             EthRequest::AutoImpersonateAccount(enable) => {
                 self.anvil_auto_impersonate_account(enable).await.to_rpc_result()
             }
+            EthRequest::AddAccount(()) => self.anvil_add_account().await.to_rpc_result(),
             EthRequest::GetAutoMine(()) => self.anvil_get_auto_mine().to_rpc_result(),
+            EthRequest::GetMiningMode(()) => self.anvil_get_mining_mode().to_rpc_result(),
             EthRequest::Mine(blocks, interval) => {
                 self.anvil_mine(blocks, interval).await.to_rpc_result()
             }
+            EthRequest::MineTo(block_number) => {
+                self.anvil_mine_to(block_number).await.to_rpc_result()
+            }
             EthRequest::SetAutomine(enabled) => {
                 self.anvil_set_auto_mine(enabled).await.to_rpc_result()
             }
@@ -292,35 +323,96 @@ impl EthApi {
             EthRequest::DropTransaction(tx) => {
                 self.anvil_drop_transaction(tx).await.to_rpc_result()
             }
+            EthRequest::DropAllTransactions(()) => {
+                self.anvil_drop_all_transactions().await.to_rpc_result()
+            }
+            EthRequest::SetTxOrdering(order) => self.anvil_set_tx_ordering(&order).to_rpc_result(),
+            EthRequest::SetNextBlockOrder(hashes) => {
+                self.anvil_set_next_block_order(hashes).to_rpc_result()
+            }
+            EthRequest::SetMinFeeBumpPercent(percent) => {
+                self.anvil_set_min_fee_bump_percent(percent).to_rpc_result()
+            }
+            EthRequest::Reorg(depth, transactions) => {
+                self.anvil_reorg(depth, transactions).await.to_rpc_result()
+            }
+            EthRequest::Rollback(depth) => self.anvil_rollback(depth).await.to_rpc_result(),
             EthRequest::Reset(fork) => {
                 self.anvil_reset(fork.and_then(|p| p.params)).await.to_rpc_result()
             }
             EthRequest::SetBalance(addr, val) => {
                 self.anvil_set_balance(addr, val).await.to_rpc_result()
             }
-            EthRequest::SetCode(addr, code) => {
-                self.anvil_set_code(addr, code).await.to_rpc_result()
-            }
+            EthRequest::SetCode(addr, code, validate) => self
+                .anvil_set_code_with_validation(addr, code, validate.unwrap_or_default())
+                .await
+                .to_rpc_result(),
             EthRequest::SetNonce(addr, nonce) => {
                 self.anvil_set_nonce(addr, nonce).await.to_rpc_result()
             }
+            EthRequest::GetNonce(addr) => self.anvil_get_nonce(addr).await.to_rpc_result(),
             EthRequest::SetStorageAt(addr, slot, val) => {
                 self.anvil_set_storage_at(addr, slot, val).await.to_rpc_result()
             }
+            EthRequest::SetStorageBatch(storage) => {
+                self.anvil_set_storage_batch(storage).await.to_rpc_result()
+            }
             EthRequest::SetCoinbase(addr) => self.anvil_set_coinbase(addr).await.to_rpc_result(),
             EthRequest::SetChainId(id) => self.anvil_set_chain_id(id).await.to_rpc_result(),
+            EthRequest::SetHardfork(hardfork) => {
+                self.anvil_set_hardfork(hardfork).await.to_rpc_result()
+            }
             EthRequest::SetLogging(log) => self.anvil_set_logging(log).await.to_rpc_result(),
+            EthRequest::GetNodeLogs(count) => self.anvil_get_node_logs(count).to_rpc_result(),
+            EthRequest::SetTracingEnabled(enabled) => {
+                self.anvil_set_tracing_enabled(enabled).to_rpc_result()
+            }
+            EthRequest::RegisterAbi(address, abi) => {
+                self.anvil_register_abi(address, abi).to_rpc_result()
+            }
             EthRequest::SetMinGasPrice(gas) => {
                 self.anvil_set_min_gas_price(gas).await.to_rpc_result()
             }
             EthRequest::SetNextBlockBaseFeePerGas(gas) => {
                 self.anvil_set_next_block_base_fee_per_gas(gas).await.to_rpc_result()
             }
-            EthRequest::DumpState(_) => self.anvil_dump_state().await.to_rpc_result(),
+            EthRequest::SetPrevRandao(prevrandao) => {
+                self.anvil_set_prevrandao(prevrandao).await.to_rpc_result()
+            }
+            EthRequest::SetNextBlockPrevRandao(prevrandao) => {
+                self.anvil_set_next_block_prevrandao(prevrandao).await.to_rpc_result()
+            }
+            EthRequest::SetBaseFeeParams(opts) => {
+                self.anvil_set_base_fee_params(opts).await.to_rpc_result()
+            }
+            EthRequest::DumpState(opts) => {
+                self.anvil_dump_state(opts.and_then(|p| p.params)).await.to_rpc_result()
+            }
             EthRequest::LoadState(buf) => self.anvil_load_state(buf).await.to_rpc_result(),
             EthRequest::NodeInfo(_) => self.anvil_node_info().await.to_rpc_result(),
             EthRequest::AnvilMetadata(_) => self.anvil_metadata().await.to_rpc_result(),
+            EthRequest::AnvilGasProfile(call, block) => {
+                self.anvil_gas_profile(call, block).await.to_rpc_result()
+            }
+            EthRequest::AnvilSimulate(call, block, block_overrides) => {
+                self.anvil_simulate(call, block, block_overrides).await.to_rpc_result()
+            }
+            EthRequest::AnvilSimulateBundle(calls, overrides, stop_on_revert) => {
+                self.anvil_simulate_bundle(calls, overrides, stop_on_revert).await.to_rpc_result()
+            }
+            EthRequest::AnvilInspectTransaction(hash) => {
+                self.anvil_inspect_transaction(hash).await.to_rpc_result()
+            }
+            EthRequest::AnvilTraceGasSummary(hash) => {
+                self.anvil_trace_gas_summary(hash).to_rpc_result()
+            }
             EthRequest::EvmSnapshot(_) => self.evm_snapshot().await.to_rpc_result(),
+            EthRequest::SnapshotFull(_) => self.anvil_snapshot_full().await.to_rpc_result(),
+            EthRequest::SnapshotNamed(name) => {
+                self.anvil_snapshot_named(name).await.to_rpc_result()
+            }
+            EthRequest::RevertNamed(name) => self.anvil_revert_named(&name).await.to_rpc_result(),
+            EthRequest::ListSnapshots(_) => self.anvil_list_snapshots().to_rpc_result(),
             EthRequest::EvmRevert(id) => self.evm_revert(id).await.to_rpc_result(),
             EthRequest::EvmIncreaseTime(time) => self.evm_increase_time(time).await.to_rpc_result(),
             EthRequest::EvmSetNextBlockTimeStamp(time) => {
@@ -350,6 +442,7 @@ impl EthApi {
             EthRequest::EvmRemoveBlockTimeStampInterval(()) => {
                 self.evm_remove_block_timestamp_interval().to_rpc_result()
             }
+            EthRequest::GetTimeOffset(()) => self.anvil_get_time_offset().to_rpc_result(),
             EthRequest::EvmMine(mine) => {
                 self.evm_mine(mine.and_then(|p| p.params)).await.to_rpc_result()
             }
@@ -425,7 +518,7 @@ impl EthApi {
                 return build_typed_transaction(request, NIL_SIGNATURE)
             }
             _ => {
-                for signer in self.signers.iter() {
+                for signer in self.signers.read().iter() {
                     if signer.accounts().contains(&from.to_ethers()) {
                         let signature =
                             signer.sign_transaction(request.clone(), &from.to_ethers())?;
@@ -539,10 +632,28 @@ impl EthApi {
     }
 
     /// Returns the current gas price
+    ///
+    /// With the [GasPriceOracle::Recent] this follows the priority fees paid in recent blocks.
     pub fn gas_price(&self) -> Result<U256> {
+        let fees = self.backend.fees();
+        if fees.gas_price_oracle() == GasPriceOracle::Recent {
+            if let Some(tip) = recent_priority_fee(&self.fee_history_cache.lock()) {
+                let gas_price =
+                    fees.base_fee().saturating_add(tip.into()).max(fees.min_gas_price());
+                return Ok(gas_price.to_alloy())
+            }
+        }
         Ok(self.backend.gas_price())
     }
 
+    /// Returns the blob base fee of the next block, introduced by EIP-4844
+    ///
+    /// Handler for ETH RPC call: `eth_blobBaseFee`
+    pub fn blob_base_fee(&self) -> Result<U256> {
+        node_info!("eth_blobBaseFee");
+        Ok(self.backend.blob_base_fee())
+    }
+
     /// Returns a fee per gas that is an estimate of how much you can pay as a priority fee, or
     /// 'tip', to get a transaction included in the current block.
     ///
@@ -563,7 +674,7 @@ impl EthApi {
         node_info!("eth_accounts");
         let mut unique = HashSet::new();
         let mut accounts = Vec::new();
-        for signer in self.signers.iter() {
+        for signer in self.signers.read().iter() {
             accounts.extend(signer.accounts().into_iter().filter(|acc| unique.insert(*acc)));
         }
         accounts.extend(
@@ -920,33 +1031,7 @@ impl EthApi {
     /// Handler for ETH RPC call: `eth_sendRawTransaction`
     pub async fn send_raw_transaction(&self, tx: Bytes) -> Result<TxHash> {
         node_info!("eth_sendRawTransaction");
-        let data = tx.as_ref();
-        if data.is_empty() {
-            return Err(BlockchainError::EmptyRawTransactionData);
-        }
-        let transaction = if data[0] > 0x7f {
-            // legacy transaction
-            match rlp::decode::<LegacyTransaction>(data) {
-                Ok(transaction) => TypedTransaction::Legacy(transaction),
-                Err(_) => return Err(BlockchainError::FailedToDecodeSignedTransaction),
-            }
-        } else {
-            // the [TypedTransaction] requires a valid rlp input,
-            // but EIP-1559 prepends a version byte, so we need to encode the data first to get a
-            // valid rlp and then rlp decode impl of `TypedTransaction` will remove and check the
-            // version byte
-            let extend = rlp::encode(&data);
-            let tx = match rlp::decode::<TypedTransaction>(&extend[..]) {
-                Ok(transaction) => transaction,
-                Err(_) => return Err(BlockchainError::FailedToDecodeSignedTransaction),
-            };
-
-            self.ensure_typed_transaction_supported(&tx)?;
-
-            tx
-        };
-
-        let pending_transaction = PendingTransaction::new(transaction)?;
+        let pending_transaction = self.decode_raw_transaction(tx)?;
 
         // pre-validate
         self.backend.validate_pool_transaction(&pending_transaction).await?;
@@ -970,14 +1055,30 @@ impl EthApi {
         Ok(*tx.hash())
     }
 
+    /// Decodes a raw signed transaction and recovers its sender
+    fn decode_raw_transaction(&self, tx: Bytes) -> Result<PendingTransaction> {
+        let data = tx.as_ref();
+        if data.is_empty() {
+            return Err(BlockchainError::EmptyRawTransactionData);
+        }
+        let transaction = TypedTransaction::decode_raw(data)
+            .map_err(|_| BlockchainError::FailedToDecodeSignedTransaction)?;
+        self.ensure_typed_transaction_supported(&transaction)?;
+
+        Ok(PendingTransaction::new(transaction)?)
+    }
+
     /// Call contract, returning the output data.
     ///
+    /// The state overrides and the [BlockOverrides] only apply to this call.
+    ///
     /// Handler for ETH RPC call: `eth_call`
     pub async fn call(
         &self,
         request: CallRequest,
         block_number: Option<BlockId>,
         overrides: Option<StateOverride>,
+        block_overrides: Option<BlockOverrides>,
     ) -> Result<Bytes> {
         node_info!("eth_call");
         let block_request = self.block_request(block_number).await?;
@@ -985,10 +1086,15 @@ impl EthApi {
         if let &BlockRequest::Number(number) = &block_request {
             if let Some(fork) = self.get_fork() {
                 if fork.predates_fork(number) {
-                    if overrides.is_some() {
-                        return Err(BlockchainError::StateOverrideError(
-                            "not available on past forked blocks".to_string(),
-                        ));
+                    if overrides.is_some() || block_overrides.is_some() {
+                        return self
+                            .call_at_forked_block(
+                                request,
+                                number,
+                                overrides.unwrap_or_default(),
+                                block_overrides,
+                            )
+                            .await
                     }
                     return fork
                         .call(&request, Some(number.into()))
@@ -1008,8 +1114,38 @@ impl EthApi {
         // this can be blocking for a bit, especially in forking mode
         // <https://github.com/foundry-rs/foundry/issues/6036>
         self.on_blocking_task(|this| async move {
-            let (exit, out, gas, _) =
-                this.backend.call(request, fees, Some(block_request), overrides).await?;
+            let (exit, out, gas, _) = this
+                .backend
+                .call(request, fees, Some(block_request), overrides, block_overrides)
+                .await?;
+            trace!(target : "node", "Call status {:?}, gas {}", exit, gas);
+
+            ensure_return_ok(exit, &out)
+        })
+        .await
+    }
+
+    /// Executes the call with the overrides against a block that predates the fork
+    async fn call_at_forked_block(
+        &self,
+        request: CallRequest,
+        block_number: u64,
+        overrides: StateOverride,
+        block_overrides: Option<BlockOverrides>,
+    ) -> Result<Bytes> {
+        let fees = FeeDetails::new(
+            request.gas_price.map(ToEthers::to_ethers),
+            request.max_fee_per_gas.map(ToEthers::to_ethers),
+            request.max_priority_fee_per_gas.map(ToEthers::to_ethers),
+        )?
+        .or_zero_fees();
+        let request = call_to_internal_tx_request(&request);
+        // the state of the block is fetched from the remote client
+        self.on_blocking_task(|this| async move {
+            let (exit, out, gas, _) = this
+                .backend
+                .call_at_forked_block(block_number, request, fees, overrides, block_overrides)
+                .await?;
             trace!(target : "node", "Call status {:?}, gas {}", exit, gas);
 
             ensure_return_ok(exit, &out)
@@ -1019,7 +1155,7 @@ impl EthApi {
 
     /// This method creates an EIP2930 type accessList based on a given Transaction. The accessList
     /// contains all storage slots and addresses read and written by the transaction, except for the
-    /// sender account and the precompiles.
+    /// accounts that are warm anyway, like the sender, the recipient and the precompiles.
     ///
     /// It returns list of addresses and storage keys used by the transaction, plus the gas
     /// consumed when the access list is added. That is, it gives you the list of addresses and
@@ -1053,16 +1189,25 @@ impl EthApi {
 
         self.backend
             .with_database_at(Some(block_request), |state, block_env| {
-                let (exit, out, _, access_list) = self.backend.build_access_list_with_state(
-                    &state,
-                    request.clone(),
-                    FeeDetails::zero(),
-                    block_env.clone(),
-                )?;
-                ensure_return_ok(exit, &out)?;
+                // the access list changes the gas costs and with that possibly the accessed
+                // accounts, so this is repeated until the list no longer changes
+                let access_list = loop {
+                    let (exit, out, _, access_list) = self.backend.build_access_list_with_state(
+                        &state,
+                        request.clone(),
+                        FeeDetails::zero(),
+                        block_env.clone(),
+                    )?;
+                    ensure_return_ok(exit, &out)?;
+
+                    let list = to_ethers_access_list(access_list.clone()).0;
+                    if request.access_list.as_ref() == Some(&list) {
+                        break access_list
+                    }
+                    request.access_list = Some(list);
+                };
 
-                // execute again but with access list set
-                request.access_list = Some(to_ethers_access_list(access_list.clone()).0);
+                // execute again with the access list set, to account for its gas
 
                 let (exit, out, gas_used, _) = self.backend.call_with_state(
                     &state,
@@ -1102,20 +1247,10 @@ impl EthApi {
     /// Handler for ETH RPC call: `eth_getTransactionByHash`
     pub async fn transaction_by_hash(&self, hash: B256) -> Result<Option<Transaction>> {
         node_info!("eth_getTransactionByHash");
-        let mut tx = self.pool.get_transaction(hash).map(|pending| {
-            let from = *pending.sender();
-            let mut tx = transaction_build(
-                Some(pending.hash().to_alloy()),
-                pending.transaction,
-                None,
-                None,
-                Some(self.backend.base_fee()),
-            );
-            // we set the from field here explicitly to the set sender of the pending transaction,
-            // in case the transaction is impersonated.
-            tx.from = from.to_alloy();
-            tx
-        });
+        let mut tx = self
+            .pool
+            .get_transaction(hash)
+            .map(|pending| self.backend.convert_pending_transaction(pending));
         if tx.is_none() {
             tx = self.backend.transaction_by_hash(hash).await?
         }
@@ -1254,6 +1389,12 @@ impl EthApi {
         reward_percentiles: Vec<f64>,
     ) -> Result<FeeHistory> {
         node_info!("eth_feeHistory");
+        if reward_percentiles.iter().any(|p| !(0.0..=100.0).contains(p)) ||
+            reward_percentiles.windows(2).any(|w| w[0] > w[1])
+        {
+            return Err(FeeHistoryError::InvalidRewardPercentiles.into());
+        }
+
         // max number of blocks in the requested range
 
         let current = self.backend.best_number();
@@ -1280,7 +1421,7 @@ impl EthApi {
         }
 
         const MAX_BLOCK_COUNT: u64 = 1024u64;
-        let block_count = block_count.to::<u64>().max(MAX_BLOCK_COUNT);
+        let block_count = block_count.to::<u64>().min(MAX_BLOCK_COUNT);
 
         // highest and lowest block num in the requested range
         let highest = number;
@@ -1310,45 +1451,22 @@ impl EthApi {
 
                 // requested percentiles
                 if !reward_percentiles.is_empty() {
-                    let mut block_rewards = Vec::new();
-                    let resolution_per_percentile: f64 = 2.0;
-                    for p in &reward_percentiles {
-                        let p = p.clamp(0.0, 100.0);
-                        let index = ((p.round() / 2f64) * 2f64) * resolution_per_percentile;
-                        let reward = if let Some(r) = block.rewards.get(index as usize) {
-                            U256::from(*r)
-                        } else {
-                            U256::ZERO
-                        };
-                        block_rewards.push(reward);
-                    }
-                    rewards.push(block_rewards);
+                    rewards.push(
+                        reward_percentiles.iter().map(|p| U256::from(block.reward(*p))).collect(),
+                    );
                 }
             }
         }
 
         response.reward = Some(rewards);
 
-        // calculate next base fee
-        if let (Some(last_gas_used), Some(last_fee_per_gas)) =
-            (response.gas_used_ratio.last(), response.base_fee_per_gas.last())
-        {
-            let elasticity = self.backend.elasticity();
-            let last_fee_per_gas = last_fee_per_gas.to::<u64>() as f64;
-            if last_gas_used > &0.5 {
-                // increase base gas
-                let increase = ((last_gas_used - 0.5) * 2f64) * elasticity;
-                let new_base_fee = (last_fee_per_gas + (last_fee_per_gas * increase)) as u64;
-                response.base_fee_per_gas.push(U256::from(new_base_fee));
-            } else if last_gas_used < &0.5 {
-                // decrease gas
-                let increase = ((0.5 - last_gas_used) * 2f64) * elasticity;
-                let new_base_fee = (last_fee_per_gas - (last_fee_per_gas * increase)) as u64;
-                response.base_fee_per_gas.push(U256::from(new_base_fee));
-            } else {
-                // same base gas
-                response.base_fee_per_gas.push(U256::from(last_fee_per_gas as u64));
-            }
+        // the response also includes the base fee of the block after the newest block
+        if !response.base_fee_per_gas.is_empty() {
+            let next_base_fee = match fee_history.get(&(highest + 1)) {
+                Some(block) => U256::from(block.base_fee),
+                None => self.backend.base_fee(),
+            };
+            response.base_fee_per_gas.push(next_base_fee);
         }
 
         Ok(response)
@@ -1420,7 +1538,7 @@ impl EthApi {
         if let Some(filter) = self.filters.get_log_filter(id).await {
             self.backend.logs(filter).await
         } else {
-            Ok(Vec::new())
+            Err(BlockchainError::FilterNotFound)
         }
     }
 
@@ -1436,20 +1554,52 @@ impl EthApi {
     pub async fn debug_trace_transaction(
         &self,
         tx_hash: B256,
-        opts: GethDebugTracingOptions,
+        opts: TraceTransactionOptions,
     ) -> Result<GethTrace> {
         node_info!("debug_traceTransaction");
-        self.backend.debug_trace_transaction(tx_hash, opts).await
+        let TraceTransactionOptions { tracing_options, decode } = opts;
+        let trace = self.backend.debug_trace_transaction(tx_hash, tracing_options).await?;
+        if decode {
+            return Ok(self.backend.decode_geth_trace(trace).await)
+        }
+        Ok(trace)
+    }
+
+    /// Returns the traces of all transactions of the block for geth's tracing endpoint
+    ///
+    /// Handler for RPC call: `debug_traceBlockByNumber`
+    pub async fn debug_trace_block_by_number(
+        &self,
+        block_number: BlockNumber,
+        opts: GethDebugTracingOptions,
+    ) -> Result<Vec<TxTraceResult>> {
+        node_info!("debug_traceBlockByNumber");
+        self.backend.debug_trace_block(block_number.into(), opts).await
     }
 
-    /// Returns traces for the transaction for geth's tracing endpoint
+    /// Returns the traces of all transactions of the block for geth's tracing endpoint
+    ///
+    /// Handler for RPC call: `debug_traceBlockByHash`
+    pub async fn debug_trace_block_by_hash(
+        &self,
+        block_hash: B256,
+        opts: GethDebugTracingOptions,
+    ) -> Result<Vec<TxTraceResult>> {
+        node_info!("debug_traceBlockByHash");
+        self.backend.debug_trace_block(block_hash.into(), opts).await
+    }
+
+    /// Returns traces for the call for geth's tracing endpoint
+    ///
+    /// The `stateOverrides` of the options are only applied for the traced call, the state of the
+    /// node is not modified.
     ///
     /// Handler for RPC call: `debug_traceCall`
     pub async fn debug_trace_call(
         &self,
         request: CallRequest,
         block_number: Option<BlockId>,
-        opts: GethDefaultTracingOptions,
+        opts: TraceCallOptions,
     ) -> Result<DefaultFrame> {
         node_info!("debug_traceCall");
         let block_request = self.block_request(block_number).await?;
@@ -1462,7 +1612,10 @@ impl EthApi {
 
         let request = call_to_internal_tx_request(&request);
 
-        self.backend.call_with_tracing(request, fees, Some(block_request), opts).await
+        let TraceCallOptions { tracing_options, state_overrides } = opts;
+        self.backend
+            .call_with_tracing(request, fees, Some(block_request), tracing_options, state_overrides)
+            .await
     }
 
     /// Returns traces for the transaction hash via parity's tracing endpoint
@@ -1480,6 +1633,29 @@ impl EthApi {
         node_info!("trace_block");
         self.backend.trace_block(block).await
     }
+
+    /// Returns the traces of the mined transactions that match the filter via parity's tracing
+    /// endpoint
+    ///
+    /// Handler for RPC call: `trace_filter`
+    pub fn trace_filter(&self, filter: TraceFilter) -> Result<Vec<LocalizedTransactionTrace>> {
+        node_info!("trace_filter");
+        let from = self
+            .backend
+            .convert_block_number(Some(filter.from_block.unwrap_or(BlockNumber::Earliest)));
+        let to = self.backend.convert_block_number(filter.to_block);
+        if from > to {
+            return Err(RpcError::invalid_params(format!(
+                "fromBlock {from} is greater than toBlock {to}"
+            ))
+            .into())
+        }
+        let best = self.backend.best_number();
+        if to > best {
+            return Err(BlockchainError::BlockOutOfRange(best, to))
+        }
+        Ok(self.backend.trace_filter(from..=to, &filter))
+    }
 }
 
 // == impl EthApi anvil endpoints ==
@@ -1503,8 +1679,40 @@ impl EthApi {
         Ok(())
     }
 
+    /// Send transactions impersonating all of the given account and contract addresses.
+    ///
+    /// Handler for ETH RPC call: `anvil_impersonateAccounts`
+    pub async fn anvil_impersonate_accounts(&self, addresses: Vec<Address>) -> Result<()> {
+        node_info!("anvil_impersonateAccounts");
+        self.backend.impersonate_accounts(addresses).await;
+        Ok(())
+    }
+
+    /// Stops impersonating all of the given accounts.
+    ///
+    /// Handler for ETH RPC call: `anvil_stopImpersonatingAccounts`
+    pub async fn anvil_stop_impersonating_accounts(&self, addresses: Vec<Address>) -> Result<()> {
+        node_info!("anvil_stopImpersonatingAccounts");
+        self.backend.stop_impersonating_accounts(addresses).await;
+        Ok(())
+    }
+
+    /// Stops impersonating all accounts that were impersonated via `anvil_impersonateAccount` or
+    /// `anvil_impersonateAccounts`.
+    ///
+    /// Handler for ETH RPC call: `anvil_stopImpersonatingAll`
+    pub async fn anvil_stop_impersonating_all(&self) -> Result<()> {
+        node_info!("anvil_stopImpersonatingAll");
+        self.backend.stop_impersonating_all().await;
+        Ok(())
+    }
+
     /// If set to true will make every account impersonated
     ///
+    /// While enabled, `eth_sendTransaction` accepts transactions of any `from` address without a
+    /// signature or a matching dev account, so anyone who can reach the node can spend the funds
+    /// of every account. Only enable this on nodes that aren't exposed to untrusted clients.
+    ///
     /// Handler for ETH RPC call: `anvil_autoImpersonateAccount`
     pub async fn anvil_auto_impersonate_account(&self, enabled: bool) -> Result<()> {
         node_info!("anvil_autoImpersonateAccount");
@@ -1512,7 +1720,22 @@ impl EthApi {
         Ok(())
     }
 
-    /// Returns true if auto mining is enabled, and false.
+    /// Derives the next dev account from the configured mnemonic, funds it with the genesis
+    /// balance and returns its address
+    ///
+    /// Handler for ETH RPC call: `anvil_addAccount`
+    pub async fn anvil_add_account(&self) -> Result<Address> {
+        node_info!("anvil_addAccount");
+        let wallet = self.backend.add_derived_account().await?;
+        let signer = DevSigner::new(vec![wallet]);
+        let address = signer.accounts()[0].to_alloy();
+        self.signers.write().push(Arc::new(signer));
+        Ok(address)
+    }
+
+    /// Returns true if auto mining is enabled, and false otherwise.
+    ///
+    /// Interval and manual mining both report `false`, see `anvil_getMiningMode` for the mode.
     ///
     /// Handler for ETH RPC call: `anvil_getAutomine`
     pub fn anvil_get_auto_mine(&self) -> Result<bool> {
@@ -1520,9 +1743,22 @@ impl EthApi {
         Ok(self.miner.is_auto_mine())
     }
 
+    /// Returns the current mining mode.
+    ///
+    /// The modes are mutually exclusive: enabling automine or interval mining replaces the current
+    /// mode, in manual mode pending transactions stay queued until `anvil_mine` or `evm_mine`.
+    ///
+    /// Handler for ETH RPC call: `anvil_getMiningMode`
+    pub fn anvil_get_mining_mode(&self) -> Result<MiningModeInfo> {
+        node_info!("anvil_getMiningMode");
+        Ok(self.miner.mode_info())
+    }
+
     /// Enables or disables, based on the single boolean argument, the automatic mining of new
     /// blocks with each new transaction submitted to the network.
     ///
+    /// Disabling automine switches to manual mining, it has no effect in interval mining mode.
+    ///
     /// Handler for ETH RPC call: `evm_setAutomine`
     pub async fn anvil_set_auto_mine(&self, enable_automine: bool) -> Result<()> {
         node_info!("evm_setAutomine");
@@ -1544,22 +1780,54 @@ impl EthApi {
     /// Handler for ETH RPC call: `anvil_mine`
     pub async fn anvil_mine(&self, num_blocks: Option<U256>, interval: Option<U256>) -> Result<()> {
         node_info!("anvil_mine");
-        let interval = interval.map(|i| i.to::<u64>());
+        let interval = interval.map(|i| i.to::<u64>()).filter(|i| *i > 0);
         let blocks = num_blocks.unwrap_or(U256::from(1));
         if blocks == U256::ZERO {
             return Ok(());
         }
 
         // mine all the blocks
-        for _ in 0..blocks.to::<u64>() {
+        for n in 0..blocks.to::<u64>() {
+            // If we have an interval, space the blocks exactly `interval` seconds apart
+            if let Some(interval) = interval.filter(|_| n > 0) {
+                let time = self.backend.time();
+                time.set_next_block_timestamp(time.last_timestamp().saturating_add(interval))?;
+            }
             self.mine_one().await;
+        }
 
-            // If we have an interval, jump forwards in time to the "next" timestamp
-            if let Some(interval) = interval {
-                self.backend.time().increase_time(interval);
-            }
+        Ok(())
+    }
+
+    /// Mines blocks until the chain reaches the given block number.
+    ///
+    /// Pending transactions are included in the first blocks, a `newHeads` notification is emitted
+    /// for every block. At most 10,000 blocks are mined per call.
+    ///
+    /// Handler for ETH RPC call: `anvil_mineTo`
+    pub async fn anvil_mine_to(&self, block_number: U256) -> Result<()> {
+        node_info!("anvil_mineTo");
+        const MAX_BLOCK_COUNT: u64 = 10_000u64;
+
+        let current = U256::from(self.backend.best_number());
+        if block_number < current {
+            return Err(RpcError::invalid_params(format!(
+                "target block {block_number} is below the current block {current}"
+            ))
+            .into());
+        }
+        let blocks = (block_number - current).saturating_to::<u64>();
+        if blocks > MAX_BLOCK_COUNT {
+            return Err(RpcError::invalid_params(format!(
+                "target block {block_number} is more than {MAX_BLOCK_COUNT} blocks ahead of the \
+                 current block {current}"
+            ))
+            .into());
         }
 
+        for _ in 0..blocks {
+            self.mine_one().await;
+        }
         Ok(())
     }
 
@@ -1582,33 +1850,179 @@ impl EthApi {
         Ok(())
     }
 
-    /// Removes transactions from the pool
+    /// Removes a pending or queued transaction from the pool, returns whether the transaction was
+    /// removed.
+    ///
+    /// Ready transactions that depended on the removed transaction are queued until the nonce gap
+    /// is filled.
     ///
     /// Handler for RPC call: `anvil_dropTransaction`
-    pub async fn anvil_drop_transaction(&self, tx_hash: B256) -> Result<Option<B256>> {
+    pub async fn anvil_drop_transaction(&self, tx_hash: B256) -> Result<bool> {
         node_info!("anvil_dropTransaction");
-        Ok(self.pool.drop_transaction(tx_hash).map(|tx| tx.hash()))
+        Ok(self.pool.drop_transaction(tx_hash).is_some())
+    }
+
+    /// Removes all transactions from the pool
+    ///
+    /// Handler for RPC call: `anvil_dropAllTransactions`
+    pub async fn anvil_drop_all_transactions(&self) -> Result<()> {
+        node_info!("anvil_dropAllTransactions");
+        self.pool.clear();
+        Ok(())
+    }
+
+    /// Sets the order in which pool transactions are mined, `fees` or `fifo`
+    ///
+    /// Transactions that are already in the pool are re-sorted.
+    ///
+    /// Handler for RPC call: `anvil_setTxOrdering`
+    pub fn anvil_set_tx_ordering(&self, order: &str) -> Result<()> {
+        node_info!("anvil_setTxOrdering");
+        let order = order.parse::<TransactionOrder>().map_err(RpcError::invalid_params)?;
+        self.set_transaction_order(order);
+        Ok(())
+    }
+
+    /// Pins the order in which the given pool transactions are included in the next block.
+    ///
+    /// The listed transactions are mined first, in the given order, followed by all other
+    /// transactions in the regular order. Transactions of the same sender with a lower nonce are
+    /// moved ahead of a listed transaction, so that it remains valid. The order only applies to the
+    /// next mined block, a later call replaces it.
+    ///
+    /// Handler for RPC call: `anvil_setNextBlockOrder`
+    pub fn anvil_set_next_block_order(&self, hashes: Vec<B256>) -> Result<()> {
+        node_info!("anvil_setNextBlockOrder");
+        if let Some(hash) = hashes.iter().find(|hash| !self.pool.contains(hash)) {
+            return Err(
+                RpcError::invalid_params(format!("transaction {hash} is not in the pool")).into()
+            )
+        }
+        self.backend.set_next_block_order(hashes);
+        Ok(())
+    }
+
+    /// Removes the last `depth` blocks from the chain and mines `depth` new blocks, the first of
+    /// which includes the given raw signed transactions.
+    ///
+    /// Logs subscribers receive the logs of the removed blocks with `removed: true`.
+    ///
+    /// Like [Self::anvil_rollback], this can't remove the genesis block, or the fork block when
+    /// forking.
+    ///
+    /// Handler for RPC call: `anvil_reorg`
+    pub async fn anvil_reorg(&self, depth: U256, transactions: Vec<Bytes>) -> Result<()> {
+        node_info!("anvil_reorg");
+        let transactions = transactions
+            .into_iter()
+            .map(|tx| {
+                let pending_transaction = self.decode_raw_transaction(tx)?;
+                let nonce = pending_transaction.transaction.nonce().as_u64();
+                let provides = vec![to_marker(nonce, pending_transaction.sender().to_alloy())];
+                let priority = self.transaction_priority(&pending_transaction.transaction);
+                Ok(Arc::new(PoolTransaction {
+                    requires: vec![],
+                    provides,
+                    pending_transaction,
+                    priority,
+                }))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        self.backend.reorg(depth.saturating_to(), transactions).await
+    }
+
+    /// Removes the last `depth` blocks from the chain, as if they were never mined, and returns
+    /// their transactions to the pool.
+    ///
+    /// The state is restored to the end of the new head block. Logs subscribers receive the logs of
+    /// the removed blocks with `removed: true`. Note that with automine enabled, the returned
+    /// transactions are mined into a new block right away.
+    ///
+    /// The chain can't be rolled back past the genesis block, or the fork block when forking.
+    ///
+    /// Handler for RPC call: `anvil_rollback`
+    pub async fn anvil_rollback(&self, depth: U256) -> Result<()> {
+        node_info!("anvil_rollback");
+        for pending_transaction in self.backend.rollback(depth.saturating_to()).await? {
+            let from = pending_transaction.sender().to_alloy();
+            let nonce = pending_transaction.transaction.nonce().as_u64();
+            let on_chain_nonce = self.backend.current_nonce(from).await?;
+            let requires = required_marker(U256::from(nonce), on_chain_nonce, from);
+            let provides = vec![to_marker(nonce, from)];
+            if let Err(err) = self.add_pending_transaction(pending_transaction, requires, provides)
+            {
+                trace!(target: "node", ?err, "failed to return transaction to the pool");
+            }
+        }
+        Ok(())
+    }
+
+    /// Sets the percentage by which a transaction that replaces a pool transaction with the same
+    /// nonce must raise its gas price
+    ///
+    /// Handler for RPC call: `anvil_setMinFeeBumpPercent`
+    pub fn anvil_set_min_fee_bump_percent(&self, percent: U256) -> Result<()> {
+        node_info!("anvil_setMinFeeBumpPercent");
+        self.pool.set_min_fee_bump_percent(percent.saturating_to());
+        Ok(())
     }
 
     /// Reset the fork to a fresh forked state, and optionally update the fork config.
     ///
     /// If `forking` is `None` then this will disable forking entirely.
     ///
+    /// If a new `json_rpc_url` is provided, the forking backend is re-initialized with the new
+    /// endpoint, the current fork remains untouched if the new endpoint is unreachable.
+    ///
     /// Handler for RPC call: `anvil_reset`
     pub async fn anvil_reset(&self, forking: Option<Forking>) -> Result<()> {
         node_info!("anvil_reset");
         if let Some(forking) = forking {
+            self.backend.reset_fork(forking).await?;
             // if we're resetting the fork we need to reset the instance id
             self.reset_instance_id();
-            self.backend.reset_fork(forking).await
+            Ok(())
         } else {
             Err(BlockchainError::RpcUnimplemented)
         }
     }
 
+    /// Sets the chain id of the node, transactions are signed for the new chain id
+    ///
+    /// Transactions in the pool that were signed for another chain id are removed, since they can
+    /// no longer be included.
+    ///
+    /// Handler for RPC call: `anvil_setChainId`
     pub async fn anvil_set_chain_id(&self, chain_id: u64) -> Result<()> {
         node_info!("anvil_setChainId");
         self.backend.set_chain_id(chain_id);
+
+        let (ready, pending) = self.pool.ready_and_pending_transactions();
+        let replayable = ready
+            .into_iter()
+            .chain(pending)
+            .filter(|tx| {
+                tx.pending_transaction.transaction.chain_id().is_some_and(|id| id != chain_id)
+            })
+            .map(|tx| tx.hash())
+            .collect::<Vec<_>>();
+        if !replayable.is_empty() {
+            trace!(target: "node", ?replayable, "removing transactions of previous chain id");
+            self.pool.remove_invalid(replayable);
+        }
+        Ok(())
+    }
+
+    /// Sets the hardfork of all following blocks, like `shanghai` or `cancun`.
+    ///
+    /// Blocks that were already mined keep the hardfork they were mined with.
+    ///
+    /// Handler for RPC call: `anvil_setHardfork`
+    pub async fn anvil_set_hardfork(&self, hardfork: String) -> Result<()> {
+        node_info!("anvil_setHardfork");
+        let hardfork = Hardfork::from_str(&hardfork).map_err(RpcError::invalid_params)?;
+        self.backend.set_hardfork(hardfork).await;
         Ok(())
     }
 
@@ -1625,20 +2039,59 @@ impl EthApi {
     ///
     /// Handler for RPC call: `anvil_setCode`
     pub async fn anvil_set_code(&self, address: Address, code: Bytes) -> Result<()> {
+        self.anvil_set_code_with_validation(address, code, false).await
+    }
+
+    /// Sets the code of a contract.
+    ///
+    /// If `validate` is set, code that halts immediately is rejected, otherwise only a warning is
+    /// logged, see [validate_runtime_code].
+    ///
+    /// Handler for RPC call: `anvil_setCode`
+    pub async fn anvil_set_code_with_validation(
+        &self,
+        address: Address,
+        code: Bytes,
+        validate: bool,
+    ) -> Result<()> {
         node_info!("anvil_setCode");
+        if let Err(err) = validate_runtime_code(&code) {
+            if validate {
+                return Err(RpcError::invalid_params(format!("invalid bytecode: {err}")).into())
+            }
+            node_info!("    Warning: {err}");
+        }
         self.backend.set_code(address, code).await?;
         Ok(())
     }
 
     /// Sets the nonce of an address.
     ///
+    /// The nonce can't be lowered below the current nonce of the account, but it can be raised to
+    /// leave a gap before the pending transactions of the account.
+    ///
     /// Handler for RPC call: `anvil_setNonce`
     pub async fn anvil_set_nonce(&self, address: Address, nonce: U256) -> Result<()> {
         node_info!("anvil_setNonce");
+        let current = self.backend.get_nonce(address, None).await?;
+        if nonce < current {
+            return Err(RpcError::invalid_params(format!(
+                "nonce {nonce} is lower than the current nonce {current} of {address}"
+            ))
+            .into())
+        }
         self.backend.set_nonce(address, nonce).await?;
         Ok(())
     }
 
+    /// Returns the next nonce of an address, which accounts for its transactions in the pool.
+    ///
+    /// Handler for RPC call: `anvil_getNonce`
+    pub async fn anvil_get_nonce(&self, address: Address) -> Result<U256> {
+        node_info!("anvil_getNonce");
+        self.get_transaction_count(address, Some(BlockId::Number(BlockNumber::Pending))).await
+    }
+
     /// Writes a single slot of the account's storage.
     ///
     /// Handler for RPC call: `anvil_setStorageAt`
@@ -1653,8 +2106,22 @@ impl EthApi {
         Ok(true)
     }
 
+    /// Writes multiple storage slots of multiple accounts at once.
+    ///
+    /// Either all slots are written or, if any write fails, none are.
+    ///
+    /// Handler for RPC call: `anvil_setStorageBatch`
+    pub async fn anvil_set_storage_batch(&self, storage: StorageBatch) -> Result<bool> {
+        node_info!("anvil_setStorageBatch");
+        self.backend.set_storage_batch(storage).await?;
+        Ok(true)
+    }
+
     /// Enable or disable logging.
     ///
+    /// This silences all node logs at runtime, including the logs of mined transactions and blocks
+    /// and `console.log` output.
+    ///
     /// Handler for RPC call: `anvil_setLoggingEnabled`
     pub async fn anvil_set_logging(&self, enable: bool) -> Result<()> {
         node_info!("anvil_setLoggingEnabled");
@@ -1662,18 +2129,58 @@ impl EthApi {
         Ok(())
     }
 
+    /// Enable or disable tracing the steps of all mined transactions.
+    ///
+    /// While enabled, every executed opcode of a mined transaction is recorded together with the
+    /// stack and memory, so that `debug_traceTransaction` returns the struct logs without
+    /// re-executing the transaction. This can take up several megabytes per transaction, so the
+    /// steps are only kept for the most recently mined transactions, see
+    /// `--steps-tracing-cache-size`. The call traces of all transactions are always recorded.
+    ///
+    /// Handler for RPC call: `anvil_setTracingEnabled`
+    pub fn anvil_set_tracing_enabled(&self, enabled: bool) -> Result<()> {
+        node_info!("anvil_setTracingEnabled");
+        self.backend.set_steps_tracing(enabled);
+        Ok(())
+    }
+
+    /// Registers the ABI of a contract, or the fallback ABI of all contracts if no address is
+    /// given.
+    ///
+    /// The registered ABIs are used to decode the calls and events of `debug_traceTransaction`
+    /// with `decode` set and the events of `anvil_simulate`. The ABI of a contract takes precedence
+    /// over the fallback ABI and the `--signatures`.
+    ///
+    /// Handler for RPC call: `anvil_registerAbi`
+    pub fn anvil_register_abi(&self, address: Option<Address>, abi: JsonAbi) -> Result<()> {
+        node_info!("anvil_registerAbi");
+        self.backend.register_abi(address, abi);
+        Ok(())
+    }
+
+    /// Returns up to `count` of the most recent node log lines, oldest first.
+    ///
+    /// The lines are recorded even if logging is disabled, the number of kept lines is configured
+    /// via `--log-buffer-size`.
+    ///
+    /// Handler for RPC call: `anvil_getNodeLogs`
+    pub fn anvil_get_node_logs(&self, count: u64) -> Result<Vec<NodeLog>> {
+        node_info!("anvil_getNodeLogs");
+        Ok(logging::node_logs().last(count.try_into().unwrap_or(usize::MAX)))
+    }
+
     /// Set the minimum gas price for the node.
     ///
+    /// Transactions with a lower effective gas price are rejected when they are submitted, post
+    /// London the effective gas price is `min(max_fee_per_gas, base_fee + max_priority_fee)`.
+    ///
     /// Handler for RPC call: `anvil_setMinGasPrice`
     pub async fn anvil_set_min_gas_price(&self, gas: U256) -> Result<()> {
         node_info!("anvil_setMinGasPrice");
-        if self.backend.is_eip1559() {
-            return Err(RpcError::invalid_params(
-                "anvil_setMinGasPrice is not supported when EIP-1559 is active",
-            )
-            .into());
+        if !self.backend.is_eip1559() {
+            self.backend.set_gas_price(gas);
         }
-        self.backend.set_gas_price(gas);
+        self.backend.fees().set_min_gas_price(gas.to_ethers());
         Ok(())
     }
 
@@ -1692,8 +2199,61 @@ impl EthApi {
         Ok(())
     }
 
+    /// Sets the prevrandao of all following blocks, which the `PREVRANDAO` opcode reads.
+    ///
+    /// Before the merge, this sets the difficulty of the blocks instead, which the `DIFFICULTY`
+    /// opcode reads.
+    ///
+    /// Handler for RPC call: `anvil_setPrevRandao`
+    pub async fn anvil_set_prevrandao(&self, prevrandao: U256) -> Result<()> {
+        node_info!("anvil_setPrevRandao");
+        self.backend.set_prevrandao(B256::from(prevrandao.to_be_bytes()));
+        Ok(())
+    }
+
+    /// Sets the prevrandao of the next block only, the following blocks use the value of
+    /// `anvil_setPrevRandao` again.
+    ///
+    /// Handler for RPC call: `anvil_setNextBlockPrevRandao`
+    pub async fn anvil_set_next_block_prevrandao(&self, prevrandao: U256) -> Result<()> {
+        node_info!("anvil_setNextBlockPrevRandao");
+        self.backend.set_next_block_prevrandao(B256::from(prevrandao.to_be_bytes()));
+        Ok(())
+    }
+
+    /// Sets the parameters of the base fee calculation of all following blocks, parameters that
+    /// are not set remain unchanged.
+    ///
+    /// Handler for RPC call: `anvil_setBaseFeeParams`
+    pub async fn anvil_set_base_fee_params(&self, opts: BaseFeeParamsOptions) -> Result<()> {
+        node_info!("anvil_setBaseFeeParams");
+        if !self.backend.is_eip1559() {
+            return Err(RpcError::invalid_params(
+                "anvil_setBaseFeeParams is only supported when EIP-1559 is active",
+            )
+            .into());
+        }
+        let BaseFeeParamsOptions { gas_target, max_change_denominator } = opts;
+        if gas_target == Some(0) || max_change_denominator == Some(0) {
+            return Err(RpcError::invalid_params(
+                "the gas target and the max change denominator must be greater than zero",
+            )
+            .into());
+        }
+        let fees = self.backend.fees();
+        let mut params = fees.base_fee_params();
+        params.gas_target = gas_target.or(params.gas_target);
+        params.max_change_denominator =
+            max_change_denominator.unwrap_or(params.max_change_denominator);
+        fees.set_base_fee_params(params);
+        Ok(())
+    }
+
     /// Sets the coinbase address.
     ///
+    /// The coinbase is the beneficiary of the following blocks, it receives the priority fees of
+    /// their transactions and is returned by `block.coinbase`.
+    ///
     /// Handler for RPC call: `anvil_setCoinbase`
     pub async fn anvil_set_coinbase(&self, address: Address) -> Result<()> {
         node_info!("anvil_setCoinbase");
@@ -1704,15 +2264,19 @@ impl EthApi {
     /// Create a buffer that represents all state on the chain, which can be loaded to separate
     /// process by calling `anvil_loadState`
     ///
+    /// If the `diff` option is set, only the state that was modified since forking is included,
+    /// which can only be loaded by a node that is forked off the same block.
+    ///
     /// Handler for RPC call: `anvil_dumpState`
-    pub async fn anvil_dump_state(&self) -> Result<Bytes> {
+    pub async fn anvil_dump_state(&self, opts: Option<DumpStateOptions>) -> Result<Bytes> {
         node_info!("anvil_dumpState");
-        self.backend.dump_state().await
+        let diff = opts.map_or(false, |opts| opts.diff);
+        self.backend.dump_state(diff).await
     }
 
     /// Returns the current state
     pub async fn serialized_state(&self) -> Result<SerializableState> {
-        self.backend.serialized_state().await
+        self.backend.serialized_state(false).await
     }
 
     /// Append chain state buffer to current chain. Will overwrite any conflicting addresses or
@@ -1733,8 +2297,16 @@ impl EthApi {
         let env = self.backend.env().read();
         let fork_config = self.backend.get_fork();
         let tx_order = self.transaction_order.read();
+        let accounts_count = self
+            .signers
+            .read()
+            .iter()
+            .flat_map(|signer| signer.accounts())
+            .collect::<HashSet<_>>()
+            .len();
 
         Ok(NodeInfo {
+            client_version: CLIENT_VERSION.to_string(),
             current_block_number: U64::from(self.backend.best_number()),
             current_block_timestamp: env.block.timestamp.try_into().unwrap_or(u64::MAX),
             current_block_hash: self.backend.best_hash(),
@@ -1743,6 +2315,8 @@ impl EthApi {
                 TransactionOrder::Fifo => "fifo".to_string(),
                 TransactionOrder::Fees => "fees".to_string(),
             },
+            mining_mode: self.miner.mode_info(),
+            accounts_count: accounts_count as u64,
             environment: NodeEnvironment {
                 base_fee: self.backend.base_fee(),
                 chain_id: self.backend.chain_id().to::<u64>(),
@@ -1754,7 +2328,7 @@ impl EthApi {
                     let config = fork.config.read();
 
                     NodeForkConfig {
-                        fork_url: Some(config.eth_rpc_url.clone()),
+                        fork_url: Some(redact_url(&config.eth_rpc_url)),
                         fork_block_number: Some(config.block_number),
                         fork_retry_backoff: Some(config.backoff.as_millis()),
                     }
@@ -1765,6 +2339,9 @@ impl EthApi {
 
     /// Retrieves metadata about the Anvil instance.
     ///
+    /// This is cheap and doesn't make any requests to the forked node, tools can poll it and use
+    /// the instance id to detect that the node restarted or the fork was reset.
+    ///
     /// Handler for RPC call: `anvil_metadata`
     pub async fn anvil_metadata(&self) -> Result<AnvilMetadata> {
         node_info!("anvil_metadata");
@@ -1786,6 +2363,129 @@ impl EthApi {
         })
     }
 
+    /// Executes the call and returns the gas spent per opcode, the hottest opcodes first.
+    ///
+    /// The gas spent by nested calls is attributed to the opcodes of the nested call.
+    ///
+    /// Handler for RPC call: `anvil_gasProfile`
+    pub async fn anvil_gas_profile(
+        &self,
+        request: CallRequest,
+        block_number: Option<BlockId>,
+    ) -> Result<Vec<OpcodeGas>> {
+        node_info!("anvil_gasProfile");
+        let block_request = self.block_request(block_number).await?;
+        let fees = FeeDetails::new(
+            request.gas_price.map(ToEthers::to_ethers),
+            request.max_fee_per_gas.map(ToEthers::to_ethers),
+            request.max_priority_fee_per_gas.map(ToEthers::to_ethers),
+        )?
+        .or_zero_fees();
+        let request = call_to_internal_tx_request(&request);
+
+        self.on_blocking_task(|this| async move {
+            this.backend.call_with_gas_profile(request, fees, Some(block_request)).await
+        })
+        .await
+    }
+
+    /// Executes the call like `eth_call`, without committing it or adding it to the pool, and
+    /// returns its gas used, output, events, `console.sol` logs and call trace.
+    ///
+    /// Unlike `eth_call` a failing call doesn't return an error, the [Simulation] reports the
+    /// revert reason instead. The [BlockOverrides] only apply to this call.
+    ///
+    /// Handler for RPC call: `anvil_simulate`
+    pub async fn anvil_simulate(
+        &self,
+        request: CallRequest,
+        block_number: Option<BlockId>,
+        block_overrides: Option<BlockOverrides>,
+    ) -> Result<Simulation> {
+        node_info!("anvil_simulate");
+        let block_request = self.block_request(block_number).await?;
+        let fees = FeeDetails::new(
+            request.gas_price.map(ToEthers::to_ethers),
+            request.max_fee_per_gas.map(ToEthers::to_ethers),
+            request.max_priority_fee_per_gas.map(ToEthers::to_ethers),
+        )?
+        .or_zero_fees();
+        let request = call_to_internal_tx_request(&request);
+
+        self.on_blocking_task(|this| async move {
+            this.backend.simulate_call(request, fees, Some(block_request), block_overrides).await
+        })
+        .await
+    }
+
+    /// Executes the calls in order on top of the latest state, like flashbots' `eth_callBundle`,
+    /// and returns the [Simulation] of every call.
+    ///
+    /// Every call sees the changes of the calls before it, all changes are discarded afterwards.
+    /// If `stop_on_revert` is set, the calls after the first failed one are not executed.
+    ///
+    /// Handler for RPC call: `anvil_simulateBundle`
+    pub async fn anvil_simulate_bundle(
+        &self,
+        requests: Vec<CallRequest>,
+        overrides: Option<BlockOverrides>,
+        stop_on_revert: Option<bool>,
+    ) -> Result<Vec<Simulation>> {
+        node_info!("anvil_simulateBundle");
+        let calls = requests
+            .iter()
+            .map(|request| {
+                let fees = FeeDetails::new(
+                    request.gas_price.map(ToEthers::to_ethers),
+                    request.max_fee_per_gas.map(ToEthers::to_ethers),
+                    request.max_priority_fee_per_gas.map(ToEthers::to_ethers),
+                )?
+                .or_zero_fees();
+                Ok((call_to_internal_tx_request(request), fees))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        self.on_blocking_task(|this| async move {
+            this.backend
+                .simulate_bundle(
+                    calls,
+                    overrides.unwrap_or_default(),
+                    stop_on_revert.unwrap_or_default(),
+                )
+                .await
+        })
+        .await
+    }
+
+    /// Re-executes the mined transaction and returns its `console.sol` logs, the gas used by the
+    /// calls its outermost call made and the revert reason, if it failed.
+    ///
+    /// Returns `None` if the transaction is unknown.
+    ///
+    /// Handler for RPC call: `anvil_inspectTransaction`
+    pub async fn anvil_inspect_transaction(
+        &self,
+        hash: B256,
+    ) -> Result<Option<TransactionInspection>> {
+        node_info!("anvil_inspectTransaction");
+        self.on_blocking_task(
+            |this| async move { this.backend.inspect_mined_transaction(hash).await },
+        )
+        .await
+    }
+
+    /// Returns the gas used by every call frame of the mined transaction, in the order the calls
+    /// were made.
+    ///
+    /// The gas of a frame is split into the gas it used itself and the gas used including its
+    /// nested calls. Returns `None` if the transaction is unknown.
+    ///
+    /// Handler for RPC call: `anvil_traceGasSummary`
+    pub fn anvil_trace_gas_summary(&self, hash: B256) -> Result<Option<Vec<CallGasSummary>>> {
+        node_info!("anvil_traceGasSummary");
+        Ok(self.backend.mined_call_gas_summary(hash))
+    }
+
     /// Snapshot the state of the blockchain at the current block.
     ///
     /// Handler for RPC call: `evm_snapshot`
@@ -1794,21 +2494,89 @@ impl EthApi {
         Ok(self.backend.create_snapshot().await)
     }
 
+    /// Snapshot the state of the blockchain and the pending and queued transactions of the pool
+    /// at the current block.
+    ///
+    /// Reverting the snapshot with `evm_revert` also replaces the transactions in the pool with
+    /// the transactions at the time of the snapshot.
+    ///
+    /// Handler for RPC call: `anvil_snapshotFull`
+    pub async fn anvil_snapshot_full(&self) -> Result<U256> {
+        node_info!("anvil_snapshotFull");
+        let id = self.backend.create_snapshot().await;
+        self.pool.snapshot(id);
+        Ok(id)
+    }
+
     /// Revert the state of the blockchain to a previous snapshot.
     /// Takes a single parameter, which is the snapshot id to revert to.
     ///
     /// Handler for RPC call: `evm_revert`
     pub async fn evm_revert(&self, id: U256) -> Result<bool> {
         node_info!("evm_revert");
-        self.backend.revert_snapshot(id).await
+        self.revert_snapshot(id).await
+    }
+
+    /// Snapshot the state of the blockchain at the current block, the snapshot can be reverted
+    /// with `anvil_revertNamed` by the given name.
+    ///
+    /// Handler for RPC call: `anvil_snapshotNamed`
+    pub async fn anvil_snapshot_named(&self, name: String) -> Result<U256> {
+        node_info!("anvil_snapshotNamed");
+        Ok(self.backend.create_named_snapshot(name).await)
+    }
+
+    /// Revert the state of the blockchain to the snapshot with the given name.
+    ///
+    /// Like `evm_revert`, this also removes all snapshots that were created after the snapshot.
+    ///
+    /// Handler for RPC call: `anvil_revertNamed`
+    pub async fn anvil_revert_named(&self, name: &str) -> Result<bool> {
+        node_info!("anvil_revertNamed");
+        match self.backend.named_snapshot(name) {
+            Some(id) => self.revert_snapshot(id).await,
+            None => Ok(false),
+        }
+    }
+
+    /// Returns the names of all active named snapshots and their ids.
+    ///
+    /// Handler for RPC call: `anvil_listSnapshots`
+    pub fn anvil_list_snapshots(&self) -> Result<BTreeMap<String, U256>> {
+        node_info!("anvil_listSnapshots");
+        Ok(self.backend.list_named_snapshots())
+    }
+
+    /// Reverts the chain and, for snapshots created with `anvil_snapshotFull`, the pool to the
+    /// snapshot
+    async fn revert_snapshot(&self, id: U256) -> Result<bool> {
+        let reverted = self.backend.revert_snapshot(id).await?;
+        if reverted && self.pool.revert_snapshot(id) {
+            // the order may have changed since the snapshot was taken
+            self.pool.set_order(*self.transaction_order.read());
+        }
+        Ok(reverted)
     }
 
     /// Jump forward in time by the given amount of time, in seconds.
     ///
+    /// The offset applies to all following blocks, returns the timestamp of the next block.
+    ///
     /// Handler for RPC call: `evm_increaseTime`
-    pub async fn evm_increase_time(&self, seconds: U256) -> Result<i64> {
+    pub async fn evm_increase_time(&self, seconds: U256) -> Result<u64> {
         node_info!("evm_increaseTime");
-        Ok(self.backend.time().increase_time(seconds.try_into().unwrap_or(u64::MAX)) as i64)
+        let time = self.backend.time();
+        time.increase_time(seconds.try_into().unwrap_or(u64::MAX));
+        Ok(time.current_call_timestamp())
+    }
+
+    /// Returns the offset in seconds of the block timestamps to the current time
+    ///
+    /// Handler for RPC call: `anvil_getTimeOffset`
+    pub fn anvil_get_time_offset(&self) -> Result<i64> {
+        node_info!("anvil_getTimeOffset");
+        let offset = self.backend.time().offset();
+        Ok(offset.clamp(i64::MIN as i128, i64::MAX as i128) as i64)
     }
 
     /// Similar to `evm_increaseTime` but takes the exact timestamp that you want in the next block
@@ -1819,25 +2587,29 @@ impl EthApi {
         self.backend.time().set_next_block_timestamp(seconds)
     }
 
-    /// Sets the specific timestamp and returns the number of seconds between the given timestamp
-    /// and the current time.
+    /// Sets the timestamp of the next block, following blocks advance from there.
     ///
-    /// Handler for RPC call: `evm_setTime`
-    pub fn evm_set_time(&self, timestamp: u64) -> Result<u64> {
+    /// The timestamp is the new absolute base of the following blocks, the offsets of previous
+    /// `evm_increaseTime` calls no longer apply. Returns the number of seconds the clock was moved
+    /// by, negative if it was moved back.
+    ///
+    /// Handler for RPC call: `anvil_setTime`, `evm_setTime`
+    pub fn evm_set_time(&self, timestamp: u64) -> Result<i64> {
         node_info!("evm_setTime");
-        let now = self.backend.time().current_call_timestamp();
-        self.backend.time().reset(timestamp);
-
-        // number of seconds between the given timestamp and the current time.
-        let offset = timestamp.saturating_sub(now);
-        Ok(Duration::from_millis(offset).as_secs())
+        let delta = self.backend.time().set_time(timestamp);
+        Ok(delta.clamp(i64::MIN as i128, i64::MAX as i128) as i64)
     }
 
-    /// Set the next block gas limit
+    /// Set the gas limit of the subsequent blocks
+    ///
+    /// Pending transactions that don't fit into the next block are included in later blocks.
     ///
-    /// Handler for RPC call: `evm_setBlockGasLimit`
+    /// Handler for RPC call: `evm_setBlockGasLimit`, `anvil_setBlockGasLimit`
     pub fn evm_set_block_gas_limit(&self, gas_limit: U256) -> Result<bool> {
         node_info!("evm_setBlockGasLimit");
+        if gas_limit.is_zero() {
+            return Err(RpcError::invalid_params("block gas limit must be greater than zero").into());
+        }
         self.backend.set_gas_limit(gas_limit);
         Ok(true)
     }
@@ -2005,8 +2777,11 @@ impl EthApi {
     /// well as the ones that are being scheduled for future execution only.
     /// Ref: [Here](https://geth.ethereum.org/docs/rpc/ns-txpool#txpool_status)
     ///
+    /// In addition to geth's counts, this includes the total size of the transactions in bytes and
+    /// the limits of the pool, see `--txpool-max-count` and `--txpool-max-bytes`.
+    ///
     /// Handler for ETH RPC call: `txpool_status`
-    pub async fn txpool_status(&self) -> Result<TxpoolStatus> {
+    pub async fn txpool_status(&self) -> Result<PoolStatus> {
         node_info!("txpool_status");
         Ok(self.pool.txpool_status())
     }
@@ -2036,15 +2811,16 @@ impl EthApi {
         // the block. _Queued transactions_ are transactions where the transaction nonce is
         // not in sequence. The transaction nonce is an incrementing number for each transaction
         // with the same From address.
-        for pending in self.pool.ready_transactions() {
+        let (ready, queued) = self.pool.ready_and_pending_transactions();
+        for pending in ready {
             let entry =
                 inspect.pending.entry(pending.pending_transaction.sender().to_alloy()).or_default();
             let key = pending.pending_transaction.nonce().to_string();
             entry.insert(key, convert(pending));
         }
-        for queued in self.pool.pending_transactions() {
+        for queued in queued {
             let entry =
-                inspect.pending.entry(queued.pending_transaction.sender().to_alloy()).or_default();
+                inspect.queued.entry(queued.pending_transaction.sender().to_alloy()).or_default();
             let key = queued.pending_transaction.nonce().to_string();
             entry.insert(key, convert(queued));
         }
@@ -2056,7 +2832,7 @@ impl EthApi {
     ///
     /// See [here](https://geth.ethereum.org/docs/rpc/ns-txpool#txpool_content) for more details
     ///
-    /// Handler for ETH RPC call: `txpool_inspect`
+    /// Handler for ETH RPC call: `txpool_content` and `anvil_getMempool`
     pub async fn txpool_content(&self) -> Result<TxpoolContent> {
         node_info!("txpool_content");
         let mut content = TxpoolContent::default();
@@ -2076,15 +2852,16 @@ impl EthApi {
             tx
         }
 
-        for pending in self.pool.ready_transactions() {
+        let (ready, queued) = self.pool.ready_and_pending_transactions();
+        for pending in ready {
             let entry =
                 content.pending.entry(pending.pending_transaction.sender().to_alloy()).or_default();
             let key = pending.pending_transaction.nonce().to_string();
             entry.insert(key, convert(pending));
         }
-        for queued in self.pool.pending_transactions() {
+        for queued in queued {
             let entry =
-                content.pending.entry(queued.pending_transaction.sender().to_alloy()).or_default();
+                content.queued.entry(queued.pending_transaction.sender().to_alloy()).or_default();
             let key = queued.pending_transaction.nonce().to_string();
             entry.insert(key, convert(queued));
         }
@@ -2369,9 +3146,10 @@ impl EthApi {
         Ok(highest_gas_limit.to_alloy())
     }
 
-    /// Updates the `TransactionOrder`
+    /// Updates the `TransactionOrder` and re-sorts the pool accordingly
     pub fn set_transaction_order(&self, order: TransactionOrder) {
         *self.transaction_order.write() = order;
+        self.pool.set_order(order);
     }
 
     /// Returns the priority of the transaction based on the current `TransactionOrder`
@@ -2400,9 +3178,8 @@ impl EthApi {
     }
 
     /// Returns the first signer that can sign for the given address
-    #[allow(clippy::borrowed_box)]
-    pub fn get_signer(&self, address: Address) -> Option<&Box<dyn Signer>> {
-        self.signers.iter().find(|signer| signer.is_signer_for(address.to_ethers()))
+    pub fn get_signer(&self, address: Address) -> Option<Arc<dyn Signer>> {
+        self.signers.read().iter().find(|signer| signer.is_signer_for(address.to_ethers())).cloned()
     }
 
     /// Returns a new block event stream that yields Notifications when a new block was added
@@ -2415,6 +3192,11 @@ impl EthApi {
         self.pool.add_ready_listener()
     }
 
+    /// Returns a listener for the full transactions that become ready
+    pub fn new_full_ready_transactions(&self) -> Receiver<Arc<PoolTransaction>> {
+        self.pool.add_full_ready_listener()
+    }
+
     /// Returns a new accessor for certain storage elements
     pub fn storage_info(&self) -> StorageInfo {
         StorageInfo::new(Arc::clone(&self.backend))
@@ -2425,12 +3207,33 @@ impl EthApi {
         self.backend.is_fork()
     }
 
+    /// Returns true if the node is ready to serve requests, see [backend::mem::Backend::is_ready]
+    pub fn is_ready(&self) -> bool {
+        self.backend.is_ready()
+    }
+
+    /// Returns the number of the latest block
+    pub fn best_number(&self) -> u64 {
+        self.backend.best_number()
+    }
+
+    /// Returns the number of blocks mined since the node started
+    pub fn blocks_mined(&self) -> u64 {
+        self.miner.blocks_mined()
+    }
+
+    /// Returns the number of transactions in the pool that are ready and queued
+    pub fn pool_status(&self) -> PoolStatus {
+        self.pool.txpool_status()
+    }
+
     /// Mines exactly one block
     pub async fn mine_one(&self) {
         let transactions = self.pool.ready_transactions().collect::<Vec<_>>();
         let outcome = self.backend.mine_block(transactions).await;
 
         trace!(target: "node", blocknumber = ?outcome.block_number, "mined block");
+        self.miner.on_block_mined();
         self.pool.on_mined_block(outcome);
     }
 
@@ -2594,6 +3397,8 @@ impl EthApi {
         match &tx {
             TypedTransaction::EIP2930(_) => self.backend.ensure_eip2930_active(),
             TypedTransaction::EIP1559(_) => self.backend.ensure_eip1559_active(),
+            TypedTransaction::EIP4844(_) => self.backend.ensure_eip4844_active(),
+            TypedTransaction::EIP7702(_) => self.backend.ensure_eip7702_active(),
             TypedTransaction::Deposit(_) => self.backend.ensure_op_deposits_active(),
             TypedTransaction::Legacy(_) => Ok(()),
         }
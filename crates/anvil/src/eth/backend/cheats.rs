@@ -36,12 +36,36 @@ impl CheatsManager {
         state.impersonated_accounts.insert(addr)
     }
 
+    /// Adds all accounts to the impersonated set
+    pub fn impersonate_accounts(&self, addrs: impl IntoIterator<Item = Address>) {
+        let mut state = self.state.write();
+        for addr in addrs {
+            trace!(target: "cheats", "Start impersonating {:?}", addr);
+            state.impersonated_accounts.insert(addr);
+        }
+    }
+
     /// Removes the account that from the impersonated set
     pub fn stop_impersonating(&self, addr: &Address) {
         trace!(target: "cheats", "Stop impersonating {:?}", addr);
         self.state.write().impersonated_accounts.remove(addr);
     }
 
+    /// Removes all accounts from the impersonated set
+    pub fn stop_impersonating_accounts<'a>(&self, addrs: impl IntoIterator<Item = &'a Address>) {
+        let mut state = self.state.write();
+        for addr in addrs {
+            trace!(target: "cheats", "Stop impersonating {:?}", addr);
+            state.impersonated_accounts.remove(addr);
+        }
+    }
+
+    /// Removes all accounts from the impersonated set
+    pub fn clear_impersonated_accounts(&self) {
+        trace!(target: "cheats", "Stop impersonating all accounts");
+        self.state.write().impersonated_accounts.clear();
+    }
+
     /// Returns true if the `addr` is currently impersonated
     pub fn is_impersonated(&self, addr: Address) -> bool {
         if self.state.read().auto_impersonate_accounts {
@@ -77,6 +101,8 @@ pub struct CheatsState {
     /// The signature used for the `eth_sendUnsignedTransaction` cheat code
     pub bypass_signature: Signature,
     /// If set to true will make the `is_impersonated` function always return true
+    ///
+    /// This effectively disables the signature checks of `eth_sendTransaction`.
     pub auto_impersonate_accounts: bool,
 }
 
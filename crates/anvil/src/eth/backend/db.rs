@@ -1,9 +1,9 @@
 //! Helper types for working with [revm](foundry_evm::revm)
 
-use crate::{mem::state::trie_hash_db, revm::primitives::AccountInfo};
+use crate::revm::primitives::AccountInfo;
 use alloy_primitives::{keccak256, Address, Bytes, B256, U256};
 use alloy_rpc_types::BlockId;
-use anvil_core::eth::trie::KeccakHasher;
+use anvil_core::eth::{block::Block, receipt::TypedReceipt};
 use foundry_common::errors::FsPathError;
 use foundry_evm::{
     backend::{DatabaseError, DatabaseResult, MemDb, StateSnapshot},
@@ -15,22 +15,14 @@ use foundry_evm::{
         Database, DatabaseCommit,
     },
 };
-use hash_db::HashDB;
 use serde::{Deserialize, Serialize};
 use std::{collections::BTreeMap, fmt, path::Path};
 
-/// Type alias for the `HashDB` representation of the Database
-pub type AsHashDB = Box<dyn HashDB<KeccakHasher, Vec<u8>>>;
-
 /// Helper trait get access to the data in `HashDb` form
 #[auto_impl::auto_impl(Box)]
 pub trait MaybeHashDatabase: DatabaseRef<Error = DatabaseError> {
-    /// Return the DB as read-only hashdb and the root key
-    fn maybe_as_hash_db(&self) -> Option<(AsHashDB, B256)> {
-        None
-    }
-    /// Return the storage DB as read-only hashdb and the storage root of the account
-    fn maybe_account_db(&self, _addr: Address) -> Option<(AsHashDB, B256)> {
+    /// Return all accounts that are held by the DB, if the DB holds the entire state
+    fn maybe_as_full_db(&self) -> Option<&HashMap<Address, DbAccount>> {
         None
     }
 
@@ -48,11 +40,8 @@ impl<'a, T: 'a + MaybeHashDatabase + ?Sized> MaybeHashDatabase for &'a T
 where
     &'a T: DatabaseRef<Error = DatabaseError>,
 {
-    fn maybe_as_hash_db(&self) -> Option<(AsHashDB, B256)> {
-        T::maybe_as_hash_db(self)
-    }
-    fn maybe_account_db(&self, addr: Address) -> Option<(AsHashDB, B256)> {
-        T::maybe_account_db(self, addr)
+    fn maybe_as_full_db(&self) -> Option<&HashMap<Address, DbAccount>> {
+        T::maybe_as_full_db(self)
     }
 
     fn clear_into_snapshot(&mut self) -> StateSnapshot {
@@ -122,12 +111,37 @@ pub trait Db:
     /// Sets the balance of the given address
     fn set_storage_at(&mut self, address: Address, slot: U256, val: U256) -> DatabaseResult<()>;
 
+    /// Sets the storage slots of multiple accounts.
+    ///
+    /// All accounts are loaded before any slot is written, so that either all slots are set or,
+    /// if loading an account fails, none are.
+    fn set_storage_batch(
+        &mut self,
+        storage: &BTreeMap<Address, BTreeMap<U256, U256>>,
+    ) -> DatabaseResult<()> {
+        for address in storage.keys() {
+            self.basic(*address)?;
+        }
+        for (address, slots) in storage {
+            for (slot, val) in slots {
+                self.set_storage_at(*address, *slot, *val)?;
+            }
+        }
+        Ok(())
+    }
+
     /// inserts a blockhash for the given number
     fn insert_block_hash(&mut self, number: U256, hash: B256);
 
     /// Write all chain data to serialized bytes buffer
     fn dump_state(&self) -> DatabaseResult<Option<SerializableState>>;
 
+    /// Returns only the accounts and storage slots that were modified locally, if the DB is backed
+    /// by a remote client
+    fn dump_state_diff(&self) -> DatabaseResult<Option<SerializableState>> {
+        Ok(None)
+    }
+
     /// Deserialize and add all chain data to the backend storage
     fn load_state(&mut self, state: SerializableState) -> DatabaseResult<bool> {
         for (addr, account) in state.accounts.into_iter() {
@@ -214,8 +228,8 @@ impl<T: DatabaseRef<Error = DatabaseError> + Send + Sync + Clone + fmt::Debug> D
 }
 
 impl<T: DatabaseRef<Error = DatabaseError>> MaybeHashDatabase for CacheDB<T> {
-    fn maybe_as_hash_db(&self) -> Option<(AsHashDB, B256)> {
-        Some(trie_hash_db(&self.accounts))
+    fn maybe_as_full_db(&self) -> Option<&HashMap<Address, DbAccount>> {
+        Some(&self.accounts)
     }
     fn clear_into_snapshot(&mut self) -> StateSnapshot {
         let db_accounts = std::mem::take(&mut self.accounts);
@@ -301,12 +315,8 @@ impl DatabaseRef for StateDb {
 }
 
 impl MaybeHashDatabase for StateDb {
-    fn maybe_as_hash_db(&self) -> Option<(AsHashDB, B256)> {
-        self.0.maybe_as_hash_db()
-    }
-
-    fn maybe_account_db(&self, addr: Address) -> Option<(AsHashDB, B256)> {
-        self.0.maybe_account_db(addr)
+    fn maybe_as_full_db(&self) -> Option<&HashMap<Address, DbAccount>> {
+        self.0.maybe_as_full_db()
     }
 
     fn clear_into_snapshot(&mut self) -> StateSnapshot {
@@ -324,7 +334,25 @@ impl MaybeHashDatabase for StateDb {
 
 #[derive(Serialize, Deserialize, Clone, Debug, Default)]
 pub struct SerializableState {
+    /// The chain id of the chain the state was dumped from
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub chain_id: Option<u64>,
+    /// The number of the latest block when the state was dumped
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub best_block_number: Option<u64>,
+    /// The block of the fork the state is a diff against.
+    ///
+    /// If set, the accounts only contain the changes that were made locally, so the state can only
+    /// be loaded by a node that is forked off the same block.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fork: Option<SerializableForkBlock>,
     pub accounts: BTreeMap<Address, SerializableAccountRecord>,
+    /// The blocks of the chain
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub blocks: Vec<Block>,
+    /// The mined transactions of the chain
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub transactions: Vec<SerializableTransaction>,
 }
 
 // === impl SerializableState ===
@@ -346,6 +374,13 @@ impl SerializableState {
     }
 }
 
+/// The block a [SerializableState] diff was dumped against
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SerializableForkBlock {
+    pub number: u64,
+    pub hash: B256,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct SerializableAccountRecord {
     pub nonce: u64,
@@ -353,3 +388,23 @@ pub struct SerializableAccountRecord {
     pub code: Bytes,
     pub storage: BTreeMap<U256, U256>,
 }
+
+/// A mined transaction of a [SerializableState]
+///
+/// The traces of the transaction are not included.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct SerializableTransaction {
+    pub hash: B256,
+    pub transaction_index: u32,
+    pub from: Address,
+    pub to: Option<Address>,
+    pub contract_address: Option<Address>,
+    /// Whether the transaction was executed successfully
+    pub success: bool,
+    pub out: Option<Bytes>,
+    pub nonce: u64,
+    pub revert_reason: Option<String>,
+    pub receipt: TypedReceipt,
+    pub block_hash: B256,
+    pub block_number: u64,
+}
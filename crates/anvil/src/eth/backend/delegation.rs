@@ -0,0 +1,436 @@
+//! Support for the delegation designators of EIP-7702 set code transactions, see
+//! <https://eips.ethereum.org/EIPS/eip-7702>
+
+use anvil_core::eth::transaction::PendingTransaction;
+use foundry_common::types::ToAlloy;
+use foundry_evm::revm::{
+    interpreter::{CallInputs, CreateInputs, Gas, InstructionResult, Interpreter},
+    primitives::{
+        Account, AccountInfo, AccountStatus, Address, Bytecode, Bytes, ExecutionResult, State,
+        B256, KECCAK_EMPTY, U256,
+    },
+    Database, DatabaseCommit, EvmContext, Inspector,
+};
+
+/// The prefix of a delegation designator, `0xef0100 || address`
+pub const DELEGATION_PREFIX: [u8; 3] = [0xef, 0x01, 0x00];
+
+/// The intrinsic gas charged for every authorization of a set code transaction
+pub const PER_EMPTY_ACCOUNT_COST: u64 = 25_000;
+
+/// The gas of an authorization of an authority that already exists, the difference to
+/// [PER_EMPTY_ACCOUNT_COST] is refunded
+pub const PER_AUTH_BASE_COST: u64 = 12_500;
+
+/// Returns the intrinsic gas of the authorizations of the transaction, on top of the intrinsic gas
+/// of the call
+pub fn authorization_intrinsic_gas(tx: &PendingTransaction) -> u64 {
+    tx.transaction.authorization_list().len() as u64 * PER_EMPTY_ACCOUNT_COST
+}
+
+/// The outcome of [apply_authorizations]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct AppliedAuthorizations {
+    /// Whether the sender authorized a delegation itself, in which case its nonce no longer
+    /// matches the transaction's and its check must be skipped
+    pub sender_authorized: bool,
+    /// The intrinsic gas of the authorizations, see [authorization_intrinsic_gas]
+    pub intrinsic_gas: u64,
+    /// The gas refunded for the authorizations of authorities that already existed
+    pub refund: u64,
+}
+
+// === impl AppliedAuthorizations ===
+
+impl AppliedAuthorizations {
+    /// Returns the gas used by the transaction, given the result of its execution with a gas limit
+    /// reduced by the intrinsic gas of the authorizations.
+    ///
+    /// The refund of the authorizations is added to the refund of the execution, and both are
+    /// capped at a fifth of the gas spent, see EIP-3529.
+    pub fn gas_used(&self, result: &ExecutionResult) -> u64 {
+        let gas_refunded = match result {
+            ExecutionResult::Success { gas_refunded, .. } => *gas_refunded,
+            _ => 0,
+        };
+        let spent = result.gas_used() + gas_refunded + self.intrinsic_gas;
+        spent - (gas_refunded + self.refund).min(spent / 5)
+    }
+}
+
+/// Returns the address the code delegates to, if the code is a delegation designator
+///
+/// No deployed code can start with `0xef` since EIP-3541, so the prefix is sufficient to tell
+/// them apart, regardless of the padding of analyzed code.
+pub fn delegated_address(code: &[u8]) -> Option<Address> {
+    let rest = code.strip_prefix(&DELEGATION_PREFIX)?;
+    rest.get(..20).map(Address::from_slice)
+}
+
+/// Returns the delegation designator code of an account that delegates to `address`
+pub fn delegation_designator(address: Address) -> Bytecode {
+    let mut code = DELEGATION_PREFIX.to_vec();
+    code.extend_from_slice(address.as_slice());
+    Bytecode::new_raw(code.into()).to_checked()
+}
+
+/// Applies the authorizations of a set code transaction to the database, before the transaction
+/// is executed
+///
+/// Authorizations for another chain, with a mismatching nonce, an invalid signature or of an
+/// authority that has code that isn't a designator are skipped. An authorization for the zero
+/// address clears the delegation.
+///
+/// The nonce of the sender is bumped before the authorizations are processed, so an
+/// authorization signed by the sender must use the nonce after the transaction's.
+///
+/// The caller must ensure the gas limit of the transaction covers the
+/// [authorization_intrinsic_gas], and charge it from the gas limit of the execution.
+pub fn apply_authorizations<DB>(
+    db: &mut DB,
+    tx: &PendingTransaction,
+    chain_id: u64,
+) -> Result<AppliedAuthorizations, DB::Error>
+where
+    DB: Database + DatabaseCommit + ?Sized,
+{
+    let authorization_list = tx.transaction.authorization_list();
+    if authorization_list.is_empty() {
+        return Ok(AppliedAuthorizations::default())
+    }
+    let sender = tx.sender().to_alloy();
+
+    let mut changes = State::default();
+    let mut refund = 0;
+    for authorization in authorization_list {
+        if authorization.chain_id != 0 && authorization.chain_id != chain_id {
+            continue
+        }
+        let Ok(authority) = authorization.recover_authority() else { continue };
+        let authority = authority.to_alloy();
+
+        let (mut info, exists) = match changes.get(&authority) {
+            Some(account) => (account.info.clone(), true),
+            None => {
+                let info = db.basic(authority)?;
+                let exists = info.as_ref().map_or(false, |info| !info.is_empty());
+                let mut info = info.unwrap_or_default();
+                if authority == sender {
+                    info.nonce += 1;
+                }
+                (info, exists)
+            }
+        };
+        if let Some(code) = load_code(db, &info)? {
+            if delegated_address(code.bytes()).is_none() {
+                continue
+            }
+        }
+        if info.nonce != authorization.nonce {
+            continue
+        }
+
+        if exists {
+            refund += PER_EMPTY_ACCOUNT_COST - PER_AUTH_BASE_COST;
+        }
+        info.nonce += 1;
+        let delegate = authorization.address.to_alloy();
+        if delegate.is_zero() {
+            info.code_hash = KECCAK_EMPTY;
+            info.code = None;
+        } else {
+            let code = delegation_designator(delegate);
+            info.code_hash = code.hash_slow();
+            info.code = Some(code);
+        }
+        changes.insert(
+            authority,
+            Account { info, storage: Default::default(), status: AccountStatus::Touched },
+        );
+    }
+
+    // undo the nonce bump of the sender, the transaction itself bumps it
+    let sender_authorized = match changes.get_mut(&sender) {
+        Some(account) => {
+            account.info.nonce -= 1;
+            true
+        }
+        None => false,
+    };
+    db.commit(changes);
+    Ok(AppliedAuthorizations {
+        sender_authorized,
+        intrinsic_gas: authorization_intrinsic_gas(tx),
+        refund,
+    })
+}
+
+/// Returns the code of the account, `None` if it has no code
+fn load_code<DB: Database + ?Sized>(
+    db: &mut DB,
+    info: &AccountInfo,
+) -> Result<Option<Bytecode>, DB::Error> {
+    if let Some(code) = &info.code {
+        return Ok((!code.is_empty()).then(|| code.clone()))
+    }
+    if info.code_hash == KECCAK_EMPTY {
+        return Ok(None)
+    }
+    db.code_by_hash(info.code_hash).map(Some)
+}
+
+/// Points the code address of a call to an account with a delegation designator at the delegate,
+/// so that the call executes the code of the delegate in the context of the account.
+///
+/// Only the code executed by the call is resolved, `EXTCODESIZE`, `EXTCODECOPY` and `EXTCODEHASH`
+/// observe the designator itself.
+fn resolve_delegation<DB: Database>(data: &mut EvmContext<'_, DB>, call: &mut CallInputs) {
+    // a database error is reported when the evm loads the code of the call
+    let Ok((account, _)) = data.journaled_state.load_code(call.contract, data.db) else { return };
+    if let Some(delegate) =
+        account.info.code.as_ref().and_then(|code| delegated_address(code.bytes()))
+    {
+        call.contract = delegate;
+    }
+}
+
+/// Wraps the inspector of an evm so that calls to accounts with a delegation designator execute
+/// the code of the delegate.
+///
+/// The evm has no hook for this other than the inspector, so every evm of the node must be run
+/// with its inspector wrapped in this, whether it inspects anything or not.
+#[derive(Clone, Debug, Default)]
+pub struct Delegation<I>(pub I);
+
+impl<DB: Database, I: Inspector<DB>> Inspector<DB> for Delegation<I> {
+    #[inline]
+    fn initialize_interp(&mut self, interp: &mut Interpreter, data: &mut EvmContext<'_, DB>) {
+        self.0.initialize_interp(interp, data);
+    }
+
+    #[inline]
+    fn step(&mut self, interp: &mut Interpreter, data: &mut EvmContext<'_, DB>) {
+        self.0.step(interp, data);
+    }
+
+    #[inline]
+    fn log(
+        &mut self,
+        evm_data: &mut EvmContext<'_, DB>,
+        address: &Address,
+        topics: &[B256],
+        data: &Bytes,
+    ) {
+        self.0.log(evm_data, address, topics, data);
+    }
+
+    #[inline]
+    fn step_end(&mut self, interp: &mut Interpreter, data: &mut EvmContext<'_, DB>) {
+        self.0.step_end(interp, data);
+    }
+
+    #[inline]
+    fn call(
+        &mut self,
+        data: &mut EvmContext<'_, DB>,
+        call: &mut CallInputs,
+    ) -> (InstructionResult, Gas, Bytes) {
+        // the inspector sees the call as it was made
+        let outcome = self.0.call(data, call);
+        if outcome.0 == InstructionResult::Continue {
+            resolve_delegation(data, call);
+        }
+        outcome
+    }
+
+    #[inline]
+    fn call_end(
+        &mut self,
+        data: &mut EvmContext<'_, DB>,
+        inputs: &CallInputs,
+        remaining_gas: Gas,
+        ret: InstructionResult,
+        out: Bytes,
+    ) -> (InstructionResult, Gas, Bytes) {
+        self.0.call_end(data, inputs, remaining_gas, ret, out)
+    }
+
+    #[inline]
+    fn create(
+        &mut self,
+        data: &mut EvmContext<'_, DB>,
+        call: &mut CreateInputs,
+    ) -> (InstructionResult, Option<Address>, Gas, Bytes) {
+        self.0.create(data, call)
+    }
+
+    #[inline]
+    fn create_end(
+        &mut self,
+        data: &mut EvmContext<'_, DB>,
+        inputs: &CreateInputs,
+        status: InstructionResult,
+        address: Option<Address>,
+        gas: Gas,
+        retdata: Bytes,
+    ) -> (InstructionResult, Option<Address>, Gas, Bytes) {
+        self.0.create_end(data, inputs, status, address, gas, retdata)
+    }
+
+    #[inline]
+    fn selfdestruct(&mut self, contract: Address, target: Address, value: U256) {
+        Inspector::<DB>::selfdestruct(&mut self.0, contract, target, value);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mem::inspector::Inspector as NodeInspector;
+    use anvil_core::eth::transaction::{
+        EIP7702Transaction, SignedAuthorization, TransactionKind, TypedTransaction,
+    };
+    use ethers::{
+        signers::{LocalWallet, Signer},
+        types::H256,
+    };
+    use foundry_common::types::ToEthers;
+    use foundry_evm::revm::{
+        self,
+        db::{CacheDB, DatabaseRef, EmptyDB},
+        primitives::{CreateScheme, Env, TransactTo},
+    };
+
+    /// Returns a set code transaction from `sender` to `authority`, with an authorization of
+    /// `authority` for an empty delegate
+    fn set_code_transaction(sender: Address, authority: &LocalWallet) -> PendingTransaction {
+        let mut authorization = SignedAuthorization {
+            chain_id: 1,
+            address: Address::repeat_byte(0x11).to_ethers(),
+            nonce: 0,
+            y_parity: false,
+            r: H256::zero(),
+            s: H256::zero(),
+        };
+        let signature = authority.sign_hash(authorization.signature_hash()).unwrap();
+        let recid: u8 = signature.recovery_id().unwrap().into();
+        authorization.y_parity = recid != 0;
+        authorization.r = H256::from_uint(&signature.r);
+        authorization.s = H256::from_uint(&signature.s);
+
+        let tx = EIP7702Transaction {
+            chain_id: 1,
+            nonce: 0.into(),
+            max_priority_fee_per_gas: 0.into(),
+            max_fee_per_gas: 0.into(),
+            gas_limit: 100_000.into(),
+            kind: TransactionKind::Call(authority.address()),
+            value: 0.into(),
+            input: Default::default(),
+            access_list: Default::default(),
+            authorization_list: vec![authorization],
+            odd_y_parity: false,
+            r: H256::zero(),
+            s: H256::zero(),
+        };
+        PendingTransaction::with_impersonated(TypedTransaction::EIP7702(tx), sender.to_ethers())
+    }
+
+    /// Applies the authorizations and executes the transaction, returns the gas used
+    fn execute(db: &mut CacheDB<EmptyDB>, tx: &PendingTransaction) -> u64 {
+        let authorizations = apply_authorizations(db, tx, 1).unwrap();
+        assert!(!authorizations.sender_authorized);
+        assert_eq!(authorizations.intrinsic_gas, PER_EMPTY_ACCOUNT_COST);
+
+        let mut evm = revm::EVM::new();
+        evm.env = Env::default();
+        evm.env.tx = tx.to_revm_tx_env();
+        evm.env.tx.gas_limit -= authorizations.intrinsic_gas;
+        evm.database(&mut *db);
+        let result = evm.inspect_commit(Delegation(NodeInspector::default())).unwrap();
+        assert!(result.is_success());
+        authorizations.gas_used(&result)
+    }
+
+    #[test]
+    fn charges_authorization_gas() {
+        let sender = Address::repeat_byte(0x22);
+        let authority: LocalWallet =
+            "ac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80".parse().unwrap();
+
+        // the authority doesn't exist, the full cost is charged on top of the call
+        let mut db = CacheDB::new(EmptyDB::default());
+        let tx = set_code_transaction(sender, &authority);
+        assert_eq!(authorization_intrinsic_gas(&tx), PER_EMPTY_ACCOUNT_COST);
+        assert_eq!(execute(&mut db, &tx), 21_000 + PER_EMPTY_ACCOUNT_COST);
+
+        // the authority exists, the refund is capped at a fifth of the gas spent
+        let mut db = CacheDB::new(EmptyDB::default());
+        db.insert_account_info(
+            authority.address().to_alloy(),
+            AccountInfo { balance: U256::from(1), ..Default::default() },
+        );
+        let spent = 21_000 + PER_EMPTY_ACCOUNT_COST;
+        assert!(PER_EMPTY_ACCOUNT_COST - PER_AUTH_BASE_COST > spent / 5);
+        assert_eq!(execute(&mut db, &tx), spent - spent / 5);
+    }
+
+    #[test]
+    fn calls_execute_delegated_code() {
+        let delegate = Address::repeat_byte(0x11);
+        let authority = Address::repeat_byte(0x22);
+        // PUSH1 1 PUSH0 SSTORE STOP
+        let code = Bytecode::new_raw(vec![0x60, 0x01, 0x5f, 0x55, 0x00].into()).to_checked();
+
+        let mut db = CacheDB::new(EmptyDB::default());
+        db.insert_account_info(
+            delegate,
+            AccountInfo {
+                code_hash: code.hash_slow(),
+                code: Some(code.clone()),
+                ..Default::default()
+            },
+        );
+        let designator = delegation_designator(delegate);
+        assert_eq!(designator.len(), 23);
+        assert_eq!(delegated_address(designator.bytes()), Some(delegate));
+        assert_eq!(delegated_address(&[0x60, 0x01]), None);
+        db.insert_account_info(
+            authority,
+            AccountInfo {
+                nonce: 1,
+                code_hash: designator.hash_slow(),
+                code: Some(designator.clone()),
+                ..Default::default()
+            },
+        );
+
+        // the call runs the code of the delegate in the context of the authority
+        let mut evm = revm::EVM::new();
+        evm.env.tx.transact_to = TransactTo::Call(authority);
+        evm.database(&mut db);
+        assert!(evm.inspect_commit(Delegation(NodeInspector::default())).unwrap().is_success());
+        assert_eq!(db.storage_ref(authority, U256::ZERO).unwrap(), U256::from(1));
+        assert_eq!(db.storage_ref(delegate, U256::ZERO).unwrap(), U256::ZERO);
+        let info = db.basic_ref(authority).unwrap().unwrap();
+        assert_eq!(info.code_hash, designator.hash_slow());
+
+        // stores the size and hash of the code of the authority:
+        // PUSH20 authority DUP1 EXTCODESIZE PUSH0 SSTORE EXTCODEHASH PUSH1 1 SSTORE STOP
+        let mut init_code = vec![0x73];
+        init_code.extend_from_slice(authority.as_slice());
+        init_code.extend_from_slice(&[0x80, 0x3b, 0x5f, 0x55, 0x3f, 0x60, 0x01, 0x55, 0x00]);
+        let mut evm = revm::EVM::new();
+        evm.env.tx.transact_to = TransactTo::Create(CreateScheme::Create);
+        evm.env.tx.data = init_code.into();
+        evm.database(&mut db);
+        assert!(evm.inspect_commit(Delegation(NodeInspector::default())).unwrap().is_success());
+
+        let probe = Address::ZERO.create(0);
+        assert_eq!(db.storage_ref(probe, U256::ZERO).unwrap(), U256::from(23));
+        assert_eq!(
+            db.storage_ref(probe, U256::from(1)).unwrap(),
+            U256::from_be_bytes(designator.hash_slow().0)
+        );
+    }
+}
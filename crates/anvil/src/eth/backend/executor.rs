@@ -1,21 +1,31 @@
 use crate::{
     eth::{
-        backend::{db::Db, validate::TransactionValidator},
+        backend::{
+            db::Db,
+            delegation::{apply_authorizations, authorization_intrinsic_gas, Delegation},
+            validate::TransactionValidator,
+        },
         error::InvalidTransactionError,
         pool::transactions::PoolTransaction,
     },
     mem::inspector::Inspector,
 };
-use anvil_core::eth::{
-    block::{Block, BlockInfo, Header, PartialHeader},
-    receipt::{DepositReceipt, EIP1559Receipt, EIP2930Receipt, EIP658Receipt, Log, TypedReceipt},
-    transaction::{PendingTransaction, TransactionInfo, TypedTransaction},
-    trie,
+use anvil_core::{
+    eth::{
+        block::{Block, BlockInfo, Header, PartialHeader},
+        receipt::{
+            DepositReceipt, EIP1559Receipt, EIP2930Receipt, EIP4844Receipt, EIP658Receipt,
+            EIP7702Receipt, Log, TypedReceipt,
+        },
+        transaction::{PendingTransaction, TransactionInfo, TypedTransaction},
+        trie,
+        utils::trie_value,
+    },
+    types::{PreState, StorageDiff},
 };
 use ethers::{
     abi::ethereum_types::BloomInput,
     types::{Bloom, H256, U256},
-    utils::rlp,
 };
 use foundry_common::types::{ToAlloy, ToEthers};
 use foundry_evm::{
@@ -24,7 +34,10 @@ use foundry_evm::{
     revm,
     revm::{
         interpreter::InstructionResult,
-        primitives::{BlockEnv, CfgEnv, EVMError, Env, ExecutionResult, Output, SpecId},
+        primitives::{
+            BlockEnv, CfgEnv, EVMError, Env, ExecutionResult, Output, SpecId,
+            MAX_BLOB_GAS_PER_BLOCK,
+        },
     },
     traces::CallTraceNode,
     utils::{eval_to_instruction_result, halt_to_instruction_result},
@@ -40,6 +53,9 @@ pub struct ExecutedTransaction {
     logs: Vec<Log>,
     traces: Vec<CallTraceNode>,
     nonce: u64,
+    storage_diff: StorageDiff,
+    pre_state: PreState,
+    revert_reason: Option<String>,
 }
 
 // == impl ExecutedTransaction ==
@@ -73,6 +89,18 @@ impl ExecutedTransaction {
                 logs_bloom: bloom,
                 logs,
             }),
+            TypedTransaction::EIP4844(_) => TypedReceipt::EIP4844(EIP4844Receipt {
+                status_code,
+                gas_used: used_gas,
+                logs_bloom: bloom,
+                logs,
+            }),
+            TypedTransaction::EIP7702(_) => TypedReceipt::EIP7702(EIP7702Receipt {
+                status_code,
+                gas_used: used_gas,
+                logs_bloom: bloom,
+                logs,
+            }),
             TypedTransaction::Deposit(_) => TypedReceipt::Deposit(DepositReceipt {
                 status_code,
                 gas_used: used_gas,
@@ -108,7 +136,11 @@ pub struct TransactionExecutor<'a, Db: ?Sized, Validator: TransactionValidator>
     pub parent_hash: H256,
     /// Cumulative gas used by all executed transactions
     pub gas_used: U256,
+    /// Cumulative blob gas used by all executed transactions
+    pub blob_gas_used: u64,
     pub enable_steps_tracing: bool,
+    /// The max depth of nested calls, deeper calls are reverted
+    pub max_call_depth: Option<u64>,
 }
 
 impl<'a, DB: Db + ?Sized, Validator: TransactionValidator> TransactionExecutor<'a, DB, Validator> {
@@ -119,6 +151,7 @@ impl<'a, DB: Db + ?Sized, Validator: TransactionValidator> TransactionExecutor<'
         let mut receipts = Vec::new();
         let mut bloom = Bloom::default();
         let mut cumulative_gas_used = U256::zero();
+        let mut cumulative_receipts = Vec::new();
         let mut invalid = Vec::new();
         let mut included = Vec::new();
         let gas_limit = self.block_env.gas_limit;
@@ -132,6 +165,13 @@ impl<'a, DB: Db + ?Sized, Validator: TransactionValidator> TransactionExecutor<'
         } else {
             None
         };
+        let is_cancun = (self.cfg_env.spec_id as u8) >= (SpecId::CANCUN as u8);
+        let excess_blob_gas = self
+            .block_env
+            .blob_excess_gas_and_price
+            .as_ref()
+            .map(|blob_gas_and_price| blob_gas_and_price.excess_blob_gas)
+            .unwrap_or_default();
 
         for tx in self.into_iter() {
             let tx = match tx {
@@ -153,7 +193,18 @@ impl<'a, DB: Db + ?Sized, Validator: TransactionValidator> TransactionExecutor<'
             };
             let receipt = tx.create_receipt();
             cumulative_gas_used = cumulative_gas_used.saturating_add(receipt.gas_used());
-            let ExecutedTransaction { transaction, logs, out, traces, exit_reason: exit, .. } = tx;
+            cumulative_receipts.push(receipt.with_cumulative_gas_used(cumulative_gas_used));
+            let ExecutedTransaction {
+                transaction,
+                logs,
+                out,
+                traces,
+                exit_reason: exit,
+                storage_diff,
+                pre_state,
+                revert_reason,
+                ..
+            } = tx;
             logs_bloom(logs.clone(), &mut bloom);
 
             let contract_address = if let Some(Output::Create(_, contract_address)) = out {
@@ -180,6 +231,9 @@ impl<'a, DB: Db + ?Sized, Validator: TransactionValidator> TransactionExecutor<'
                     _ => None,
                 },
                 nonce: tx.nonce,
+                storage_diff,
+                pre_state,
+                revert_reason,
             };
 
             transaction_infos.push(info);
@@ -188,7 +242,8 @@ impl<'a, DB: Db + ?Sized, Validator: TransactionValidator> TransactionExecutor<'
         }
 
         let ommers: Vec<Header> = Vec::new();
-        let receipts_root = trie::ordered_trie_root(receipts.iter().map(rlp::encode));
+        // the receipts root commits to the cumulative gas used of the receipts
+        let receipts_root = trie::ordered_trie_root(cumulative_receipts.iter().map(trie_value));
 
         let partial_header = PartialHeader {
             parent_hash,
@@ -202,9 +257,11 @@ impl<'a, DB: Db + ?Sized, Validator: TransactionValidator> TransactionExecutor<'
             gas_used: cumulative_gas_used,
             timestamp,
             extra_data: Default::default(),
-            mix_hash: Default::default(),
+            mix_hash: self.block_env.prevrandao.unwrap_or_default().to_ethers(),
             nonce: Default::default(),
             base_fee: base_fee.map(|b| b.to_ethers()),
+            blob_gas_used: is_cancun.then_some(self.blob_gas_used),
+            excess_blob_gas: is_cancun.then_some(excess_blob_gas),
         };
 
         let block = Block::new(partial_header, transactions.clone(), ommers);
@@ -242,9 +299,20 @@ impl<'a, 'b, DB: Db + ?Sized, Validator: TransactionValidator> Iterator
             Err(err) => return Some(TransactionExecutionOutcome::DatabaseError(transaction, err)),
         };
         let env = self.env_for(&transaction.pending_transaction);
-        // check that we comply with the block's gas limit
+        // check that we comply with the block's gas limit, the remaining transactions are
+        // included in the next block. A transaction that exceeds the block gas limit on its own
+        // would never fit and is rejected by the validator instead
+        let block_gas_limit = env.block.gas_limit.to_ethers();
         let max_gas = self.gas_used.saturating_add(U256::from(env.tx.gas_limit));
-        if max_gas > env.block.gas_limit.to_ethers() {
+        if max_gas > block_gas_limit && U256::from(env.tx.gas_limit) <= block_gas_limit {
+            return Some(TransactionExecutionOutcome::Exhausted(transaction))
+        }
+
+        // the same applies to the blob gas of the block
+        let blob_gas = transaction.pending_transaction.transaction.blob_gas();
+        if self.blob_gas_used.saturating_add(blob_gas) > MAX_BLOB_GAS_PER_BLOCK &&
+            blob_gas <= MAX_BLOB_GAS_PER_BLOCK
+        {
             return Some(TransactionExecutionOutcome::Exhausted(transaction))
         }
 
@@ -260,19 +328,53 @@ impl<'a, 'b, DB: Db + ?Sized, Validator: TransactionValidator> Iterator
 
         let nonce = account.nonce;
 
+        // the authorizations of set code transactions are applied before the call, their
+        // intrinsic gas is charged from the gas limit of the call
+        let mut env = env;
+        let Some(gas_limit) = env
+            .tx
+            .gas_limit
+            .checked_sub(authorization_intrinsic_gas(&transaction.pending_transaction))
+        else {
+            warn!(target: "backend", "[{:?}] gas too low for the authorizations", transaction.hash());
+            return Some(TransactionExecutionOutcome::Invalid(
+                transaction,
+                InvalidTransactionError::GasTooLow,
+            ))
+        };
+        let authorizations = match apply_authorizations(
+            &mut *self.db,
+            &transaction.pending_transaction,
+            env.cfg.chain_id,
+        ) {
+            Ok(authorizations) => authorizations,
+            Err(err) => return Some(TransactionExecutionOutcome::DatabaseError(transaction, err)),
+        };
+        if authorizations.sender_authorized {
+            env.tx.nonce = None;
+        }
+        env.tx.gas_limit = gas_limit;
+
         let mut evm = revm::EVM::new();
         evm.env = env;
         evm.database(&mut self.db);
 
-        // records all call and step traces
-        let mut inspector = Inspector::default().with_tracing();
-        if self.enable_steps_tracing {
-            inspector = inspector.with_steps_tracing();
-        }
+        // records the call traces, the accessed storage and the state before the transaction for
+        // `debug_traceTransaction`, the steps are only recorded if enabled since they take up a lot
+        // of memory
+        let mut inspector = Inspector::default()
+            .with_max_call_depth(self.max_call_depth)
+            .with_storage_diff()
+            .with_prestate();
+        inspector = if self.enable_steps_tracing {
+            inspector.with_steps_tracing()
+        } else {
+            inspector.with_call_tracing()
+        };
 
         trace!(target: "backend", "[{:?}] executing", transaction.hash());
         // transact and commit the transaction
-        let exec_result = match evm.inspect_commit(&mut inspector) {
+        let exec_result = match evm.inspect_commit(Delegation(&mut inspector)) {
             Ok(exec_result) => exec_result,
             Err(err) => {
                 warn!(target: "backend", "[{:?}] failed to execute: {:?}", transaction.hash(), err);
@@ -293,15 +395,16 @@ impl<'a, 'b, DB: Db + ?Sized, Validator: TransactionValidator> Iterator
         };
         inspector.print_logs();
 
-        let (exit_reason, gas_used, out, logs) = match exec_result {
-            ExecutionResult::Success { reason, gas_used, logs, output, .. } => {
-                (eval_to_instruction_result(reason), gas_used, Some(output), Some(logs))
+        let gas_used = authorizations.gas_used(&exec_result);
+        let (exit_reason, out, logs) = match exec_result {
+            ExecutionResult::Success { reason, logs, output, .. } => {
+                (eval_to_instruction_result(reason), Some(output), Some(logs))
             }
-            ExecutionResult::Revert { gas_used, output } => {
-                (InstructionResult::Revert, gas_used, Some(Output::Call(output)), None)
+            ExecutionResult::Revert { output, .. } => {
+                (InstructionResult::Revert, Some(Output::Call(output)), None)
             }
-            ExecutionResult::Halt { reason, gas_used } => {
-                (halt_to_instruction_result(reason), gas_used, None, None)
+            ExecutionResult::Halt { reason, .. } => {
+                (halt_to_instruction_result(reason), None, None)
             }
         };
 
@@ -312,7 +415,8 @@ impl<'a, 'b, DB: Db + ?Sized, Validator: TransactionValidator> Iterator
 
         trace!(target: "backend", ?exit_reason, ?gas_used, "[{:?}] executed with out={:?}", transaction.hash(), out);
 
-        self.gas_used.saturating_add(U256::from(gas_used));
+        self.gas_used = self.gas_used.saturating_add(U256::from(gas_used));
+        self.blob_gas_used = self.blob_gas_used.saturating_add(blob_gas);
 
         trace!(target: "backend::executor", "transacted [{:?}], result: {:?} gas {}", transaction.hash(), exit_reason, gas_used);
 
@@ -322,6 +426,9 @@ impl<'a, 'b, DB: Db + ?Sized, Validator: TransactionValidator> Iterator
             out,
             gas_used,
             logs: logs.unwrap_or_default().into_iter().map(Into::into).collect(),
+            storage_diff: inspector.storage_diff(),
+            pre_state: inspector.pre_state(),
+            revert_reason: inspector.revert_reason().map(str::to_string),
             traces: inspector
                 .tracer
                 .unwrap_or(TracingInspector::new(TracingInspectorConfig::all()))
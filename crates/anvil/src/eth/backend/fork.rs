@@ -14,6 +14,7 @@ use foundry_common::{
     provider::alloy::{ProviderBuilder, RetryProvider},
     types::ToReth,
 };
+use foundry_evm::fork::{BlockchainDb, BlockchainDbMeta, SharedBackend};
 use parking_lot::{
     lock_api::{RwLockReadGuard, RwLockWriteGuard},
     RawRwLock, RwLock,
@@ -138,6 +139,19 @@ impl ClientFork {
         self.config.read().provider.clone()
     }
 
+    /// Returns a database that reads the state of the remote client at the given block.
+    ///
+    /// This is used to execute calls against blocks that predate the fork, the fetched state is
+    /// only kept in memory for as long as the returned database exists.
+    pub fn database_at(&self, block_number: u64) -> SharedBackend {
+        let meta = BlockchainDbMeta::new(Default::default(), self.eth_rpc_url());
+        SharedBackend::spawn_backend_thread(
+            self.provider(),
+            BlockchainDb::new(meta, None),
+            Some(block_number.into()),
+        )
+    }
+
     fn storage_read(&self) -> RwLockReadGuard<'_, RawRwLock, ForkedStorage> {
         self.storage.read()
     }
@@ -1,8 +1,8 @@
 //! Handler that can get current storage related data
 
-use crate::mem::Backend;
+use crate::{eth::pool::transactions::PoolTransaction, mem::Backend};
 use alloy_primitives::B256;
-use alloy_rpc_types::Block as AlloyBlock;
+use alloy_rpc_types::{Block as AlloyBlock, Transaction as AlloyTransaction};
 use anvil_core::eth::{block::Block, receipt::TypedReceipt};
 use std::{fmt, sync::Arc};
 
@@ -48,6 +48,16 @@ impl StorageInfo {
         let block = self.block(hash)?;
         Some(self.backend.convert_block(block))
     }
+
+    /// Same as [StorageInfo::eth_block] but includes the entire transactions
+    pub fn eth_block_full(&self, hash: B256) -> Option<AlloyBlock> {
+        self.backend.get_full_block(hash)
+    }
+
+    /// Returns the transaction of the pool in the format of the ethereum API
+    pub fn pending_transaction(&self, tx: &PoolTransaction) -> AlloyTransaction {
+        self.backend.convert_pending_transaction(tx.pending_transaction.clone())
+    }
 }
 
 impl fmt::Debug for StorageInfo {
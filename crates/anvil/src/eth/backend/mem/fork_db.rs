@@ -10,8 +10,13 @@ use alloy_rpc_types::BlockId;
 use foundry_evm::{
     backend::{DatabaseResult, RevertSnapshotAction, StateSnapshot},
     fork::{database::ForkDbSnapshot, BlockchainDb},
-    revm::Database,
+    hashbrown::HashMap,
+    revm::{
+        db::{DatabaseRef, DbAccount},
+        Database,
+    },
 };
+use std::collections::BTreeMap;
 
 pub use foundry_evm::fork::database::ForkedDatabase;
 
@@ -56,7 +61,59 @@ impl Db for ForkedDatabase {
                 ))
             })
             .collect::<Result<_, _>>()?;
-        Ok(Some(SerializableState { accounts }))
+        Ok(Some(SerializableState { accounts, ..Default::default() }))
+    }
+
+    fn dump_state_diff(&self) -> DatabaseResult<Option<SerializableState>> {
+        // the remote db only holds the unchanged state that was fetched from the remote client
+        let modified = {
+            let remote_accounts = self.inner().accounts().read();
+            let remote_storage = self.inner().storage().read();
+            self.database()
+                .accounts
+                .iter()
+                .filter_map(|(address, account)| {
+                    let remote_slots = remote_storage.get(address);
+                    let storage = account
+                        .storage
+                        .iter()
+                        .filter(|(slot, value)| {
+                            remote_slots.and_then(|slots| slots.get(*slot)) != Some(*value)
+                        })
+                        .map(|(slot, value)| (*slot, *value))
+                        .collect::<BTreeMap<_, _>>();
+                    let unchanged = remote_accounts.get(address).map_or(false, |remote| {
+                        remote.nonce == account.info.nonce &&
+                            remote.balance == account.info.balance &&
+                            remote.code_hash == account.info.code_hash
+                    });
+                    (!unchanged || !storage.is_empty())
+                        .then(|| (*address, account.info.clone(), storage))
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let accounts = modified
+            .into_iter()
+            .map(|(address, info, storage)| -> DatabaseResult<_> {
+                let code = if let Some(code) = info.code {
+                    code
+                } else {
+                    self.database().code_by_hash_ref(info.code_hash)?
+                }
+                .to_checked();
+                Ok((
+                    address,
+                    SerializableAccountRecord {
+                        nonce: info.nonce,
+                        balance: info.balance,
+                        code: code.original_bytes(),
+                        storage,
+                    },
+                ))
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Some(SerializableState { accounts, ..Default::default() }))
     }
 
     fn snapshot(&mut self) -> U256 {
@@ -96,6 +153,10 @@ impl MaybeHashDatabase for ForkedDatabase {
 }
 
 impl MaybeHashDatabase for ForkDbSnapshot {
+    fn maybe_as_full_db(&self) -> Option<&HashMap<Address, DbAccount>> {
+        Some(&self.local.accounts)
+    }
+
     fn clear_into_snapshot(&mut self) -> StateSnapshot {
         std::mem::take(&mut self.snapshot)
     }
@@ -2,10 +2,10 @@
 
 use crate::{
     eth::backend::db::{
-        AsHashDB, Db, MaybeForkedDatabase, MaybeHashDatabase, SerializableAccountRecord,
-        SerializableState, StateDb,
+        Db, MaybeForkedDatabase, MaybeHashDatabase, SerializableAccountRecord, SerializableState,
+        StateDb,
     },
-    mem::state::{state_merkle_trie_root, storage_trie_db, trie_hash_db},
+    mem::state::state_merkle_trie_root,
     revm::primitives::AccountInfo,
 };
 use alloy_primitives::{Address, B256, U256};
@@ -13,6 +13,8 @@ use alloy_rpc_types::BlockId;
 use foundry_evm::{
     backend::{DatabaseResult, StateSnapshot},
     fork::BlockchainDb,
+    hashbrown::HashMap,
+    revm::db::DbAccount,
 };
 
 // reexport for convenience
@@ -57,7 +59,7 @@ impl Db for MemDb {
             })
             .collect::<Result<_, _>>()?;
 
-        Ok(Some(SerializableState { accounts }))
+        Ok(Some(SerializableState { accounts, ..Default::default() }))
     }
 
     /// Creates a new snapshot
@@ -91,16 +93,8 @@ impl Db for MemDb {
 }
 
 impl MaybeHashDatabase for MemDb {
-    fn maybe_as_hash_db(&self) -> Option<(AsHashDB, B256)> {
-        Some(trie_hash_db(&self.inner.accounts))
-    }
-
-    fn maybe_account_db(&self, addr: Address) -> Option<(AsHashDB, B256)> {
-        if let Some(acc) = self.inner.accounts.get(&addr) {
-            Some(storage_trie_db(&acc.storage))
-        } else {
-            Some(storage_trie_db(&Default::default()))
-        }
+    fn maybe_as_full_db(&self) -> Option<&HashMap<Address, DbAccount>> {
+        Some(&self.inner.accounts)
     }
 
     fn clear_into_snapshot(&mut self) -> StateSnapshot {
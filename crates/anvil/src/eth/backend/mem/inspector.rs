@@ -1,18 +1,28 @@
 //! Anvil specific [`revm::Inspector`] implementation
 
 use crate::{eth::macros::node_info, revm::Database};
+use alloy_json_abi::JsonAbi;
+use alloy_primitives::Selector;
+use alloy_rpc_types::{AccessList, AccessListItem};
+use anvil_core::types::{AccountPreState, CallGasSummary, OpcodeGas, PreState, StorageDiff};
 use ethers::types::Log;
 use foundry_evm::{
     call_inspectors,
-    decode::decode_console_logs,
+    decode::{decode_console_log, decode_console_logs, maybe_decode_revert},
     inspectors::{LogCollector, TracingInspector},
     revm,
     revm::{
-        interpreter::{CallInputs, CreateInputs, Gas, InstructionResult, Interpreter},
-        primitives::{Address, Bytes, B256},
+        interpreter::{
+            opcode, CallInputs, CreateInputs, Gas, InstructionResult, Interpreter, OpCode,
+        },
+        primitives::{Address, Bytes, B256, U256},
         EvmContext,
     },
-    traces::TracingInspectorConfig,
+    traces::{CallKind, CallTraceNode, StackSnapshotType, TracingInspectorConfig},
+};
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
+    str::FromStr,
 };
 
 /// The [`revm::Inspector`] used when transacting in the evm
@@ -21,6 +31,27 @@ pub struct Inspector {
     pub tracer: Option<TracingInspector>,
     /// collects all `console.sol` logs
     pub log_collector: LogCollector,
+    /// accumulates the gas spent per opcode
+    pub gas_profiler: Option<GasProfiler>,
+    /// records the storage slots that were read and written
+    pub storage: Option<StorageInspector>,
+    /// records the state of the accessed accounts before the transaction
+    pub prestate: Option<PrestateInspector>,
+    /// counts how many times each opcode was executed
+    pub opcode_counter: Option<OpcodeCounter>,
+    /// records all events emitted via `LOG0..LOG4`
+    pub event_collector: Option<EventCollector>,
+    /// the minimum level of the `console.sol` logs that are printed, see [LogLevel::of] for how
+    /// the level of a log is determined
+    pub log_level: LogLevel,
+    /// collects the accounts and storage slots for an access list
+    pub access_list: Option<AccessListInspector>,
+    /// the ABI used to decode custom errors of reverted calls
+    pub revert_abi: Option<JsonAbi>,
+    /// calls nested deeper than this are reverted, this is independent of the EVM's own limit
+    pub max_call_depth: Option<u64>,
+    /// the decoded reason of the revert of the last call that ended
+    revert_reason: Option<String>,
 }
 
 // === impl Inspector ===
@@ -28,14 +59,47 @@ pub struct Inspector {
 impl Inspector {
     /// Called after the inspecting the evm
     ///
-    /// This will log all `console.sol` logs
-    pub fn print_logs(&self) {
-        print_logs(&self.log_collector.logs)
+    /// This will log all `console.sol` logs of at least the configured [LogLevel]
+    pub fn print_logs(&mut self) {
+        let log_level = self.log_level;
+        for log in self.take_console_logs() {
+            if log.level >= log_level {
+                node_info!("{}", log.message);
+            }
+        }
+    }
+
+    /// Drains all collected `console.sol` logs and returns them decoded, without printing them
+    ///
+    /// Logs of all levels are returned, regardless of the configured [LogLevel].
+    pub fn take_console_logs(&mut self) -> Vec<DecodedConsoleLog> {
+        std::mem::take(&mut self.log_collector.logs)
+            .into_iter()
+            .filter_map(DecodedConsoleLog::decode)
+            .collect()
+    }
+
+    /// Sets the minimum level of the `console.sol` logs that are printed
+    ///
+    /// `console.sol` logs don't carry a level, it is derived from a leading tag of the message
+    /// like `[warn]` or `error:`, see [LogLevel::of]. Messages without a tag are
+    /// [LogLevel::Info], so they are hidden at [LogLevel::Warn] and above.
+    pub fn with_log_level(mut self, log_level: LogLevel) -> Self {
+        self.log_level = log_level;
+        self
+    }
+
+    /// Configures the `Tracer` [`revm::Inspector`], recording everything
+    pub fn with_tracing(self) -> Self {
+        self.with_tracing_config(TracingInspectorConfig::all())
     }
 
-    /// Configures the `Tracer` [`revm::Inspector`]
-    pub fn with_tracing(mut self) -> Self {
-        self.tracer = Some(TracingInspector::new(TracingInspectorConfig::all()));
+    /// Configures the `Tracer` [`revm::Inspector`] with the given config
+    ///
+    /// This can be used to only record the call frames, without the memory and stack of every
+    /// step.
+    pub fn with_tracing_config(mut self, config: TracingInspectorConfig) -> Self {
+        self.tracer = Some(TracingInspector::new(config));
         self
     }
 
@@ -43,6 +107,145 @@ impl Inspector {
     pub fn with_steps_tracing(self) -> Self {
         self.with_tracing()
     }
+
+    /// Configures the `Tracer` [`revm::Inspector`] to only record the call frames and their logs,
+    /// without the steps
+    pub fn with_call_tracing(self) -> Self {
+        self.with_tracing_config(TracingInspectorConfig {
+            record_steps: false,
+            record_memory_snapshots: false,
+            record_stack_snapshots: StackSnapshotType::None,
+            ..TracingInspectorConfig::all()
+        })
+    }
+
+    /// Configures the [`GasProfiler`] [`revm::Inspector`]
+    pub fn with_gas_profiling(mut self) -> Self {
+        self.gas_profiler = Some(GasProfiler::default());
+        self
+    }
+
+    /// Configures the [`StorageInspector`] [`revm::Inspector`]
+    pub fn with_storage_diff(mut self) -> Self {
+        self.storage = Some(StorageInspector::default());
+        self
+    }
+
+    /// Returns the storage slots that were read and written, see [`StorageInspector`]
+    ///
+    /// This is empty if the storage diff is not enabled.
+    pub fn storage_diff(&self) -> StorageDiff {
+        self.storage.as_ref().map(|storage| storage.diff.clone()).unwrap_or_default()
+    }
+
+    /// Configures the [`PrestateInspector`] [`revm::Inspector`]
+    pub fn with_prestate(mut self) -> Self {
+        self.prestate = Some(PrestateInspector::default());
+        self
+    }
+
+    /// Returns the state of the accessed accounts before the transaction, see
+    /// [`PrestateInspector`]
+    ///
+    /// This is empty if the prestate inspector is not enabled.
+    pub fn pre_state(&self) -> PreState {
+        self.prestate.as_ref().map(|prestate| prestate.pre_state.clone()).unwrap_or_default()
+    }
+
+    /// Configures the [`OpcodeCounter`] [`revm::Inspector`]
+    pub fn with_opcode_counts(mut self) -> Self {
+        self.opcode_counter = Some(OpcodeCounter::default());
+        self
+    }
+
+    /// Returns how many times each opcode was executed
+    ///
+    /// This is empty if opcode counting is not enabled.
+    pub fn opcode_counts(&self) -> HashMap<OpCode, u64> {
+        self.opcode_counter.as_ref().map(|counter| counter.counts.clone()).unwrap_or_default()
+    }
+
+    /// Returns the gas used by every call frame, see [call_gas_summary]
+    ///
+    /// This is empty if tracing is not enabled.
+    pub fn call_gas_summary(&self) -> Vec<CallGasSummary> {
+        self.tracer
+            .as_ref()
+            .map(|tracer| call_gas_summary(tracer.get_traces().nodes()))
+            .unwrap_or_default()
+    }
+
+    /// Sets the max depth of nested calls, deeper calls are reverted
+    ///
+    /// The outermost call of a transaction has depth 0.
+    pub fn with_max_call_depth(mut self, max_call_depth: Option<u64>) -> Self {
+        self.max_call_depth = max_call_depth;
+        self
+    }
+
+    /// Returns true if a call at the current depth exceeds the max call depth
+    fn exceeds_call_depth<DB: Database>(&self, data: &EvmContext<'_, DB>) -> bool {
+        self.max_call_depth.map_or(false, |max| data.journaled_state.depth() > max)
+    }
+
+    /// Configures the [`EventCollector`] [`revm::Inspector`]
+    pub fn with_events(mut self) -> Self {
+        self.event_collector = Some(EventCollector::default());
+        self
+    }
+
+    /// Takes the events emitted by the calls that didn't revert, in emission order
+    ///
+    /// This is empty if event collection is not enabled.
+    pub fn take_events(&mut self) -> Vec<EmittedEvent> {
+        self.event_collector
+            .as_mut()
+            .map(|collector| std::mem::take(&mut collector.events))
+            .unwrap_or_default()
+    }
+
+    /// Configures the [`AccessListInspector`] [`revm::Inspector`]
+    pub fn with_access_list(mut self, access_list: AccessListInspector) -> Self {
+        self.access_list = Some(access_list);
+        self
+    }
+
+    /// Returns the access list of the accounts and storage slots that were accessed
+    ///
+    /// This is empty if the access list inspector is not configured.
+    pub fn access_list(&self) -> AccessList {
+        self.access_list.as_ref().map(AccessListInspector::access_list).unwrap_or_default()
+    }
+
+    /// Sets the ABI used to decode custom errors of reverted calls
+    ///
+    /// `Error(string)` and `Panic(uint256)` reverts are always decoded.
+    pub fn with_revert_abi(mut self, abi: JsonAbi) -> Self {
+        self.revert_abi = Some(abi);
+        self
+    }
+
+    /// Returns the human readable reason why the outermost call failed, if it did
+    pub fn revert_reason(&self) -> Option<&str> {
+        self.revert_reason.as_deref()
+    }
+
+    /// Decodes the reason of a failed call, this is overwritten by every call that ends so that it
+    /// ends up with the reason of the outermost call
+    fn record_revert_reason(&mut self, status: InstructionResult, out: &Bytes) {
+        self.revert_reason = if status.is_ok() {
+            None
+        } else {
+            maybe_decode_revert(out, self.revert_abi.as_ref(), Some(status))
+        };
+    }
+
+    /// Returns the gas spent per opcode, see [`GasProfiler::gas_report`]
+    ///
+    /// This is empty if gas profiling is not enabled.
+    pub fn gas_report(&self) -> Vec<OpcodeGas> {
+        self.gas_profiler.as_ref().map(GasProfiler::gas_report).unwrap_or_default()
+    }
 }
 
 impl<DB: Database> revm::Inspector<DB> for Inspector {
@@ -55,9 +258,18 @@ impl<DB: Database> revm::Inspector<DB> for Inspector {
 
     #[inline]
     fn step(&mut self, interp: &mut Interpreter, data: &mut EvmContext<'_, DB>) {
-        call_inspectors!([&mut self.tracer], |inspector| {
-            inspector.step(interp, data);
-        });
+        call_inspectors!(
+            [
+                &mut self.tracer,
+                &mut self.gas_profiler,
+                &mut self.storage,
+                &mut self.opcode_counter,
+                &mut self.access_list
+            ],
+            |inspector| {
+                inspector.step(interp, data);
+            }
+        );
     }
 
     #[inline]
@@ -68,16 +280,22 @@ impl<DB: Database> revm::Inspector<DB> for Inspector {
         topics: &[B256],
         data: &Bytes,
     ) {
-        call_inspectors!([&mut self.tracer, Some(&mut self.log_collector)], |inspector| {
-            inspector.log(evm_data, address, topics, data);
-        });
+        call_inspectors!(
+            [&mut self.tracer, Some(&mut self.log_collector), &mut self.event_collector],
+            |inspector| {
+                inspector.log(evm_data, address, topics, data);
+            }
+        );
     }
 
     #[inline]
     fn step_end(&mut self, interp: &mut Interpreter, data: &mut EvmContext<'_, DB>) {
-        call_inspectors!([&mut self.tracer], |inspector| {
-            inspector.step_end(interp, data);
-        });
+        call_inspectors!(
+            [&mut self.tracer, &mut self.gas_profiler, &mut self.storage],
+            |inspector| {
+                inspector.step_end(interp, data);
+            }
+        );
     }
 
     #[inline]
@@ -86,10 +304,24 @@ impl<DB: Database> revm::Inspector<DB> for Inspector {
         data: &mut EvmContext<'_, DB>,
         call: &mut CallInputs,
     ) -> (InstructionResult, Gas, Bytes) {
-        call_inspectors!([&mut self.tracer, Some(&mut self.log_collector)], |inspector| {
-            inspector.call(data, call);
-        });
+        call_inspectors!(
+            [
+                &mut self.tracer,
+                Some(&mut self.log_collector),
+                &mut self.storage,
+                &mut self.prestate,
+                &mut self.event_collector,
+                &mut self.access_list
+            ],
+            |inspector| {
+                inspector.call(data, call);
+            }
+        );
 
+        // the inspectors saw the call start, so they also see it end with the revert
+        if self.exceeds_call_depth(data) {
+            return (InstructionResult::Revert, Gas::new(call.gas_limit), Bytes::new())
+        }
         (InstructionResult::Continue, Gas::new(call.gas_limit), Bytes::new())
     }
 
@@ -102,9 +334,20 @@ impl<DB: Database> revm::Inspector<DB> for Inspector {
         ret: InstructionResult,
         out: Bytes,
     ) -> (InstructionResult, Gas, Bytes) {
-        call_inspectors!([&mut self.tracer], |inspector| {
-            inspector.call_end(data, inputs, remaining_gas, ret, out.clone());
-        });
+        // every inspector sees the original outcome, the last one that overrides it wins
+        let mut outcome = None;
+        call_inspectors!(
+            [&mut self.tracer, &mut self.storage, &mut self.prestate, &mut self.event_collector],
+            |inspector| {
+                let (new_ret, new_gas, new_out) =
+                    inspector.call_end(data, inputs, remaining_gas, ret, out.clone());
+                if is_override(ret, &out, new_ret, &new_out) {
+                    outcome = Some((new_ret, new_gas, new_out));
+                }
+            }
+        );
+        let (ret, remaining_gas, out) = outcome.unwrap_or((ret, remaining_gas, out));
+        self.record_revert_reason(ret, &out);
         (ret, remaining_gas, out)
     }
 
@@ -114,10 +357,16 @@ impl<DB: Database> revm::Inspector<DB> for Inspector {
         data: &mut EvmContext<'_, DB>,
         call: &mut CreateInputs,
     ) -> (InstructionResult, Option<Address>, Gas, Bytes) {
-        call_inspectors!([&mut self.tracer], |inspector| {
-            inspector.create(data, call);
-        });
+        call_inspectors!(
+            [&mut self.tracer, &mut self.storage, &mut self.prestate, &mut self.event_collector],
+            |inspector| {
+                inspector.create(data, call);
+            }
+        );
 
+        if self.exceeds_call_depth(data) {
+            return (InstructionResult::Revert, None, Gas::new(call.gas_limit), Bytes::new())
+        }
         (InstructionResult::Continue, None, Gas::new(call.gas_limit), Bytes::new())
     }
 
@@ -131,17 +380,793 @@ impl<DB: Database> revm::Inspector<DB> for Inspector {
         gas: Gas,
         retdata: Bytes,
     ) -> (InstructionResult, Option<Address>, Gas, Bytes) {
-        call_inspectors!([&mut self.tracer], |inspector| {
-            inspector.create_end(data, inputs, status, address, gas, retdata.clone());
+        // every inspector sees the original outcome, the last one that overrides it wins
+        let mut outcome = None;
+        call_inspectors!(
+            [
+                &mut self.tracer,
+                &mut self.storage,
+                &mut self.prestate,
+                &mut self.event_collector,
+                &mut self.access_list
+            ],
+            |inspector| {
+                let (new_status, new_address, new_gas, new_retdata) =
+                    inspector.create_end(data, inputs, status, address, gas, retdata.clone());
+                if is_override(status, &retdata, new_status, &new_retdata) {
+                    outcome = Some((new_status, new_address, new_gas, new_retdata));
+                }
+            }
+        );
+        let (status, address, gas, retdata) = outcome.unwrap_or((status, address, gas, retdata));
+        self.record_revert_reason(status, &retdata);
+        (status, address, gas, retdata)
+    }
+}
+
+/// Returns the gas used by every call frame of the traces, in the order the calls were made
+///
+/// The gas used by a frame's nested calls is attributed to those calls, so that the contracts
+/// that used the most gas themselves stand out.
+pub fn call_gas_summary(nodes: &[CallTraceNode]) -> Vec<CallGasSummary> {
+    nodes
+        .iter()
+        .map(|node| {
+            let nested_gas: u64 = node.children.iter().map(|idx| nodes[*idx].trace.gas_used).sum();
+            let is_create = matches!(node.trace.kind, CallKind::Create | CallKind::Create2);
+            CallGasSummary {
+                address: node.trace.address,
+                selector: node.trace.data.get(..4).filter(|_| !is_create).map(Selector::from_slice),
+                gas_used: node.trace.gas_used,
+                self_gas_used: node.trace.gas_used.saturating_sub(nested_gas),
+            }
+        })
+        .collect()
+}
+
+/// Returns true if an inspector changed the outcome of a call, by returning a different status or
+/// different return data.
+///
+/// Returning [`InstructionResult::Continue`] is never an override.
+#[inline]
+fn is_override(
+    status: InstructionResult,
+    retdata: &Bytes,
+    new_status: InstructionResult,
+    new_retdata: &Bytes,
+) -> bool {
+    new_status != InstructionResult::Continue && (new_status != status || new_retdata != retdata)
+}
+
+/// A [`revm::Inspector`] that accumulates the gas spent and the number of invocations per opcode.
+///
+/// The gas spent by nested calls is attributed to the opcodes of the nested call, so the gas of a
+/// `CALL` only covers the cost of the call itself.
+#[derive(Clone, Debug, Default)]
+pub struct GasProfiler {
+    /// `(gas, count)` per opcode
+    opcodes: HashMap<u8, (u64, u64)>,
+    /// The steps that are currently executing, one per call depth
+    pending: Vec<PendingStep>,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct PendingStep {
+    opcode: u8,
+    gas_remaining: u64,
+    /// The gas spent by the steps of the call made by this step
+    nested_gas: u64,
+}
+
+// === impl GasProfiler ===
+
+impl GasProfiler {
+    /// Returns the gas spent per opcode, the hottest opcodes first
+    pub fn gas_report(&self) -> Vec<OpcodeGas> {
+        let mut report = self
+            .opcodes
+            .iter()
+            .map(|(&opcode, &(gas, count))| OpcodeGas {
+                opcode: OpCode::new(opcode).map_or_else(
+                    || format!("UNDEFINED(0x{opcode:02x})"),
+                    |opcode| opcode.as_str().to_string(),
+                ),
+                gas,
+                count,
+            })
+            .collect::<Vec<_>>();
+        report.sort_by(|a, b| b.gas.cmp(&a.gas).then_with(|| a.opcode.cmp(&b.opcode)));
+        report
+    }
+}
+
+impl<DB: Database> revm::Inspector<DB> for GasProfiler {
+    #[inline]
+    fn step(&mut self, interp: &mut Interpreter, _data: &mut EvmContext<'_, DB>) {
+        self.pending.push(PendingStep {
+            opcode: interp.current_opcode(),
+            gas_remaining: interp.gas.remaining(),
+            nested_gas: 0,
         });
+    }
+
+    #[inline]
+    fn step_end(&mut self, interp: &mut Interpreter, _data: &mut EvmContext<'_, DB>) {
+        let Some(step) = self.pending.pop() else { return };
+        let spent = step.gas_remaining.saturating_sub(interp.gas.remaining());
+        let (gas, count) = self.opcodes.entry(step.opcode).or_default();
+        *gas += spent.saturating_sub(step.nested_gas);
+        *count += 1;
+        // this step was executed by a call of the step of the parent call
+        if let Some(parent) = self.pending.last_mut() {
+            parent.nested_gas += spent;
+        }
+    }
+}
+
+/// A [`revm::Inspector`] that counts how many times each opcode was executed, independent of the
+/// gas the opcodes spent.
+#[derive(Clone, Debug, Default)]
+pub struct OpcodeCounter {
+    counts: HashMap<OpCode, u64>,
+}
+
+// === impl OpcodeCounter ===
+
+impl OpcodeCounter {
+    /// Returns how many times each opcode was executed
+    pub fn counts(&self) -> &HashMap<OpCode, u64> {
+        &self.counts
+    }
+}
+
+impl<DB: Database> revm::Inspector<DB> for OpcodeCounter {
+    #[inline]
+    fn step(&mut self, interp: &mut Interpreter, _data: &mut EvmContext<'_, DB>) {
+        // undefined opcodes are not executed but halt the call
+        if let Some(opcode) = OpCode::new(interp.current_opcode()) {
+            *self.counts.entry(opcode).or_default() += 1;
+        }
+    }
+}
+
+/// A [`revm::Inspector`] that records the storage slots that were read via `SLOAD` and written
+/// via `SSTORE`, see [StorageDiff].
+///
+/// The old value of a slot is captured when it is touched for the first time. Writes of calls
+/// that revert are rolled back, so the new value is the value the slot has after the transaction.
+#[derive(Clone, Debug, Default)]
+pub struct StorageInspector {
+    diff: StorageDiff,
+    /// The slot that is accessed by the current step
+    pending: Option<(Address, U256)>,
+    /// `(address, slot, previous new value)` of all writes, used to roll back reverted calls
+    writes: Vec<(Address, U256, U256)>,
+    /// The length of `writes` when the currently executing calls started
+    checkpoints: Vec<usize>,
+}
+
+// === impl StorageInspector ===
+
+impl StorageInspector {
+    /// Returns the storage slots that were read and written
+    pub fn diff(&self) -> &StorageDiff {
+        &self.diff
+    }
+
+    fn checkpoint(&mut self) {
+        self.checkpoints.push(self.writes.len());
+    }
+
+    /// Rolls back the writes of the call that ended, if it failed
+    fn call_ended(&mut self, status: InstructionResult) {
+        let Some(checkpoint) = self.checkpoints.pop() else { return };
+        if status.is_ok() {
+            return
+        }
+        for (address, slot, previous) in self.writes.drain(checkpoint..).rev() {
+            if let Some((_, new)) =
+                self.diff.get_mut(&address).and_then(|slots| slots.get_mut(&slot))
+            {
+                *new = previous;
+            }
+        }
+    }
+}
+
+impl<DB: Database> revm::Inspector<DB> for StorageInspector {
+    #[inline]
+    fn step(&mut self, interp: &mut Interpreter, _data: &mut EvmContext<'_, DB>) {
+        self.pending = match interp.current_opcode() {
+            opcode::SLOAD | opcode::SSTORE => {
+                interp.stack().peek(0).ok().map(|slot| (interp.contract.address, slot))
+            }
+            _ => None,
+        };
+    }
+
+    #[inline]
+    fn step_end(&mut self, _interp: &mut Interpreter, data: &mut EvmContext<'_, DB>) {
+        let Some((address, slot)) = self.pending.take() else { return };
+        // the slot is loaded into the journaled state by the opcode
+        let Some(value) =
+            data.journaled_state.state.get(&address).and_then(|account| account.storage.get(&slot))
+        else {
+            return
+        };
+        let (original, present) = (value.previous_or_original_value, value.present_value);
+        let (_, new) =
+            self.diff.entry(address).or_default().entry(slot).or_insert((original, original));
+        if *new != present {
+            self.writes.push((address, slot, *new));
+            *new = present;
+        }
+    }
+
+    #[inline]
+    fn call(
+        &mut self,
+        _data: &mut EvmContext<'_, DB>,
+        call: &mut CallInputs,
+    ) -> (InstructionResult, Gas, Bytes) {
+        self.checkpoint();
+        (InstructionResult::Continue, Gas::new(call.gas_limit), Bytes::new())
+    }
+
+    #[inline]
+    fn call_end(
+        &mut self,
+        _data: &mut EvmContext<'_, DB>,
+        _inputs: &CallInputs,
+        remaining_gas: Gas,
+        ret: InstructionResult,
+        out: Bytes,
+    ) -> (InstructionResult, Gas, Bytes) {
+        self.call_ended(ret);
+        (ret, remaining_gas, out)
+    }
+
+    #[inline]
+    fn create(
+        &mut self,
+        _data: &mut EvmContext<'_, DB>,
+        call: &mut CreateInputs,
+    ) -> (InstructionResult, Option<Address>, Gas, Bytes) {
+        self.checkpoint();
+        (InstructionResult::Continue, None, Gas::new(call.gas_limit), Bytes::new())
+    }
+
+    #[inline]
+    fn create_end(
+        &mut self,
+        _data: &mut EvmContext<'_, DB>,
+        _inputs: &CreateInputs,
+        status: InstructionResult,
+        address: Option<Address>,
+        gas: Gas,
+        retdata: Bytes,
+    ) -> (InstructionResult, Option<Address>, Gas, Bytes) {
+        self.call_ended(status);
         (status, address, gas, retdata)
     }
 }
 
-/// Prints all the logs
+/// An event emitted via one of the `LOG0..LOG4` instructions
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EmittedEvent {
+    /// The contract that emitted the event
+    pub address: Address,
+    pub topics: Vec<B256>,
+    pub data: Bytes,
+}
+
+/// A [`revm::Inspector`] that records the events emitted during execution, in emission order.
+///
+/// The events of calls that revert are discarded, like the EVM discards them, so the recorded
+/// events are the ones the transaction would emit if it was mined.
+#[derive(Clone, Debug, Default)]
+pub struct EventCollector {
+    events: Vec<EmittedEvent>,
+    /// The length of `events` when the currently executing calls started
+    checkpoints: Vec<usize>,
+}
+
+// === impl EventCollector ===
+
+impl EventCollector {
+    /// Returns the events recorded so far
+    pub fn events(&self) -> &[EmittedEvent] {
+        &self.events
+    }
+
+    fn checkpoint(&mut self) {
+        self.checkpoints.push(self.events.len());
+    }
+
+    /// Discards the events of the call that ended, if it failed
+    fn call_ended(&mut self, status: InstructionResult) {
+        let Some(checkpoint) = self.checkpoints.pop() else { return };
+        if !status.is_ok() {
+            self.events.truncate(checkpoint);
+        }
+    }
+}
+
+impl<DB: Database> revm::Inspector<DB> for EventCollector {
+    #[inline]
+    fn log(
+        &mut self,
+        _evm_data: &mut EvmContext<'_, DB>,
+        address: &Address,
+        topics: &[B256],
+        data: &Bytes,
+    ) {
+        self.events.push(EmittedEvent {
+            address: *address,
+            topics: topics.to_vec(),
+            data: data.clone(),
+        });
+    }
+
+    #[inline]
+    fn call(
+        &mut self,
+        _data: &mut EvmContext<'_, DB>,
+        call: &mut CallInputs,
+    ) -> (InstructionResult, Gas, Bytes) {
+        self.checkpoint();
+        (InstructionResult::Continue, Gas::new(call.gas_limit), Bytes::new())
+    }
+
+    #[inline]
+    fn call_end(
+        &mut self,
+        _data: &mut EvmContext<'_, DB>,
+        _inputs: &CallInputs,
+        remaining_gas: Gas,
+        ret: InstructionResult,
+        out: Bytes,
+    ) -> (InstructionResult, Gas, Bytes) {
+        self.call_ended(ret);
+        (ret, remaining_gas, out)
+    }
+
+    #[inline]
+    fn create(
+        &mut self,
+        _data: &mut EvmContext<'_, DB>,
+        call: &mut CreateInputs,
+    ) -> (InstructionResult, Option<Address>, Gas, Bytes) {
+        self.checkpoint();
+        (InstructionResult::Continue, None, Gas::new(call.gas_limit), Bytes::new())
+    }
+
+    #[inline]
+    fn create_end(
+        &mut self,
+        _data: &mut EvmContext<'_, DB>,
+        _inputs: &CreateInputs,
+        status: InstructionResult,
+        address: Option<Address>,
+        gas: Gas,
+        retdata: Bytes,
+    ) -> (InstructionResult, Option<Address>, Gas, Bytes) {
+        self.call_ended(status);
+        (status, address, gas, retdata)
+    }
+}
+
+/// A [`revm::Inspector`] that records the state of all accounts a transaction accessed as it was
+/// before the transaction, see [PreState].
+///
+/// The state is read from the database once the outermost call ended, the changes of the
+/// transaction are only committed to the database afterwards.
+#[derive(Clone, Debug, Default)]
+pub struct PrestateInspector {
+    pre_state: PreState,
+    /// The number of currently executing calls
+    depth: usize,
+}
+
+// === impl PrestateInspector ===
+
+impl PrestateInspector {
+    /// Returns the state of the accessed accounts before the transaction
+    pub fn pre_state(&self) -> &PreState {
+        &self.pre_state
+    }
+
+    fn call_ended<DB: Database>(&mut self, data: &mut EvmContext<'_, DB>) {
+        self.depth = self.depth.saturating_sub(1);
+        if self.depth > 0 {
+            return
+        }
+        for (address, account) in data.journaled_state.state.iter() {
+            let Ok(info) = data.db.basic(*address) else { continue };
+            let info = info.unwrap_or_default();
+            let code = match info.code {
+                Some(code) => code.original_bytes(),
+                None => data
+                    .db
+                    .code_by_hash(info.code_hash)
+                    .map(|code| code.original_bytes())
+                    .unwrap_or_default(),
+            };
+            let storage = account
+                .storage
+                .iter()
+                .map(|(slot, value)| (*slot, value.previous_or_original_value))
+                .collect();
+            self.pre_state.insert(
+                *address,
+                AccountPreState { balance: info.balance, nonce: info.nonce, code, storage },
+            );
+        }
+    }
+}
+
+impl<DB: Database> revm::Inspector<DB> for PrestateInspector {
+    #[inline]
+    fn call(
+        &mut self,
+        _data: &mut EvmContext<'_, DB>,
+        call: &mut CallInputs,
+    ) -> (InstructionResult, Gas, Bytes) {
+        self.depth += 1;
+        (InstructionResult::Continue, Gas::new(call.gas_limit), Bytes::new())
+    }
+
+    #[inline]
+    fn call_end(
+        &mut self,
+        data: &mut EvmContext<'_, DB>,
+        _inputs: &CallInputs,
+        remaining_gas: Gas,
+        ret: InstructionResult,
+        out: Bytes,
+    ) -> (InstructionResult, Gas, Bytes) {
+        self.call_ended(data);
+        (ret, remaining_gas, out)
+    }
+
+    #[inline]
+    fn create(
+        &mut self,
+        _data: &mut EvmContext<'_, DB>,
+        call: &mut CreateInputs,
+    ) -> (InstructionResult, Option<Address>, Gas, Bytes) {
+        self.depth += 1;
+        (InstructionResult::Continue, None, Gas::new(call.gas_limit), Bytes::new())
+    }
+
+    #[inline]
+    fn create_end(
+        &mut self,
+        data: &mut EvmContext<'_, DB>,
+        _inputs: &CreateInputs,
+        status: InstructionResult,
+        address: Option<Address>,
+        gas: Gas,
+        retdata: Bytes,
+    ) -> (InstructionResult, Option<Address>, Gas, Bytes) {
+        self.call_ended(data);
+        (status, address, gas, retdata)
+    }
+}
+
+/// A [`revm::Inspector`] that collects the accounts and storage slots accessed by a transaction,
+/// for `eth_createAccessList`.
+///
+/// Accounts that are warm regardless of the access list, like the sender, the recipient, the
+/// precompiles and contracts created by the transaction, are only included if storage slots of
+/// theirs were accessed.
+#[derive(Clone, Debug, Default)]
+pub struct AccessListInspector {
+    /// accounts that are already warm
+    excluded: HashSet<Address>,
+    access_list: BTreeMap<Address, BTreeSet<B256>>,
+}
+
+// === impl AccessListInspector ===
+
+impl AccessListInspector {
+    /// Creates a new inspector that starts with the given access list.
+    ///
+    /// The `excluded` accounts are warm regardless of the access list.
+    pub fn new(access_list: AccessList, excluded: impl IntoIterator<Item = Address>) -> Self {
+        Self {
+            excluded: excluded.into_iter().collect(),
+            access_list: access_list
+                .0
+                .into_iter()
+                .map(|item| (item.address, item.storage_keys.into_iter().collect()))
+                .collect(),
+        }
+    }
+
+    /// Returns the collected access list, sorted by address and storage slot
+    pub fn access_list(&self) -> AccessList {
+        AccessList(
+            self.access_list
+                .iter()
+                .filter(|(address, slots)| !slots.is_empty() || !self.excluded.contains(*address))
+                .map(|(address, slots)| AccessListItem {
+                    address: *address,
+                    storage_keys: slots.iter().copied().collect(),
+                })
+                .collect(),
+        )
+    }
+
+    fn touch(&mut self, address: Address) {
+        self.access_list.entry(address).or_default();
+    }
+}
+
+impl<DB: Database> revm::Inspector<DB> for AccessListInspector {
+    #[inline]
+    fn step(&mut self, interp: &mut Interpreter, _data: &mut EvmContext<'_, DB>) {
+        match interp.current_opcode() {
+            opcode::SLOAD | opcode::SSTORE => {
+                if let Ok(slot) = interp.stack().peek(0) {
+                    self.access_list
+                        .entry(interp.contract.address)
+                        .or_default()
+                        .insert(B256::from(slot));
+                }
+            }
+            opcode::EXTCODECOPY |
+            opcode::EXTCODEHASH |
+            opcode::EXTCODESIZE |
+            opcode::BALANCE |
+            opcode::SELFDESTRUCT => {
+                if let Ok(address) = interp.stack().peek(0) {
+                    self.touch(Address::from_word(B256::from(address)));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    #[inline]
+    fn call(
+        &mut self,
+        _data: &mut EvmContext<'_, DB>,
+        call: &mut CallInputs,
+    ) -> (InstructionResult, Gas, Bytes) {
+        // this is the address of the code, which differs from the context for delegate calls
+        self.touch(call.contract);
+        (InstructionResult::Continue, Gas::new(call.gas_limit), Bytes::new())
+    }
+
+    #[inline]
+    fn create_end(
+        &mut self,
+        _data: &mut EvmContext<'_, DB>,
+        _inputs: &CreateInputs,
+        status: InstructionResult,
+        address: Option<Address>,
+        gas: Gas,
+        retdata: Bytes,
+    ) -> (InstructionResult, Option<Address>, Gas, Bytes) {
+        // created contracts are warm
+        if let Some(address) = address {
+            self.excluded.insert(address);
+        }
+        (status, address, gas, retdata)
+    }
+}
+
+/// Prints all the logs of at least the given level, which is derived from the message, see
+/// [LogLevel::of]
 #[inline]
-pub fn print_logs(logs: &[Log]) {
+pub fn print_logs(logs: &[Log], log_level: LogLevel) {
     for log in decode_console_logs(logs) {
-        node_info!("{}", log);
+        if LogLevel::of(&log) >= log_level {
+            node_info!("{}", log);
+        }
+    }
+}
+
+/// A decoded `console.sol` log
+#[derive(Clone, Debug)]
+pub struct DecodedConsoleLog {
+    /// the address of the contract that emitted the log
+    pub address: ethers::types::Address,
+    /// the level derived from the message, see [LogLevel::of]
+    pub level: LogLevel,
+    pub message: String,
+    /// the log as it was emitted
+    pub raw: Log,
+}
+
+// === impl DecodedConsoleLog ===
+
+impl DecodedConsoleLog {
+    /// Decodes the log, returns `None` if it is not a `console.sol` log
+    pub fn decode(raw: Log) -> Option<Self> {
+        let message = decode_console_log(&raw)?;
+        Some(Self { address: raw.address, level: LogLevel::of(&message), message, raw })
+    }
+}
+
+/// The severity of a `console.sol` log
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LogLevel {
+    /// Shows all logs
+    #[default]
+    Debug,
+    Info,
+    Warn,
+    Error,
+}
+
+// === impl LogLevel ===
+
+impl LogLevel {
+    /// Returns the level of the decoded log message.
+    ///
+    /// `console.sol` logs don't carry a level, so it is derived from a leading `debug`, `info`,
+    /// `warn` (or `warning`) or `error` tag of the message, like `[warn] low balance` or
+    /// `error: reverted`. The tag is the first word of the message, optionally in brackets, and is
+    /// matched case-insensitively.
+    ///
+    /// Messages without a tag, including messages whose first word merely starts with a tag like
+    /// `errors: 0`, are [LogLevel::Info].
+    pub fn of(message: &str) -> Self {
+        let message = message.trim_start();
+        let message = message.strip_prefix('[').unwrap_or(message);
+        let end = message.find(|c: char| !c.is_ascii_alphabetic()).unwrap_or(message.len());
+        message[..end].parse().unwrap_or(LogLevel::Info)
+    }
+}
+
+impl FromStr for LogLevel {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "debug" => Ok(LogLevel::Debug),
+            "info" => Ok(LogLevel::Info),
+            "warn" | "warning" => Ok(LogLevel::Warn),
+            "error" => Ok(LogLevel::Error),
+            _ => Err(format!("unknown log level: {s}")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use foundry_evm::revm::{
+        db::{CacheDB, EmptyDB},
+        primitives::{CreateScheme, TransactTo},
+    };
+
+    /// Executes the init code with the inspector
+    fn inspect(init_code: &str, inspector: &mut Inspector) {
+        let mut evm = revm::EVM::new();
+        evm.env.tx.transact_to = TransactTo::Create(CreateScheme::Create);
+        evm.env.tx.data = alloy_primitives::hex::decode(init_code).unwrap().into();
+        evm.database(CacheDB::new(EmptyDB::default()));
+        evm.inspect(inspector).unwrap();
+    }
+
+    #[test]
+    fn derives_log_level_from_tag() {
+        assert_eq!(LogLevel::of("[debug] entering loop"), LogLevel::Debug);
+        assert_eq!(LogLevel::of("Warning: low balance"), LogLevel::Warn);
+        assert_eq!(LogLevel::of("error: reverted"), LogLevel::Error);
+        assert_eq!(LogLevel::of("errors: 0"), LogLevel::Info);
+        assert_eq!(LogLevel::of("balance 42"), LogLevel::Info);
+    }
+
+    #[test]
+    fn untagged_logs_are_info() {
+        for message in ["", "   ", "[] empty", "[fatal] unknown tag", "42 warnings", "debugging"] {
+            assert_eq!(LogLevel::of(message), LogLevel::Info, "{message:?}");
+        }
+        assert_eq!(LogLevel::of("  [WARN] low balance"), LogLevel::Warn);
+        // untagged logs are hidden once only warnings and errors are printed
+        assert!(LogLevel::of("balance 42") < LogLevel::Warn);
+        assert!(LogLevel::of("balance 42") >= LogLevel::Debug);
+    }
+
+    #[test]
+    fn decodes_revert_reason() {
+        // reverts with `Error("insufficient balance")`
+        let mut inspector = Inspector::default();
+        inspect("610064600e6000396100646000fd08c379a000000000000000000000000000000000000000000000000000000000000000200000000000000000000000000000000000000000000000000000000000000014696e73756666696369656e742062616c616e6365000000000000000000000000", &mut inspector);
+        assert_eq!(inspector.revert_reason(), Some("revert: insufficient balance"));
+    }
+
+    #[test]
+    fn takes_only_console_logs() {
+        let mut inspector = Inspector::default();
+        inspector.log_collector.logs.push(Log::default());
+        assert!(inspector.take_console_logs().is_empty());
+        assert!(inspector.log_collector.logs.is_empty());
+    }
+
+    #[test]
+    fn detects_overridden_outcome() {
+        let out = Bytes::from_static(b"out");
+        assert!(!is_override(InstructionResult::Return, &out, InstructionResult::Return, &out));
+        assert!(is_override(InstructionResult::Return, &out, InstructionResult::Revert, &out));
+        assert!(is_override(
+            InstructionResult::Return,
+            &out,
+            InstructionResult::Return,
+            &Bytes::new()
+        ));
+        assert!(!is_override(
+            InstructionResult::Return,
+            &out,
+            InstructionResult::Continue,
+            &Bytes::new()
+        ));
+    }
+
+    #[test]
+    fn records_state_before_transaction() {
+        let mut inspector = Inspector::default().with_prestate();
+        // PUSH1 0x2a PUSH1 0x00 SSTORE STOP
+        inspect("602a60005500", &mut inspector);
+
+        let pre_state = inspector.pre_state();
+        let created = pre_state.get(&Address::ZERO.create(0)).unwrap();
+        assert_eq!(created.nonce, 0);
+        assert_eq!(created.storage[&U256::ZERO], U256::ZERO);
+        assert!(pre_state.contains_key(&Address::ZERO));
+    }
+
+    #[test]
+    fn counts_opcodes_alongside_tracer() {
+        let mut inspector = Inspector::default().with_tracing().with_opcode_counts();
+        // PUSH1 0x01 PUSH1 0x02 ADD POP
+        inspect("600160020150", &mut inspector);
+
+        let counts = inspector.opcode_counts();
+        assert_eq!(counts[&OpCode::new(opcode::PUSH1).unwrap()], 2);
+        assert_eq!(counts[&OpCode::new(opcode::ADD).unwrap()], 1);
+        assert_eq!(counts[&OpCode::new(opcode::POP).unwrap()], 1);
+        assert!(!inspector.tracer.unwrap().get_traces().clone().into_nodes().is_empty());
+    }
+
+    #[test]
+    fn collects_events_of_successful_calls() {
+        let mut inspector = Inspector::default().with_events();
+        // PUSH1 0x2a PUSH1 0x00 MSTORE PUSH1 0x07 PUSH1 0x20 PUSH1 0x00 LOG1 STOP
+        inspect("602a600052600760206000a100", &mut inspector);
+
+        let events = inspector.take_events();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].address, Address::ZERO.create(0));
+        assert_eq!(events[0].topics, vec![B256::with_last_byte(7)]);
+        assert_eq!(events[0].data, Bytes::from(B256::with_last_byte(0x2a).to_vec()));
+        assert!(inspector.take_events().is_empty());
+
+        // the same event, but the call reverts afterwards
+        let mut inspector = Inspector::default().with_events();
+        inspect("602a600052600760206000a160006000fd", &mut inspector);
+        assert!(inspector.take_events().is_empty());
+    }
+
+    #[test]
+    fn summarizes_gas_per_call() {
+        let mut inspector = Inspector::default().with_tracing();
+        // CALL(gas, 0xaa, 0, 0, 4, 0, 0) POP STOP
+        inspect("6000600060046000600060aa5af15000", &mut inspector);
+
+        let summary = inspector.call_gas_summary();
+        assert_eq!(summary.len(), 2);
+        assert_eq!(summary[0].address, Address::ZERO.create(0));
+        assert_eq!(summary[0].selector, None);
+        assert_eq!(summary[0].self_gas_used, summary[0].gas_used - summary[1].gas_used);
+        assert_eq!(summary[1].address, Address::with_last_byte(0xaa));
+        assert_eq!(summary[1].selector, Some(Selector::ZERO));
+        assert_eq!(summary[1].self_gas_used, summary[1].gas_used);
+
+        assert!(Inspector::default().call_gas_summary().is_empty());
     }
 }
@@ -4,58 +4,68 @@ use crate::{
     eth::{
         backend::{
             cheats::CheatsManager,
-            db::{AsHashDB, Db, MaybeHashDatabase, SerializableState},
+            db::{Db, MaybeHashDatabase, SerializableForkBlock, SerializableState},
+            delegation::{apply_authorizations, authorization_intrinsic_gas, Delegation},
             executor::{ExecutedTransactions, TransactionExecutor},
             fork::ClientFork,
             genesis::GenesisConfig,
             mem::storage::MinedTransactionReceipt,
-            notifications::{NewBlockNotification, NewBlockNotifications},
+            notifications::{NewBlockNotification, NewBlockNotifications, ReorgedBlock},
             time::{utc_from_secs, TimeManager},
             validate::TransactionValidator,
         },
-        error::{BlockchainError, ErrDetail, InvalidTransactionError},
+        error::{BlockchainError, InvalidTransactionError},
         fees::{FeeDetails, FeeManager},
         macros::node_info,
         pool::transactions::PoolTransaction,
+        signatures::AbiRegistry,
         util::get_precompiles_for,
     },
     mem::{
-        inspector::Inspector,
+        inspector::{AccessListInspector, Inspector},
         storage::{BlockchainStorage, InMemoryBlockStates, MinedBlockOutcome},
     },
     revm::{
         db::DatabaseRef,
         primitives::{AccountInfo, U256 as rU256},
     },
-    NodeConfig,
+    Hardfork, NodeConfig,
 };
+use alloy_json_abi::JsonAbi;
 use alloy_primitives::{Address, Bloom, Bytes, TxHash, B256, B64, U128, U256, U64, U8};
+use alloy_providers::provider::TempProvider;
 use alloy_rpc_types::{
     state::StateOverride,
     trace::{
-        DefaultFrame, GethDebugTracingOptions, GethDefaultTracingOptions, GethTrace,
-        LocalizedTransactionTrace,
+        geth::CallConfig, DefaultFrame, GethDebugBuiltInTracerType, GethDebugTracerType,
+        GethDebugTracingOptions, GethDefaultTracingOptions, GethTrace, LocalizedTransactionTrace,
+        PreStateFrame,
     },
-    AccessList, Block as AlloyBlock, BlockId, BlockNumberOrTag as BlockNumber, Filter,
-    FilteredParams, Header as AlloyHeader, Log, Transaction, TransactionReceipt,
+    AccessList, Block as AlloyBlock, BlockId, BlockNumberOrTag as BlockNumber, BlockTransactions,
+    Filter, FilteredParams, Header as AlloyHeader, Log, Transaction, TransactionReceipt,
 };
 use anvil_core::{
     eth::{
         block::{Block, BlockInfo, Header},
-        proof::{AccountProof, BasicAccount, StorageProof},
+        proof::{AccountProof, StorageProof},
         receipt::{EIP658Receipt, TypedReceipt},
         transaction::{
             from_ethers_access_list, EthTransactionRequest, MaybeImpersonatedTransaction,
             PendingTransaction, TransactionInfo, TypedTransaction,
         },
-        trie::RefTrieDB,
         utils::to_revm_access_list,
     },
-    types::{Forking, Index},
+    types::{
+        BlockOverrides, CallGas, CallGasSummary, Forking, Index, OpcodeGas, SimulatedLog,
+        Simulation, StorageBatch, TraceFilter, TransactionInspection, TxTraceResult,
+        STORAGE_DIFF_TRACER,
+    },
 };
 use anvil_rpc::error::RpcError;
 use ethers::{
-    abi::ethereum_types::BigEndianHash,
+    core::k256::ecdsa::SigningKey,
+    prelude::Wallet,
+    signers::Signer,
     types::transaction::eip2930::AccessList as EthersAccessList,
     utils::{keccak256, rlp},
 };
@@ -65,33 +75,34 @@ use foundry_evm::{
     backend::{DatabaseError, DatabaseResult, RevertSnapshotAction},
     constants::DEFAULT_CREATE2_DEPLOYER_RUNTIME_CODE,
     decode::decode_revert,
-    inspectors::AccessListTracer,
     revm::{
         self,
-        db::CacheDB,
+        db::{AccountState, CacheDB},
         interpreter::InstructionResult,
         primitives::{
-            BlockEnv, CreateScheme, EVMError, Env, ExecutionResult, InvalidHeader, Output, SpecId,
-            TransactTo, TxEnv, KECCAK_EMPTY,
+            calc_excess_blob_gas, BlobExcessGasAndPrice, BlockEnv, CreateScheme, EVMError, Env,
+            ExecutionResult, InvalidHeader, Output, SpecId, TransactTo, TxEnv, KECCAK_EMPTY,
+            MAX_BLOB_NUMBER_PER_BLOCK, VERSIONED_HASH_VERSION_KZG,
         },
     },
     traces::{TracingInspector, TracingInspectorConfig},
     utils::{eval_to_instruction_result, halt_to_instruction_result, u256_to_h256_be},
 };
 use futures::channel::mpsc::{unbounded, UnboundedSender};
-use hash_db::HashDB;
 use itertools::Itertools;
 use parking_lot::{Mutex, RwLock};
 use std::{
-    collections::{BTreeMap, HashMap},
+    collections::{BTreeMap, HashMap, VecDeque},
     io::{Read, Write},
-    ops::Deref,
-    sync::Arc,
+    ops::RangeInclusive,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
     time::Duration,
 };
 use storage::{Blockchain, MinedTransaction};
 use tokio::sync::RwLock as AsyncRwLock;
-use trie_db::{Recorder, Trie};
 
 pub mod cache;
 pub mod fork_db;
@@ -105,6 +116,9 @@ pub const MIN_TRANSACTION_GAS: U256 = U256::from_limbs([21_000, 0, 0, 0]);
 // Gas per transaction creating a contract.
 pub const MIN_CREATE_GAS: U256 = U256::from_limbs([53_000, 0, 0, 0]);
 
+/// The default number of mined transactions whose traced steps are kept
+pub const DEFAULT_STEPS_TRACING_CACHE_SIZE: usize = 100;
+
 pub type State = foundry_evm::utils::StateChangeset;
 
 /// A block request, which includes the Pool Transactions if it's Pending
@@ -151,6 +165,8 @@ pub struct Backend {
     states: Arc<RwLock<InMemoryBlockStates>>,
     /// env data of the chain
     env: Arc<RwLock<Env>>,
+    /// the configured hardfork, which may be ahead of the spec of the `env`
+    hardfork: Arc<RwLock<Hardfork>>,
     /// this is set if this is currently forked off another client
     fork: Arc<RwLock<Option<ClientFork>>>,
     /// provides time related info, like timestamp
@@ -163,14 +179,35 @@ pub struct Backend {
     genesis: GenesisConfig,
     /// listeners for new blocks that get notified when a new block was imported
     new_block_listeners: Arc<Mutex<Vec<UnboundedSender<NewBlockNotification>>>>,
+    /// blocks removed by a reorg that are sent along with the next new block notification
+    reorged_blocks: Arc<Mutex<Vec<ReorgedBlock>>>,
+    /// the pinned order of transactions in the next mined block
+    next_block_order: Arc<Mutex<Option<Vec<TxHash>>>>,
+    /// the pinned prevrandao of all following blocks
+    prevrandao: Arc<RwLock<Option<B256>>>,
+    /// the prevrandao of the next mined block only, takes precedence over `prevrandao`
+    next_block_prevrandao: Arc<Mutex<Option<B256>>>,
     /// keeps track of active snapshots at a specific block
     active_snapshots: Arc<Mutex<HashMap<U256, (u64, B256)>>>,
-    enable_steps_tracing: bool,
+    /// the ids of snapshots that were created with a name
+    snapshot_names: Arc<Mutex<HashMap<String, U256>>>,
+    /// whether the steps of mined transactions are traced
+    enable_steps_tracing: Arc<AtomicBool>,
+    /// the mined transactions whose traced steps are kept, oldest first
+    steps_traced_transactions: Arc<Mutex<VecDeque<B256>>>,
+    /// the max number of mined transactions whose traced steps are kept
+    steps_tracing_cache_size: usize,
+    /// the max depth of nested calls, deeper calls are reverted
+    max_call_depth: Option<u64>,
+    /// the ABIs used to decode calls and events
+    abis: Arc<RwLock<AbiRegistry>>,
     /// How to keep history state
     prune_state_history_config: PruneStateHistoryConfig,
     /// max number of blocks with transactions in memory
     transaction_block_keeper: Option<usize>,
     node_config: Arc<AsyncRwLock<NodeConfig>>,
+    /// Whether the fork is currently being reset, during which the chain is in flux
+    resetting_fork: Arc<AtomicBool>,
 }
 
 impl Backend {
@@ -183,6 +220,8 @@ impl Backend {
         fees: FeeManager,
         fork: Arc<RwLock<Option<ClientFork>>>,
         enable_steps_tracing: bool,
+        steps_tracing_cache_size: usize,
+        max_call_depth: Option<u64>,
         prune_state_history_config: PruneStateHistoryConfig,
         transaction_block_keeper: Option<usize>,
         automine_block_time: Option<Duration>,
@@ -217,22 +256,35 @@ impl Backend {
             Default::default()
         };
 
+        let hardfork = node_config.read().await.get_hardfork();
+
         let backend = Self {
             db,
             blockchain,
             states: Arc::new(RwLock::new(states)),
             env,
+            hardfork: Arc::new(RwLock::new(hardfork)),
             fork,
             time: TimeManager::new(start_timestamp),
             cheats: Default::default(),
             new_block_listeners: Default::default(),
+            reorged_blocks: Default::default(),
+            next_block_order: Default::default(),
+            prevrandao: Default::default(),
+            next_block_prevrandao: Default::default(),
             fees,
             genesis,
             active_snapshots: Arc::new(Mutex::new(Default::default())),
-            enable_steps_tracing,
+            snapshot_names: Default::default(),
+            enable_steps_tracing: Arc::new(AtomicBool::new(enable_steps_tracing)),
+            steps_traced_transactions: Default::default(),
+            steps_tracing_cache_size,
+            max_call_depth,
+            abis: Default::default(),
             prune_state_history_config,
             transaction_block_keeper,
             node_config,
+            resetting_fork: Default::default(),
         };
 
         if let Some(interval_block_time) = automine_block_time {
@@ -251,6 +303,18 @@ impl Backend {
         Ok(())
     }
 
+    /// Sets the state root of the genesis block to the root of the current state
+    ///
+    /// This is a noop in fork mode or once blocks were mined.
+    pub async fn seal_genesis_state_root(&self) {
+        if self.is_fork() || self.best_number() != 0 {
+            return
+        }
+        if let Some(state_root) = self.db.read().await.maybe_state_root() {
+            self.blockchain.storage.write().set_genesis_state_root(state_root);
+        }
+    }
+
     /// Updates memory limits that should be more strict when auto-mine is enabled
     pub(crate) fn update_interval_mine_block_time(&self, block_time: Duration) {
         self.states.write().update_interval_mine_block_time(block_time)
@@ -329,8 +393,46 @@ impl Backend {
         Ok(())
     }
 
+    /// Derives the next dev account from the configured mnemonic and funds it with the genesis
+    /// balance
+    pub async fn add_derived_account(&self) -> Result<Wallet<SigningKey>, BlockchainError> {
+        let (wallet, balance) = {
+            let mut node_config = self.node_config.write().await;
+            let wallet = node_config.derive_next_account().ok_or_else(|| {
+                RpcError::invalid_params("No mnemonic configured to derive accounts from")
+            })?;
+            (wallet, node_config.genesis_balance)
+        };
+        self.set_balance(wallet.address().to_alloy(), balance).await?;
+        Ok(wallet)
+    }
+
+    /// Sets all accounts to impersonate
+    pub async fn impersonate_accounts(&self, addrs: Vec<Address>) {
+        // Ensure EIP-3607 is disabled
+        self.env.write().cfg.disable_eip3607 = true;
+        self.cheats.impersonate_accounts(addrs.into_iter().map(|addr| addr.to_ethers()));
+    }
+
+    /// Removes all accounts from the impersonated set
+    pub async fn stop_impersonating_accounts(&self, addrs: Vec<Address>) {
+        let addrs = addrs.into_iter().map(|addr| addr.to_ethers()).collect::<Vec<_>>();
+        self.cheats.stop_impersonating_accounts(&addrs);
+    }
+
+    /// Removes every account that was impersonated explicitly from the impersonated set
+    pub async fn stop_impersonating_all(&self) {
+        self.cheats.clear_impersonated_accounts();
+    }
+
     /// If set to true will make every account impersonated
+    ///
+    /// This also disables EIP-3607 so that contracts can be used as senders.
     pub async fn auto_impersonate_account(&self, enabled: bool) {
+        if enabled {
+            warn!(target: "backend", "auto impersonation enabled, transactions of any sender are accepted without a signature");
+            self.env.write().cfg.disable_eip3607 = true;
+        }
         self.cheats.set_auto_impersonate_account(enabled);
     }
 
@@ -361,36 +463,83 @@ impl Backend {
             .collect_vec()
     }
 
+    /// Returns true if the backend is ready to serve requests.
+    ///
+    /// This is false while the fork is being reset to a new remote head.
+    pub fn is_ready(&self) -> bool {
+        !self.resetting_fork.load(Ordering::Acquire)
+    }
+
+    /// Returns true if the steps of mined transactions are traced
+    pub fn is_steps_tracing_enabled(&self) -> bool {
+        self.enable_steps_tracing.load(Ordering::Relaxed)
+    }
+
+    /// Sets whether the steps of mined transactions are traced, so that `debug_traceTransaction`
+    /// can return the executed opcodes together with their stack and memory
+    ///
+    /// The steps are only kept for the most recently mined transactions, up to the configured
+    /// steps tracing cache size.
+    pub fn set_steps_tracing(&self, enabled: bool) {
+        self.enable_steps_tracing.store(enabled, Ordering::Relaxed);
+    }
+
+    /// Returns an [Inspector] that reverts calls nested deeper than the configured max call depth
+    fn inspector(&self) -> Inspector {
+        Inspector::default().with_max_call_depth(self.max_call_depth)
+    }
+
+    /// Registers the ABI of the contract, or the fallback ABI if the address is `None`, see
+    /// [AbiRegistry]
+    pub fn register_abi(&self, address: Option<Address>, abi: JsonAbi) {
+        self.abis.write().register(address, abi);
+    }
+
     /// Resets the fork to a fresh state
     pub async fn reset_fork(&self, forking: Forking) -> Result<(), BlockchainError> {
-        if !self.is_fork() {
-            if let Some(eth_rpc_url) = forking.clone().json_rpc_url {
-                let mut env = self.env.read().clone();
+        // the node is ready again once the reset finished, or if the reset is cancelled
+        let _resetting = FlagGuard::set(&self.resetting_fork);
+        self.do_reset_fork(forking).await
+    }
 
-                let mut node_config = self.node_config.write().await;
+    async fn do_reset_fork(&self, forking: Forking) -> Result<(), BlockchainError> {
+        if let Some(eth_rpc_url) = forking.json_rpc_url.clone() {
+            // make sure the new endpoint is usable before the current fork is discarded
+            self.check_fork_endpoint(&eth_rpc_url, forking.block_number).await?;
 
-                let (db, config) =
-                    node_config.setup_fork_db_config(eth_rpc_url, &mut env, &self.fees).await;
+            let mut env = self.env.read().clone();
 
-                *self.db.write().await = Box::new(db);
+            let mut node_config = self.node_config.write().await;
+            node_config.eth_rpc_url = Some(eth_rpc_url.clone());
+            node_config.fork_block_number = forking.block_number;
 
-                let fork = ClientFork::new(config, Arc::clone(&self.db));
+            // re-initialize the forking backend, so that all data is fetched from the new endpoint
+            let (db, config) =
+                node_config.setup_fork_db_config(eth_rpc_url, &mut env, &self.fees).await;
 
-                *self.env.write() = env;
-                *self.fork.write() = Some(fork);
-            } else {
-                return Err(RpcError::invalid_params(
-                    "Forking not enabled and RPC URL not provided to start forking",
-                )
-                .into());
-            }
+            *self.db.write().await = Box::new(db);
+
+            let fork = ClientFork::new(config, Arc::clone(&self.db));
+
+            *self.env.write() = env;
+            *self.fork.write() = Some(fork);
+        } else if !self.is_fork() {
+            return Err(RpcError::invalid_params(
+                "Forking not enabled and RPC URL not provided to start forking",
+            )
+            .into());
+        }
+
+        if !forking.keep_impersonated_accounts {
+            self.cheats.clear_impersonated_accounts();
         }
 
         if let Some(fork) = self.get_fork() {
             let block_number =
                 forking.block_number.map(BlockNumber::from).unwrap_or(BlockNumber::Latest);
             // reset the fork entirely and reapply the genesis config
-            fork.reset(forking.json_rpc_url.clone(), block_number).await?;
+            // the fork already uses the new endpoint
+            fork.reset(None, block_number).await?;
             let fork_block_number = fork.block_number();
             let fork_block = fork
                 .block_by_number(fork_block_number)
@@ -452,6 +601,34 @@ impl Backend {
         }
     }
 
+    /// Returns an error if the endpoint can't be reached or doesn't serve the block to fork off
+    async fn check_fork_endpoint(
+        &self,
+        eth_rpc_url: &str,
+        block_number: Option<u64>,
+    ) -> Result<(), BlockchainError> {
+        let provider = self
+            .node_config
+            .read()
+            .await
+            .fork_provider(eth_rpc_url)
+            .map_err(|_| BlockchainError::InvalidUrl(eth_rpc_url.to_string()))?;
+        let block_id = block_number.map(BlockNumber::from).unwrap_or(BlockNumber::Latest);
+        let block = provider.get_block(block_id.into(), false).await.map_err(|err| {
+            RpcError::invalid_params(format!(
+                "Failed to connect to fork endpoint {eth_rpc_url}: {err}"
+            ))
+        })?;
+        if block.is_none() {
+            let block = block_number.map_or_else(|| "latest".to_string(), |n| n.to_string());
+            return Err(RpcError::invalid_params(format!(
+                "Fork endpoint {eth_rpc_url} does not serve block {block}"
+            ))
+            .into())
+        }
+        Ok(())
+    }
+
     /// Returns the `TimeManager` responsible for timestamps
     pub fn time(&self) -> &TimeManager {
         &self.time
@@ -502,6 +679,15 @@ impl Backend {
         self.env.write().cfg.chain_id = chain_id;
     }
 
+    /// Sets the hardfork of all following blocks and calls, mined blocks are not affected
+    pub async fn set_hardfork(&self, hardfork: Hardfork) {
+        let spec_id = SpecId::from(hardfork);
+        self.env.write().cfg.spec_id = spec_id;
+        self.fees.set_spec_id(spec_id);
+        *self.hardfork.write() = hardfork;
+        self.node_config.write().await.hardfork = Some(hardfork);
+    }
+
     /// Returns balance of the given account.
     pub async fn current_balance(&self, address: Address) -> DatabaseResult<U256> {
         Ok(self.get_account(address).await?.balance)
@@ -539,7 +725,22 @@ impl Backend {
         slot: U256,
         val: B256,
     ) -> DatabaseResult<()> {
-        self.db.write().await.set_storage_at(address, slot, val.to_ethers().into_uint().to_alloy())
+        self.set_storage_batch(StorageBatch::from([(address, BTreeMap::from([(slot, val)]))])).await
+    }
+
+    /// Sets the storage slots of multiple accounts, either all slots are set or none
+    pub async fn set_storage_batch(&self, storage: StorageBatch) -> DatabaseResult<()> {
+        let storage = storage
+            .into_iter()
+            .map(|(address, slots)| {
+                let slots = slots
+                    .into_iter()
+                    .map(|(slot, val)| (slot, U256::from_be_bytes(val.0)))
+                    .collect::<BTreeMap<_, _>>();
+                (address, slots)
+            })
+            .collect();
+        self.db.write().await.set_storage_batch(&storage)
     }
 
     /// Returns the configured specid
@@ -562,6 +763,18 @@ impl Backend {
         (self.spec_id() as u8) >= (SpecId::BERLIN as u8)
     }
 
+    /// Returns true for post Cancun
+    pub fn is_eip4844(&self) -> bool {
+        (self.spec_id() as u8) >= (SpecId::CANCUN as u8)
+    }
+
+    /// Returns true for post Prague
+    ///
+    /// The EVM has no spec for Prague yet, so this is only active if Prague was configured.
+    pub fn is_eip7702(&self) -> bool {
+        *self.hardfork.read() == Hardfork::Prague
+    }
+
     /// Returns true if op-stack deposits are active
     pub fn is_optimism(&self) -> bool {
         self.env.read().cfg.optimism
@@ -583,6 +796,22 @@ impl Backend {
         Err(BlockchainError::EIP2930TransactionUnsupportedAtHardfork)
     }
 
+    /// Returns an error if EIP4844 is not active (pre Cancun)
+    pub fn ensure_eip4844_active(&self) -> Result<(), BlockchainError> {
+        if self.is_eip4844() {
+            return Ok(())
+        }
+        Err(BlockchainError::EIP4844TransactionUnsupportedAtHardfork)
+    }
+
+    /// Returns an error if EIP7702 is not active (pre Prague)
+    pub fn ensure_eip7702_active(&self) -> Result<(), BlockchainError> {
+        if self.is_eip7702() {
+            return Ok(())
+        }
+        Err(BlockchainError::EIP7702TransactionUnsupportedAtHardfork)
+    }
+
     /// Returns an error if op-stack deposits are not active
     pub fn ensure_op_deposits_active(&self) -> Result<(), BlockchainError> {
         if self.is_optimism() {
@@ -591,6 +820,34 @@ impl Backend {
         Err(BlockchainError::DepositTransactionUnsupported)
     }
 
+    /// Returns the excess blob gas of the next block, which is derived from the latest block
+    pub fn next_excess_blob_gas(&self) -> u64 {
+        let excess_blob_gas = {
+            let storage = self.blockchain.storage.read();
+            storage.blocks.get(&storage.best_hash).map(|block| {
+                calc_excess_blob_gas(
+                    block.header.excess_blob_gas.unwrap_or_default(),
+                    block.header.blob_gas_used.unwrap_or_default(),
+                )
+            })
+        };
+        // the forked block isn't stored, so we fall back to the excess blob gas of the env
+        excess_blob_gas.unwrap_or_else(|| {
+            self.env
+                .read()
+                .block
+                .blob_excess_gas_and_price
+                .as_ref()
+                .map(|blob_gas_and_price| blob_gas_and_price.excess_blob_gas)
+                .unwrap_or_default()
+        })
+    }
+
+    /// Returns the blob base fee of the next block
+    pub fn blob_base_fee(&self) -> U256 {
+        U256::from(BlobExcessGasAndPrice::new(self.next_excess_blob_gas()).blob_gasprice)
+    }
+
     /// Returns the block gas limit
     pub fn gas_limit(&self) -> U256 {
         self.env.read().block.gas_limit
@@ -650,70 +907,304 @@ impl Backend {
         id
     }
 
+    /// Creates a new `evm_snapshot` at the current height that can be referenced by the given
+    /// name
+    ///
+    /// An existing snapshot with the same name can no longer be referenced by the name.
+    pub async fn create_named_snapshot(&self, name: String) -> U256 {
+        let id = self.create_snapshot().await;
+        trace!(target: "backend", "naming snapshot {} {:?}", id, name);
+        self.snapshot_names.lock().insert(name, id);
+        id
+    }
+
+    /// Returns the id of the snapshot with the given name
+    pub fn named_snapshot(&self, name: &str) -> Option<U256> {
+        self.snapshot_names.lock().get(name).copied()
+    }
+
+    /// Returns the names of all active named snapshots and their ids
+    pub fn list_named_snapshots(&self) -> BTreeMap<String, U256> {
+        self.snapshot_names.lock().iter().map(|(name, id)| (name.clone(), *id)).collect()
+    }
+
     /// Reverts the state to the snapshot identified by the given `id`.
+    ///
+    /// This also removes all snapshots that were created after the snapshot.
     pub async fn revert_snapshot(&self, id: U256) -> Result<bool, BlockchainError> {
-        let block = { self.active_snapshots.lock().remove(&id) };
+        let block = {
+            let mut snapshots = self.active_snapshots.lock();
+            let block = snapshots.remove(&id);
+            snapshots.retain(|snapshot_id, _| *snapshot_id < id);
+            block
+        };
+        self.snapshot_names.lock().retain(|_, snapshot_id| *snapshot_id < id);
         if let Some((num, hash)) = block {
-            let best_block_hash = {
-                // revert the storage that's newer than the snapshot
-                let current_height = self.best_number();
-                let mut storage = self.blockchain.storage.write();
-
-                for n in ((num + 1)..=current_height).rev() {
-                    trace!(target: "backend", "reverting block {}", n);
-                    let n = U64::from(n);
-                    if let Some(hash) = storage.hashes.remove(&n) {
-                        if let Some(block) = storage.blocks.remove(&hash) {
-                            for tx in block.transactions {
-                                let _ = storage.transactions.remove(&tx.hash().to_alloy());
-                            }
-                        }
-                    }
-                }
+            self.unwind_to(num, hash).await?;
+        }
+        Ok(self.db.write().await.revert(id, RevertSnapshotAction::RevertRemove))
+    }
 
-                storage.best_number = U64::from(num);
-                storage.best_hash = hash;
-                hash
-            };
+    /// Removes all blocks that are newer than the given block and resets the time and the block
+    /// env to that block.
+    ///
+    /// This does not touch the state of the db. Returns the removed blocks, the newest first.
+    async fn unwind_to(&self, num: u64, hash: B256) -> Result<Vec<ReorgedBlock>, BlockchainError> {
+        // revert the storage that's newer than the block
+        let removed = self.blockchain.storage.write().unwind_to(U64::from(num), hash);
+
+        let block = self.block_by_hash(hash).await?.ok_or(BlockchainError::BlockNotFound)?;
+
+        let reset_time = block.header.timestamp.to::<u64>();
+        self.time.reset(reset_time);
+
+        let mut env = self.env.write();
+        env.block = BlockEnv {
+            number: rU256::from(num),
+            timestamp: block.header.timestamp,
+            difficulty: block.header.difficulty,
+            // ensures prevrandao is set
+            prevrandao: Some(block.header.mix_hash.unwrap_or_default()),
+            gas_limit: block.header.gas_limit,
+            // Keep previous `coinbase` and `basefee` value
+            coinbase: env.block.coinbase,
+            basefee: env.block.basefee,
+            ..Default::default()
+        };
+        Ok(removed)
+    }
+
+    /// Simulates a reorg of the last `depth` blocks.
+    ///
+    /// The chain and the state are rolled back to the block at `best_number - depth`, then `depth`
+    /// new blocks are mined, the first of which includes the given `transactions`. The
+    /// transactions of the removed blocks are dropped.
+    ///
+    /// The removed blocks are sent along with the notification of the first new block, so that
+    /// subscribers can emit the removed logs.
+    pub async fn reorg(
+        &self,
+        depth: u64,
+        transactions: Vec<Arc<PoolTransaction>>,
+    ) -> Result<(), BlockchainError> {
+        trace!(target: "backend", "reorg of {} blocks", depth);
+        let removed = self.remove_blocks(depth).await?;
+
+        *self.reorged_blocks.lock() = removed;
+        self.mine_block(transactions).await;
+        for _ in 1..depth {
+            self.mine_block(Vec::new()).await;
+        }
+        Ok(())
+    }
+
+    /// Removes the last `depth` blocks, as if they were never mined.
+    ///
+    /// The chain and the state are rolled back to the block at `best_number - depth`, which is
+    /// announced as the new head along with the removed blocks, so that subscribers can emit the
+    /// removed logs.
+    ///
+    /// Returns the transactions of the removed blocks, the oldest first.
+    pub async fn rollback(&self, depth: u64) -> Result<Vec<PendingTransaction>, BlockchainError> {
+        trace!(target: "backend", "rollback of {} blocks", depth);
+        let removed = self.remove_blocks(depth).await?;
+
+        let transactions = removed
+            .iter()
+            .rev()
+            .flat_map(|reorged| reorged.block.transactions.iter().cloned())
+            .filter_map(|tx| match tx.impersonated_sender {
+                Some(sender) => Some(PendingTransaction::with_impersonated(tx.transaction, sender)),
+                None => PendingTransaction::new(tx.transaction).ok(),
+            })
+            .collect();
+
+        let (hash, header) = {
+            let storage = self.blockchain.storage.read();
             let block =
-                self.block_by_hash(best_block_hash).await?.ok_or(BlockchainError::BlockNotFound)?;
-
-            let reset_time = block.header.timestamp.to::<u64>();
-            self.time.reset(reset_time);
-
-            let mut env = self.env.write();
-            env.block = BlockEnv {
-                number: rU256::from(num),
-                timestamp: block.header.timestamp,
-                difficulty: block.header.difficulty,
-                // ensures prevrandao is set
-                prevrandao: Some(block.header.mix_hash.unwrap_or_default()),
-                gas_limit: block.header.gas_limit,
-                // Keep previous `coinbase` and `basefee` value
-                coinbase: env.block.coinbase,
-                basefee: env.block.basefee,
-                ..Default::default()
+                storage.blocks.get(&storage.best_hash).ok_or(BlockchainError::BlockNotFound)?;
+            (storage.best_hash, block.header.clone())
+        };
+        *self.reorged_blocks.lock() = removed;
+        self.notify_on_new_head(header, hash, false);
+        Ok(transactions)
+    }
+
+    /// Rolls the chain and the state back to the block at `best_number - depth`.
+    ///
+    /// Returns an error if `depth` is zero or the chain would be rolled back past the genesis
+    /// block, or the fork block since the blocks before it aren't stored locally.
+    ///
+    /// Returns the removed blocks, the newest first.
+    async fn remove_blocks(&self, depth: u64) -> Result<Vec<ReorgedBlock>, BlockchainError> {
+        if depth == 0 {
+            return Err(RpcError::invalid_params("at least one block must be removed").into())
+        }
+        let fork_number = self.get_fork().map(|fork| fork.block_number());
+        let lowest = fork_number.unwrap_or_default();
+        let Some(num) = self.best_number().checked_sub(depth).filter(|num| *num >= lowest) else {
+            let msg = match fork_number {
+                Some(number) => format!("cannot roll back past the fork block {number}"),
+                None => "cannot roll back past the genesis block".to_string(),
             };
+            return Err(RpcError::invalid_params(msg).into())
+        };
+        let hash = self
+            .blockchain
+            .storage
+            .read()
+            .hashes
+            .get(&U64::from(num))
+            .copied()
+            .ok_or(BlockchainError::BlockNotFound)?;
+
+        // the state at the end of a block is stored when its child block is mined
+        let accounts = {
+            let mut states = self.states.write();
+            let state = states.get(&hash).ok_or(BlockchainError::DataUnavailable)?;
+            let accounts = state.maybe_as_full_db().ok_or(BlockchainError::DataUnavailable)?;
+            accounts
+                .iter()
+                .map(|(address, account)| {
+                    let mut info = account.info.clone();
+                    if info.code.is_none() {
+                        info.code = state.code_by_hash_ref(info.code_hash).ok();
+                    }
+                    (*address, info, account.storage.clone())
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let removed = self.unwind_to(num, hash).await?;
+        let mut db = self.db.write().await;
+        db.clear();
+        for (address, info, storage) in accounts {
+            db.insert_account(address, info);
+            for (slot, value) in storage {
+                db.set_storage_at(address, slot, value)?;
+            }
         }
-        Ok(self.db.write().await.revert(id, RevertSnapshotAction::RevertRemove))
+        for (number, hash) in self.blockchain.storage.read().hashes.iter() {
+            db.insert_block_hash(U256::from(number.to::<u64>()), *hash);
+        }
+        Ok(removed)
     }
 
     pub fn list_snapshots(&self) -> BTreeMap<U256, (u64, B256)> {
         self.active_snapshots.lock().clone().into_iter().collect()
     }
 
-    /// Get the current state.
-    pub async fn serialized_state(&self) -> Result<SerializableState, BlockchainError> {
-        let state = self.db.read().await.dump_state()?;
-        state.ok_or_else(|| {
-            RpcError::invalid_params("Dumping state not supported with the current configuration")
-                .into()
+    /// Returns the block the node is forked off, if any
+    fn fork_block(&self) -> Option<SerializableForkBlock> {
+        self.get_fork().map(|fork| SerializableForkBlock {
+            number: fork.block_number(),
+            hash: fork.block_hash(),
         })
     }
 
+    /// Get the current state, including the blocks and transactions of the chain.
+    ///
+    /// If `diff` is set, only the accounts and storage slots that were modified since forking are
+    /// included.
+    pub async fn serialized_state(&self, diff: bool) -> Result<SerializableState, BlockchainError> {
+        let mut state = if diff {
+            let state = self.db.read().await.dump_state_diff()?;
+            let mut state = state.ok_or_else(|| {
+                RpcError::invalid_params("Dumping a state diff is only supported in fork mode")
+            })?;
+            state.fork = self.fork_block();
+            state
+        } else {
+            let state = self.db.read().await.dump_state()?;
+            state.ok_or_else(|| {
+                RpcError::invalid_params(
+                    "Dumping state not supported with the current configuration",
+                )
+            })?
+        };
+
+        let storage = self.blockchain.storage.read();
+        state.chain_id = Some(self.chain_id().to::<u64>());
+        state.best_block_number = Some(storage.best_number.to::<u64>());
+        let mut numbers = storage.hashes.keys().copied().collect::<Vec<_>>();
+        numbers.sort_unstable();
+        state.blocks = numbers
+            .into_iter()
+            .filter_map(|number| storage.blocks.get(&storage.hashes[&number]).cloned())
+            .collect();
+        state.transactions = storage.transactions.values().cloned().map(Into::into).collect();
+        Ok(state)
+    }
+
+    /// Loads the state, and if included the chain, of a [SerializableState].
+    ///
+    /// Fails if the state was dumped from a chain with a different chain id, or if the state is a
+    /// diff against a different fork block.
+    pub async fn load_serialized_state(
+        &self,
+        state: SerializableState,
+    ) -> Result<bool, BlockchainError> {
+        let SerializableState { chain_id, best_block_number, fork, accounts, blocks, transactions } =
+            state;
+        if let Some(chain_id) = chain_id {
+            let current = self.chain_id().to::<u64>();
+            if chain_id != current {
+                return Err(RpcError::invalid_params(format!(
+                    "the state was dumped from chain {chain_id} but the chain id is {current}"
+                ))
+                .into())
+            }
+        }
+        if let Some(fork) = fork {
+            if self.fork_block() != Some(fork) {
+                return Err(RpcError::invalid_params(format!(
+                    "the state diff requires a fork off block {} ({})",
+                    fork.number, fork.hash
+                ))
+                .into())
+            }
+        }
+
+        let state = SerializableState { accounts, ..Default::default() };
+        if !self.db.write().await.load_state(state)? {
+            return Err(RpcError::invalid_params(
+                "Loading state not supported with the current configuration",
+            )
+            .into())
+        }
+
+        // older dumps only contain the accounts
+        let Some(best_number) = best_block_number.filter(|_| !blocks.is_empty()) else {
+            return Ok(true)
+        };
+        let best_hash = {
+            let mut db = self.db.write().await;
+            let mut storage = self.blockchain.storage.write();
+            for block in blocks {
+                let hash = block.header.hash().to_alloy();
+                let number = block.header.number.as_u64();
+                db.insert_block_hash(U256::from(number), hash);
+                storage.hashes.insert(U64::from(number), hash);
+                storage.blocks.insert(hash, block);
+            }
+            for tx in transactions {
+                let tx = MinedTransaction::from(tx);
+                storage.transactions.insert(tx.info.transaction_hash.to_alloy(), tx);
+            }
+            // blocks of the current chain that are newer than the loaded chain are removed below
+            storage.best_number = storage.best_number.max(U64::from(best_number));
+            storage.hashes.get(&U64::from(best_number)).copied()
+        }
+        .ok_or(BlockchainError::FailedToDecodeStateDump)?;
+
+        // makes the loaded block the latest block and resets the time to its timestamp
+        self.unwind_to(best_number, best_hash).await?;
+        Ok(true)
+    }
+
     /// Write all chain data to serialized bytes buffer
-    pub async fn dump_state(&self) -> Result<Bytes, BlockchainError> {
-        let state = self.serialized_state().await?;
+    pub async fn dump_state(&self, diff: bool) -> Result<Bytes, BlockchainError> {
+        let state = self.serialized_state(diff).await?;
         let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
         encoder
             .write_all(&serde_json::to_vec(&state).unwrap_or_default())
@@ -737,14 +1228,7 @@ impl Backend {
         })
         .map_err(|_| BlockchainError::FailedToDecodeStateDump)?;
 
-        if !self.db.write().await.load_state(state)? {
-            Err(RpcError::invalid_params(
-                "Loading state not supported with the current configuration",
-            )
-            .into())
-        } else {
-            Ok(true)
-        }
+        self.load_serialized_state(state).await
     }
 
     /// Returns the environment for the next block
@@ -754,6 +1238,8 @@ impl Backend {
         env.block.number = env.block.number.saturating_add(rU256::from(1));
         env.block.basefee = self.base_fee();
         env.block.timestamp = rU256::from(self.time.current_call_timestamp());
+        env.block.blob_excess_gas_and_price =
+            Some(BlobExcessGasAndPrice::new(self.next_excess_blob_gas()));
         env
     }
 
@@ -768,12 +1254,12 @@ impl Backend {
         let mut env = self.next_env();
         env.tx = tx.pending_transaction.to_revm_tx_env();
         let db = self.db.read().await;
-        let mut inspector = Inspector::default();
+        let mut inspector = self.inspector();
 
         let mut evm = revm::EVM::new();
         evm.env = env;
         evm.database(&*db);
-        let result_and_state = match evm.inspect_ref(&mut inspector) {
+        let result_and_state = match evm.inspect_ref(Delegation(&mut inspector)) {
             Ok(res) => res,
             Err(e) => return Err(e.into()),
         };
@@ -828,7 +1314,9 @@ impl Backend {
             cfg_env: env.cfg,
             parent_hash: storage.best_hash.to_ethers(),
             gas_used: U256::ZERO.to_ethers(),
-            enable_steps_tracing: self.enable_steps_tracing,
+            blob_gas_used: 0,
+            enable_steps_tracing: self.is_steps_tracing_enabled(),
+            max_call_depth: self.max_call_depth,
         };
 
         // create a new pending block
@@ -847,11 +1335,68 @@ impl Backend {
         self.do_mine_block(pool_transactions).await
     }
 
+    /// Pins the order of the given transactions in the next mined block, see
+    /// [Self::apply_next_block_order]
+    pub fn set_next_block_order(&self, hashes: Vec<TxHash>) {
+        *self.next_block_order.lock() = Some(hashes);
+    }
+
+    /// Moves the transactions of the pinned order to the front, in the pinned order, and clears
+    /// the pinned order
+    ///
+    /// Transactions of the same sender with a lower nonce are moved ahead of a pinned transaction.
+    fn apply_next_block_order(
+        &self,
+        mut pool_transactions: Vec<Arc<PoolTransaction>>,
+    ) -> Vec<Arc<PoolTransaction>> {
+        let Some(order) = self.next_block_order.lock().take() else { return pool_transactions };
+        let mut ordered = Vec::with_capacity(pool_transactions.len());
+        for hash in order {
+            let Some(idx) = pool_transactions.iter().position(|tx| tx.hash() == hash) else {
+                continue
+            };
+            let tx = pool_transactions.remove(idx);
+            let sender = *tx.pending_transaction.sender();
+            let nonce = *tx.pending_transaction.nonce();
+            let (mut predecessors, rest): (Vec<_>, Vec<_>) =
+                pool_transactions.into_iter().partition(|other| {
+                    *other.pending_transaction.sender() == sender &&
+                        *other.pending_transaction.nonce() < nonce
+                });
+            predecessors.sort_by_key(|other| *other.pending_transaction.nonce());
+            ordered.extend(predecessors);
+            ordered.push(tx);
+            pool_transactions = rest;
+        }
+        ordered.extend(pool_transactions);
+        ordered
+    }
+
+    /// Pins the prevrandao of all following blocks
+    ///
+    /// Before the merge, this is the difficulty of the blocks, which the `DIFFICULTY` opcode reads.
+    pub fn set_prevrandao(&self, prevrandao: B256) {
+        *self.prevrandao.write() = Some(prevrandao);
+        let is_merged = self.is_eip3675();
+        let mut env = self.env.write();
+        env.block.prevrandao = Some(prevrandao);
+        if !is_merged {
+            env.block.difficulty = rU256::from_be_bytes(prevrandao.0);
+        }
+    }
+
+    /// Sets the prevrandao of the next mined block only, see [Self::set_prevrandao]
+    pub fn set_next_block_prevrandao(&self, prevrandao: B256) {
+        *self.next_block_prevrandao.lock() = Some(prevrandao);
+    }
+
     async fn do_mine_block(
         &self,
         pool_transactions: Vec<Arc<PoolTransaction>>,
     ) -> MinedBlockOutcome {
+        let pool_transactions = self.apply_next_block_order(pool_transactions);
         trace!(target: "backend", "creating new block with {} transactions", pool_transactions.len());
+        let enable_steps_tracing = self.is_steps_tracing_enabled();
 
         let (outcome, header, block_hash) = {
             let current_base_fee = self.base_fee();
@@ -868,6 +1413,18 @@ impl Backend {
             env.block.number = env.block.number.saturating_add(rU256::from(1));
             env.block.basefee = current_base_fee;
             env.block.timestamp = rU256::from(self.time.next_timestamp());
+            env.block.blob_excess_gas_and_price =
+                Some(BlobExcessGasAndPrice::new(self.next_excess_blob_gas()));
+            let default_prevrandao = env.block.prevrandao;
+            if let Some(prevrandao) =
+                self.next_block_prevrandao.lock().take().or(*self.prevrandao.read())
+            {
+                env.block.prevrandao = Some(prevrandao);
+                // before the merge the `DIFFICULTY` opcode reads the difficulty instead
+                if !self.is_eip3675() {
+                    env.block.difficulty = rU256::from_be_bytes(prevrandao.0);
+                }
+            }
 
             let best_hash = self.blockchain.storage.read().best_hash;
 
@@ -887,7 +1444,9 @@ impl Backend {
                     cfg_env: env.cfg.clone(),
                     parent_hash: best_hash.to_ethers(),
                     gas_used: U256::ZERO.to_ethers(),
-                    enable_steps_tracing: self.enable_steps_tracing,
+                    blob_gas_used: 0,
+                    enable_steps_tracing,
+                    max_call_depth: self.max_call_depth,
                 };
                 let executed_tx = executor.execute();
 
@@ -940,11 +1499,13 @@ impl Backend {
                 }
                 node_info!("    Gas used: {}", receipt.gas_used());
                 if !info.exit.is_ok() {
-                    let r = decode_revert(
-                        info.out.as_deref().unwrap_or_default(),
-                        None,
-                        Some(info.exit),
-                    );
+                    let r = info.revert_reason.clone().unwrap_or_else(|| {
+                        decode_revert(
+                            info.out.as_deref().unwrap_or_default(),
+                            None,
+                            Some(info.exit),
+                        )
+                    });
                     node_info!("    Error: reverted with: {r}");
                 }
                 node_info!("");
@@ -955,7 +1516,24 @@ impl Backend {
                     block_hash: block_hash.to_alloy(),
                     block_number: block_number.to::<u64>(),
                 };
-                storage.transactions.insert(mined_tx.info.transaction_hash.to_alloy(), mined_tx);
+                let tx_hash = mined_tx.info.transaction_hash.to_alloy();
+                storage.transactions.insert(tx_hash, mined_tx);
+                if enable_steps_tracing {
+                    self.steps_traced_transactions.lock().push_back(tx_hash);
+                }
+            }
+
+            // drop the steps of the transactions that exceed the cache, their call traces are kept
+            {
+                let mut traced = self.steps_traced_transactions.lock();
+                while traced.len() > self.steps_tracing_cache_size {
+                    let Some(tx_hash) = traced.pop_front() else { break };
+                    if let Some(tx) = storage.transactions.get_mut(&tx_hash) {
+                        for node in tx.info.traces.iter_mut() {
+                            node.trace.steps = Vec::new();
+                        }
+                    }
+                }
             }
 
             // remove old transactions that exceed the transaction block keeper
@@ -970,6 +1548,8 @@ impl Backend {
 
             // we intentionally set the difficulty to `0` for newer blocks
             env.block.difficulty = rU256::from(0);
+            // the prevrandao of the next block only applies to this block
+            env.block.prevrandao = default_prevrandao;
 
             // update env with new values
             *self.env.write() = env;
@@ -1010,14 +1590,21 @@ impl Backend {
         fee_details: FeeDetails,
         block_request: Option<BlockRequest>,
         overrides: Option<StateOverride>,
+        block_overrides: Option<BlockOverrides>,
     ) -> Result<(InstructionResult, Option<Output>, u64, State), BlockchainError> {
         self.with_database_at(block_request, |state, block| {
-            let block_number = (block.number.to_ethers()).as_u64();
+            let env = self.build_call_env_with_overrides(
+                request,
+                fee_details,
+                block,
+                block_overrides.as_ref(),
+            );
+            let block_number = (env.block.number.to_ethers()).as_u64();
             let (exit, out, gas, state) = match overrides {
-                None => self.call_with_state(state, request, fee_details, block),
+                None => self.call_with_env(state, env),
                 Some(overrides) => {
                     let state = state::apply_state_override(overrides.into_iter().collect(), state)?;
-                    self.call_with_state(state, request, fee_details, block)
+                    self.call_with_env(state, env)
                 },
             }?;
             trace!(target: "backend", "call return {:?} out: {:?} gas {} on block {}", exit, out, gas, block_number);
@@ -1025,6 +1612,49 @@ impl Backend {
         }).await?
     }
 
+    /// Executes the `EthTransactionRequest` with the state overrides against the state of the
+    /// remote client at a block that predates the fork
+    ///
+    /// The required state is fetched into a temporary database that is discarded after the call.
+    pub async fn call_at_forked_block(
+        &self,
+        block_number: u64,
+        request: EthTransactionRequest,
+        fee_details: FeeDetails,
+        overrides: StateOverride,
+        block_overrides: Option<BlockOverrides>,
+    ) -> Result<(InstructionResult, Option<Output>, u64, State), BlockchainError> {
+        let fork = self.get_fork().ok_or(BlockchainError::DataUnavailable)?;
+        let block = fork
+            .block_by_number(block_number)
+            .await
+            .map_err(|_| BlockchainError::DataUnavailable)?
+            .ok_or(BlockchainError::BlockNotFound)?;
+        let block_env = BlockEnv {
+            number: rU256::from(block_number),
+            coinbase: block.header.miner,
+            timestamp: block.header.timestamp,
+            difficulty: block.header.difficulty,
+            prevrandao: Some(block.header.mix_hash.unwrap_or_default()),
+            basefee: block.header.base_fee_per_gas.unwrap_or_default(),
+            gas_limit: block.header.gas_limit,
+            ..Default::default()
+        };
+        let env = self.build_call_env_with_overrides(
+            request,
+            fee_details,
+            block_env,
+            block_overrides.as_ref(),
+        );
+        let state = state::apply_state_override(
+            overrides.into_iter().collect(),
+            fork.database_at(block_number),
+        )?;
+        let (exit, out, gas, state) = self.call_with_env(state, env)?;
+        trace!(target: "backend", "forked call return {:?} out: {:?} gas {} on block {}", exit, out, gas, block_number);
+        Ok((exit, out, gas, state))
+    }
+
     fn build_call_env(
         &self,
         request: EthTransactionRequest,
@@ -1078,6 +1708,46 @@ impl Backend {
         env
     }
 
+    /// Returns the [Env] of the call with the [BlockOverrides] applied to its block
+    ///
+    /// Like geth, the base fee check is skipped if the gas price of the call is below the
+    /// overridden base fee, so that the `BASEFEE` opcode can be overridden for calls without
+    /// fees.
+    fn build_call_env_with_overrides(
+        &self,
+        request: EthTransactionRequest,
+        fee_details: FeeDetails,
+        block_env: BlockEnv,
+        block_overrides: Option<&BlockOverrides>,
+    ) -> Env {
+        let mut env = self.build_call_env(request, fee_details, block_env);
+        let Some(overrides) = block_overrides else { return env };
+        let BlockOverrides { number, time, coinbase, prev_randao, base_fee, gas_limit } =
+            overrides.clone();
+        if let Some(number) = number {
+            env.block.number = rU256::from(number);
+        }
+        if let Some(time) = time {
+            env.block.timestamp = rU256::from(time);
+        }
+        if let Some(coinbase) = coinbase {
+            env.block.coinbase = coinbase;
+        }
+        if let Some(prev_randao) = prev_randao {
+            env.block.prevrandao = Some(prev_randao);
+        }
+        if let Some(base_fee) = base_fee {
+            env.block.basefee = base_fee;
+            if env.tx.gas_price < base_fee {
+                env.cfg.disable_base_fee = true;
+            }
+        }
+        if let Some(gas_limit) = gas_limit {
+            env.block.gas_limit = rU256::from(gas_limit);
+        }
+        env
+    }
+
     pub fn call_with_state<D>(
         &self,
         state: D,
@@ -1088,11 +1758,23 @@ impl Backend {
     where
         D: DatabaseRef<Error = DatabaseError>,
     {
-        let mut inspector = Inspector::default();
+        self.call_with_env(state, self.build_call_env(request, fee_details, block_env))
+    }
+
+    /// Executes the call of the [Env] against the given state, without committing it
+    fn call_with_env<D>(
+        &self,
+        state: D,
+        env: Env,
+    ) -> Result<(InstructionResult, Option<Output>, u64, State), BlockchainError>
+    where
+        D: DatabaseRef<Error = DatabaseError>,
+    {
+        let mut inspector = self.inspector();
         let mut evm = revm::EVM::new();
-        evm.env = self.build_call_env(request, fee_details, block_env);
+        evm.env = env;
         evm.database(state);
-        let result_and_state = match evm.inspect_ref(&mut inspector) {
+        let result_and_state = match evm.inspect_ref(Delegation(&mut inspector)) {
             Ok(result_and_state) => result_and_state,
             Err(e) => match e {
                 EVMError::Transaction(invalid_tx) => {
@@ -1131,35 +1813,323 @@ impl Backend {
         fee_details: FeeDetails,
         block_request: Option<BlockRequest>,
         opts: GethDefaultTracingOptions,
+        overrides: Option<StateOverride>,
     ) -> Result<DefaultFrame, BlockchainError> {
+        self.with_database_at(block_request, |state, block| match overrides {
+            None => self.trace_call_with_state(state, request, fee_details, block, opts),
+            Some(overrides) => {
+                // the overrides only live in this cache and are discarded after the call
+                let state = state::apply_state_override(overrides.into_iter().collect(), state)?;
+                self.trace_call_with_state(state, request, fee_details, block, opts)
+            }
+        })
+        .await?
+    }
+
+    /// Executes the call against the given state and returns the geth trace of it
+    pub fn trace_call_with_state<D>(
+        &self,
+        state: D,
+        request: EthTransactionRequest,
+        fee_details: FeeDetails,
+        block_env: BlockEnv,
+        opts: GethDefaultTracingOptions,
+    ) -> Result<DefaultFrame, BlockchainError>
+    where
+        D: DatabaseRef<Error = DatabaseError>,
+    {
+        let mut inspector = self.inspector().with_steps_tracing();
+        let block_number = block_env.number;
+        let mut evm = revm::EVM::new();
+        evm.env = self.build_call_env(request, fee_details, block_env);
+        evm.database(state);
+        let result_and_state = match evm.inspect_ref(Delegation(&mut inspector)) {
+            Ok(result_and_state) => result_and_state,
+            Err(e) => return Err(e.into()),
+        };
+        let (exit_reason, gas_used, out) = match result_and_state.result {
+            ExecutionResult::Success { reason, gas_used, output, .. } => {
+                (eval_to_instruction_result(reason), gas_used, Some(output))
+            }
+            ExecutionResult::Revert { gas_used, output } => {
+                (InstructionResult::Revert, gas_used, Some(Output::Call(output)))
+            }
+            ExecutionResult::Halt { reason, gas_used } => {
+                (halt_to_instruction_result(reason), gas_used, None)
+            }
+        };
+        let res = inspector
+            .tracer
+            .unwrap_or(TracingInspector::new(TracingInspectorConfig::all()))
+            .into_geth_builder()
+            .geth_traces(
+                gas_used,
+                match &out {
+                    Some(out) => out.data().clone(),
+                    None => Bytes::new(),
+                },
+                opts,
+            );
+        trace!(target: "backend", "trace call return {:?} out: {:?} gas {} on block {}", exit_reason, out, gas_used, block_number);
+        Ok(res)
+    }
+
+    /// Executes the call and returns the gas spent per opcode, the hottest opcodes first
+    pub async fn call_with_gas_profile(
+        &self,
+        request: EthTransactionRequest,
+        fee_details: FeeDetails,
+        block_request: Option<BlockRequest>,
+    ) -> Result<Vec<OpcodeGas>, BlockchainError> {
         self.with_database_at(block_request, |state, block| {
-            let mut inspector = Inspector::default().with_steps_tracing();
-            let block_number = block.number;
+            let mut inspector = self.inspector().with_gas_profiling();
             let mut evm = revm::EVM::new();
             evm.env = self.build_call_env(request, fee_details, block);
             evm.database(state);
-            let result_and_state =
-                match evm.inspect_ref(&mut inspector) {
-                    Ok(result_and_state) => result_and_state,
-                    Err(e) => return Err(e.into()),
-                };
-            let (exit_reason, gas_used, out, ) = match result_and_state.result {
-                ExecutionResult::Success { reason, gas_used, output, .. } => {
-                    (eval_to_instruction_result(reason), gas_used, Some(output), )
-                },
-                ExecutionResult::Revert { gas_used, output} => {
-                    (InstructionResult::Revert, gas_used, Some(Output::Call(output)))
-                },
-                ExecutionResult::Halt { reason, gas_used } => {
-                    (halt_to_instruction_result(reason), gas_used, None)
-                },
+            let result_and_state = match evm.inspect_ref(Delegation(&mut inspector)) {
+                Ok(result_and_state) => result_and_state,
+                Err(e) => return Err(e.into()),
             };
-            let res = inspector.tracer.unwrap_or(TracingInspector::new(TracingInspectorConfig::all())).into_geth_builder().geth_traces(gas_used, match &out {
-                Some(out) => out.data().clone(),
-                None => Bytes::new()
-            }, opts);
-            trace!(target: "backend", "trace call return {:?} out: {:?} gas {} on block {}", exit_reason, out, gas_used, block_number);
-            Ok(res)
+            trace!(target: "backend", "gas profile call result {:?}", result_and_state.result);
+            Ok(inspector.gas_report())
+        })
+        .await?
+    }
+
+    /// Executes the call with an [Inspector] that records its calls, events and `console.sol`
+    /// logs and returns the [Simulation] of it
+    ///
+    /// The changes of the call are discarded.
+    pub async fn simulate_call(
+        &self,
+        request: EthTransactionRequest,
+        fee_details: FeeDetails,
+        block_request: Option<BlockRequest>,
+        block_overrides: Option<BlockOverrides>,
+    ) -> Result<Simulation, BlockchainError> {
+        self.with_database_at(block_request, |state, block| {
+            let env = self.build_call_env_with_overrides(
+                request,
+                fee_details,
+                block,
+                block_overrides.as_ref(),
+            );
+            self.simulate_with_env(&mut CacheDB::new(state), env)
+        })
+        .await?
+    }
+
+    /// Executes the calls in order on top of the current state, each on top of the changes of the
+    /// ones before it, and returns the [Simulation] of every call
+    ///
+    /// If `stop_on_revert` is set, no calls are executed after the first failed one. All changes
+    /// are discarded.
+    pub async fn simulate_bundle(
+        &self,
+        calls: Vec<(EthTransactionRequest, FeeDetails)>,
+        overrides: BlockOverrides,
+        stop_on_revert: bool,
+    ) -> Result<Vec<Simulation>, BlockchainError> {
+        self.with_database_at(None, |state, block| {
+            let mut db = CacheDB::new(state);
+            let mut simulations = Vec::with_capacity(calls.len());
+            for (request, fee_details) in calls {
+                let env = self.build_call_env_with_overrides(
+                    request,
+                    fee_details,
+                    block.clone(),
+                    Some(&overrides),
+                );
+                let simulation = self.simulate_with_env(&mut db, env)?;
+                let failed = !simulation.success;
+                simulations.push(simulation);
+                if failed && stop_on_revert {
+                    break
+                }
+            }
+            Ok(simulations)
+        })
+        .await?
+    }
+
+    /// Executes the call of the [Env] on top of the given state and commits its changes to it,
+    /// see [Self::simulate_call]
+    fn simulate_with_env<D>(
+        &self,
+        db: &mut CacheDB<D>,
+        env: Env,
+    ) -> Result<Simulation, BlockchainError>
+    where
+        D: DatabaseRef<Error = DatabaseError>,
+    {
+        let config =
+            TracingInspectorConfig { record_logs: true, ..TracingInspectorConfig::default_geth() };
+        let mut inspector = self.inspector().with_tracing_config(config).with_events();
+        let mut evm = revm::EVM::new();
+        evm.env = env;
+        evm.database(db);
+        let result = evm.inspect_commit(Delegation(&mut inspector))?;
+        trace!(target: "backend", "simulated call result {:?}", result);
+
+        let success = result.is_success();
+        let gas_used = result.gas_used();
+        let return_data = match result {
+            ExecutionResult::Success { output, .. } => output.into_data(),
+            ExecutionResult::Revert { output, .. } => output,
+            ExecutionResult::Halt { .. } => Bytes::new(),
+        };
+        let trace = inspector
+            .tracer
+            .take()
+            .map(|tracer| {
+                let config = CallConfig { with_log: Some(true), ..Default::default() };
+                tracer.into_geth_builder().geth_call_traces(config, gas_used)
+            })
+            .unwrap_or_default();
+        let abis = self.abis.read();
+        Ok(Simulation {
+            success,
+            gas_used,
+            return_data,
+            revert_reason: inspector.revert_reason().map(str::to_string),
+            console_logs: inspector
+                .take_console_logs()
+                .into_iter()
+                .map(|log| log.message)
+                .collect(),
+            logs: inspector
+                .take_events()
+                .into_iter()
+                .map(|event| SimulatedLog {
+                    decoded: abis.decode_event(event.address, &event.topics, &event.data),
+                    address: event.address,
+                    topics: event.topics,
+                    data: event.data,
+                })
+                .collect(),
+            trace,
+        })
+    }
+
+    /// Re-executes the mined transaction on top of the transactions before it in its block, with
+    /// an [Inspector] that records the calls and the `console.sol` logs of the transaction
+    ///
+    /// Returns `None` if the transaction is unknown, transactions that predate the fork can't be
+    /// inspected.
+    pub async fn inspect_mined_transaction(
+        &self,
+        hash: B256,
+    ) -> Result<Option<TransactionInspection>, BlockchainError> {
+        let Some((block_number, index)) = self
+            .blockchain
+            .storage
+            .read()
+            .transactions
+            .get(&hash)
+            .map(|tx| (tx.block_number, tx.info.transaction_index as usize))
+        else {
+            return Ok(None)
+        };
+        let block = self.get_block(block_number).ok_or(BlockchainError::BlockNotFound)?;
+        let block_env = BlockEnv {
+            number: block.header.number.to_alloy(),
+            coinbase: block.header.beneficiary.to_alloy(),
+            timestamp: rU256::from(block.header.timestamp),
+            difficulty: block.header.difficulty.to_alloy(),
+            prevrandao: Some(block.header.mix_hash).map(|h| h.to_alloy()),
+            basefee: block.header.base_fee_per_gas.unwrap_or_default().to_alloy(),
+            gas_limit: block.header.gas_limit.to_alloy(),
+            ..Default::default()
+        };
+
+        // the transactions of the block up to and including the inspected one, with the senders
+        // they were executed with
+        let transactions = {
+            let storage = self.blockchain.storage.read();
+            block
+                .transactions
+                .into_iter()
+                .take(index + 1)
+                .map(|tx| {
+                    let mined = storage
+                        .transactions
+                        .get(&tx.hash().to_alloy())
+                        .ok_or(BlockchainError::DataUnavailable)?;
+                    Ok(PendingTransaction::with_impersonated(tx.transaction, mined.info.from))
+                })
+                .collect::<Result<Vec<_>, BlockchainError>>()?
+        };
+        let cfg = self.env.read().cfg.clone();
+        let env_for = |tx: &PendingTransaction| Env {
+            cfg: cfg.clone(),
+            block: block_env.clone(),
+            tx: tx.to_revm_tx_env(),
+        };
+
+        let parent = Some(BlockRequest::Number(block_number.saturating_sub(1)));
+        self.with_database_at(parent, |state, _| {
+            let mut db = CacheDB::new(state);
+            let (target, previous) =
+                transactions.split_last().ok_or(BlockchainError::DataUnavailable)?;
+            for tx in previous {
+                let mut evm = revm::EVM::new();
+                evm.env = env_for(tx);
+                let authorizations = apply_authorizations(&mut db, tx, cfg.chain_id)?;
+                if authorizations.sender_authorized {
+                    evm.env.tx.nonce = None;
+                }
+                evm.env.tx.gas_limit =
+                    evm.env.tx.gas_limit.saturating_sub(authorizations.intrinsic_gas);
+                evm.database(&mut db);
+                evm.inspect_commit(Delegation(self.inspector()))?;
+            }
+
+            let mut inspector =
+                self.inspector().with_tracing_config(TracingInspectorConfig::default_parity());
+            let mut evm = revm::EVM::new();
+            evm.env = env_for(target);
+            let authorizations = apply_authorizations(&mut db, target, cfg.chain_id)?;
+            if authorizations.sender_authorized {
+                evm.env.tx.nonce = None;
+            }
+            evm.env.tx.gas_limit =
+                evm.env.tx.gas_limit.saturating_sub(authorizations.intrinsic_gas);
+            evm.database(&mut db);
+            let result = evm.inspect(Delegation(&mut inspector))?.result;
+
+            let success = result.is_success();
+            let calls = inspector
+                .tracer
+                .as_ref()
+                .map(|tracer| {
+                    let nodes = tracer.get_traces().nodes();
+                    nodes
+                        .first()
+                        .map(|root| root.children.iter().map(|idx| &nodes[*idx].trace))
+                        .into_iter()
+                        .flatten()
+                        .map(|call| CallGas {
+                            from: call.caller,
+                            to: call.address,
+                            input: call.data.clone(),
+                            gas_used: call.gas_used,
+                            success: call.success,
+                        })
+                        .collect()
+                })
+                .unwrap_or_default();
+            Ok(Some(TransactionInspection {
+                transaction_hash: hash,
+                success,
+                gas_used: authorizations.gas_used(&result),
+                calls,
+                console_logs: inspector
+                    .take_console_logs()
+                    .into_iter()
+                    .map(|log| log.message)
+                    .collect(),
+                revert_reason: inspector.revert_reason().map(str::to_string),
+            }))
         })
         .await?
     }
@@ -1182,17 +2152,22 @@ impl Backend {
             from.create(nonce)
         };
 
-        let mut tracer = AccessListTracer::new(
-            EthersAccessList(request.access_list.clone().unwrap_or_default()),
-            from,
-            to,
-            self.precompiles(),
-        );
+        let mut excluded = self.precompiles();
+        excluded.extend([from, to]);
+        // the coinbase is warm since EIP-3651
+        if (self.spec_id() as u8) >= (SpecId::SHANGHAI as u8) {
+            excluded.push(block_env.coinbase);
+        }
+        let access_list = from_ethers_access_list(EthersAccessList(
+            request.access_list.clone().unwrap_or_default(),
+        ));
+        let mut inspector =
+            self.inspector().with_access_list(AccessListInspector::new(access_list, excluded));
 
         let mut evm = revm::EVM::new();
         evm.env = self.build_call_env(request, fee_details, block_env);
         evm.database(state);
-        let result_and_state = match evm.inspect_ref(&mut tracer) {
+        let result_and_state = match evm.inspect_ref(Delegation(&mut inspector)) {
             Ok(result_and_state) => result_and_state,
             Err(e) => return Err(e.into()),
         };
@@ -1207,8 +2182,7 @@ impl Backend {
                 (halt_to_instruction_result(reason), gas_used, None)
             }
         };
-        let access_list = tracer.access_list();
-        Ok((exit_reason, out, gas_used, from_ethers_access_list(access_list)))
+        Ok((exit_reason, out, gas_used, inspector.access_list()))
     }
 
     /// returns all receipts for the given transactions
@@ -1230,22 +2204,34 @@ impl Backend {
         &self,
         filter: Filter,
         hash: B256,
+        max_logs: Option<usize>,
     ) -> Result<Vec<Log>, BlockchainError> {
+        let mut all_logs = Vec::new();
         if let Some(block) = self.blockchain.get_block_by_hash(&hash) {
-            return Ok(self.mined_logs_for_block(filter, block));
+            self.mined_logs_for_block(&filter, block, &mut all_logs, max_logs)?;
+            return Ok(all_logs);
         }
 
         if let Some(fork) = self.get_fork() {
-            return fork.logs(&filter).await.map_err(|_| BlockchainError::DataUnavailable);
+            all_logs = fork.logs(&filter).await.map_err(|_| BlockchainError::DataUnavailable)?;
+            ensure_max_logs(&all_logs, max_logs)?;
         }
 
-        Ok(Vec::new())
+        Ok(all_logs)
     }
 
-    /// Returns all `Log`s mined by the node that were emitted in the `block` and match the `Filter`
-    fn mined_logs_for_block(&self, filter: Filter, block: Block) -> Vec<Log> {
+    /// Appends all `Log`s mined by the node that were emitted in the `block` and match the `Filter`
+    /// to `all_logs`
+    ///
+    /// Fails as soon as there are more than `max_logs` logs.
+    fn mined_logs_for_block(
+        &self,
+        filter: &Filter,
+        block: Block,
+        all_logs: &mut Vec<Log>,
+        max_logs: Option<usize>,
+    ) -> Result<(), BlockchainError> {
         let params = FilteredParams::new(Some(filter.clone()));
-        let mut all_logs = Vec::new();
         let block_hash = block.header.hash();
         let mut block_log_index = 0u32;
 
@@ -1296,12 +2282,13 @@ impl Backend {
                     log.transaction_index = Some(U256::from(transaction.transaction_index));
                     log.log_index = Some(U256::from(block_log_index));
                     all_logs.push(log);
+                    ensure_max_logs(all_logs, max_logs)?;
                 }
                 block_log_index += 1;
             }
         }
 
-        all_logs
+        Ok(())
     }
 
     /// Returns the logs that match the filter in the given range of blocks
@@ -1310,6 +2297,8 @@ impl Backend {
         filter: &Filter,
         mut from: u64,
         to: u64,
+        max_logs: Option<usize>,
+        max_block_range: Option<u64>,
     ) -> Result<Vec<Log>, BlockchainError> {
         let mut all_logs = Vec::new();
 
@@ -1323,10 +2312,18 @@ impl Backend {
             }
 
             if fork.predates_fork(from) {
+                // queries of locally mined blocks only are not limited
+                if let Some(max_block_range) = max_block_range {
+                    if to - from >= max_block_range {
+                        return Err(BlockchainError::LogsBlockRangeTooLarge(max_block_range))
+                    }
+                }
+
                 // this data is only available on the forked client
                 let filter = filter.clone().from_block(from).to_block(to_on_fork);
                 all_logs =
                     fork.logs(&filter).await.map_err(|_| BlockchainError::DataUnavailable)?;
+                ensure_max_logs(&all_logs, max_logs)?;
 
                 // update the range
                 from = fork.block_number() + 1;
@@ -1335,7 +2332,7 @@ impl Backend {
 
         for number in from..=to {
             if let Some(block) = self.get_block(number) {
-                all_logs.extend(self.mined_logs_for_block(filter.clone(), block));
+                self.mined_logs_for_block(filter, block, &mut all_logs, max_logs)?;
             }
         }
 
@@ -1343,10 +2340,17 @@ impl Backend {
     }
 
     /// Returns the logs according to the filter
+    ///
+    /// Fails if more logs than the configured `max_logs_returned` match the filter, or if the
+    /// range reaches into the forked chain and spans more than `max_logs_block_range` blocks.
     pub async fn logs(&self, filter: Filter) -> Result<Vec<Log>, BlockchainError> {
         trace!(target: "backend", "get logs [{:?}]", filter);
+        let (max_logs, max_block_range) = {
+            let config = self.node_config.read().await;
+            (config.max_logs_returned, config.max_logs_block_range)
+        };
         if let Some(hash) = filter.get_block_hash() {
-            self.logs_for_block(filter, hash).await
+            self.logs_for_block(filter, hash, max_logs).await
         } else {
             let best = self.best_number();
             let to_block =
@@ -1358,7 +2362,7 @@ impl Backend {
                 return Ok(vec![]);
             }
 
-            self.logs_for_range(&filter, from_block, to_block).await
+            self.logs_for_range(&filter, from_block, to_block, max_logs, max_block_range).await
         }
     }
 
@@ -1527,6 +2531,22 @@ impl Backend {
         Some(block.into_full_block(transactions))
     }
 
+    /// Takes a transaction of the pool and returns the eth api conform transaction format
+    pub fn convert_pending_transaction(&self, pending: PendingTransaction) -> Transaction {
+        let from = *pending.sender();
+        let mut tx = transaction_build(
+            Some(pending.hash().to_alloy()),
+            pending.transaction,
+            None,
+            None,
+            Some(self.base_fee()),
+        );
+        // we set the from field here explicitly to the set sender of the pending transaction,
+        // in case the transaction is impersonated.
+        tx.from = from.to_alloy();
+        tx
+    }
+
     /// Takes a block as it's stored internally and returns the eth api conform block format
     pub fn convert_block(&self, block: Block) -> AlloyBlock {
         let size = U256::from(rlp::encode(&block).len() as u32);
@@ -1551,6 +2571,8 @@ impl Backend {
             mix_hash,
             nonce,
             base_fee_per_gas,
+            blob_gas_used,
+            excess_blob_gas,
         } = header;
 
         AlloyBlock {
@@ -1574,8 +2596,8 @@ impl Backend {
                 nonce: Some(B64::from_slice(nonce.as_bytes())),
                 base_fee_per_gas: base_fee_per_gas.map(|f| f.to_alloy()),
                 withdrawals_root: None,
-                blob_gas_used: None,
-                excess_blob_gas: None,
+                blob_gas_used: blob_gas_used.map(|gas| U256::from(gas).to()),
+                excess_blob_gas: excess_blob_gas.map(|gas| U256::from(gas).to()),
                 parent_beacon_block_root: None,
             },
             size: Some(size),
@@ -1850,6 +2872,14 @@ impl Backend {
         self.blockchain.storage.read().transactions.get(&hash).map(|tx| tx.parity_traces())
     }
 
+    /// Returns the gas used by every call frame of the mined transaction
+    ///
+    /// Returns `None` if the transaction is unknown, the call traces of transactions that predate
+    /// the fork are not available.
+    pub fn mined_call_gas_summary(&self, hash: B256) -> Option<Vec<CallGasSummary>> {
+        self.blockchain.storage.read().transactions.get(&hash).map(|tx| tx.call_gas_summary())
+    }
+
     /// Returns the traces for the given transaction
     pub(crate) fn mined_transaction(&self, hash: B256) -> Option<MinedTransaction> {
         self.blockchain.storage.read().transactions.get(&hash).cloned()
@@ -1869,14 +2899,33 @@ impl Backend {
         Some(traces)
     }
 
+    /// Returns the traces of the mined transactions in the given blocks that match the filter
+    ///
+    /// Blocks that predate the fork are not included.
+    pub fn trace_filter(
+        &self,
+        blocks: RangeInclusive<u64>,
+        filter: &TraceFilter,
+    ) -> Vec<LocalizedTransactionTrace> {
+        let after = filter.after.unwrap_or_default() as usize;
+        let count = filter.count.map_or(usize::MAX, |count| count as usize);
+        blocks
+            .filter_map(|number| self.mined_parity_trace_block(number))
+            .flatten()
+            .filter(|trace| filter.matches(trace))
+            .skip(after)
+            .take(count)
+            .collect()
+    }
+
     /// Returns the traces for the given transaction
     pub async fn debug_trace_transaction(
         &self,
         hash: B256,
         opts: GethDebugTracingOptions,
     ) -> Result<GethTrace, BlockchainError> {
-        if let Some(traces) = self.mined_geth_trace_transaction(hash, opts.clone()) {
-            return Ok(GethTrace::Default(traces));
+        if let Some(trace) = self.mined_geth_trace_transaction(hash, opts.clone()) {
+            return trace
         }
 
         if let Some(fork) = self.get_fork() {
@@ -1890,17 +2939,83 @@ impl Backend {
         Ok(GethTrace::Default(Default::default()))
     }
 
+    /// Attaches the decoded calls and events to the frames of a `callTracer` trace, using the
+    /// registered ABIs and the configured
+    /// [SignatureDatabase](crate::eth::signatures::SignatureDatabase)
+    ///
+    /// The frames of unknown functions keep their raw input, other traces are returned as is.
+    pub async fn decode_geth_trace(&self, trace: GethTrace) -> GethTrace {
+        match trace {
+            GethTrace::CallTracer(frame) => {
+                let signatures = self.node_config.read().await.signatures.clone();
+                GethTrace::JS(signatures.decode_call_frame(&frame, &self.abis.read()))
+            }
+            trace => trace,
+        }
+    }
+
     fn mined_geth_trace_transaction(
         &self,
         hash: B256,
         opts: GethDebugTracingOptions,
-    ) -> Option<DefaultFrame> {
-        self.blockchain
-            .storage
-            .read()
-            .transactions
-            .get(&hash)
-            .map(|tx| tx.geth_trace(opts.config.to_reth()))
+    ) -> Option<Result<GethTrace, BlockchainError>> {
+        self.blockchain.storage.read().transactions.get(&hash).map(|tx| geth_trace(tx, opts))
+    }
+
+    /// Returns the geth traces of all transactions of the block, in order
+    ///
+    /// The traces of mined blocks are the ones recorded when the transactions were executed on top
+    /// of each other, blocks that predate the fork are traced by the remote client.
+    pub async fn debug_trace_block(
+        &self,
+        block: BlockId,
+        opts: GethDebugTracingOptions,
+    ) -> Result<Vec<TxTraceResult>, BlockchainError> {
+        if let Some(block) = self.get_block(block) {
+            let storage = self.blockchain.storage.read();
+            return block
+                .transactions
+                .iter()
+                .map(|tx| {
+                    let tx_hash = tx.hash().to_alloy();
+                    let tx = storage
+                        .transactions
+                        .get(&tx_hash)
+                        .ok_or(BlockchainError::DataUnavailable)?;
+                    Ok(TxTraceResult { tx_hash, result: geth_trace(tx, opts.clone())? })
+                })
+                .collect()
+        }
+
+        if let Some(fork) = self.get_fork() {
+            let block = match block {
+                BlockId::Hash(hash) => fork.block_by_hash(hash.block_hash).await,
+                BlockId::Number(number) => {
+                    let number = self.convert_block_number(Some(number));
+                    if !fork.predates_fork(number) {
+                        return Err(BlockchainError::BlockNotFound)
+                    }
+                    fork.block_by_number(number).await
+                }
+            }
+            .map_err(|_| BlockchainError::DataUnavailable)?
+            .ok_or(BlockchainError::BlockNotFound)?;
+
+            let BlockTransactions::Hashes(hashes) = block.transactions else {
+                return Err(BlockchainError::DataUnavailable)
+            };
+            let mut traces = Vec::with_capacity(hashes.len());
+            for tx_hash in hashes {
+                let result = fork
+                    .debug_trace_transaction(tx_hash, opts.clone())
+                    .await
+                    .map_err(|_| BlockchainError::DataUnavailable)?;
+                traces.push(TxTraceResult { tx_hash, result });
+            }
+            return Ok(traces)
+        }
+
+        Err(BlockchainError::BlockNotFound)
     }
 
     /// Returns the traces for the given block
@@ -1990,19 +3105,21 @@ impl Backend {
 
         let transaction_type = transaction.transaction.r#type();
 
-        let effective_gas_price = match transaction.transaction {
-            TypedTransaction::Legacy(t) => t.gas_price.to_alloy(),
-            TypedTransaction::EIP2930(t) => t.gas_price.to_alloy(),
-            TypedTransaction::EIP1559(t) => block
-                .header
-                .base_fee_per_gas
-                .map(|f| f.to_alloy())
-                .unwrap_or(self.base_fee())
-                .checked_add(t.max_priority_fee_per_gas.to_alloy())
-                .unwrap_or(U256::MAX),
-            TypedTransaction::Deposit(_) => U256::from(0),
+        let (blob_gas_used, blob_gas_price) = if transaction_type == Some(3) {
+            let blob_gas_price =
+                BlobExcessGasAndPrice::new(block.header.excess_blob_gas.unwrap_or_default())
+                    .blob_gasprice;
+            (
+                Some(U256::from(transaction.transaction.blob_gas()).to()),
+                Some(U256::from(blob_gas_price).to()),
+            )
+        } else {
+            (None, None)
         };
 
+        let effective_gas_price =
+            transaction.transaction.effective_gas_price(block.header.base_fee_per_gas).to_alloy();
+
         let deposit_nonce = transaction_type.and_then(|x| (x == 0x7E).then_some(info.nonce));
 
         let mut inner = TransactionReceipt {
@@ -2044,8 +3161,8 @@ impl Backend {
             logs_bloom: Bloom::from_slice(logs_bloom.as_bytes()),
             transaction_type: transaction_type.map(U8::from).unwrap_or_default(),
             effective_gas_price: effective_gas_price.to::<U128>(),
-            blob_gas_price: None,
-            blob_gas_used: None,
+            blob_gas_price,
+            blob_gas_used,
             other: Default::default(),
         };
 
@@ -2158,81 +3275,57 @@ impl Backend {
 
     /// Prove an account's existence or nonexistence in the state trie.
     ///
-    /// Returns a merkle proof of the account's trie node, `account_key` == keccak(address)
+    /// Returns the merkle proofs of the account and the storage `keys` of the state at the block,
+    /// which verify against the state root of the block's header. Proofs are unavailable in fork
+    /// mode, since the local state is incomplete.
     pub async fn prove_account_at(
         &self,
         address: Address,
         keys: Vec<B256>,
         block_request: Option<BlockRequest>,
     ) -> Result<AccountProof, BlockchainError> {
-        let account_key = B256::from(keccak256(address.to_ethers().as_bytes()));
+        if self.is_fork() {
+            // the state of a fork is incomplete, its proofs wouldn't match the state root
+            return Err(BlockchainError::DataUnavailable)
+        }
         let block_number = block_request.as_ref().map(|r| r.block_number());
 
         self.with_database_at(block_request, |block_db, _| {
             trace!(target: "backend", "get proof for {:?} at {:?}", address, block_number);
-            let (db, root) = block_db.maybe_as_hash_db().ok_or(BlockchainError::DataUnavailable)?;
-
-            let data: &dyn HashDB<_, _> = db.deref();
-            let mut recorder = Recorder::new();
-            let trie = RefTrieDB::new(&data, &root.0)
-                .map_err(|err| BlockchainError::TrieError(err.to_string()))?;
+            let accounts = block_db.maybe_as_full_db().ok_or(BlockchainError::DataUnavailable)?;
 
-            let maybe_account: Option<BasicAccount> = {
-                let acc_decoder = |bytes: &[u8]| {
-                    rlp::decode(bytes).unwrap_or_else(|_| {
-                        panic!("prove_account_at, could not query trie for account={:?}", &address)
-                    })
-                };
-                let query = (&mut recorder, acc_decoder);
-                trie.get_with(account_key.to_ethers().as_bytes(), query)
-                    .map_err(|err| BlockchainError::TrieError(err.to_string()))?
+            let empty = Default::default();
+            let (info, storage) = match accounts.get(&address) {
+                Some(account) if !matches!(account.account_state, AccountState::NotExisting) => {
+                    (account.info.clone(), &account.storage)
+                }
+                _ => (AccountInfo::default(), &empty),
             };
-            let account = maybe_account.unwrap_or_default();
-
-            let proof = recorder
-                .drain()
-                .into_iter()
-                .map(|r| r.data)
-                .map(|record| {
-                    // proof is rlp encoded:
-                    // <https://github.com/foundry-rs/foundry/issues/5004>
-                    // <https://www.quicknode.com/docs/ethereum/eth_getProof>
-                    rlp::encode(&record).to_vec().into()
-                })
-                .collect::<Vec<_>>();
-
-            let account_db =
-                block_db.maybe_account_db(address).ok_or(BlockchainError::DataUnavailable)?;
 
             let account_proof = AccountProof {
                 address: address.to_ethers(),
-                balance: account.balance,
-                nonce: account.nonce.as_u64().into(),
-                code_hash: account.code_hash,
-                storage_hash: account.storage_root,
-                account_proof: proof,
+                balance: info.balance.to_ethers(),
+                nonce: info.nonce.into(),
+                code_hash: info.code_hash.to_ethers(),
+                storage_hash: state::storage_root(storage).to_ethers(),
+                account_proof: state::prove_account(accounts, address)
+                    .into_iter()
+                    .map(|node| node.to_ethers())
+                    .collect(),
                 storage_proof: keys
                     .into_iter()
-                    .map(|storage_key| {
-                        // the key that should be proofed is the keccak256 of the storage key
-                        let key = B256::from(keccak256(storage_key));
-                        prove_storage(&account, &account_db.0, key).map(
-                            |(storage_proof, storage_value)| StorageProof {
-                                key: storage_key.to_ethers(),
-                                value: storage_value.to_ethers().into_uint(),
-                                proof: storage_proof
-                                    .into_iter()
-                                    .map(|proof| {
-                                        // proof is rlp encoded:
-                                        // <https://github.com/foundry-rs/foundry/issues/5004>
-                                        // <https://www.quicknode.com/docs/ethereum/eth_getProof>
-                                        rlp::encode(&proof).to_vec().into()
-                                    })
-                                    .collect(),
-                            },
-                        )
+                    .map(|key| {
+                        let slot = U256::from_be_bytes(key.0);
+                        StorageProof {
+                            key: key.to_ethers(),
+                            value: storage.get(&slot).copied().unwrap_or_default().to_ethers(),
+                            proof: state::prove_storage(storage, slot)
+                                .into_iter()
+                                .map(|node| node.to_ethers())
+                                .collect(),
+                        }
                     })
-                    .collect::<Result<Vec<_>, _>>()?,
+                    .collect(),
             };
 
             Ok(account_proof)
@@ -2250,12 +3343,23 @@ impl Backend {
 
     /// Notifies all `new_block_listeners` about the new block
     fn notify_on_new_block(&self, header: Header, hash: B256) {
+        self.notify_on_new_head(header, hash, true)
+    }
+
+    /// Notifies all `new_block_listeners` about the new head of the chain, which is either a new
+    /// block or the block the chain was rolled back to
+    fn notify_on_new_head(&self, header: Header, hash: B256, imported: bool) {
         // cleanup closed notification streams first, if the channel is closed we can remove the
         // sender half for the set
         self.new_block_listeners.lock().retain(|tx| !tx.is_closed());
 
-        let notification =
-            NewBlockNotification { hash: hash.to_ethers(), header: Arc::new(header) };
+        let reorged = Arc::new(std::mem::take(&mut *self.reorged_blocks.lock()));
+        let notification = NewBlockNotification {
+            hash: hash.to_ethers(),
+            header: Arc::new(header),
+            reorged,
+            imported,
+        };
 
         self.new_block_listeners
             .lock()
@@ -2263,6 +3367,67 @@ impl Backend {
     }
 }
 
+/// Returns an error if there are more than `max_logs` logs
+fn ensure_max_logs(logs: &[Log], max_logs: Option<usize>) -> Result<(), BlockchainError> {
+    match max_logs {
+        Some(max_logs) if logs.len() > max_logs => Err(BlockchainError::TooManyLogs(max_logs)),
+        _ => Ok(()),
+    }
+}
+
+/// Returns the geth trace of the mined transaction for the tracer of the options
+///
+/// Tracers other than the `callTracer`, the `prestateTracer` and the [STORAGE_DIFF_TRACER] return
+/// the default struct logs.
+fn geth_trace(
+    tx: &MinedTransaction,
+    opts: GethDebugTracingOptions,
+) -> Result<GethTrace, BlockchainError> {
+    match &opts.tracer {
+        Some(GethDebugTracerType::JsTracer(tracer)) if tracer == STORAGE_DIFF_TRACER => {
+            Ok(GethTrace::JS(serde_json::to_value(&tx.info.storage_diff).unwrap_or_default()))
+        }
+        Some(GethDebugTracerType::BuiltInTracer(GethDebugBuiltInTracerType::CallTracer)) => {
+            let config = opts
+                .tracer_config
+                .into_call_config()
+                .map_err(|err| RpcError::invalid_params(err.to_string()))?;
+            Ok(GethTrace::CallTracer(tx.geth_call_trace(config)))
+        }
+        Some(GethDebugTracerType::BuiltInTracer(GethDebugBuiltInTracerType::PreStateTracer)) => {
+            let config = opts
+                .tracer_config
+                .into_pre_state_config()
+                .map_err(|err| RpcError::invalid_params(err.to_string()))?;
+            if config.diff_mode.unwrap_or_default() {
+                return Err(RpcError::invalid_params(
+                    "diffMode is not supported by the prestateTracer",
+                )
+                .into())
+            }
+            Ok(GethTrace::PreStateTracer(PreStateFrame::Default(tx.geth_pre_state())))
+        }
+        _ => Ok(GethTrace::Default(tx.geth_trace(opts.config.to_reth()))),
+    }
+}
+
+/// Sets a flag while it is alive and clears the flag when dropped, which also happens if the future
+/// that holds it is dropped before it completes
+struct FlagGuard<'a>(&'a AtomicBool);
+
+impl<'a> FlagGuard<'a> {
+    fn set(flag: &'a AtomicBool) -> Self {
+        flag.store(true, Ordering::Release);
+        Self(flag)
+    }
+}
+
+impl Drop for FlagGuard<'_> {
+    fn drop(&mut self) {
+        self.0.store(false, Ordering::Release);
+    }
+}
+
 /// Get max nonce from transaction pool by address
 fn get_pool_transactions_nonce(
     pool_transactions: &[Arc<PoolTransaction>],
@@ -2332,10 +3497,11 @@ impl TransactionValidator for Backend {
 
         // Check gas limit, iff block gas limit is set.
         if !env.cfg.disable_block_gas_limit && tx.gas_limit() > env.block.gas_limit.to_ethers() {
-            warn!(target: "backend", "[{:?}] gas too high", tx.hash());
-            return Err(InvalidTransactionError::GasTooHigh(ErrDetail {
-                detail: String::from("tx.gas_limit > env.block.gas_limit"),
-            }));
+            warn!(target: "backend", "[{:?}] exceeds block gas limit", tx.hash());
+            return Err(InvalidTransactionError::ExceedsBlockGasLimit(
+                tx.gas_limit(),
+                env.block.gas_limit.to_ethers(),
+            ));
         }
 
         // check nonce
@@ -2364,6 +3530,74 @@ impl TransactionValidator for Backend {
             }
         }
 
+        // check the blobs of EIP4844 transactions, <https://eips.ethereum.org/EIPS/eip-4844>
+        if let TypedTransaction::EIP4844(blob_tx) = &tx.transaction {
+            if env.cfg.spec_id < SpecId::CANCUN {
+                return Err(InvalidTransactionError::BlobVersionedHashesNotSupported);
+            }
+            if blob_tx.kind.as_call().is_none() {
+                return Err(InvalidTransactionError::BlobCreateTransaction);
+            }
+            let hashes = &blob_tx.blob_versioned_hashes;
+            if hashes.is_empty() {
+                return Err(InvalidTransactionError::EmptyBlobs);
+            }
+            if hashes.len() > MAX_BLOB_NUMBER_PER_BLOCK as usize {
+                warn!(target: "backend", "[{:?}] too many blobs={}", tx.hash(), hashes.len());
+                return Err(InvalidTransactionError::TooManyBlobs);
+            }
+            if hashes.iter().any(|hash| hash[0] != VERSIONED_HASH_VERSION_KZG) {
+                return Err(InvalidTransactionError::BlobVersionNotSupported);
+            }
+            if let Some(blob_gas_and_price) = &env.block.blob_excess_gas_and_price {
+                let blob_gas_price = blob_gas_and_price.blob_gasprice;
+                if blob_tx.max_fee_per_blob_gas < blob_gas_price.into() {
+                    warn!(target: "backend", "max fee per blob gas={}, too low, blob gas price={}", blob_tx.max_fee_per_blob_gas, blob_gas_price);
+                    return Err(InvalidTransactionError::BlobGasPriceGreaterThanMax);
+                }
+            }
+        }
+
+        // check the authorizations of EIP7702 transactions, <https://eips.ethereum.org/EIPS/eip-7702>
+        if let TypedTransaction::EIP7702(set_code_tx) = &tx.transaction {
+            if !self.is_eip7702() {
+                return Err(InvalidTransactionError::SetCodeTransactionNotSupported);
+            }
+            if set_code_tx.kind.as_call().is_none() {
+                return Err(InvalidTransactionError::SetCodeCreateTransaction);
+            }
+            if set_code_tx.authorization_list.is_empty() {
+                return Err(InvalidTransactionError::EmptyAuthorizationList);
+            }
+            // every authorization is charged on top of the intrinsic gas of the call
+            let intrinsic_gas = MIN_TRANSACTION_GAS
+                .saturating_add(U256::from(authorization_intrinsic_gas(pending)));
+            if tx.gas_limit() < intrinsic_gas.to_ethers() {
+                warn!(target: "backend", "[{:?}] gas too low for the authorizations", tx.hash());
+                return Err(InvalidTransactionError::GasTooLow);
+            }
+        }
+
+        // check the effective gas price against the configured floor
+        let min_gas_price = self.fees.min_gas_price();
+        if !min_gas_price.is_zero() && !is_deposit_tx {
+            let essentials = tx.essentials();
+            let effective_gas_price =
+                match (essentials.max_fee_per_gas, essentials.max_priority_fee_per_gas) {
+                    (Some(max_fee_per_gas), Some(max_priority_fee_per_gas)) => max_fee_per_gas.min(
+                        env.block.basefee.to_ethers().saturating_add(max_priority_fee_per_gas),
+                    ),
+                    _ => tx.gas_price(),
+                };
+            if effective_gas_price < min_gas_price {
+                warn!(target: "backend", "[{:?}] effective gas price={}, below min gas price={}", tx.hash(), effective_gas_price, min_gas_price);
+                return Err(InvalidTransactionError::GasPriceBelowMinimum(
+                    effective_gas_price,
+                    min_gas_price,
+                ));
+            }
+        }
+
         let max_cost = tx.max_cost();
         let value = tx.value();
         // check sufficient funds: `gas * price + value`
@@ -2413,14 +3647,10 @@ pub fn transaction_build(
             // transaction is not mined yet, gas price is considered just `max_fee_per_gas`
             transaction.gas_price = transaction.max_fee_per_gas;
         } else {
-            // if transaction is already mined, gas price is considered base fee + priority fee: the
-            // effective gas price.
-            let base_fee = base_fee.unwrap_or(U256::ZERO);
-            let max_priority_fee_per_gas =
-                transaction.max_priority_fee_per_gas.map(|g| g.to::<U256>()).unwrap_or(U256::ZERO);
-            transaction.gas_price = Some(
-                base_fee.checked_add(max_priority_fee_per_gas).unwrap_or(U256::MAX).to::<U128>(),
-            );
+            // if transaction is already mined, gas price is considered the effective gas price
+            let effective_gas_price =
+                eth_transaction.effective_gas_price(base_fee.map(|fee| fee.to_ethers()));
+            transaction.gas_price = Some(effective_gas_price.to_alloy().saturating_to::<U128>());
         }
     } else {
         transaction.max_fee_per_gas = None;
@@ -2463,30 +3693,21 @@ pub fn transaction_build(
     transaction
 }
 
-/// Prove a storage key's existence or nonexistence in the account's storage
-/// trie.
-/// `storage_key` is the hash of the desired storage key, meaning
-/// this will only work correctly under a secure trie.
-/// `storage_key` == keccak(key)
-pub fn prove_storage(
-    acc: &BasicAccount,
-    data: &AsHashDB,
-    storage_key: B256,
-) -> Result<(Vec<Vec<u8>>, B256), BlockchainError> {
-    let data: &dyn HashDB<_, _> = data.deref();
-    let mut recorder = Recorder::new();
-    let trie = RefTrieDB::new(&data, &acc.storage_root.0)
-        .map_err(|err| BlockchainError::TrieError(err.to_string()))
-        .unwrap();
-
-    let item: U256 = {
-        let decode_value = |bytes: &[u8]| rlp::decode(bytes).expect("decoding db value failed");
-        let query = (&mut recorder, decode_value);
-        trie.get_with(storage_key.to_ethers().as_bytes(), query)
-            .map_err(|err| BlockchainError::TrieError(err.to_string()))?
-            .unwrap_or_else(|| U256::ZERO.to_ethers())
-            .to_alloy()
-    };
-
-    Ok((recorder.drain().into_iter().map(|r| r.data).collect(), B256::from(item)))
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn flag_is_cleared_when_future_is_dropped() {
+        let flag = AtomicBool::new(false);
+        let fut = async {
+            let _guard = FlagGuard::set(&flag);
+            futures::future::pending::<()>().await;
+        };
+        let mut fut = Box::pin(fut);
+        assert!(futures::poll!(&mut fut).is_pending());
+        assert!(flag.load(Ordering::Acquire));
+        drop(fut);
+        assert!(!flag.load(Ordering::Acquire));
+    }
 }
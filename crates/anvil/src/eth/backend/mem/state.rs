@@ -1,21 +1,19 @@
-//! Support for generating the state root for memdb storage
+//! Support for generating the state root and merkle proofs for memdb storage
 
-use crate::eth::{backend::db::AsHashDB, error::BlockchainError};
+use crate::eth::error::BlockchainError;
 use alloy_primitives::{Address, Bytes, B256, U256 as rU256};
 use alloy_rpc_types::state::StateOverride;
-use anvil_core::eth::trie::RefSecTrieDBMut;
+use anvil_core::eth::trie::{sec_trie_root, sec_trie_root_with_proof};
 use ethers::utils::{rlp, rlp::RlpStream};
-use foundry_common::types::ToEthers;
+use foundry_common::types::{ToAlloy, ToEthers};
 use foundry_evm::{
     backend::DatabaseError,
     hashbrown::HashMap as Map,
     revm::{
-        db::{CacheDB, DatabaseRef, DbAccount},
+        db::{AccountState, CacheDB, DatabaseRef, DbAccount},
         primitives::{AccountInfo, Bytecode, Log},
     },
 };
-use memory_db::HashKey;
-use trie_db::TrieMut;
 
 /// Returns the log hash for all `logs`
 ///
@@ -37,52 +35,29 @@ pub fn log_rlp_hash(logs: Vec<Log>) -> B256 {
     B256::from_slice(out.as_slice())
 }
 
-/// Returns storage trie of an account as `HashDB`
-pub fn storage_trie_db(storage: &Map<rU256, rU256>) -> (AsHashDB, B256) {
-    // Populate DB with full trie from entries.
-    let (db, root) = {
-        let mut db = <memory_db::MemoryDB<_, HashKey<_>, _>>::default();
-        let mut root = Default::default();
-        {
-            let mut trie = RefSecTrieDBMut::new(&mut db, &mut root);
-            for (k, v) in storage.iter().filter(|(_k, v)| *v != &rU256::from(0)) {
-                let mut temp: [u8; 32] = [0; 32];
-                (*k).to_ethers().to_big_endian(&mut temp);
-                let key = B256::from(temp).to_ethers();
-                let value = rlp::encode(&(*v).to_ethers());
-                trie.insert(key.as_bytes(), value.as_ref()).unwrap();
-            }
-        }
-        (db, root)
-    };
-
-    (Box::new(db), B256::from(root))
+/// Returns all RLP-encoded non-zero storage slots of an account, keyed by the big endian slot
+pub fn trie_storage(storage: &Map<rU256, rU256>) -> Vec<([u8; 32], Bytes)> {
+    storage
+        .iter()
+        .filter(|(_key, value)| **value != rU256::ZERO)
+        .map(|(key, value)| {
+            (key.to_be_bytes::<32>(), rlp::encode(&value.to_ethers()).freeze().into())
+        })
+        .collect()
 }
 
-/// Returns the account data as `HashDB`
-pub fn trie_hash_db(accounts: &Map<Address, DbAccount>) -> (AsHashDB, B256) {
-    let accounts = trie_accounts(accounts);
-
-    // Populate DB with full trie from entries.
-    let (db, root) = {
-        let mut db = <memory_db::MemoryDB<_, HashKey<_>, _>>::default();
-        let mut root = Default::default();
-        {
-            let mut trie = RefSecTrieDBMut::new(&mut db, &mut root);
-            for (address, value) in accounts {
-                trie.insert(address.as_ref(), value.as_ref()).unwrap();
-            }
-        }
-        (db, root)
-    };
-
-    (Box::new(db), B256::from(root))
+/// Returns the storage root of an account
+pub fn storage_root(storage: &Map<rU256, rU256>) -> B256 {
+    sec_trie_root(trie_storage(storage)).to_alloy()
 }
 
 /// Returns all RLP-encoded Accounts
+///
+/// Accounts that were only looked up but don't exist are not part of the state.
 pub fn trie_accounts(accounts: &Map<Address, DbAccount>) -> Vec<(Address, Bytes)> {
     accounts
         .iter()
+        .filter(|(_address, account)| !matches!(account.account_state, AccountState::NotExisting))
         .map(|(address, account)| {
             let storage_root = trie_account_rlp(&account.info, &account.storage);
             (*address, storage_root)
@@ -91,7 +66,19 @@ pub fn trie_accounts(accounts: &Map<Address, DbAccount>) -> Vec<(Address, Bytes)
 }
 
 pub fn state_merkle_trie_root(accounts: &Map<Address, DbAccount>) -> B256 {
-    trie_hash_db(accounts).1
+    sec_trie_root(trie_accounts(accounts)).to_alloy()
+}
+
+/// Returns the merkle proof of the account in the state trie of the `accounts`
+pub fn prove_account(accounts: &Map<Address, DbAccount>, address: Address) -> Vec<Bytes> {
+    let (_, proof) = sec_trie_root_with_proof(trie_accounts(accounts), address.as_slice());
+    proof.into_iter().map(Into::into).collect()
+}
+
+/// Returns the merkle proof of the slot in the storage trie of an account
+pub fn prove_storage(storage: &Map<rU256, rU256>, slot: rU256) -> Vec<Bytes> {
+    let (_, proof) = sec_trie_root_with_proof(trie_storage(storage), &slot.to_be_bytes::<32>());
+    proof.into_iter().map(Into::into).collect()
 }
 
 /// Returns the RLP for this account.
@@ -99,7 +86,7 @@ pub fn trie_account_rlp(info: &AccountInfo, storage: &Map<rU256, rU256>) -> Byte
     let mut stream = RlpStream::new_list(4);
     stream.append(&info.nonce);
     stream.append(&info.balance.to_ethers());
-    stream.append(&storage_trie_db(storage).1.to_ethers());
+    stream.append(&storage_root(storage).to_ethers());
     stream.append(&info.code_hash.as_slice());
     stream.out().freeze().into()
 }
@@ -1,27 +1,34 @@
 //! In-memory blockchain storage
 use crate::eth::{
     backend::{
-        db::{MaybeHashDatabase, StateDb},
-        mem::cache::DiskStateCache,
+        db::{MaybeHashDatabase, SerializableTransaction, StateDb},
+        mem::{cache::DiskStateCache, inspector::call_gas_summary},
+        notifications::ReorgedBlock,
     },
     pool::transactions::PoolTransaction,
 };
 use alloy_primitives::{Bytes, TxHash, B256, U256, U64};
 use alloy_rpc_types::{
     trace::{
-        geth::{DefaultFrame, GethDefaultTracingOptions},
+        geth::{
+            AccountState, CallConfig, CallFrame, DefaultFrame, GethDefaultTracingOptions,
+            PreStateMode,
+        },
         parity::LocalizedTransactionTrace,
     },
     BlockId, BlockNumberOrTag, TransactionInfo as RethTransactionInfo, TransactionReceipt,
 };
-use anvil_core::eth::{
-    block::{Block, PartialHeader},
-    receipt::TypedReceipt,
-    transaction::{MaybeImpersonatedTransaction, TransactionInfo},
+use anvil_core::{
+    eth::{
+        block::{Block, PartialHeader},
+        receipt::{EIP658Receipt, TypedReceipt},
+        transaction::{MaybeImpersonatedTransaction, TransactionInfo},
+    },
+    types::CallGasSummary,
 };
 use foundry_common::types::{ToAlloy, ToEthers};
 use foundry_evm::{
-    revm::primitives::Env,
+    revm::{interpreter::InstructionResult, primitives::Env},
     traces::{GethTraceBuilder, ParityTraceBuilder, TracingInspectorConfig},
 };
 use parking_lot::RwLock;
@@ -280,6 +287,45 @@ impl BlockchainStorage {
         }
     }
 
+    /// Sets the state root of the genesis block, which changes the hash of the genesis block
+    ///
+    /// This must only be called before any block was mined on top of the genesis block.
+    pub fn set_genesis_state_root(&mut self, state_root: B256) {
+        let Some(mut block) = self.blocks.remove(&self.genesis_hash) else { return };
+        block.header.state_root = state_root.to_ethers();
+        let genesis_hash = block.header.hash().to_alloy();
+        self.blocks.insert(genesis_hash, block);
+        self.hashes.insert(U64::ZERO, genesis_hash);
+        if self.best_hash == self.genesis_hash {
+            self.best_hash = genesis_hash;
+        }
+        self.genesis_hash = genesis_hash;
+    }
+
+    /// Removes all blocks after the given block, including their transactions, and makes the given
+    /// block the best block
+    ///
+    /// Returns the removed blocks, the newest first
+    pub fn unwind_to(&mut self, block_number: U64, block_hash: B256) -> Vec<ReorgedBlock> {
+        let mut removed = Vec::new();
+        for n in ((block_number.to::<u64>() + 1)..=self.best_number.to::<u64>()).rev() {
+            trace!(target: "backend", "reverting block {}", n);
+            let Some(hash) = self.hashes.remove(&U64::from(n)) else { continue };
+            if let Some(block) = self.blocks.remove(&hash) {
+                let receipts = block
+                    .transactions
+                    .iter()
+                    .map(|tx| self.transactions.remove(&tx.hash().to_alloy()).map(|tx| tx.receipt))
+                    .collect::<Option<Vec<_>>>()
+                    .unwrap_or_default();
+                removed.push(ReorgedBlock { block, receipts });
+            }
+        }
+        self.best_number = block_number;
+        self.best_hash = block_hash;
+        removed
+    }
+
     /// Removes all stored transactions for the given block number
     pub fn remove_block_transactions_by_number(&mut self, num: u64) {
         if let Some(hash) = self.hashes.get(&(U64::from(num))).copied() {
@@ -399,6 +445,49 @@ pub struct MinedTransaction {
 
 // === impl MinedTransaction ===
 
+impl From<MinedTransaction> for SerializableTransaction {
+    fn from(tx: MinedTransaction) -> Self {
+        let MinedTransaction { info, receipt, block_hash, block_number } = tx;
+        Self {
+            hash: info.transaction_hash.to_alloy(),
+            transaction_index: info.transaction_index,
+            from: info.from.to_alloy(),
+            to: info.to.map(ToAlloy::to_alloy),
+            contract_address: info.contract_address.map(ToAlloy::to_alloy),
+            success: info.exit.is_ok(),
+            out: info.out.map(ToAlloy::to_alloy),
+            nonce: info.nonce,
+            revert_reason: info.revert_reason,
+            receipt,
+            block_hash,
+            block_number,
+        }
+    }
+}
+
+impl From<SerializableTransaction> for MinedTransaction {
+    fn from(tx: SerializableTransaction) -> Self {
+        let EIP658Receipt { logs, logs_bloom, .. } = tx.receipt.clone().into();
+        let info = TransactionInfo {
+            transaction_hash: tx.hash.to_ethers(),
+            transaction_index: tx.transaction_index,
+            from: tx.from.to_ethers(),
+            to: tx.to.map(ToEthers::to_ethers),
+            contract_address: tx.contract_address.map(ToEthers::to_ethers),
+            logs,
+            logs_bloom,
+            traces: Vec::new(),
+            exit: if tx.success { InstructionResult::Stop } else { InstructionResult::Revert },
+            out: tx.out.map(ToEthers::to_ethers),
+            nonce: tx.nonce,
+            storage_diff: Default::default(),
+            pre_state: Default::default(),
+            revert_reason: tx.revert_reason,
+        };
+        Self { info, receipt: tx.receipt, block_hash: tx.block_hash, block_number: tx.block_number }
+    }
+}
+
 impl MinedTransaction {
     /// Returns the traces of the transaction for `trace_transaction`
     pub fn parity_traces(&self) -> Vec<LocalizedTransactionTrace> {
@@ -424,6 +513,40 @@ impl MinedTransaction {
                 opts,
             )
     }
+
+    /// Returns the state of the accounts the transaction accessed, before the transaction, for
+    /// the `prestateTracer`
+    pub fn geth_pre_state(&self) -> PreStateMode {
+        PreStateMode(
+            self.info
+                .pre_state
+                .iter()
+                .map(|(address, account)| {
+                    let state = AccountState {
+                        balance: Some(account.balance),
+                        nonce: (account.nonce > 0).then_some(account.nonce),
+                        code: (!account.code.is_empty()).then(|| account.code.clone()),
+                        storage: account
+                            .storage
+                            .iter()
+                            .map(|(slot, value)| (B256::from(*slot), B256::from(*value)))
+                            .collect(),
+                    };
+                    (*address, state)
+                })
+                .collect(),
+        )
+    }
+
+    pub fn geth_call_trace(&self, config: CallConfig) -> CallFrame {
+        GethTraceBuilder::new(self.info.traces.clone(), TracingInspectorConfig::default_geth())
+            .geth_call_traces(config, self.receipt.gas_used().as_u64())
+    }
+
+    /// Returns the gas used by every call frame of the transaction, for `anvil_traceGasSummary`
+    pub fn call_gas_summary(&self) -> Vec<CallGasSummary> {
+        call_gas_summary(&self.info.traces)
+    }
 }
 
 /// Intermediary Anvil representation of a receipt
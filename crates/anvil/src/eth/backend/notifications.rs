@@ -1,6 +1,9 @@
 //! Notifications emitted from the backed
 
-use anvil_core::eth::block::Header;
+use anvil_core::eth::{
+    block::{Block, Header},
+    receipt::TypedReceipt,
+};
 use ethers::types::H256;
 use futures::channel::mpsc::UnboundedReceiver;
 use std::sync::Arc;
@@ -12,6 +15,20 @@ pub struct NewBlockNotification {
     pub hash: H256,
     /// block header
     pub header: Arc<Header>,
+    /// The blocks that were removed from the chain by a reorg before this block was imported, the
+    /// newest first
+    pub reorged: Arc<Vec<ReorgedBlock>>,
+    /// Whether the block was just imported, `false` if the chain was rolled back to this block,
+    /// which was already announced when it was imported
+    pub imported: bool,
+}
+
+/// A block that was removed from the chain by a reorg
+#[derive(Clone, Debug)]
+pub struct ReorgedBlock {
+    pub block: Block,
+    /// The receipts of the block's transactions
+    pub receipts: Vec<TypedReceipt>,
 }
 
 /// Type alias for a receiver that receives [NewBlockNotification]
@@ -26,6 +26,8 @@ pub struct TimeManager {
     next_exact_timestamp: Arc<RwLock<Option<u64>>>,
     /// The interval to use when determining the next block's timestamp
     interval: Arc<RwLock<Option<u64>>>,
+    /// Whether the exact next timestamp may be lower than or equal to the last timestamp
+    allow_non_increasing: Arc<RwLock<bool>>,
 }
 
 // === impl TimeManager ===
@@ -37,6 +39,7 @@ impl TimeManager {
             offset: Default::default(),
             next_exact_timestamp: Default::default(),
             interval: Default::default(),
+            allow_non_increasing: Default::default(),
         };
         time_manager.reset(start_timestamp);
         time_manager
@@ -51,10 +54,32 @@ impl TimeManager {
         self.next_exact_timestamp.write().take();
     }
 
+    /// Sets the clock to the given timestamp, the next block uses it as its timestamp and the
+    /// following blocks advance from there.
+    ///
+    /// This replaces the offset of previous [TimeManager::increase_time] calls and any exact next
+    /// timestamp. Returns the change of the offset in seconds.
+    pub fn set_time(&self, timestamp: u64) -> i128 {
+        let current = duration_since_unix_epoch().as_secs() as i128;
+        let previous = self.offset();
+        let next = (timestamp as i128) - current;
+        trace!(target: "time", "set time {}, offset={}", timestamp, next);
+        // the next block must be greater than the last timestamp
+        *self.last_timestamp.write() = timestamp.saturating_sub(1);
+        *self.offset.write() = next;
+        self.next_exact_timestamp.write().take();
+        next - previous
+    }
+
     pub fn offset(&self) -> i128 {
         *self.offset.read()
     }
 
+    /// Returns the timestamp of the last block header
+    pub fn last_timestamp(&self) -> u64 {
+        *self.last_timestamp.read()
+    }
+
     /// Adds the given `offset` to the already tracked offset and returns the result
     fn add_offset(&self, offset: i128) -> i128 {
         let mut current = self.offset.write();
@@ -66,16 +91,28 @@ impl TimeManager {
 
     /// Jumps forward in time by the given seconds
     ///
-    /// This will apply a permanent offset to the natural UNIX Epoch timestamp
+    /// This will apply a permanent offset to the natural UNIX Epoch timestamp, which applies to
+    /// all following blocks until it's replaced by [TimeManager::reset]
     pub fn increase_time(&self, seconds: u64) -> i128 {
         self.add_offset(seconds as i128)
     }
 
+    /// Sets whether [TimeManager::set_next_block_timestamp] accepts timestamps that are lower
+    /// than or equal to the last timestamp, to deliberately produce invalid chains
+    pub fn set_allow_non_increasing_timestamps(&self, allow: bool) {
+        *self.allow_non_increasing.write() = allow;
+    }
+
     /// Sets the exact timestamp to use in the next block
-    /// Fails if it's before (or at the same time) the last timestamp
+    ///
+    /// This overrides any offset applied with [TimeManager::increase_time] for the next block,
+    /// following blocks are offset from this timestamp.
+    ///
+    /// Fails if it's before (or at the same time) the last timestamp, unless non increasing
+    /// timestamps are allowed
     pub fn set_next_block_timestamp(&self, timestamp: u64) -> Result<(), BlockchainError> {
         trace!(target: "time", "override next timestamp {}", timestamp);
-        if timestamp <= *self.last_timestamp.read() {
+        if timestamp <= *self.last_timestamp.read() && !*self.allow_non_increasing.read() {
             return Err(BlockchainError::TimestampError(format!(
                 "{timestamp} is lower than or equal to previous block's timestamp"
             )))
@@ -116,8 +153,10 @@ impl TimeManager {
             } else {
                 (current.saturating_add(self.offset()) as u64, false)
             };
-        // Ensures that the timestamp is always increasing
-        if next_timestamp <= last_timestamp {
+        // Ensures that the timestamp is always increasing, unless an exact timestamp was
+        // deliberately set to a non increasing one
+        let exact_non_increasing = update_offset && *self.allow_non_increasing.read();
+        if next_timestamp <= last_timestamp && !exact_non_increasing {
             next_timestamp = last_timestamp + 1;
         }
         let next_offset = update_offset.then_some((next_timestamp as i128) - current);
@@ -89,8 +89,18 @@ pub enum BlockchainError {
     EIP2930TransactionUnsupportedAtHardfork,
     #[error("op-stack deposit tx received but is not supported.\n\nYou can use it by running anvil with '--optimism'.")]
     DepositTransactionUnsupported,
+    #[error("Blob transaction received but is not supported by the current hardfork.\n\nYou can use it by running anvil with '--hardfork cancun' or later.")]
+    EIP4844TransactionUnsupportedAtHardfork,
+    #[error("Set code transaction received but is not supported by the current hardfork.\n\nSet code transactions require the Prague hardfork, which is not supported yet.")]
+    EIP7702TransactionUnsupportedAtHardfork,
     #[error("Excess blob gas not set.")]
     ExcessBlobGasNotSet,
+    #[error("query returned more than {0} results")]
+    TooManyLogs(usize),
+    #[error("block range of the query exceeds the maximum of {0} blocks")]
+    LogsBlockRangeTooLarge(u64),
+    #[error("filter not found")]
+    FilterNotFound,
 }
 
 impl From<RpcError> for BlockchainError {
@@ -120,11 +130,19 @@ where
 pub enum PoolError {
     #[error("Transaction with cyclic dependent transactions")]
     CyclicTransaction,
-    /// Thrown if a replacement transaction's gas price is below the already imported transaction
-    #[error("Tx: [{0:?}] insufficient gas price to replace existing transaction")]
-    ReplacementUnderpriced(Box<PoolTransaction>),
+    /// Thrown if a replacement transaction doesn't raise the gas price of the already imported
+    /// transaction by at least the configured percentage
+    #[error("Tx: [{0:?}] insufficient gas price to replace existing transaction, requires a bump of at least {1}%")]
+    ReplacementUnderpriced(Box<PoolTransaction>, u64),
     #[error("Tx: [{0:?}] already Imported")]
     AlreadyImported(Box<PoolTransaction>),
+    /// Thrown if the pool is full and the transaction pays less than every transaction that could
+    /// be evicted for it
+    #[error("Tx: [{0:?}] underpriced, the pool is full")]
+    Underpriced(Box<PoolTransaction>),
+    /// Thrown if the transaction alone exceeds the max size of the pool in bytes
+    #[error("Tx: [{0:?}] exceeds the max size of the pool of {1} bytes")]
+    Oversized(Box<PoolTransaction>, usize),
 }
 
 /// Errors that can occur with `eth_feeHistory`
@@ -132,6 +150,8 @@ pub enum PoolError {
 pub enum FeeHistoryError {
     #[error("Requested block range is out of bounds")]
     InvalidBlockRange,
+    #[error("Invalid reward percentiles, they must be increasing values between 0 and 100")]
+    InvalidRewardPercentiles,
 }
 
 #[derive(Debug)]
@@ -172,6 +192,10 @@ pub enum InvalidTransactionError {
     /// returned if the transaction gas exceeds the limit
     #[error("intrinsic gas too high -- {}",.0.detail)]
     GasTooHigh(ErrDetail),
+    /// returned if the gas limit of the transaction exceeds the gas limit of the block, so it can
+    /// never be included in a block
+    #[error("exceeds block gas limit: transaction gas limit {0}, block gas limit {1}")]
+    ExceedsBlockGasLimit(U256, U256),
     /// Thrown to ensure no one is able to specify a transaction with a tip higher than the total
     /// fee cap.
     #[error("max priority fee per gas higher than max fee per gas")]
@@ -179,6 +203,10 @@ pub enum InvalidTransactionError {
     /// Thrown post London if the transaction's fee is less than the base fee of the block
     #[error("max fee per gas less than block base fee")]
     FeeCapTooLow,
+    /// Thrown if the effective gas price of the transaction is below the minimum gas price set
+    /// via `anvil_setMinGasPrice`
+    #[error("transaction underpriced: effective gas price {0} is below the minimum gas price {1}")]
+    GasPriceBelowMinimum(U256, U256),
     /// Thrown during estimate if caller has insufficient funds to cover the tx.
     #[error("Out of gas: gas required exceeds allowance: {0:?}")]
     BasicOutOfGas(U256),
@@ -208,6 +236,27 @@ pub enum InvalidTransactionError {
     /// Thrown when `max_fee_per_blob_gas` is not supported for blocks before the Cancun hardfork.
     #[error("`max_fee_per_blob_gas` is not supported for blocks before the Cancun hardfork.")]
     MaxFeePerBlobGasNotSupported,
+    /// Thrown when a blob transaction doesn't carry any blobs
+    #[error("blob transactions must have at least one blob")]
+    EmptyBlobs,
+    /// Thrown when a blob transaction has more blobs than fit into a block
+    #[error("blob transaction has more blobs than fit into a block")]
+    TooManyBlobs,
+    /// Thrown when a versioned hash of a blob doesn't have the KZG version
+    #[error("blob versioned hash has an unsupported version")]
+    BlobVersionNotSupported,
+    /// Thrown when a blob transaction tries to create a contract
+    #[error("blob transactions can't create contracts")]
+    BlobCreateTransaction,
+    /// Thrown when a set code transaction is received before it is supported
+    #[error("set code transactions are not supported by the current hardfork")]
+    SetCodeTransactionNotSupported,
+    /// Thrown when a set code transaction doesn't carry any authorizations
+    #[error("set code transactions must have at least one authorization")]
+    EmptyAuthorizationList,
+    /// Thrown when a set code transaction tries to create a contract
+    #[error("set code transactions can't create contracts")]
+    SetCodeCreateTransaction,
 }
 
 impl From<revm::primitives::InvalidTransaction> for InvalidTransactionError {
@@ -256,8 +305,14 @@ impl From<revm::primitives::InvalidTransaction> for InvalidTransactionError {
             InvalidTransaction::MaxFeePerBlobGasNotSupported => {
                 InvalidTransactionError::MaxFeePerBlobGasNotSupported
             }
-            // TODO: Blob-related errors should be handled once the Reth migration is done and code
-            // is moved over.
+            InvalidTransaction::EmptyBlobs => InvalidTransactionError::EmptyBlobs,
+            InvalidTransaction::TooManyBlobs => InvalidTransactionError::TooManyBlobs,
+            InvalidTransaction::BlobVersionNotSupported => {
+                InvalidTransactionError::BlobVersionNotSupported
+            }
+            InvalidTransaction::BlobCreateTransaction => {
+                InvalidTransactionError::BlobCreateTransaction
+            }
             _ => todo!(),
         }
     }
@@ -301,12 +356,22 @@ impl<T: Serialize> ToRpcResponseResult for Result<T> {
                         PoolError::CyclicTransaction => {
                             RpcError::transaction_rejected("Cyclic transaction detected")
                         }
-                        PoolError::ReplacementUnderpriced(_) => {
-                            RpcError::transaction_rejected("replacement transaction underpriced")
+                        PoolError::ReplacementUnderpriced(_, min_fee_bump_percent) => {
+                            RpcError::transaction_rejected(format!(
+                                "replacement transaction underpriced, the gas price must be raised by at least {min_fee_bump_percent}%"
+                            ))
                         }
                         PoolError::AlreadyImported(_) => {
                             RpcError::transaction_rejected("transaction already imported")
                         }
+                        PoolError::Underpriced(_) => RpcError::transaction_rejected(
+                            "transaction underpriced, the txpool is full and the transaction pays less than any transaction that can be evicted",
+                        ),
+                        PoolError::Oversized(_, max_bytes) => {
+                            RpcError::transaction_rejected(format!(
+                                "transaction exceeds the max size of the txpool of {max_bytes} bytes"
+                            ))
+                        }
                     }
                 }
                 BlockchainError::NoSignerAvailable => {
@@ -337,7 +402,8 @@ impl<T: Serialize> ToRpcResponseResult for Result<T> {
                             data: None,
                         }
                     }
-                    InvalidTransactionError::GasTooHigh(_) => {
+                    InvalidTransactionError::GasTooHigh(_) |
+                    InvalidTransactionError::ExceedsBlockGasLimit(..) => {
                         // <https://eips.ethereum.org/EIPS/eip-1898>
                         RpcError {
                             code: ErrorCode::ServerError(-32000),
@@ -417,9 +483,27 @@ impl<T: Serialize> ToRpcResponseResult for Result<T> {
                 err @ BlockchainError::DepositTransactionUnsupported => {
                     RpcError::invalid_params(err.to_string())
                 }
+                err @ BlockchainError::EIP4844TransactionUnsupportedAtHardfork => {
+                    RpcError::invalid_params(err.to_string())
+                }
+                err @ BlockchainError::EIP7702TransactionUnsupportedAtHardfork => {
+                    RpcError::invalid_params(err.to_string())
+                }
                 err @ BlockchainError::ExcessBlobGasNotSet => {
                     RpcError::invalid_params(err.to_string())
                 }
+                err @ BlockchainError::FilterNotFound => RpcError {
+                    code: ErrorCode::ServerError(-32000),
+                    message: err.to_string().into(),
+                    data: None,
+                },
+                err @ BlockchainError::TooManyLogs(_) |
+                err @ BlockchainError::LogsBlockRangeTooLarge(_) => RpcError {
+                    // limit exceeded, <https://eips.ethereum.org/EIPS/eip-1474>
+                    code: ErrorCode::ServerError(-32005),
+                    message: err.to_string().into(),
+                    data: None,
+                },
             }
             .into(),
         }
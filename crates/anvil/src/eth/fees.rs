@@ -13,6 +13,7 @@ use std::{
     fmt,
     future::Future,
     pin::Pin,
+    str::FromStr,
     sync::Arc,
     task::{Context, Poll},
 };
@@ -32,15 +33,66 @@ pub const BASE_FEE_CHANGE_DENOMINATOR: u64 = 8;
 /// Elasticity multiplier as defined in [EIP-1559](https://eips.ethereum.org/EIPS/eip-1559)
 pub const EIP1559_ELASTICITY_MULTIPLIER: u64 = 2;
 
+/// The number of cached reward percentiles per percent, see [FeeHistoryCacheItem::rewards]
+pub const REWARD_PERCENTILE_RESOLUTION: f64 = 2.0;
+
+/// The number of recent non empty blocks [GasPriceOracle::Recent] samples
+pub const GAS_PRICE_ORACLE_BLOCKS: usize = 20;
+
+/// The reward percentile of a block [GasPriceOracle::Recent] samples
+pub const GAS_PRICE_ORACLE_PERCENTILE: f64 = 60.0;
+
 pub fn default_elasticity() -> f64 {
     1f64 / BASE_FEE_CHANGE_DENOMINATOR as f64
 }
 
+/// The parameters of the base fee calculation of [EIP-1559](https://eips.ethereum.org/EIPS/eip-1559)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BaseFeeParams {
+    /// The gas used by a block at which the base fee stays the same, defaults to half the block
+    /// gas limit
+    pub gas_target: Option<u64>,
+    /// Bounds the amount the base fee can change between blocks
+    pub max_change_denominator: u64,
+}
+
+impl BaseFeeParams {
+    /// Calculates the base fee of the block that follows a block with the given gas usage
+    pub fn next_block_base_fee(&self, gas_used: u64, gas_limit: u64, base_fee: u64) -> u64 {
+        let gas_target =
+            self.gas_target.unwrap_or(gas_limit / EIP1559_ELASTICITY_MULTIPLIER).max(1) as u128;
+        let denominator = self.max_change_denominator.max(1) as u128;
+        let gas_used = gas_used as u128;
+
+        if gas_used == gas_target {
+            return base_fee
+        }
+        if gas_used > gas_target {
+            let gas_used_delta = gas_used - gas_target;
+            let base_fee_delta =
+                std::cmp::max(1, base_fee as u128 * gas_used_delta / gas_target / denominator);
+            base_fee.saturating_add(base_fee_delta as u64)
+        } else {
+            let gas_used_delta = gas_target - gas_used;
+            let base_fee_per_gas_delta =
+                base_fee as u128 * gas_used_delta / gas_target / denominator;
+
+            base_fee.saturating_sub(base_fee_per_gas_delta as u64)
+        }
+    }
+}
+
+impl Default for BaseFeeParams {
+    fn default() -> Self {
+        Self { gas_target: None, max_change_denominator: BASE_FEE_CHANGE_DENOMINATOR }
+    }
+}
+
 /// Stores the fee related information
 #[derive(Clone, Debug)]
 pub struct FeeManager {
-    /// Hardfork identifier
-    spec_id: SpecId,
+    /// Hardfork identifier, this can be changed with `anvil_setHardfork`
+    spec_id: Arc<RwLock<SpecId>>,
     /// Tracks the base fee for the next block post London
     ///
     /// This value will be updated after a new block was mined
@@ -49,7 +101,12 @@ pub struct FeeManager {
     ///
     /// This will be constant value unless changed manually
     gas_price: Arc<RwLock<U256>>,
-    elasticity: Arc<RwLock<f64>>,
+    /// The parameters used to calculate the base fee of the next block
+    base_fee_params: Arc<RwLock<BaseFeeParams>>,
+    /// Transactions with a lower effective gas price are rejected
+    min_gas_price: Arc<RwLock<U256>>,
+    /// How `eth_gasPrice` is computed
+    gas_price_oracle: Arc<RwLock<GasPriceOracle>>,
 }
 
 // === impl FeeManager ===
@@ -57,29 +114,69 @@ pub struct FeeManager {
 impl FeeManager {
     pub fn new(spec_id: SpecId, base_fee: U256, gas_price: U256) -> Self {
         Self {
-            spec_id,
+            spec_id: Arc::new(RwLock::new(spec_id)),
             base_fee: Arc::new(RwLock::new(base_fee)),
             gas_price: Arc::new(RwLock::new(gas_price)),
-            elasticity: Arc::new(RwLock::new(default_elasticity())),
+            base_fee_params: Default::default(),
+            min_gas_price: Default::default(),
+            gas_price_oracle: Default::default(),
         }
     }
 
+    /// Returns how `eth_gasPrice` is computed
+    pub fn gas_price_oracle(&self) -> GasPriceOracle {
+        *self.gas_price_oracle.read()
+    }
+
+    /// Sets how `eth_gasPrice` is computed
+    pub fn set_gas_price_oracle(&self, oracle: GasPriceOracle) {
+        *self.gas_price_oracle.write() = oracle;
+    }
+
     pub fn elasticity(&self) -> f64 {
-        *self.elasticity.read()
+        1f64 / self.base_fee_params.read().max_change_denominator as f64
+    }
+
+    /// Returns the parameters used to calculate the base fee of the next block
+    pub fn base_fee_params(&self) -> BaseFeeParams {
+        *self.base_fee_params.read()
+    }
+
+    /// Sets the parameters used to calculate the base fee of the next block
+    pub fn set_base_fee_params(&self, params: BaseFeeParams) {
+        trace!(target: "backend::fees", ?params, "updated base fee params");
+        *self.base_fee_params.write() = params;
+    }
+
+    /// Sets the hardfork of all following blocks
+    pub fn set_spec_id(&self, spec_id: SpecId) {
+        *self.spec_id.write() = spec_id;
     }
 
     /// Returns true for post London
     pub fn is_eip1559(&self) -> bool {
-        (self.spec_id as u8) >= (SpecId::LONDON as u8)
+        (*self.spec_id.read() as u8) >= (SpecId::LONDON as u8)
     }
 
-    /// Calculates the current gas price
+    /// Calculates the current gas price, this is never below the minimum gas price
     pub fn gas_price(&self) -> U256 {
-        if self.is_eip1559() {
+        let gas_price = if self.is_eip1559() {
             self.base_fee().saturating_add(self.suggested_priority_fee())
         } else {
             *self.gas_price.read()
-        }
+        };
+        gas_price.max(self.min_gas_price())
+    }
+
+    /// Returns the minimum effective gas price of accepted transactions
+    pub fn min_gas_price(&self) -> U256 {
+        *self.min_gas_price.read()
+    }
+
+    /// Sets the minimum effective gas price of accepted transactions
+    pub fn set_min_gas_price(&self, price: U256) {
+        trace!(target: "backend::fees", "updated min gas price {:?}", price);
+        *self.min_gas_price.write() = price;
     }
 
     /// Suggested priority fee to add to the base fee
@@ -129,7 +226,7 @@ impl FeeManager {
         if self.base_fee() == U256::zero() {
             return 0
         }
-        calculate_next_block_base_fee(
+        self.base_fee_params().next_block_base_fee(
             gas_used.as_u64(),
             gas_limit.as_u64(),
             last_fee_per_gas.as_u64(),
@@ -139,28 +236,7 @@ impl FeeManager {
 
 /// Calculate base fee for next block. [EIP-1559](https://github.com/ethereum/EIPs/blob/master/EIPS/eip-1559.md) spec
 pub fn calculate_next_block_base_fee(gas_used: u64, gas_limit: u64, base_fee: u64) -> u64 {
-    let gas_target = gas_limit / EIP1559_ELASTICITY_MULTIPLIER;
-
-    if gas_used == gas_target {
-        return base_fee
-    }
-    if gas_used > gas_target {
-        let gas_used_delta = gas_used - gas_target;
-        let base_fee_delta = std::cmp::max(
-            1,
-            base_fee as u128 * gas_used_delta as u128 /
-                gas_target as u128 /
-                BASE_FEE_CHANGE_DENOMINATOR as u128,
-        );
-        base_fee + (base_fee_delta as u64)
-    } else {
-        let gas_used_delta = gas_target - gas_used;
-        let base_fee_per_gas_delta = base_fee as u128 * gas_used_delta as u128 /
-            gas_target as u128 /
-            BASE_FEE_CHANGE_DENOMINATOR as u128;
-
-        base_fee.saturating_sub(base_fee_per_gas_delta as u64)
-    }
+    BaseFeeParams::default().next_block_base_fee(gas_used, gas_limit, base_fee)
 }
 
 /// An async service that takes care of the `FeeHistory` cache
@@ -207,20 +283,12 @@ impl FeeHistoryService {
         elasticity: f64,
     ) -> (FeeHistoryCacheItem, Option<u64>) {
         // percentile list from 0.0 to 100.0 with a 0.5 resolution.
-        // this will create 200 percentile points
-        let reward_percentiles: Vec<f64> = {
-            let mut percentile: f64 = 0.0;
-            (0..=200)
-                .map(|_| {
-                    let val = percentile;
-                    percentile += 0.5;
-                    val
-                })
-                .collect()
-        };
+        // this will create 201 percentile points
+        let reward_percentiles: Vec<f64> =
+            (0..=200).map(|idx| idx as f64 / REWARD_PERCENTILE_RESOLUTION).collect();
 
         let mut block_number: Option<u64> = None;
-        let base_fee = self.fees.base_fee();
+        let mut base_fee = self.fees.base_fee();
         let mut item = FeeHistoryCacheItem {
             base_fee: base_fee.as_u64(),
             gas_used_ratio: 0f64,
@@ -232,6 +300,11 @@ impl FeeHistoryService {
 
         if let (Some(block), Some(receipts)) = (current_block, current_receipts) {
             block_number = Some(block.header.number.as_u64());
+            // the rewards are relative to the base fee the block was mined with
+            if let Some(block_base_fee) = block.header.base_fee_per_gas {
+                base_fee = block_base_fee;
+                item.base_fee = base_fee.as_u64();
+            }
 
             let gas_used = block.header.gas_used.as_u64() as f64;
             let gas_limit = block.header.gas_limit.as_u64() as f64;
@@ -257,6 +330,14 @@ impl FeeHistoryService {
                             .max_priority_fee_per_gas
                             .min(t.max_fee_per_gas.saturating_sub(base_fee))
                             .as_u64(),
+                        Some(TypedTransaction::EIP4844(t)) => t
+                            .max_priority_fee_per_gas
+                            .min(t.max_fee_per_gas.saturating_sub(base_fee))
+                            .as_u64(),
+                        Some(TypedTransaction::EIP7702(t)) => t
+                            .max_priority_fee_per_gas
+                            .min(t.max_fee_per_gas.saturating_sub(base_fee))
+                            .as_u64(),
                         Some(TypedTransaction::Deposit(_)) => 0,
                         None => 0,
                     };
@@ -268,19 +349,20 @@ impl FeeHistoryService {
             // sort by effective reward asc
             transactions.sort_by(|(_, a), (_, b)| a.cmp(b));
 
-            // calculate percentile rewards
+            // calculate percentile rewards, there's an entry for every percentile so that they can
+            // be looked up by index, empty blocks have a reward of zero
             item.rewards = reward_percentiles
                 .into_iter()
-                .filter_map(|p| {
+                .map(|p| {
                     let target_gas = (p * gas_used / 100f64) as u64;
                     let mut sum_gas = 0;
                     for (gas_used, effective_reward) in transactions.iter().cloned() {
                         sum_gas += gas_used;
                         if target_gas <= sum_gas {
-                            return Some(effective_reward)
+                            return effective_reward
                         }
                     }
-                    None
+                    transactions.last().map(|(_, reward)| *reward).unwrap_or_default()
                 })
                 .collect();
         } else {
@@ -329,14 +411,65 @@ impl Future for FeeHistoryService {
 
 pub type FeeHistoryCache = Arc<Mutex<BTreeMap<u64, FeeHistoryCacheItem>>>;
 
+/// Modes that determine how `eth_gasPrice` is computed
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum GasPriceOracle {
+    /// The configured gas price, or the base fee and the suggested priority fee post London
+    #[default]
+    Fixed,
+    /// The base fee and the median of the priority fees paid in recent blocks, see
+    /// [recent_priority_fee]
+    Recent,
+}
+
+impl FromStr for GasPriceOracle {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.to_lowercase();
+        let oracle = match s.as_str() {
+            "fixed" => GasPriceOracle::Fixed,
+            "recent" => GasPriceOracle::Recent,
+            _ => return Err(format!("Unknown GasPriceOracle: `{s}`")),
+        };
+        Ok(oracle)
+    }
+}
+
+/// Returns the median of the [GAS_PRICE_ORACLE_PERCENTILE] rewards of the last
+/// [GAS_PRICE_ORACLE_BLOCKS] non empty blocks, `None` if there are no such blocks
+pub fn recent_priority_fee(cache: &BTreeMap<u64, FeeHistoryCacheItem>) -> Option<u64> {
+    let mut rewards = cache
+        .values()
+        .rev()
+        .filter(|item| item.gas_used_ratio > 0.0)
+        .take(GAS_PRICE_ORACLE_BLOCKS)
+        .map(|item| item.reward(GAS_PRICE_ORACLE_PERCENTILE))
+        .collect::<Vec<_>>();
+    if rewards.is_empty() {
+        return None
+    }
+    rewards.sort_unstable();
+    Some(rewards[rewards.len() / 2])
+}
+
 /// A single item in the whole fee history cache
 #[derive(Clone, Debug)]
 pub struct FeeHistoryCacheItem {
     pub base_fee: u64,
     pub gas_used_ratio: f64,
+    /// The rewards of the percentiles from 0.0 to 100.0 in steps of 0.5
     pub rewards: Vec<u64>,
 }
 
+impl FeeHistoryCacheItem {
+    /// Returns the reward of the percentile, rounded to the cached resolution
+    pub fn reward(&self, percentile: f64) -> u64 {
+        let index = (percentile.clamp(0.0, 100.0) * REWARD_PERCENTILE_RESOLUTION).round();
+        self.rewards.get(index as usize).copied().unwrap_or_default()
+    }
+}
+
 #[derive(Clone, Default)]
 pub struct FeeDetails {
     pub gas_price: Option<U256>,
@@ -1,6 +1,9 @@
 /// A `info!` helper macro that emits to the target, the node logger listens for
+///
+/// The message is also recorded in the [node log buffer](crate::logging::node_logs), if enabled.
 macro_rules! node_info {
     ($($arg:tt)*) => {
+         $crate::logging::node_logs().record(tracing::Level::INFO, || format!($($arg)*));
          tracing::info!(target: $crate::logging::NODE_USER_LOG_TARGET, $($arg)*);
     };
 }
@@ -2,20 +2,26 @@
 
 use crate::eth::pool::{transactions::PoolTransaction, Pool};
 use alloy_primitives::TxHash;
+use anvil_core::types::MiningModeInfo;
 use futures::{
     channel::mpsc::Receiver,
     stream::{Fuse, Stream, StreamExt},
     task::AtomicWaker,
 };
 use parking_lot::{lock_api::RwLockWriteGuard, RawRwLock, RwLock};
+use rand::Rng;
 use std::{
     fmt,
+    future::Future,
     pin::Pin,
-    sync::Arc,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
     task::{Context, Poll},
     time::Duration,
 };
-use tokio::time::Interval;
+use tokio::time::Sleep;
 
 #[derive(Clone, Debug)]
 pub struct Miner {
@@ -51,6 +57,17 @@ impl Miner {
         matches!(*mode, MiningMode::FixedBlockTime(_))
     }
 
+    /// Returns the mode the miner currently operates in
+    pub fn mode_info(&self) -> MiningModeInfo {
+        match &*self.mode.read() {
+            MiningMode::None => MiningModeInfo::Manual,
+            MiningMode::Auto(_) => MiningModeInfo::Auto,
+            MiningMode::FixedBlockTime(miner) => {
+                MiningModeInfo::Interval { block_time: miner.interval().as_secs() }
+            }
+        }
+    }
+
     /// Sets the mining mode to operate in
     pub fn set_mining_mode(&self, mode: MiningMode) {
         let new_mode = format!("{mode:?}");
@@ -59,6 +76,16 @@ impl Miner {
         self.inner.wake();
     }
 
+    /// Records that a block was mined, either by this miner or on request
+    pub fn on_block_mined(&self) {
+        self.inner.blocks_mined.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Returns the number of blocks mined since the node started
+    pub fn blocks_mined(&self) -> u64 {
+        self.inner.blocks_mined.load(Ordering::Relaxed)
+    }
+
     /// polls the [Pool] and returns those transactions that should be put in a block according to
     /// the current mode.
     ///
@@ -77,6 +104,8 @@ impl Miner {
 #[derive(Debug)]
 pub struct MinerInner {
     waker: AtomicWaker,
+    /// the number of mined blocks
+    blocks_mined: AtomicU64,
 }
 
 // === impl MinerInner ===
@@ -94,7 +123,7 @@ impl MinerInner {
 
 impl Default for MinerInner {
     fn default() -> Self {
-        Self { waker: AtomicWaker::new() }
+        Self { waker: AtomicWaker::new(), blocks_mined: AtomicU64::new(0) }
     }
 }
 
@@ -127,6 +156,11 @@ impl MiningMode {
         MiningMode::FixedBlockTime(FixedBlockTimeMiner::new(duration))
     }
 
+    /// Mines a block every `duration`, varied by up to `jitter`
+    pub fn interval_with_jitter(duration: Duration, jitter: Duration) -> Self {
+        MiningMode::FixedBlockTime(FixedBlockTimeMiner::with_jitter(duration, jitter))
+    }
+
     /// polls the [Pool] and returns those transactions that should be put in a block, if any.
     pub fn poll(
         &mut self,
@@ -144,11 +178,18 @@ impl MiningMode {
 /// A miner that's supposed to create a new block every `interval`, mining all transactions that are
 /// ready at that time.
 ///
+/// If a jitter is configured, the delay until the next block is sampled uniformly from
+/// `[interval - jitter, interval + jitter]` every round.
+///
 /// The default blocktime is set to 6 seconds
 #[derive(Debug)]
 pub struct FixedBlockTimeMiner {
     /// The interval this fixed block time miner operates with
-    interval: Interval,
+    interval: Duration,
+    /// The maximum deviation from the interval
+    jitter: Duration,
+    /// Completes when the next block should be mined
+    delay: Pin<Box<Sleep>>,
 }
 
 // === impl FixedBlockTimeMiner ===
@@ -156,12 +197,39 @@ pub struct FixedBlockTimeMiner {
 impl FixedBlockTimeMiner {
     /// Creates a new instance with an interval of `duration`
     pub fn new(duration: Duration) -> Self {
-        let start = tokio::time::Instant::now() + duration;
-        Self { interval: tokio::time::interval_at(start, duration) }
+        let delay = Box::pin(tokio::time::sleep(duration));
+        Self { interval: duration, jitter: Duration::ZERO, delay }
+    }
+
+    /// Creates a new instance with an interval of `duration` that varies by up to `jitter`
+    pub fn with_jitter(duration: Duration, jitter: Duration) -> Self {
+        let mut miner = Self::new(duration);
+        miner.jitter = jitter;
+        let delay = miner.next_delay();
+        miner.delay.as_mut().reset(tokio::time::Instant::now() + delay);
+        miner
+    }
+
+    /// Returns the configured interval between blocks
+    pub fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    /// Returns the delay until the next block
+    fn next_delay(&self) -> Duration {
+        if self.jitter.is_zero() {
+            return self.interval
+        }
+        let min = self.interval.saturating_sub(self.jitter);
+        let max = self.interval.saturating_add(self.jitter);
+        rand::thread_rng().gen_range(min..=max)
     }
 
     fn poll(&mut self, pool: &Arc<Pool>, cx: &mut Context<'_>) -> Poll<Vec<Arc<PoolTransaction>>> {
-        if self.interval.poll_tick(cx).is_ready() {
+        if self.delay.as_mut().poll(cx).is_ready() {
+            // schedule the next block relative to this one so that delays don't accumulate
+            let deadline = self.delay.deadline() + self.next_delay();
+            self.delay.as_mut().reset(deadline);
             // drain the pool
             return Poll::Ready(pool.ready_transactions().collect())
         }
@@ -219,3 +287,22 @@ impl fmt::Debug for ReadyTransactionMiner {
             .finish_non_exhaustive()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn samples_delay_within_jitter() {
+        let interval = Duration::from_secs(12);
+        let jitter = Duration::from_secs(3);
+        let miner = FixedBlockTimeMiner::with_jitter(interval, jitter);
+        for _ in 0..100 {
+            let delay = miner.next_delay();
+            assert!(delay >= interval - jitter && delay <= interval + jitter, "{delay:?}");
+        }
+
+        let miner = FixedBlockTimeMiner::new(interval);
+        assert_eq!(miner.next_delay(), interval);
+    }
+}
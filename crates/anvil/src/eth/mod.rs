@@ -11,4 +11,5 @@ pub(crate) mod macros;
 pub mod miner;
 pub mod pool;
 pub mod sign;
+pub mod signatures;
 pub mod util;
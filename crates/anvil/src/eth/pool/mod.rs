@@ -24,25 +24,32 @@
 //! a `nonce > nonce on chain` will _require_ `(nonce -1, account)` first, before it is ready to be
 //! included in a block.
 //!
+//! The pool can be limited in the number of transactions and their total size. Once full, the
+//! transaction with the lowest priority that no other transaction in the pool depends on is
+//! evicted, so that nonce chains are only ever shortened from their end.
+//!
 //! This implementation is adapted from <https://github.com/paritytech/substrate/tree/master/client/transaction-pool>
 
 use crate::{
     eth::{
         error::PoolError,
         pool::transactions::{
-            PendingPoolTransaction, PendingTransactions, PoolTransaction, ReadyTransactions,
-            TransactionsIterator, TxMarker,
+            PendingPoolTransaction, PendingTransactions, PoolEntry, PoolTransaction,
+            ReadyTransactions, TransactionOrder, TransactionsIterator, TxMarker,
+            DEFAULT_MIN_FEE_BUMP_PERCENT,
         },
     },
     mem::storage::MinedBlockOutcome,
 };
-use alloy_primitives::{TxHash, U64};
-use alloy_rpc_types::TxpoolStatus;
-use anvil_core::eth::transaction::PendingTransaction;
-use foundry_common::types::ToAlloy;
+use alloy_primitives::{TxHash, U256, U64};
+use anvil_core::{eth::transaction::PendingTransaction, types::PoolStatus};
 use futures::channel::mpsc::{channel, Receiver, Sender};
 use parking_lot::{Mutex, RwLock};
-use std::{collections::VecDeque, fmt, sync::Arc};
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    fmt,
+    sync::Arc,
+};
 
 pub mod transactions;
 
@@ -53,6 +60,10 @@ pub struct Pool {
     inner: RwLock<PoolInner>,
     /// listeners for new ready transactions
     transaction_listener: Mutex<Vec<Sender<TxHash>>>,
+    /// listeners for new ready transactions that receive the entire transaction
+    full_transaction_listener: Mutex<Vec<Sender<Arc<PoolTransaction>>>>,
+    /// copies of the pool, taken alongside the state snapshot with the same id
+    snapshots: Mutex<HashMap<U256, PoolInner>>,
 }
 
 // == impl Pool ==
@@ -68,17 +79,35 @@ impl Pool {
         self.inner.read().pending_transactions.transactions().collect()
     }
 
+    /// Returns both the ready and the not yet ready transactions, taken from the same state of the
+    /// pool
+    pub fn ready_and_pending_transactions(
+        &self,
+    ) -> (Vec<Arc<PoolTransaction>>, Vec<Arc<PoolTransaction>>) {
+        let pool = self.inner.read();
+        (pool.ready_transactions().collect(), pool.pending_transactions.transactions().collect())
+    }
+
     /// Returns the _pending_ transaction for that `hash` if it exists in the mempool
     pub fn get_transaction(&self, hash: TxHash) -> Option<PendingTransaction> {
         self.inner.read().get_transaction(hash)
     }
 
-    /// Returns the number of tx that are ready and queued for further execution
-    pub fn txpool_status(&self) -> TxpoolStatus {
+    /// Returns the number of tx that are ready and queued for further execution, as well as the
+    /// size and limits of the pool
+    pub fn txpool_status(&self) -> PoolStatus {
+        let inner = self.inner.read();
         // Note: naming differs here compared to geth's `TxpoolStatus`
-        let pending = U64::from(self.ready_transactions().count());
-        let queued = U64::from(self.inner.read().pending_transactions.len());
-        TxpoolStatus { pending, queued }
+        let pending = U64::from(inner.ready_transactions().count());
+        let queued = U64::from(inner.pending_transactions.len());
+        let bytes = inner.entries().iter().map(|entry| entry.size).sum::<usize>();
+        PoolStatus {
+            pending,
+            queued,
+            bytes: U64::from(bytes),
+            max_count: inner.max_count.map(U64::from),
+            max_bytes: inner.max_bytes.map(U64::from),
+        }
     }
 
     /// Invoked when a set of transactions ([Self::ready_transactions()]) was executed.
@@ -132,6 +161,14 @@ impl Pool {
         rx
     }
 
+    /// Same as [Self::add_ready_listener] but the listener receives the entire transaction
+    pub fn add_full_ready_listener(&self) -> Receiver<Arc<PoolTransaction>> {
+        const TX_LISTENER_BUFFER_SIZE: usize = 2048;
+        let (tx, rx) = channel(TX_LISTENER_BUFFER_SIZE);
+        self.full_transaction_listener.lock().push(tx);
+        rx
+    }
+
     /// Returns true if this pool already contains the transaction
     pub fn contains(&self, tx_hash: &TxHash) -> bool {
         self.inner.read().contains(tx_hash)
@@ -142,50 +179,112 @@ impl Pool {
         self.inner.write().remove_invalid(tx_hashes)
     }
 
-    /// Removes a single transaction from the pool
+    /// Re-sorts all transactions in the pool according to the given [TransactionOrder]
+    pub fn set_order(&self, order: TransactionOrder) {
+        let mut pool = self.inner.write();
+        pool.ready_transactions.set_order(order);
+        pool.pending_transactions.set_order(order);
+    }
+
+    /// Stores a copy of all transactions that are currently in the pool under the given snapshot
+    /// id
+    pub fn snapshot(&self, id: U256) {
+        let snapshot = self.inner.read().clone();
+        self.snapshots.lock().insert(id, snapshot);
+    }
+
+    /// Replaces all transactions in the pool with the transactions of the snapshot, if it exists.
     ///
-    /// This is similar to `[Pool::remove_invalid()]` but for a single transaction.
+    /// Like state snapshots, this also removes all snapshots that were taken after the snapshot.
+    pub fn revert_snapshot(&self, id: U256) -> bool {
+        let snapshot = {
+            let mut snapshots = self.snapshots.lock();
+            let snapshot = snapshots.remove(&id);
+            snapshots.retain(|snapshot_id, _| *snapshot_id < id);
+            snapshot
+        };
+        let Some(mut snapshot) = snapshot else { return false };
+        trace!(target: "txpool", "reverting pool to snapshot {}", id);
+
+        let mut inner = self.inner.write();
+        // the fee bump and the limits are settings of the pool and not part of the snapshot
+        snapshot.min_fee_bump_percent = inner.min_fee_bump_percent;
+        snapshot.max_count = inner.max_count;
+        snapshot.max_bytes = inner.max_bytes;
+        *inner = snapshot;
+        true
+    }
+
+    /// Sets the percentage by which a replacement transaction must raise the gas price of the
+    /// transaction it replaces
+    pub fn set_min_fee_bump_percent(&self, min_fee_bump_percent: u64) {
+        self.inner.write().min_fee_bump_percent = min_fee_bump_percent;
+    }
+
+    /// Sets the max number of transactions and the max total size of the transactions in bytes,
+    /// `None` for no limit
     ///
-    /// **Note**: this will also drop any transaction that depend on the `tx`
+    /// Transactions that are already in the pool are evicted with the next added transaction.
+    pub fn set_limits(&self, max_count: Option<usize>, max_bytes: Option<usize>) {
+        let mut inner = self.inner.write();
+        inner.max_count = max_count;
+        inner.max_bytes = max_bytes;
+    }
+
+    /// Removes a single transaction from the pool
+    ///
+    /// The ready transactions that depended on the removed transaction are moved back to the
+    /// pending queue, where they wait for a replacement.
     pub fn drop_transaction(&self, tx: TxHash) -> Option<Arc<PoolTransaction>> {
         trace!(target: "txpool", "Dropping transaction: [{:?}]", tx);
-        let removed = {
-            let mut pool = self.inner.write();
-            pool.ready_transactions.remove_with_markers(vec![tx], None)
-        };
-        trace!(target: "txpool", "Dropped transactions: {:?}", removed);
-
-        let mut dropped = None;
-        if !removed.is_empty() {
-            dropped = removed.into_iter().find(|t| t.pending_transaction.hash().to_alloy() == tx);
-        }
+        let dropped = self.inner.write().drop_transaction(tx);
+        trace!(target: "txpool", "Dropped transaction: {:?}", dropped);
         dropped
     }
 
+    /// Removes all transactions from the pool
+    pub fn clear(&self) {
+        trace!(target: "txpool", "Dropping all transactions");
+        let mut inner = self.inner.write();
+        inner.ready_transactions = Default::default();
+        inner.pending_transactions = Default::default();
+    }
+
     /// notifies all listeners about the transaction
     fn notify_listener(&self, hash: TxHash) {
-        let mut listener = self.transaction_listener.lock();
-        // this is basically a retain but with mut reference
-        for n in (0..listener.len()).rev() {
-            let mut listener_tx = listener.swap_remove(n);
-            let retain = match listener_tx.try_send(hash) {
-                Ok(()) => true,
-                Err(e) => {
-                    if e.is_full() {
-                        warn!(
-                            target: "txpool",
-                            "[{:?}] Failed to send tx notification because channel is full",
-                            hash,
-                        );
-                        true
-                    } else {
-                        false
-                    }
+        notify_listeners(&self.transaction_listener, hash, hash);
+        if !self.full_transaction_listener.lock().is_empty() {
+            let tx = self.inner.read().ready_transactions.get(&hash);
+            if let Some(tx) = tx {
+                notify_listeners(&self.full_transaction_listener, hash, tx.transaction.transaction);
+            }
+        }
+    }
+}
+
+/// Sends the notification to all listeners and removes those that are closed
+fn notify_listeners<T: Clone>(listeners: &Mutex<Vec<Sender<T>>>, hash: TxHash, notification: T) {
+    let mut listener = listeners.lock();
+    // this is basically a retain but with mut reference
+    for n in (0..listener.len()).rev() {
+        let mut listener_tx = listener.swap_remove(n);
+        let retain = match listener_tx.try_send(notification.clone()) {
+            Ok(()) => true,
+            Err(e) => {
+                if e.is_full() {
+                    warn!(
+                        target: "txpool",
+                        "[{:?}] Failed to send tx notification because channel is full",
+                        hash,
+                    );
+                    true
+                } else {
+                    false
                 }
-            };
-            if retain {
-                listener.push(listener_tx)
             }
+        };
+        if retain {
+            listener.push(listener_tx)
         }
     }
 }
@@ -193,10 +292,28 @@ impl Pool {
 /// A Transaction Pool
 ///
 /// Contains all transactions that are ready to be executed
-#[derive(Debug, Default)]
+#[derive(Clone, Debug)]
 struct PoolInner {
     ready_transactions: ReadyTransactions,
     pending_transactions: PendingTransactions,
+    /// the percentage by which a replacement transaction must raise the gas price
+    min_fee_bump_percent: u64,
+    /// the max number of transactions in the pool
+    max_count: Option<usize>,
+    /// the max total size of the transactions in the pool in bytes
+    max_bytes: Option<usize>,
+}
+
+impl Default for PoolInner {
+    fn default() -> Self {
+        Self {
+            ready_transactions: Default::default(),
+            pending_transactions: Default::default(),
+            min_fee_bump_percent: DEFAULT_MIN_FEE_BUMP_PERCENT,
+            max_count: None,
+            max_bytes: None,
+        }
+    }
 }
 
 // == impl PoolInner ==
@@ -224,6 +341,19 @@ impl PoolInner {
         self.pending_transactions.contains(tx_hash) || self.ready_transactions.contains(tx_hash)
     }
 
+    /// Returns the [PoolEntry] of all transactions in the pool
+    fn entries(&self) -> Vec<PoolEntry> {
+        let mut entries = self.ready_transactions.entries();
+        entries.extend(self.pending_transactions.entries());
+        entries
+    }
+
+    /// Returns true if the given number of transactions or their total size exceed the limits
+    fn exceeds_limits(&self, count: usize, bytes: usize) -> bool {
+        self.max_count.map_or(false, |max_count| count > max_count) ||
+            self.max_bytes.map_or(false, |max_bytes| bytes > max_bytes)
+    }
+
     fn add_transaction(&mut self, tx: PoolTransaction) -> Result<AddedTransaction, PoolError> {
         if self.contains(&tx.hash()) {
             warn!(target: "txpool", "[{:?}] Already imported", tx.hash());
@@ -232,14 +362,88 @@ impl PoolInner {
 
         let tx = PendingPoolTransaction::new(tx, self.ready_transactions.provided_markers());
         trace!(target: "txpool", "[{:?}] {:?}", tx.transaction.hash(), tx);
+        self.ensure_capacity(&tx)?;
 
+        let hash = tx.transaction.hash();
         // If all markers are not satisfied import to future
-        if !tx.is_ready() {
-            let hash = tx.transaction.hash();
-            self.pending_transactions.add_transaction(tx)?;
-            return Ok(AddedTransaction::Pending { hash })
+        let added = if !tx.is_ready() {
+            self.pending_transactions.add_transaction(tx, self.min_fee_bump_percent)?;
+            AddedTransaction::Pending { hash }
+        } else {
+            self.add_ready_transaction(tx)?
+        };
+        self.evict(hash);
+        Ok(added)
+    }
+
+    /// Checks whether the transaction can be added to a full pool
+    ///
+    /// A transaction that replaces a transaction or that other transactions depend on is always
+    /// accepted, any other must pay at least as much as a transaction that can be evicted for it.
+    fn ensure_capacity(&self, tx: &PendingPoolTransaction) -> Result<(), PoolError> {
+        if self.max_count.is_none() && self.max_bytes.is_none() {
+            return Ok(())
+        }
+        if let Some(max_bytes) = self.max_bytes.filter(|max_bytes| tx.size > *max_bytes) {
+            warn!(target: "txpool", "[{:?}] exceeds the size of the pool", tx.transaction.hash());
+            return Err(PoolError::Oversized(Box::new(tx.transaction.as_ref().clone()), max_bytes))
+        }
+
+        let entries = self.entries();
+        let bytes = entries.iter().map(|entry| entry.size).sum::<usize>();
+        if !self.exceeds_limits(entries.len() + 1, bytes + tx.size) {
+            return Ok(())
+        }
+        let new = &tx.transaction;
+        let is_needed = entries.iter().any(|entry| {
+            entry.transaction.provides == new.provides ||
+                entry.transaction.requires.iter().any(|marker| new.provides.contains(marker))
+        });
+        if is_needed {
+            return Ok(())
+        }
+
+        // the transactions the new transaction depends on are no longer evictable once it's added
+        let cheapest = evictable(&entries)
+            .filter(|entry| {
+                !entry.transaction.provides.iter().any(|marker| new.requires.contains(marker))
+            })
+            .map(|entry| entry.transaction.priority)
+            .min();
+        match cheapest {
+            Some(priority) if priority <= new.priority => Ok(()),
+            _ => {
+                warn!(target: "txpool", "[{:?}] underpriced, the pool is full", new.hash());
+                Err(PoolError::Underpriced(Box::new(new.as_ref().clone())))
+            }
+        }
+    }
+
+    /// Evicts transactions until the pool is within its limits again
+    ///
+    /// The evicted transaction is the one with the lowest priority, the oldest for equal
+    /// priorities, among those that no other transaction depends on, except the `added`
+    /// transaction.
+    fn evict(&mut self, added: TxHash) {
+        if self.max_count.is_none() && self.max_bytes.is_none() {
+            return
+        }
+        loop {
+            let entries = self.entries();
+            let bytes = entries.iter().map(|entry| entry.size).sum::<usize>();
+            if !self.exceeds_limits(entries.len(), bytes) {
+                return
+            }
+            let Some(evicted) = evictable(&entries)
+                .filter(|entry| entry.transaction.hash() != added)
+                .min_by_key(|entry| (entry.transaction.priority, entry.added_at))
+                .map(|entry| entry.transaction.hash())
+            else {
+                return
+            };
+            debug!(target: "txpool", "[{:?}] evicting transaction, the pool is full", evicted);
+            self.remove_invalid(vec![evicted]);
         }
-        self.add_ready_transaction(tx)
     }
 
     /// Adds the transaction to the ready queue
@@ -264,7 +468,7 @@ impl PoolInner {
 
             let current_hash = current_tx.transaction.hash();
             // try to add the transaction to the ready pool
-            match self.ready_transactions.add_transaction(current_tx) {
+            match self.ready_transactions.add_transaction(current_tx, self.min_fee_bump_percent) {
                 Ok(replaced_transactions) => {
                     if !is_new_tx {
                         ready.promoted.push(current_hash);
@@ -328,6 +532,29 @@ impl PoolInner {
         PruneResult { pruned, failed, promoted }
     }
 
+    /// Removes the transaction from the pool and re-imports the ready transactions that depended
+    /// on it
+    fn drop_transaction(&mut self, hash: TxHash) -> Option<Arc<PoolTransaction>> {
+        if let Some(dropped) = self.pending_transactions.remove(vec![hash]).pop() {
+            return Some(dropped)
+        }
+
+        let mut removed = self.ready_transactions.remove_with_markers(vec![hash], None);
+        let idx = removed.iter().position(|tx| tx.hash() == hash)?;
+        let dropped = removed.swap_remove(idx);
+
+        // the markers of the dropped transaction are no longer provided, so its dependents are
+        // queued until the gap is filled
+        for tx in removed {
+            let tx_hash = tx.hash();
+            if let Err(err) = self.add_transaction(PoolTransaction::clone(&tx)) {
+                warn!(target: "txpool", "Failed to re-import tx [{:?}] : {:?}", tx_hash, err);
+            }
+        }
+
+        Some(dropped)
+    }
+
     /// Remove the given transactions from the pool
     pub fn remove_invalid(&mut self, tx_hashes: Vec<TxHash>) -> Vec<Arc<PoolTransaction>> {
         // early exit in case there is no invalid transactions.
@@ -345,6 +572,16 @@ impl PoolInner {
     }
 }
 
+/// Returns the entries that no other transaction in the pool depends on, so that they can be
+/// evicted without stranding the transactions that follow them
+fn evictable(entries: &[PoolEntry]) -> impl Iterator<Item = &PoolEntry> + '_ {
+    let required =
+        entries.iter().flat_map(|entry| &entry.transaction.requires).collect::<HashSet<_>>();
+    entries.iter().filter(move |entry| {
+        !entry.transaction.provides.iter().any(|marker| required.contains(marker))
+    })
+}
+
 /// Represents the outcome of a prune
 pub struct PruneResult {
     /// a list of added transactions that a pruned marker satisfied
@@ -1,6 +1,7 @@
 use crate::eth::{error::PoolError, util::hex_fmt_many};
 use alloy_primitives::{Address, TxHash, U256};
 use anvil_core::eth::transaction::{PendingTransaction, TypedTransaction};
+use ethers::utils::rlp;
 use foundry_common::types::ToAlloy;
 use parking_lot::RwLock;
 use std::{
@@ -23,6 +24,23 @@ pub fn to_marker(nonce: u64, from: Address) -> TxMarker {
     data.to_vec()
 }
 
+/// The default percentage by which a replacement transaction must raise the gas price of the
+/// transaction it replaces, same as geth's `--txpool.pricebump`
+pub const DEFAULT_MIN_FEE_BUMP_PERCENT: u64 = 10;
+
+/// Returns true if the gas price of the replacement transaction is not higher than the gas price of
+/// the replaced transaction by at least `min_fee_bump_percent` percent
+pub fn is_underpriced_replacement(
+    replacement_gas_price: U256,
+    replaced_gas_price: U256,
+    min_fee_bump_percent: u64,
+) -> bool {
+    let min_gas_price = replaced_gas_price
+        .saturating_mul(U256::from(100u64.saturating_add(min_fee_bump_percent))) /
+        U256::from(100u64);
+    replacement_gas_price <= replaced_gas_price || replacement_gas_price < min_gas_price
+}
+
 /// Modes that determine the transaction ordering of the mempool
 ///
 /// This type controls the transaction order via the priority metric of a transaction
@@ -96,6 +114,13 @@ impl PoolTransaction {
     pub fn gas_price(&self) -> U256 {
         self.pending_transaction.transaction.gas_price().to_alloy()
     }
+
+    /// Returns a copy of this transaction with the priority of the given [TransactionOrder]
+    fn with_order(&self, order: TransactionOrder) -> Arc<Self> {
+        let mut tx = self.clone();
+        tx.priority = order.priority(&tx.pending_transaction.transaction);
+        Arc::new(tx)
+    }
 }
 
 impl fmt::Debug for PoolTransaction {
@@ -110,6 +135,16 @@ impl fmt::Debug for PoolTransaction {
     }
 }
 
+/// A transaction in the pool, with the details that are used to enforce the limits of the pool
+#[derive(Clone, Debug)]
+pub struct PoolEntry {
+    pub transaction: Arc<PoolTransaction>,
+    /// timestamp when the tx was added to the pool
+    pub added_at: Instant,
+    /// the size of the encoded transaction in bytes
+    pub size: usize,
+}
+
 /// A waiting pool of transaction that are pending, but not yet ready to be included in a new block.
 ///
 /// Keeps a set of transactions that are waiting for other transactions
@@ -140,8 +175,22 @@ impl PendingTransactions {
         self.waiting_queue.values().map(|tx| tx.transaction.clone())
     }
 
+    /// Updates the priority of all waiting transactions to the given [TransactionOrder]
+    pub fn set_order(&mut self, order: TransactionOrder) {
+        for tx in self.waiting_queue.values_mut() {
+            tx.transaction = tx.transaction.with_order(order);
+        }
+    }
+
     /// Adds a transaction to Pending queue of transactions
-    pub fn add_transaction(&mut self, tx: PendingPoolTransaction) -> Result<(), PoolError> {
+    ///
+    /// A transaction that replaces a waiting transaction must raise its gas price by at least
+    /// `min_fee_bump_percent` percent.
+    pub fn add_transaction(
+        &mut self,
+        tx: PendingPoolTransaction,
+        min_fee_bump_percent: u64,
+    ) -> Result<(), PoolError> {
         assert!(!tx.is_ready(), "transaction must not be ready");
         assert!(
             !self.waiting_queue.contains_key(&tx.transaction.hash()),
@@ -154,11 +203,16 @@ impl PendingTransactions {
             .and_then(|hash| self.waiting_queue.get(hash))
         {
             // check if underpriced
-            if tx.transaction.gas_price() < replace.transaction.gas_price() {
+            if is_underpriced_replacement(
+                tx.transaction.gas_price(),
+                replace.transaction.gas_price(),
+                min_fee_bump_percent,
+            ) {
                 warn!(target: "txpool", "pending replacement transaction underpriced [{:?}]", tx.transaction.hash());
-                return Err(PoolError::ReplacementUnderpriced(Box::new(
-                    tx.transaction.as_ref().clone(),
-                )))
+                return Err(PoolError::ReplacementUnderpriced(
+                    Box::new(tx.transaction.as_ref().clone()),
+                    min_fee_bump_percent,
+                ))
             }
         }
 
@@ -180,6 +234,15 @@ impl PendingTransactions {
         self.waiting_queue.contains_key(hash)
     }
 
+    /// Returns the [PoolEntry] of all waiting transactions
+    pub fn entries(&self) -> impl Iterator<Item = PoolEntry> + '_ {
+        self.waiting_queue.values().map(|tx| PoolEntry {
+            transaction: tx.transaction.clone(),
+            added_at: tx.added_at,
+            size: tx.size,
+        })
+    }
+
     /// Returns the transaction for the hash if it's pending
     pub fn get(&self, hash: &TxHash) -> Option<&PendingPoolTransaction> {
         self.waiting_queue.get(hash)
@@ -248,6 +311,8 @@ pub struct PendingPoolTransaction {
     pub missing_markers: HashSet<TxMarker>,
     /// timestamp when the tx was added
     pub added_at: Instant,
+    /// the size of the encoded transaction in bytes
+    pub size: usize,
 }
 
 // == impl PendingTransaction ==
@@ -268,7 +333,8 @@ impl PendingPoolTransaction {
             .cloned()
             .collect();
 
-        Self { transaction: Arc::new(transaction), missing_markers, added_at: Instant::now() }
+        let size = rlp::encode(&transaction.pending_transaction.transaction).len();
+        Self { transaction: Arc::new(transaction), missing_markers, added_at: Instant::now(), size }
     }
 
     /// Removes the required marker
@@ -286,6 +352,7 @@ impl fmt::Debug for PendingPoolTransaction {
     fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(fmt, "PendingTransaction {{ ")?;
         write!(fmt, "added_at: {:?}, ", self.added_at)?;
+        write!(fmt, "size: {}, ", self.size)?;
         write!(fmt, "tx: {:?}, ", self.transaction)?;
         write!(fmt, "missing_markers: {{{}}}", hex_fmt_many(self.missing_markers.iter()))?;
         write!(fmt, "}}")
@@ -350,7 +417,7 @@ impl Iterator for TransactionsIterator {
 }
 
 /// transactions that are ready to be included in a block.
-#[derive(Clone, Debug, Default)]
+#[derive(Debug, Default)]
 pub struct ReadyTransactions {
     /// keeps track of transactions inserted in the pool
     ///
@@ -367,6 +434,18 @@ pub struct ReadyTransactions {
 
 // == impl ReadyTransactions ==
 
+impl Clone for ReadyTransactions {
+    /// Clones the ready transactions, the clone doesn't share the transactions with `self`
+    fn clone(&self) -> Self {
+        Self {
+            id: self.id,
+            provided_markers: self.provided_markers.clone(),
+            ready_tx: Arc::new(RwLock::new(self.ready_tx.read().clone())),
+            independent_transactions: self.independent_transactions.clone(),
+        }
+    }
+}
+
 impl ReadyTransactions {
     /// Returns an iterator over all transactions
     pub fn get_transactions(&self) -> TransactionsIterator {
@@ -388,10 +467,41 @@ impl ReadyTransactions {
         self.ready_tx.read().get(hash).cloned()
     }
 
+    /// Returns the [PoolEntry] of all ready transactions
+    pub fn entries(&self) -> Vec<PoolEntry> {
+        self.ready_tx
+            .read()
+            .values()
+            .map(|tx| PoolEntry {
+                transaction: tx.transaction.transaction.clone(),
+                added_at: tx.added_at,
+                size: tx.size,
+            })
+            .collect()
+    }
+
     pub fn provided_markers(&self) -> &HashMap<TxMarker, TxHash> {
         &self.provided_markers
     }
 
+    /// Updates the priority of all ready transactions to the given [TransactionOrder]
+    ///
+    /// This re-sorts the independent transactions, transactions with the same priority keep the
+    /// order in which they were added.
+    pub fn set_order(&mut self, order: TransactionOrder) {
+        let mut ready = self.ready_tx.write();
+        for tx in ready.values_mut() {
+            tx.transaction.transaction = tx.transaction.transaction.with_order(order);
+        }
+        let independent = self
+            .independent_transactions
+            .iter()
+            .filter_map(|tx| ready.get(&tx.transaction.hash()))
+            .map(|tx| tx.transaction.clone())
+            .collect();
+        self.independent_transactions = independent;
+    }
+
     fn next_id(&mut self) -> u64 {
         let id = self.id;
         self.id = self.id.wrapping_add(1);
@@ -407,6 +517,7 @@ impl ReadyTransactions {
     pub fn add_transaction(
         &mut self,
         tx: PendingPoolTransaction,
+        min_fee_bump_percent: u64,
     ) -> Result<Vec<Arc<PoolTransaction>>, PoolError> {
         assert!(tx.is_ready(), "transaction must be ready",);
         assert!(
@@ -414,7 +525,8 @@ impl ReadyTransactions {
             "transaction already included"
         );
 
-        let (replaced_tx, unlocks) = self.replaced_transactions(&tx.transaction)?;
+        let (replaced_tx, unlocks) =
+            self.replaced_transactions(&tx.transaction, min_fee_bump_percent)?;
 
         let id = self.next_id();
         let hash = tx.transaction.hash();
@@ -448,7 +560,16 @@ impl ReadyTransactions {
         }
 
         // insert to ready queue
-        ready.insert(hash, ReadyTransaction { transaction, unlocks, requires_offset });
+        ready.insert(
+            hash,
+            ReadyTransaction {
+                transaction,
+                unlocks,
+                requires_offset,
+                added_at: tx.added_at,
+                size: tx.size,
+            },
+        );
 
         Ok(replaced_tx)
     }
@@ -457,6 +578,7 @@ impl ReadyTransactions {
     fn replaced_transactions(
         &mut self,
         tx: &PoolTransaction,
+        min_fee_bump_percent: u64,
     ) -> Result<(Vec<Arc<PoolTransaction>>, Vec<TxHash>), PoolError> {
         // check if we are replacing transactions
         let remove_hashes: HashSet<_> =
@@ -479,11 +601,16 @@ impl ReadyTransactions {
                 // (addr + nonce) then we check for gas price
                 if to_remove.provides() == tx.provides {
                     // check if underpriced
-                    if tx.pending_transaction.transaction.gas_price().to_alloy() <=
-                        to_remove.gas_price()
-                    {
+                    if is_underpriced_replacement(
+                        tx.gas_price(),
+                        to_remove.gas_price(),
+                        min_fee_bump_percent,
+                    ) {
                         warn!(target: "txpool", "ready replacement transaction underpriced [{:?}]", tx.hash());
-                        return Err(PoolError::ReplacementUnderpriced(Box::new(tx.clone())))
+                        return Err(PoolError::ReplacementUnderpriced(
+                            Box::new(tx.clone()),
+                            min_fee_bump_percent,
+                        ))
                     } else {
                         trace!(target: "txpool", "replacing ready transaction [{:?}] with higher gas price [{:?}]", to_remove.transaction.transaction.hash(), tx.hash());
                     }
@@ -605,12 +732,12 @@ impl ReadyTransactions {
                     self.provided_markers.remove(mark);
                 }
 
-                // remove from unlocks
+                // remove from the unlocks of the transactions it depends on
                 for mark in &tx.transaction.transaction.requires {
-                    if let Some(hash) = self.provided_markers.get(mark) {
-                        if let Some(tx) = ready.get_mut(hash) {
-                            if let Some(idx) = tx.unlocks.iter().position(|i| i == hash) {
-                                tx.unlocks.swap_remove(idx);
+                    if let Some(provider) = self.provided_markers.get(mark) {
+                        if let Some(provider) = ready.get_mut(provider) {
+                            if let Some(idx) = provider.unlocks.iter().position(|i| *i == hash) {
+                                provider.unlocks.swap_remove(idx);
                             }
                         }
                     }
@@ -673,6 +800,10 @@ pub struct ReadyTransaction {
     pub unlocks: Vec<TxHash>,
     /// amount of required markers that are inherently provided
     pub requires_offset: usize,
+    /// timestamp when the tx was added to the pool
+    pub added_at: Instant,
+    /// the size of the encoded transaction in bytes
+    pub size: usize,
 }
 
 // === impl ReadyTransaction ==
@@ -697,4 +828,14 @@ mod tests {
         assert_eq!(to_marker(1, addr), to_marker(1, addr));
         assert_ne!(to_marker(2, addr), to_marker(1, addr));
     }
+
+    #[test]
+    fn requires_min_fee_bump() {
+        let price = U256::from(100u64);
+        assert!(is_underpriced_replacement(U256::from(109u64), price, 10));
+        assert!(!is_underpriced_replacement(U256::from(110u64), price, 10));
+        // the gas price must always be raised
+        assert!(is_underpriced_replacement(price, price, 0));
+        assert!(!is_underpriced_replacement(U256::from(101u64), price, 0));
+    }
 }
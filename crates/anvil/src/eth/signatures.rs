@@ -0,0 +1,289 @@
+//! Resolves the function selectors of calls to function signatures and the events of contracts
+//! with registered ABIs, for decoded traces
+
+use alloy_dyn_abi::{DynSolValue, EventExt, JsonAbiExt};
+use alloy_json_abi::{Event, Function, JsonAbi};
+use alloy_primitives::{Address, Log, Selector, B256};
+use alloy_rpc_types::trace::geth::CallFrame;
+use anvil_core::types::DecodedEvent;
+use foundry_common::{errors::FsPathError, fmt::format_token_raw};
+use serde::Serialize;
+use std::{collections::HashMap, path::Path, sync::Arc};
+
+/// The signatures of common functions that are always known
+const COMMON_SIGNATURES: &[&str] = &[
+    "name()",
+    "symbol()",
+    "decimals()",
+    "totalSupply()",
+    "balanceOf(address)",
+    "allowance(address,address)",
+    "approve(address,uint256)",
+    "transfer(address,uint256)",
+    "transferFrom(address,address,uint256)",
+    "deposit()",
+    "withdraw(uint256)",
+    "ownerOf(uint256)",
+    "getApproved(uint256)",
+    "isApprovedForAll(address,address)",
+    "setApprovalForAll(address,bool)",
+    "safeTransferFrom(address,address,uint256)",
+    "safeTransferFrom(address,address,uint256,bytes)",
+    "balanceOfBatch(address[],uint256[])",
+    "safeTransferFrom(address,address,uint256,uint256,bytes)",
+    "safeBatchTransferFrom(address,address,uint256[],uint256[],bytes)",
+    "supportsInterface(bytes4)",
+    "owner()",
+    "transferOwnership(address)",
+    "renounceOwnership()",
+    "multicall(bytes[])",
+    "aggregate((address,bytes)[])",
+];
+
+/// A database of function signatures, keyed by their selector.
+///
+/// This type is cheap to clone.
+#[derive(Clone, Debug)]
+pub struct SignatureDatabase {
+    functions: Arc<HashMap<Selector, Function>>,
+}
+
+/// A call whose function could be resolved
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct DecodedCall {
+    /// The signature of the function, like `transfer(address,uint256)`
+    pub signature: String,
+    /// The arguments of the call, formatted so that they can be parsed again
+    pub params: Vec<String>,
+    /// The names of the parameters, empty for unnamed parameters
+    pub names: Vec<String>,
+}
+
+/// The ABIs of contracts, registered via `anvil_registerAbi`
+///
+/// The ABI of a contract takes precedence over the fallback ABI, which applies to all contracts.
+#[derive(Clone, Debug, Default)]
+pub struct AbiRegistry {
+    contracts: HashMap<Address, JsonAbi>,
+    fallback: Option<JsonAbi>,
+}
+
+// === impl SignatureDatabase ===
+
+impl SignatureDatabase {
+    /// Creates a database of the common signatures and the given additional signatures
+    ///
+    /// Signatures that can't be parsed are skipped.
+    pub fn new<S: AsRef<str>>(signatures: impl IntoIterator<Item = S>) -> Self {
+        let mut functions = HashMap::new();
+        let common = COMMON_SIGNATURES.iter().map(|sig| sig.to_string());
+        for signature in common.chain(signatures.into_iter().map(|sig| sig.as_ref().to_string())) {
+            match Function::parse(&signature) {
+                Ok(function) => {
+                    functions.insert(function.selector(), function);
+                }
+                Err(err) => {
+                    warn!(target: "node", %signature, ?err, "skipping invalid function signature")
+                }
+            }
+        }
+        Self { functions: Arc::new(functions) }
+    }
+
+    /// Loads the additional signatures from a JSON file with an array of function signatures,
+    /// like `["transfer(address,uint256)"]`
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, FsPathError> {
+        let signatures: Vec<String> = foundry_common::fs::read_json_file(path.as_ref())?;
+        Ok(Self::new(signatures))
+    }
+
+    /// This is used as the clap `value_parser` implementation
+    pub(crate) fn parse(path: &str) -> Result<Self, String> {
+        Self::load(path).map_err(|err| err.to_string())
+    }
+
+    /// Returns the function with the selector of the calldata, if it is known
+    pub fn function(&self, input: &[u8]) -> Option<&Function> {
+        let selector = Selector::try_from(input.get(..4)?).ok()?;
+        self.functions.get(&selector)
+    }
+
+    /// Decodes the calldata, returns `None` if the selector is unknown or the arguments don't
+    /// match the function
+    pub fn decode(&self, input: &[u8]) -> Option<DecodedCall> {
+        decode_function(self.function(input)?, input)
+    }
+
+    /// Returns the JSON of the call frame with the [DecodedCall] attached to all frames whose
+    /// input could be decoded, as `decoded`, and the [DecodedEvent] attached to all logs of
+    /// contracts with a registered ABI
+    ///
+    /// The ABIs of the [AbiRegistry] take precedence over the signatures of this database. The
+    /// input of the other frames and the other logs are left as is.
+    pub fn decode_call_frame(&self, frame: &CallFrame, abis: &AbiRegistry) -> serde_json::Value {
+        let mut value = serde_json::to_value(frame).unwrap_or_default();
+        if let Some(decoded) =
+            abis.decode_call(frame.to, &frame.input).or_else(|| self.decode(&frame.input))
+        {
+            value["decoded"] = serde_json::to_value(decoded).unwrap_or_default();
+        }
+        for (idx, log) in frame.logs.iter().enumerate() {
+            let (Some(address), Some(topics)) = (log.address, &log.topics) else { continue };
+            let data = log.data.as_deref().unwrap_or_default();
+            if let Some(decoded) = abis.decode_event(address, topics, data) {
+                if let Some(log) = value["logs"].get_mut(idx) {
+                    log["decoded"] = serde_json::to_value(decoded).unwrap_or_default();
+                }
+            }
+        }
+        if !frame.calls.is_empty() {
+            value["calls"] =
+                frame.calls.iter().map(|call| self.decode_call_frame(call, abis)).collect();
+        }
+        value
+    }
+}
+
+impl Default for SignatureDatabase {
+    fn default() -> Self {
+        Self::new(Vec::<String>::new())
+    }
+}
+
+// === impl AbiRegistry ===
+
+impl AbiRegistry {
+    /// Registers the ABI of the contract, or the fallback ABI if the address is `None`
+    ///
+    /// This replaces the ABI that was previously registered for the contract.
+    pub fn register(&mut self, address: Option<Address>, abi: JsonAbi) {
+        match address {
+            Some(address) => {
+                self.contracts.insert(address, abi);
+            }
+            None => self.fallback = Some(abi),
+        }
+    }
+
+    /// Returns the ABIs that apply to the contract, its own ABI first
+    fn abis(&self, address: Option<Address>) -> impl Iterator<Item = &JsonAbi> {
+        address
+            .and_then(|address| self.contracts.get(&address))
+            .into_iter()
+            .chain(self.fallback.as_ref())
+    }
+
+    /// Decodes the calldata of a call to the contract, returns `None` if no registered ABI has a
+    /// matching function
+    pub fn decode_call(&self, address: Option<Address>, input: &[u8]) -> Option<DecodedCall> {
+        let selector = Selector::try_from(input.get(..4)?).ok()?;
+        self.abis(address)
+            .flat_map(|abi| abi.functions())
+            .filter(|function| function.selector() == selector)
+            .find_map(|function| decode_function(function, input))
+    }
+
+    /// Decodes an event emitted by the contract, returns `None` if no registered ABI has a
+    /// matching event
+    ///
+    /// Anonymous events can't be identified and are never decoded.
+    pub fn decode_event(
+        &self,
+        address: Address,
+        topics: &[B256],
+        data: &[u8],
+    ) -> Option<DecodedEvent> {
+        let selector = *topics.first()?;
+        let log = Log::new_unchecked(topics.to_vec(), data.to_vec().into());
+        self.abis(Some(address))
+            .flat_map(|abi| abi.events())
+            .filter(|event| !event.anonymous && event.selector() == selector)
+            .find_map(|event| decode_event(event, &log))
+    }
+}
+
+/// Decodes the calldata with the function, `None` if the arguments don't match
+fn decode_function(function: &Function, input: &[u8]) -> Option<DecodedCall> {
+    let params = function.abi_decode_input(input.get(4..)?, false).ok()?;
+    Some(DecodedCall {
+        signature: function.signature(),
+        params: params.iter().map(format_token_raw).collect(),
+        names: function.inputs.iter().map(|input| input.name.clone()).collect(),
+    })
+}
+
+/// Decodes the log with the event, `None` if the topics or the data don't match
+fn decode_event(event: &Event, log: &Log) -> Option<DecodedEvent> {
+    let decoded = event.decode_log(log, false).ok()?;
+    // the indexed and the other params are decoded separately
+    let (mut indexed, mut body) = (decoded.indexed.iter(), decoded.body.iter());
+    let params = event
+        .inputs
+        .iter()
+        .map(|input| if input.indexed { indexed.next() } else { body.next() })
+        .collect::<Option<Vec<&DynSolValue>>>()?;
+    Some(DecodedEvent {
+        signature: event.signature(),
+        params: params.into_iter().map(format_token_raw).collect(),
+        names: event.inputs.iter().map(|input| input.name.clone()).collect(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::hex;
+
+    #[test]
+    fn decodes_known_selectors() {
+        let db = SignatureDatabase::new(["foo(uint256)"]);
+
+        // transfer(0x000000000000000000000000000000000000dEaD, 1)
+        let input = hex::decode("a9059cbb000000000000000000000000000000000000000000000000000000000000dead0000000000000000000000000000000000000000000000000000000000000001").unwrap();
+        let decoded = db.decode(&input).unwrap();
+        assert_eq!(decoded.signature, "transfer(address,uint256)");
+        assert_eq!(decoded.params.len(), 2);
+        assert_eq!(decoded.params[1], "1");
+
+        // foo(42)
+        let input =
+            hex::decode("2fbebd38000000000000000000000000000000000000000000000000000000000000002a")
+                .unwrap();
+        assert_eq!(db.decode(&input).unwrap().params, vec!["42".to_string()]);
+
+        // unknown selectors and truncated arguments
+        assert!(db.decode(&hex::decode("deadbeef").unwrap()).is_none());
+        assert!(db.decode(&input[..10]).is_none());
+        assert!(db.decode(&[]).is_none());
+    }
+
+    #[test]
+    fn decodes_with_registered_abis() {
+        let token = Address::repeat_byte(0x11);
+        let abi: JsonAbi = serde_json::from_str(r#"[
+            {"type":"function","name":"transfer","inputs":[{"name":"to","type":"address"},{"name":"amount","type":"uint256"}],"outputs":[],"stateMutability":"nonpayable"},
+            {"type":"event","name":"Transfer","inputs":[{"name":"from","type":"address","indexed":true},{"name":"to","type":"address","indexed":true},{"name":"value","type":"uint256","indexed":false}],"anonymous":false}
+        ]"#).unwrap();
+        let mut abis = AbiRegistry::default();
+        abis.register(Some(token), abi.clone());
+
+        let input = hex::decode("a9059cbb000000000000000000000000000000000000000000000000000000000000dead0000000000000000000000000000000000000000000000000000000000000001").unwrap();
+        let decoded = abis.decode_call(Some(token), &input).unwrap();
+        assert_eq!(decoded.signature, "transfer(address,uint256)");
+        assert_eq!(decoded.names, vec!["to".to_string(), "amount".to_string()]);
+        assert!(abis.decode_call(Some(Address::ZERO), &input).is_none());
+
+        let selector = abi.events["Transfer"][0].selector();
+        let topics = [selector, B256::left_padding_from(&[0xaa]), B256::left_padding_from(&[0xbb])];
+        let data = B256::with_last_byte(42);
+        let decoded = abis.decode_event(token, &topics, data.as_slice()).unwrap();
+        assert_eq!(decoded.signature, "Transfer(address,address,uint256)");
+        assert_eq!(decoded.names, vec!["from".to_string(), "to".to_string(), "value".to_string()]);
+        assert_eq!(decoded.params[2], "42");
+
+        // the fallback applies to all contracts
+        assert!(abis.decode_event(Address::ZERO, &topics, data.as_slice()).is_none());
+        abis.register(None, abi);
+        assert!(abis.decode_event(Address::ZERO, &topics, data.as_slice()).is_some());
+    }
+}
@@ -1,6 +1,9 @@
 //! Support for polling based filters
 use crate::{
-    eth::{backend::notifications::NewBlockNotifications, error::ToRpcResponseResult},
+    eth::{
+        backend::notifications::NewBlockNotifications,
+        error::{BlockchainError, ToRpcResponseResult},
+    },
     pubsub::filter_logs,
     StorageInfo,
 };
@@ -37,6 +40,11 @@ pub struct Filters {
 // === impl Filters ===
 
 impl Filters {
+    /// Creates a new set of filters that are removed if they weren't polled for `keepalive`
+    pub fn new(keepalive: Duration) -> Self {
+        Self { active_filters: Arc::new(Default::default()), keepalive }
+    }
+
     /// Adds a new `EthFilter` to the set
     pub async fn add_filter(&self, filter: EthFilter) -> String {
         let id = new_id();
@@ -46,9 +54,13 @@ impl Filters {
         id
     }
 
+    /// Returns the changes of the filter since it was last polled and extends its deadline
+    ///
+    /// Fails with "filter not found" if the filter doesn't exist or expired.
     pub async fn get_filter_changes(&self, id: &str) -> ResponseResult {
         {
             let mut filters = self.active_filters.lock().await;
+            remove_if_expired(&mut filters, id);
             if let Some((filter, deadline)) = filters.get_mut(id) {
                 let resp = filter
                     .next()
@@ -59,12 +71,13 @@ impl Filters {
             }
         }
         warn!(target: "node::filter", "No filter found for {}", id);
-        ResponseResult::success(Vec::<()>::new())
+        Err::<(), _>(BlockchainError::FilterNotFound).to_rpc_result()
     }
 
     /// Returns the original `Filter` of an `eth_newFilter`
     pub async fn get_log_filter(&self, id: &str) -> Option<Filter> {
-        let filters = self.active_filters.lock().await;
+        let mut filters = self.active_filters.lock().await;
+        remove_if_expired(&mut filters, id);
         if let Some((EthFilter::Logs(ref log), _)) = filters.get(id) {
             return log.filter.filter.clone()
         }
@@ -104,10 +117,15 @@ impl Filters {
 
 impl Default for Filters {
     fn default() -> Self {
-        Self {
-            active_filters: Arc::new(Default::default()),
-            keepalive: Duration::from_secs(ACTIVE_FILTER_TIMEOUT_SECS),
-        }
+        Self::new(Duration::from_secs(ACTIVE_FILTER_TIMEOUT_SECS))
+    }
+}
+
+/// Removes the filter if it reached its deadline, so that it's gone even if it wasn't evicted yet
+fn remove_if_expired(filters: &mut HashMap<String, (EthFilter, Instant)>, id: &str) {
+    if filters.get(id).is_some_and(|(_, deadline)| Instant::now() > *deadline) {
+        trace!(target: "node::filter", ?id, "Evicting stale filter");
+        filters.remove(id);
     }
 }
 
@@ -173,6 +191,10 @@ impl LogsFilter {
     pub fn poll(&mut self, cx: &mut Context<'_>) -> Vec<AlloyLog> {
         let mut logs = self.historic.take().unwrap_or_default();
         while let Poll::Ready(Some(block)) = self.blocks.poll_next_unpin(cx) {
+            // the logs of a block the chain was rolled back to were already returned
+            if !block.imported {
+                continue
+            }
             let b = self.storage.block(block.hash.to_alloy());
             let receipts = self.storage.receipts(block.hash.to_alloy());
             if let (Some(receipts), Some(block)) = (receipts, b) {
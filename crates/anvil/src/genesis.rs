@@ -92,7 +92,9 @@ impl Genesis {
             env.block.coinbase = coinbase;
         }
         env.block.difficulty = U256::from(self.difficulty);
-        env.block.gas_limit = U256::from(self.gas_limit);
+        if self.gas_limit != 0 {
+            env.block.gas_limit = U256::from(self.gas_limit);
+        }
     }
 
     /// Returns all private keys from the genesis accounts, if they exist
@@ -22,6 +22,7 @@ pub enum Hardfork {
     Paris,
     Shanghai,
     Cancun,
+    Prague,
     #[default]
     Latest,
 }
@@ -47,7 +48,7 @@ impl Hardfork {
             Hardfork::Shanghai | Hardfork::Latest => 17034870,
 
             // TODO: set block number after activation
-            Hardfork::Cancun => unreachable!(),
+            Hardfork::Cancun | Hardfork::Prague => unreachable!(),
         }
     }
 
@@ -96,7 +97,7 @@ impl Hardfork {
                 // update `next` when another fork block num is known
                 ForkId { hash: ForkHash([0xc1, 0xfd, 0xf1, 0x81]), next: 0 }
             }
-            Hardfork::Cancun => {
+            Hardfork::Cancun | Hardfork::Prague => {
                 // TODO: set fork hash once known
                 ForkId { hash: ForkHash([0xc1, 0xfd, 0xf1, 0x81]), next: 0 }
             }
@@ -127,6 +128,7 @@ impl FromStr for Hardfork {
             "paris" | "merge" | "15" => Hardfork::Paris,
             "shanghai" | "16" => Hardfork::Shanghai,
             "cancun" | "17" => Hardfork::Cancun,
+            "prague" | "18" => Hardfork::Prague,
             "latest" => Hardfork::Latest,
             _ => return Err(format!("Unknown hardfork {s}")),
         };
@@ -156,6 +158,8 @@ impl From<Hardfork> for SpecId {
 
             // TODO: switch to latest after activation
             Hardfork::Cancun => SpecId::CANCUN,
+            // the EVM has no Prague spec yet, EIP-7702 is handled by anvil on top of Cancun
+            Hardfork::Prague => SpecId::CANCUN,
         }
     }
 }
@@ -43,7 +43,7 @@ use std::{
 };
 use tokio::{
     runtime::Handle,
-    task::{JoinError, JoinHandle},
+    task::{AbortHandle, JoinError, JoinHandle},
 };
 
 /// contains the background service that drives the node
@@ -54,7 +54,7 @@ pub use config::{AccountGenerator, NodeConfig, CHAIN_ID, VERSION_MESSAGE};
 mod hardfork;
 use crate::server::{
     error::{NodeError, NodeResult},
-    spawn_ipc,
+    try_spawn_ipc, TlsConfig,
 };
 pub use hardfork::Hardfork;
 
@@ -71,7 +71,7 @@ pub mod pubsub;
 /// axum RPC server implementations
 pub mod server;
 /// Futures for shutdown signal
-mod shutdown;
+pub mod shutdown;
 /// additional task management
 mod tasks;
 
@@ -98,34 +98,149 @@ pub mod cmd;
 /// handle.await.unwrap();
 /// # }
 /// ```
-pub async fn spawn(mut config: NodeConfig) -> (EthApi, NodeHandle) {
+///
+/// # Panics
+///
+/// Panics if the node can't be launched, see [try_spawn]
+pub async fn spawn(config: NodeConfig) -> (EthApi, NodeHandle) {
+    try_spawn(config).await.expect("failed to spawn node")
+}
+
+/// Creates the node and runs the server
+///
+/// Same as [spawn], but returns an error if the node can't be launched, for example if the initial
+/// state can't be loaded, the TLS certificate or key can't be read or the server or the IPC
+/// endpoint can't bind to its address.
+pub async fn try_spawn(mut config: NodeConfig) -> NodeResult<(EthApi, NodeHandle)> {
     let logger = if config.enable_tracing { init_tracing() } else { Default::default() };
     logger.set_enabled(!config.silent);
+    logging::node_logs().set_capacity(config.node_log_buffer_size);
+
+    for (alias, _) in &config.chains {
+        server::validate_chain_alias(alias).map_err(NodeError::InvalidChainAlias)?;
+    }
+
+    // everything that doesn't need a running node is checked before any task is spawned
+    let tls = TlsConfig::from_server_config(&config.server_config)?;
+
+    // aborts the tasks that were already spawned if the node can't be launched
+    let mut guard = LaunchGuard::default();
+
+    let (api, node_service) = spawn_node(&mut config, logger.clone()).await?;
+    guard.push(&node_service);
+    let fork = api.get_fork();
+
+    let mut chains = Vec::with_capacity(config.chains.len());
+    for (alias, mut chain_config) in std::mem::take(&mut config.chains) {
+        let (api, service) = spawn_node(&mut chain_config, logger.clone()).await?;
+        guard.push(&service);
+        chains.push(ChainNode { alias, api, config: chain_config, service });
+    }
+
+    let NodeConfig { port, server_config, .. } = config.clone();
+
+    let mut servers = Vec::with_capacity(config.host.len());
+    let mut addresses = Vec::with_capacity(config.host.len());
+
+    // the servers are shut down gracefully once the signal fires
+    let (signal, on_shutdown) = shutdown::signal();
+
+    for addr in &config.host {
+        let sock_addr = SocketAddr::new(*addr, port);
+        let router = server::chains_router(
+            api.clone(),
+            chains.iter().map(|chain| (chain.alias.clone(), chain.api.clone())),
+            server_config.clone(),
+        );
+
+        // spawn the server on a new task
+        let srv = server::serve_router(sock_addr, router, tls.clone(), on_shutdown.clone()).await?;
+        // if the port was `0`, this is the port the OS picked
+        addresses.push(srv.local_addr);
+        let server = tokio::task::spawn(srv.server.map_err(Into::into));
+        guard.push(&server);
+        servers.push(server);
+    }
 
-    let backend = Arc::new(config.setup().await);
+    let tokio_handle = Handle::current();
+    let task_manager = TaskManager::new(tokio_handle, on_shutdown);
+
+    let ipc_task =
+        config.get_ipc_path().map(|path| try_spawn_ipc(api.clone(), path)).transpose()?;
+
+    let handle = NodeHandle {
+        config,
+        node_service,
+        servers,
+        ipc_task,
+        addresses,
+        chains,
+        _signal: Some(signal),
+        task_manager,
+    };
+
+    guard.disarm();
+    handle.print(fork.as_ref());
+
+    Ok((api, handle))
+}
+
+/// Aborts the tasks that were spawned while launching a node when dropped, unless disarmed
+/// because the node was launched.
+#[derive(Default)]
+struct LaunchGuard(Vec<AbortHandle>);
+
+impl LaunchGuard {
+    fn push<T>(&mut self, task: &JoinHandle<T>) {
+        self.0.push(task.abort_handle());
+    }
+
+    fn disarm(mut self) {
+        self.0.clear();
+    }
+}
+
+impl Drop for LaunchGuard {
+    fn drop(&mut self) {
+        for task in &self.0 {
+            task.abort();
+        }
+    }
+}
+
+/// Sets up the backend of the node and spawns the service that drives it
+async fn spawn_node(
+    config: &mut NodeConfig,
+    logger: LoggingManager,
+) -> NodeResult<(EthApi, JoinHandle<Result<(), NodeError>>)> {
+    let backend = Arc::new(config.setup().await?);
 
     if config.enable_auto_impersonate {
         backend.auto_impersonate_account(true).await;
     }
-
-    let fork = backend.get_fork();
+    backend.time().set_allow_non_increasing_timestamps(config.allow_non_increasing_timestamps);
 
     let NodeConfig {
         signer_accounts,
         block_time,
-        port,
+        block_time_jitter,
         max_transactions,
-        server_config,
         no_mining,
         transaction_order,
+        min_fee_bump_percent,
+        txpool_max_count,
+        txpool_max_bytes,
         genesis,
+        filter_timeout,
         ..
     } = config.clone();
 
     let pool = Arc::new(Pool::default());
+    pool.set_min_fee_bump_percent(min_fee_bump_percent);
+    pool.set_limits(txpool_max_count, txpool_max_bytes);
 
     let mode = if let Some(block_time) = block_time {
-        MiningMode::interval(block_time)
+        MiningMode::interval_with_jitter(block_time, block_time_jitter.unwrap_or_default())
     } else if no_mining {
         MiningMode::None
     } else {
@@ -135,13 +250,13 @@ pub async fn spawn(mut config: NodeConfig) -> (EthApi, NodeHandle) {
     };
     let miner = Miner::new(mode);
 
-    let dev_signer: Box<dyn EthSigner> = Box::new(DevSigner::new(signer_accounts));
+    let dev_signer: Arc<dyn EthSigner> = Arc::new(DevSigner::new(signer_accounts));
     let mut signers = vec![dev_signer];
     if let Some(genesis) = genesis {
         // include all signers from genesis.json if any
         let genesis_signers = genesis.private_keys();
         if !genesis_signers.is_empty() {
-            let genesis_signers: Box<dyn EthSigner> = Box::new(DevSigner::new(genesis_signers));
+            let genesis_signers: Arc<dyn EthSigner> = Arc::new(DevSigner::new(genesis_signers));
             signers.push(genesis_signers);
         }
     }
@@ -155,13 +270,13 @@ pub async fn spawn(mut config: NodeConfig) -> (EthApi, NodeHandle) {
         StorageInfo::new(Arc::clone(&backend)),
     );
 
-    let filters = Filters::default();
+    let filters = Filters::new(filter_timeout);
 
     // create the cloneable api wrapper
     let api = EthApi::new(
         Arc::clone(&pool),
         Arc::clone(&backend),
-        Arc::new(signers),
+        signers,
         fee_history_cache,
         fee_history_service.fee_history_limit(),
         miner.clone(),
@@ -174,41 +289,20 @@ pub async fn spawn(mut config: NodeConfig) -> (EthApi, NodeHandle) {
     let node_service =
         tokio::task::spawn(NodeService::new(pool, backend, miner, fee_history_service, filters));
 
-    let mut servers = Vec::with_capacity(config.host.len());
-    let mut addresses = Vec::with_capacity(config.host.len());
-
-    for addr in &config.host {
-        let sock_addr = SocketAddr::new(*addr, port);
-        addresses.push(sock_addr);
-
-        // spawn the server on a new task
-        let srv = server::serve(sock_addr, api.clone(), server_config.clone()).await.unwrap();
-        servers.push(tokio::task::spawn(srv.map_err(Into::into)));
-    }
-
-    let tokio_handle = Handle::current();
-    let (signal, on_shutdown) = shutdown::signal();
-    let task_manager = TaskManager::new(tokio_handle, on_shutdown);
-
-    let ipc_task = config.get_ipc_path().map(|path| spawn_ipc(api.clone(), path));
-
-    let handle = NodeHandle {
-        config,
-        node_service,
-        servers,
-        ipc_task,
-        addresses,
-        _signal: Some(signal),
-        task_manager,
-    };
-
-    handle.print(fork.as_ref());
-
-    (api, handle)
+    Ok((api, node_service))
 }
 
 type IpcTask = JoinHandle<io::Result<()>>;
 
+/// A chain that is served under its alias by the server of a [NodeHandle], see
+/// [NodeConfig::with_chain]
+struct ChainNode {
+    alias: String,
+    api: EthApi,
+    config: NodeConfig,
+    service: JoinHandle<Result<(), NodeError>>,
+}
+
 /// A handle to the spawned node and server tasks
 ///
 /// This future will resolve if either the node or server task resolve/fail.
@@ -222,6 +316,8 @@ pub struct NodeHandle {
     pub servers: Vec<JoinHandle<Result<(), NodeError>>>,
     // The future that joins the ipc server, if any
     ipc_task: Option<IpcTask>,
+    /// The additional chains that are served under their alias
+    chains: Vec<ChainNode>,
     /// A signal that fires the shutdown, fired on drop.
     _signal: Option<Signal>,
     /// A task manager that can be used to spawn additional tasks
@@ -245,10 +341,37 @@ impl NodeHandle {
                     .map(|addr| { addr.to_string() })
                     .collect::<Vec<String>>()
                     .join(", ")
-            )
+            );
+            for chain in &self.chains {
+                println!("Serving chain {} at /{}", chain.config.get_chain_id(), chain.alias);
+            }
         }
     }
 
+    /// Returns the [EthApi] of the chain with the given alias, see [NodeConfig::with_chain]
+    pub fn chain_api(&self, alias: &str) -> Option<&EthApi> {
+        self.chain(alias).map(|chain| &chain.api)
+    }
+
+    /// Returns the [NodeConfig] the chain with the given alias was launched with
+    pub fn chain_config(&self, alias: &str) -> Option<&NodeConfig> {
+        self.chain(alias).map(|chain| &chain.config)
+    }
+
+    /// Returns the http endpoint of the chain with the given alias
+    pub fn chain_http_endpoint(&self, alias: &str) -> String {
+        format!("{}/{alias}", self.http_endpoint())
+    }
+
+    /// Returns the websocket endpoint of the chain with the given alias
+    pub fn chain_ws_endpoint(&self, alias: &str) -> String {
+        format!("{}/{alias}", self.ws_endpoint())
+    }
+
+    fn chain(&self, alias: &str) -> Option<&ChainNode> {
+        self.chains.iter().find(|chain| chain.alias == alias)
+    }
+
     /// The address of the launched server
     ///
     /// **N.B.** this may not necessarily be the same `host + port` as configured in the
@@ -259,12 +382,14 @@ impl NodeHandle {
 
     /// Returns the http endpoint
     pub fn http_endpoint(&self) -> String {
-        format!("http://{}", self.socket_address())
+        let scheme = if self.config.server_config.is_tls_enabled() { "https" } else { "http" };
+        format!("{scheme}://{}", self.socket_address())
     }
 
     /// Returns the websocket endpoint
     pub fn ws_endpoint(&self) -> String {
-        format!("ws://{}", self.socket_address())
+        let scheme = if self.config.server_config.is_tls_enabled() { "wss" } else { "ws" };
+        format!("{scheme}://{}", self.socket_address())
     }
 
     /// Returns the path of the launched ipc server, if any
@@ -381,6 +506,13 @@ impl Future for NodeHandle {
             return Poll::Ready(res);
         }
 
+        // poll the node service tasks of the additional chains
+        for chain in pin.chains.iter_mut() {
+            if let Poll::Ready(res) = chain.service.poll_unpin(cx) {
+                return Poll::Ready(res);
+            }
+        }
+
         // poll the axum server handles
         for server in pin.servers.iter_mut() {
             if let Poll::Ready(res) = server.poll_unpin(cx) {
@@ -1,8 +1,16 @@
 //! User facing Logger
 
-use parking_lot::RwLock;
-use std::sync::Arc;
-use tracing::{subscriber::Interest, Metadata};
+use anvil_core::types::NodeLog;
+use parking_lot::{const_mutex, Mutex};
+use std::{
+    collections::VecDeque,
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc,
+    },
+    time::{SystemTime, UNIX_EPOCH},
+};
+use tracing::{subscriber::Interest, Level, Metadata};
 use tracing_subscriber::{layer::Context, Layer};
 
 /// The target that identifies the events intended to be logged to stdout
@@ -32,8 +40,10 @@ where
     S: tracing::Subscriber,
 {
     fn register_callsite(&self, metadata: &'static Metadata<'static>) -> Interest {
-        if self.state.is_enabled() && metadata.target() == NODE_USER_LOG_TARGET {
-            Interest::always()
+        // the interest is cached per callsite, so it must not depend on the current state,
+        // otherwise toggling the logger at runtime would have no effect on registered callsites
+        if metadata.target() == NODE_USER_LOG_TARGET {
+            Interest::sometimes()
         } else {
             Interest::never()
         }
@@ -48,7 +58,7 @@ where
 #[derive(Clone, Debug)]
 pub struct LoggingManager {
     /// Whether the logger is currently enabled
-    pub enabled: Arc<RwLock<bool>>,
+    pub enabled: Arc<AtomicBool>,
 }
 
 // === impl LoggingManager ===
@@ -56,18 +66,140 @@ pub struct LoggingManager {
 impl LoggingManager {
     /// Returns true if logging is currently enabled
     pub fn is_enabled(&self) -> bool {
-        *self.enabled.read()
+        self.enabled.load(Ordering::Relaxed)
     }
 
     /// Updates the `enabled` state
     pub fn set_enabled(&self, enabled: bool) {
-        let mut current = self.enabled.write();
-        *current = enabled;
+        self.enabled.store(enabled, Ordering::Relaxed);
     }
 }
 
 impl Default for LoggingManager {
     fn default() -> Self {
-        Self { enabled: Arc::new(RwLock::new(true)) }
+        Self { enabled: Arc::new(AtomicBool::new(true)) }
+    }
+}
+
+/// The default number of log lines kept in the [NodeLogBuffer]
+pub const DEFAULT_NODE_LOG_BUFFER_SIZE: usize = 1_000;
+
+/// The buffer of the most recent node logs of this process
+static NODE_LOGS: NodeLogBuffer = NodeLogBuffer::new();
+
+/// Returns the buffer of the most recent node logs, this is shared by all nodes of the process
+pub fn node_logs() -> &'static NodeLogBuffer {
+    &NODE_LOGS
+}
+
+/// A bounded ring buffer of node log lines.
+///
+/// Lines are only recorded if the buffer has a non-zero capacity, independently of whether node
+/// logs are printed to stdout.
+#[derive(Debug)]
+pub struct NodeLogBuffer {
+    capacity: AtomicUsize,
+    lines: Mutex<VecDeque<NodeLog>>,
+}
+
+// === impl NodeLogBuffer ===
+
+impl NodeLogBuffer {
+    const fn new() -> Self {
+        Self { capacity: AtomicUsize::new(0), lines: const_mutex(VecDeque::new()) }
+    }
+
+    /// Sets the maximum number of lines, `0` disables the buffer
+    pub fn set_capacity(&self, capacity: usize) {
+        self.capacity.store(capacity, Ordering::Relaxed);
+        let mut lines = self.lines.lock();
+        while lines.len() > capacity {
+            lines.pop_front();
+        }
+    }
+
+    /// Records the message, the message is only formatted if the buffer is enabled
+    pub fn record(&self, level: Level, message: impl FnOnce() -> String) {
+        let capacity = self.capacity.load(Ordering::Relaxed);
+        if capacity == 0 {
+            return
+        }
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_millis() as u64)
+            .unwrap_or_default();
+        let line = NodeLog { timestamp, level: level.to_string(), message: message() };
+
+        let mut lines = self.lines.lock();
+        while lines.len() >= capacity {
+            lines.pop_front();
+        }
+        lines.push_back(line);
+    }
+
+    /// Returns up to `count` of the most recent lines, oldest first
+    pub fn last(&self, count: usize) -> Vec<NodeLog> {
+        let lines = self.lines.lock();
+        lines.iter().skip(lines.len().saturating_sub(count)).cloned().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::AtomicUsize;
+    use tracing_subscriber::prelude::*;
+
+    /// Counts all events that pass the filter
+    #[derive(Clone, Default)]
+    struct EventCounter(Arc<AtomicUsize>);
+
+    impl<S: tracing::Subscriber> Layer<S> for EventCounter {
+        fn on_event(&self, _event: &tracing::Event<'_>, _ctx: Context<'_, S>) {
+            self.0.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    #[test]
+    fn can_toggle_logging_at_runtime() {
+        let manager = LoggingManager::default();
+        let counter = EventCounter::default();
+        let subscriber = tracing_subscriber::Registry::default()
+            .with(NodeLogLayer::new(manager.clone()))
+            .with(counter.clone());
+
+        tracing::subscriber::with_default(subscriber, || {
+            // the same callsite is hit every time
+            let log = || tracing::info!(target: NODE_USER_LOG_TARGET, "node log");
+            log();
+            manager.set_enabled(false);
+            log();
+            manager.set_enabled(true);
+            log();
+            tracing::info!(target: "other", "not a node log");
+        });
+
+        assert_eq!(counter.0.load(Ordering::Relaxed), 2);
+    }
+
+    #[test]
+    fn keeps_most_recent_logs() {
+        let buffer = NodeLogBuffer::new();
+        buffer.record(Level::INFO, || unreachable!("disabled buffer formats message"));
+        assert!(buffer.last(10).is_empty());
+
+        buffer.set_capacity(2);
+        for n in 0..3 {
+            buffer.record(Level::INFO, || format!("line {n}"));
+        }
+        let messages = |lines: Vec<NodeLog>| -> Vec<String> {
+            lines.into_iter().map(|line| line.message).collect()
+        };
+        assert_eq!(messages(buffer.last(10)), vec!["line 1", "line 2"]);
+        assert_eq!(messages(buffer.last(1)), vec!["line 2"]);
+        assert_eq!(buffer.last(1)[0].level, "INFO");
+
+        buffer.set_capacity(1);
+        assert_eq!(messages(buffer.last(10)), vec!["line 2"]);
     }
 }
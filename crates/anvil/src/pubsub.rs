@@ -1,9 +1,14 @@
 use crate::{
-    eth::{backend::notifications::NewBlockNotifications, error::to_rpc_result},
+    eth::{
+        backend::notifications::NewBlockNotifications, error::to_rpc_result,
+        pool::transactions::PoolTransaction,
+    },
     StorageInfo,
 };
 use alloy_primitives::{TxHash, B256, U256};
-use alloy_rpc_types::{pubsub::SubscriptionResult, FilteredParams, Log as AlloyLog};
+use alloy_rpc_types::{
+    pubsub::SubscriptionResult, Block as AlloyBlock, FilteredParams, Log as AlloyLog,
+};
 use anvil_core::eth::{
     block::Block,
     receipt::{EIP658Receipt, Log, TypedReceipt},
@@ -16,6 +21,7 @@ use serde::Serialize;
 use std::{
     collections::VecDeque,
     pin::Pin,
+    sync::Arc,
     task::{Context, Poll},
 };
 
@@ -43,18 +49,29 @@ impl LogsSubscription {
             }
 
             if let Some(block) = ready!(self.blocks.poll_next_unpin(cx)) {
-                let b = self.storage.block(block.hash.to_alloy());
-                let receipts = self.storage.receipts(block.hash.to_alloy());
-                if let (Some(receipts), Some(block)) = (receipts, b) {
-                    let logs = filter_logs(block, receipts, &self.filter);
-                    if logs.is_empty() {
-                        // this ensures we poll the receiver until it is pending, in which case the
-                        // underlying `UnboundedReceiver` will register the new waker, see
-                        // [`futures::channel::mpsc::UnboundedReceiver::poll_next()`]
-                        continue;
+                // logs of blocks that were removed by a reorg are emitted first
+                let mut logs = Vec::new();
+                for reorged in block.reorged.iter() {
+                    let removed =
+                        filter_logs(reorged.block.clone(), reorged.receipts.clone(), &self.filter);
+                    logs.extend(removed.into_iter().map(|log| AlloyLog { removed: true, ..log }));
+                }
+
+                // the logs of a block the chain was rolled back to were already emitted
+                if block.imported {
+                    let b = self.storage.block(block.hash.to_alloy());
+                    let receipts = self.storage.receipts(block.hash.to_alloy());
+                    if let (Some(receipts), Some(block)) = (receipts, b) {
+                        logs.extend(filter_logs(block, receipts, &self.filter));
                     }
-                    self.queued.extend(logs)
                 }
+                if logs.is_empty() {
+                    // this ensures we poll the receiver until it is pending, in which case the
+                    // underlying `UnboundedReceiver` will register the new waker, see
+                    // [`futures::channel::mpsc::UnboundedReceiver::poll_next()`]
+                    continue;
+                }
+                self.queued.extend(logs)
             } else {
                 return Poll::Ready(None);
             }
@@ -94,7 +111,11 @@ pub struct EthSubscriptionParams {
 pub enum EthSubscription {
     Logs(Box<LogsSubscription>),
     Header(NewBlockNotifications, StorageInfo, SubscriptionId),
+    /// Same as `Header` but yields the entire blocks including their transactions
+    FullBlocks(NewBlockNotifications, StorageInfo, SubscriptionId),
     PendingTransactions(Receiver<TxHash>, SubscriptionId),
+    /// Same as `PendingTransactions` but yields the entire transactions
+    FullPendingTransactions(Receiver<Arc<PoolTransaction>>, StorageInfo, SubscriptionId),
 }
 
 // === impl EthSubscription ===
@@ -104,22 +125,10 @@ impl EthSubscription {
         match self {
             EthSubscription::Logs(listener) => listener.poll(cx),
             EthSubscription::Header(blocks, storage, id) => {
-                // this loop ensures we poll the receiver until it is pending, in which case the
-                // underlying `UnboundedReceiver` will register the new waker, see
-                // [`futures::channel::mpsc::UnboundedReceiver::poll_next()`]
-                loop {
-                    if let Some(block) = ready!(blocks.poll_next_unpin(cx)) {
-                        if let Some(block) = storage.eth_block(block.hash.to_alloy()) {
-                            let params = EthSubscriptionParams {
-                                subscription: id.clone(),
-                                result: to_rpc_result(block),
-                            };
-                            return Poll::Ready(Some(EthSubscriptionResponse::new(params)));
-                        }
-                    } else {
-                        return Poll::Ready(None);
-                    }
-                }
+                poll_blocks(blocks, id, cx, |hash| storage.eth_block(hash))
+            }
+            EthSubscription::FullBlocks(blocks, storage, id) => {
+                poll_blocks(blocks, id, cx, |hash| storage.eth_block_full(hash))
             }
             EthSubscription::PendingTransactions(tx, id) => {
                 let res = ready!(tx.poll_next_unpin(cx))
@@ -131,6 +140,40 @@ impl EthSubscription {
                     });
                 Poll::Ready(res)
             }
+            EthSubscription::FullPendingTransactions(tx, storage, id) => {
+                let res = ready!(tx.poll_next_unpin(cx))
+                    .map(|tx| to_rpc_result(storage.pending_transaction(&tx)))
+                    .map(|result| {
+                        let params = EthSubscriptionParams { subscription: id.clone(), result };
+                        EthSubscriptionResponse::new(params)
+                    });
+                Poll::Ready(res)
+            }
+        }
+    }
+}
+
+/// Yields the next new block that could be converted
+fn poll_blocks(
+    blocks: &mut NewBlockNotifications,
+    id: &SubscriptionId,
+    cx: &mut Context<'_>,
+    convert: impl Fn(B256) -> Option<AlloyBlock>,
+) -> Poll<Option<EthSubscriptionResponse>> {
+    // this loop ensures we poll the receiver until it is pending, in which case the
+    // underlying `UnboundedReceiver` will register the new waker, see
+    // [`futures::channel::mpsc::UnboundedReceiver::poll_next()`]
+    loop {
+        if let Some(block) = ready!(blocks.poll_next_unpin(cx)) {
+            if let Some(block) = convert(block.hash.to_alloy()) {
+                let params = EthSubscriptionParams {
+                    subscription: id.clone(),
+                    result: to_rpc_result(block),
+                };
+                return Poll::Ready(Some(EthSubscriptionResponse::new(params)));
+            }
+        } else {
+            return Poll::Ready(None);
         }
     }
 }
@@ -1,3 +1,5 @@
+use crate::eth::error::BlockchainError;
+
 /// Result alias
 pub type NodeResult<T> = Result<T, NodeError>;
 
@@ -8,4 +10,13 @@ pub enum NodeError {
     Hyper(#[from] hyper::Error),
     #[error(transparent)]
     Io(#[from] std::io::Error),
+    /// The configured initial state can't be loaded, see [NodeConfig::with_init_state]
+    ///
+    /// [NodeConfig::with_init_state]: crate::NodeConfig::with_init_state
+    #[error("failed to load the initial state: {0}")]
+    LoadState(#[source] BlockchainError),
+    /// The alias of a chain can't be used as its path, see
+    /// [validate_chain_alias](crate::server::validate_chain_alias)
+    #[error("{0}")]
+    InvalidChainAlias(String),
 }
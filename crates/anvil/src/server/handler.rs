@@ -1,4 +1,5 @@
 //! Contains RPC handlers
+use super::metrics::{RpcMetrics, TrackedSubscription};
 use crate::{
     eth::error::to_rpc_result,
     pubsub::{EthSubscription, LogsSubscription},
@@ -8,15 +9,27 @@ use alloy_rpc_types::{
     pubsub::{Params, SubscriptionKind},
     FilteredParams,
 };
-use anvil_core::eth::{subscription::SubscriptionId, EthPubSub, EthRequest, EthRpcCall};
-use anvil_rpc::{error::RpcError, response::ResponseResult};
+use anvil_core::eth::{
+    subscription::{AnvilSubscriptionKind, SubscriptionId},
+    EthPubSub, EthRequest, EthRpcCall,
+};
+use anvil_rpc::{
+    error::RpcError,
+    response::{ResponseResult, RpcResponse},
+};
 use anvil_server::{PubSubContext, PubSubRpcHandler, RpcHandler};
+use std::{sync::Arc, time::Duration};
+use tokio::sync::{Semaphore, SemaphorePermit};
 
 /// A `RpcHandler` that expects `EthRequest` rpc calls via http
 #[derive(Clone)]
 pub struct HttpEthRpcHandler {
     /// Access to the node
     api: EthApi,
+    /// Collects metrics of handled calls, if enabled
+    metrics: Option<RpcMetrics>,
+    /// Limits the number of calls that are executed concurrently, if set
+    permits: Option<Arc<Semaphore>>,
 }
 
 // === impl WsEthRpcHandler ===
@@ -24,7 +37,19 @@ pub struct HttpEthRpcHandler {
 impl HttpEthRpcHandler {
     /// Creates a new instance of the handler using the given `EthApi`
     pub fn new(api: EthApi) -> Self {
-        Self { api }
+        Self { api, metrics: None, permits: None }
+    }
+
+    /// Records metrics of all handled calls
+    pub fn with_metrics(mut self, metrics: Option<RpcMetrics>) -> Self {
+        self.metrics = metrics;
+        self
+    }
+
+    /// Only executes as many calls concurrently as there are permits in the semaphore
+    pub fn with_permits(mut self, permits: Option<Arc<Semaphore>>) -> Self {
+        self.permits = permits;
+        self
     }
 }
 
@@ -33,8 +58,15 @@ impl RpcHandler for HttpEthRpcHandler {
     type Request = EthRequest;
 
     async fn on_request(&self, request: Self::Request) -> ResponseResult {
+        let _permit = acquire_permit(&self.permits).await;
         self.api.execute(request).await
     }
+
+    fn on_call_complete(&self, method: &str, elapsed: Duration, response: &RpcResponse) {
+        if let Some(metrics) = &self.metrics {
+            metrics.record_call(method, elapsed, response);
+        }
+    }
 }
 
 /// A `RpcHandler` that expects `EthRequest` rpc calls and `EthPubSub` via pubsub connection
@@ -42,12 +74,28 @@ impl RpcHandler for HttpEthRpcHandler {
 pub struct PubSubEthRpcHandler {
     /// Access to the node
     api: EthApi,
+    /// Collects metrics of handled calls and active subscriptions, if enabled
+    metrics: Option<RpcMetrics>,
+    /// Limits the number of calls that are executed concurrently, if set
+    permits: Option<Arc<Semaphore>>,
 }
 
 impl PubSubEthRpcHandler {
     /// Creates a new instance of the handler using the given `EthApi`
     pub fn new(api: EthApi) -> Self {
-        Self { api }
+        Self { api, metrics: None, permits: None }
+    }
+
+    /// Records metrics of all handled calls and active subscriptions
+    pub fn with_metrics(mut self, metrics: Option<RpcMetrics>) -> Self {
+        self.metrics = metrics;
+        self
+    }
+
+    /// Only executes as many calls concurrently as there are permits in the semaphore
+    pub fn with_permits(mut self, permits: Option<Arc<Semaphore>>) -> Self {
+        self.permits = permits;
+        self
     }
 
     /// Invoked for an ethereum pubsub rpc call
@@ -61,9 +109,16 @@ impl PubSubEthRpcHandler {
                 ResponseResult::Success(canceled.into())
             }
             EthPubSub::EthSubscribe(kind, params) => {
+                let mut full_transactions = false;
                 let filter = match *params {
                     Params::None => None,
                     Params::Logs(filter) => Some(*filter),
+                    Params::Bool(full)
+                        if matches!(kind, SubscriptionKind::NewPendingTransactions) =>
+                    {
+                        full_transactions = full;
+                        None
+                    }
                     Params::Bool(_) => {
                         return ResponseResult::Error(RpcError::invalid_params(
                             "Expected params for logs subscription",
@@ -91,6 +146,14 @@ impl PubSubEthRpcHandler {
                         let storage = self.api.storage_info();
                         EthSubscription::Header(blocks, storage, id.clone())
                     }
+                    SubscriptionKind::NewPendingTransactions if full_transactions => {
+                        trace!(target: "rpc::ws", "received full pending transactions subscription");
+                        EthSubscription::FullPendingTransactions(
+                            self.api.new_full_ready_transactions(),
+                            self.api.storage_info(),
+                            id.clone(),
+                        )
+                    }
                     SubscriptionKind::NewPendingTransactions => {
                         trace!(target: "rpc::ws", "received pending transactions subscription");
                         EthSubscription::PendingTransactions(
@@ -102,27 +165,72 @@ impl PubSubEthRpcHandler {
                         return RpcError::internal_error_with("Not implemented").into()
                     }
                 };
-
-                cx.add_subscription(id.clone(), subscription);
-
-                trace!(target: "rpc::ws", "created new subscription: {:?}", id);
-                to_rpc_result(id)
+                self.add_subscription(cx, id, subscription)
+            }
+            EthPubSub::AnvilSubscribe(kind, params) => {
+                let subscription = match kind {
+                    AnvilSubscriptionKind::Blocks => {
+                        trace!(target: "rpc::ws", ?params, "received blocks subscription");
+                        let blocks = self.api.new_block_notifications();
+                        let storage = self.api.storage_info();
+                        if params.full_transactions {
+                            EthSubscription::FullBlocks(blocks, storage, id.clone())
+                        } else {
+                            EthSubscription::Header(blocks, storage, id.clone())
+                        }
+                    }
+                };
+                self.add_subscription(cx, id, subscription)
             }
         }
     }
+
+    /// Adds the subscription to the connection and returns its id
+    fn add_subscription(
+        &self,
+        cx: PubSubContext<Self>,
+        id: SubscriptionId,
+        subscription: EthSubscription,
+    ) -> ResponseResult {
+        let subscription = match &self.metrics {
+            Some(metrics) => metrics.track_subscription(subscription),
+            None => subscription.into(),
+        };
+        cx.add_subscription(id.clone(), subscription);
+
+        trace!(target: "rpc::ws", "created new subscription: {:?}", id);
+        to_rpc_result(id)
+    }
 }
 
 #[async_trait::async_trait]
 impl PubSubRpcHandler for PubSubEthRpcHandler {
     type Request = EthRpcCall;
     type SubscriptionId = SubscriptionId;
-    type Subscription = EthSubscription;
+    type Subscription = TrackedSubscription;
 
     async fn on_request(&self, request: Self::Request, cx: PubSubContext<Self>) -> ResponseResult {
         trace!(target: "rpc", "received pubsub request {:?}", request);
         match request {
-            EthRpcCall::Request(request) => self.api.execute(*request).await,
+            EthRpcCall::Request(request) => {
+                let _permit = acquire_permit(&self.permits).await;
+                self.api.execute(*request).await
+            }
             EthRpcCall::PubSub(pubsub) => self.on_pub_sub(pubsub, cx).await,
         }
     }
+
+    fn on_call_complete(&self, method: &str, elapsed: Duration, response: &RpcResponse) {
+        if let Some(metrics) = &self.metrics {
+            metrics.record_call(method, elapsed, response);
+        }
+    }
+}
+
+/// Waits for a permit of the semaphore, if set
+async fn acquire_permit(permits: &Option<Arc<Semaphore>>) -> Option<SemaphorePermit<'_>> {
+    match permits {
+        Some(permits) => Some(permits.acquire().await.expect("semaphore is never closed")),
+        None => None,
+    }
 }
@@ -0,0 +1,28 @@
+//! Health endpoint of the RPC server
+
+use crate::EthApi;
+use axum::{
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::Serialize;
+
+/// The response of a healthy node
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Health {
+    block_number: u64,
+    chain_id: u64,
+}
+
+/// Handler for `GET /health`
+///
+/// Returns `200` with the current block number and chain id if the node is ready to serve
+/// requests, and `503` while the fork is being reset.
+pub async fn handle_health(api: EthApi) -> Response {
+    if !api.is_ready() {
+        return StatusCode::SERVICE_UNAVAILABLE.into_response()
+    }
+    Json(Health { block_number: api.best_number(), chain_id: api.chain_id() }).into_response()
+}
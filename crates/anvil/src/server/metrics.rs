@@ -0,0 +1,218 @@
+//! Prometheus metrics of the RPC server
+
+use crate::{pubsub::EthSubscription, EthApi};
+use anvil_rpc::{error::ErrorCode, response::RpcResponse};
+use axum::{
+    http::header,
+    response::{IntoResponse, Response},
+};
+use futures::{Stream, StreamExt};
+use parking_lot::Mutex;
+use std::{
+    collections::BTreeMap,
+    fmt::Write,
+    pin::Pin,
+    sync::{
+        atomic::{AtomicI64, Ordering},
+        Arc,
+    },
+    task::{Context, Poll},
+    time::Duration,
+};
+
+/// The upper bounds of the request latency histogram buckets, in seconds
+const LATENCY_BUCKETS: [f64; 12] =
+    [0.001, 0.0025, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0];
+
+/// The label used for calls to methods that don't exist, so that clients can't create an
+/// unbounded number of series
+const UNKNOWN_METHOD: &str = "unknown";
+
+/// Collects the metrics of the RPC server.
+///
+/// This type is cheap to clone.
+#[derive(Clone, Debug, Default)]
+pub struct RpcMetrics {
+    inner: Arc<MetricsInner>,
+}
+
+#[derive(Debug, Default)]
+struct MetricsInner {
+    /// Metrics per method
+    methods: Mutex<BTreeMap<String, MethodMetrics>>,
+    /// The number of active websocket subscriptions
+    active_subscriptions: AtomicI64,
+}
+
+#[derive(Debug, Default)]
+struct MethodMetrics {
+    requests: u64,
+    errors: u64,
+    /// The number of requests per bucket of [LATENCY_BUCKETS], not cumulative
+    latency_buckets: [u64; LATENCY_BUCKETS.len()],
+    latency_sum: f64,
+}
+
+// === impl RpcMetrics ===
+
+impl RpcMetrics {
+    /// Records a handled method call
+    pub fn record_call(&self, method: &str, elapsed: Duration, response: &RpcResponse) {
+        let error = response.result().as_error();
+        let method = match error {
+            Some(err) if err.code == ErrorCode::MethodNotFound => UNKNOWN_METHOD,
+            _ => method,
+        };
+        let secs = elapsed.as_secs_f64();
+
+        let mut methods = self.inner.methods.lock();
+        let metrics = methods.entry(method.to_string()).or_default();
+        metrics.requests += 1;
+        if error.is_some() {
+            metrics.errors += 1;
+        }
+        if let Some(bucket) = LATENCY_BUCKETS.iter().position(|le| secs <= *le) {
+            metrics.latency_buckets[bucket] += 1;
+        }
+        metrics.latency_sum += secs;
+    }
+
+    /// Wraps the subscription so that it is counted as active until it is dropped
+    pub fn track_subscription(&self, subscription: EthSubscription) -> TrackedSubscription {
+        self.inner.active_subscriptions.fetch_add(1, Ordering::Relaxed);
+        TrackedSubscription { subscription, metrics: Some(self.clone()) }
+    }
+
+    /// Renders all metrics in the prometheus text format
+    pub fn render(&self, api: &EthApi) -> String {
+        let mut out = String::new();
+        self.render_methods(&mut out);
+
+        let status = api.pool_status();
+        write_gauge(
+            &mut out,
+            "anvil_ws_subscriptions_active",
+            "The number of active websocket subscriptions",
+            self.inner.active_subscriptions.load(Ordering::Relaxed),
+        );
+        write_gauge(
+            &mut out,
+            "anvil_txpool_pending",
+            "The number of transactions in the pool that are ready to be mined",
+            status.pending.to::<u64>(),
+        );
+        write_gauge(
+            &mut out,
+            "anvil_txpool_queued",
+            "The number of transactions in the pool that are scheduled for future execution",
+            status.queued.to::<u64>(),
+        );
+        write_gauge(
+            &mut out,
+            "anvil_block_number",
+            "The number of the latest block",
+            api.best_number(),
+        );
+        write_counter(
+            &mut out,
+            "anvil_blocks_mined_total",
+            "The number of blocks mined since the node started",
+            api.blocks_mined(),
+        );
+        out
+    }
+
+    fn render_methods(&self, out: &mut String) {
+        let methods = self.inner.methods.lock();
+
+        write_header(out, "anvil_rpc_requests_total", "The number of handled requests", "counter");
+        for (method, metrics) in methods.iter() {
+            let _ = writeln!(
+                out,
+                "anvil_rpc_requests_total{{method=\"{method}\"}} {}",
+                metrics.requests
+            );
+        }
+
+        write_header(
+            out,
+            "anvil_rpc_request_errors_total",
+            "The number of requests that returned an error",
+            "counter",
+        );
+        for (method, metrics) in methods.iter() {
+            let _ = writeln!(
+                out,
+                "anvil_rpc_request_errors_total{{method=\"{method}\"}} {}",
+                metrics.errors
+            );
+        }
+
+        let name = "anvil_rpc_request_duration_seconds";
+        write_header(out, name, "The time it took to handle a request", "histogram");
+        for (method, metrics) in methods.iter() {
+            let mut cumulative = 0;
+            for (le, count) in LATENCY_BUCKETS.iter().zip(metrics.latency_buckets) {
+                cumulative += count;
+                let _ =
+                    writeln!(out, "{name}_bucket{{method=\"{method}\",le=\"{le}\"}} {cumulative}");
+            }
+            let _ = writeln!(
+                out,
+                "{name}_bucket{{method=\"{method}\",le=\"+Inf\"}} {}",
+                metrics.requests
+            );
+            let _ = writeln!(out, "{name}_sum{{method=\"{method}\"}} {}", metrics.latency_sum);
+            let _ = writeln!(out, "{name}_count{{method=\"{method}\"}} {}", metrics.requests);
+        }
+    }
+}
+
+/// Handler for `GET /metrics`
+pub async fn handle_metrics(metrics: RpcMetrics, api: EthApi) -> Response {
+    ([(header::CONTENT_TYPE, "text/plain; version=0.0.4; charset=utf-8")], metrics.render(&api))
+        .into_response()
+}
+
+fn write_header(out: &mut String, name: &str, help: &str, kind: &str) {
+    let _ = writeln!(out, "# HELP {name} {help}");
+    let _ = writeln!(out, "# TYPE {name} {kind}");
+}
+
+fn write_gauge(out: &mut String, name: &str, help: &str, value: impl std::fmt::Display) {
+    write_header(out, name, help, "gauge");
+    let _ = writeln!(out, "{name} {value}");
+}
+
+fn write_counter(out: &mut String, name: &str, help: &str, value: impl std::fmt::Display) {
+    write_header(out, name, help, "counter");
+    let _ = writeln!(out, "{name} {value}");
+}
+
+/// An [EthSubscription] that is counted as an active subscription until it is dropped
+pub struct TrackedSubscription {
+    subscription: EthSubscription,
+    metrics: Option<RpcMetrics>,
+}
+
+impl From<EthSubscription> for TrackedSubscription {
+    fn from(subscription: EthSubscription) -> Self {
+        Self { subscription, metrics: None }
+    }
+}
+
+impl Stream for TrackedSubscription {
+    type Item = serde_json::Value;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.get_mut().subscription.poll_next_unpin(cx)
+    }
+}
+
+impl Drop for TrackedSubscription {
+    fn drop(&mut self) {
+        if let Some(metrics) = &self.metrics {
+            metrics.inner.active_subscriptions.fetch_sub(1, Ordering::Relaxed);
+        }
+    }
+}
@@ -1,39 +1,151 @@
 //! Contains the code to launch an Ethereum RPC server.
 
-use crate::EthApi;
+use crate::{shutdown::Shutdown, EthApi};
 use anvil_server::{ipc::IpcEndpoint, ServerConfig};
-use axum::Router;
-use futures::StreamExt;
+use axum::{routing::get, Router};
+use futures::{future::Either, StreamExt};
 use handler::{HttpEthRpcHandler, PubSubEthRpcHandler};
 use std::{
     future::{Future, IntoFuture},
     net::SocketAddr,
-    pin::pin,
+    sync::Arc,
 };
-use tokio::{io, task::JoinHandle};
+use tokio::{io, sync::Semaphore, task::JoinHandle};
 
 pub mod error;
 mod handler;
+mod health;
+mod metrics;
+pub use metrics::RpcMetrics;
+mod tls;
+pub use tls::TlsConfig;
+
+/// A server that is bound to a socket but not yet running, see [serve].
+#[derive(Debug)]
+pub struct BoundServer<F> {
+    /// The address the server is bound to.
+    ///
+    /// If the server was bound to port `0`, this is the port the OS picked.
+    pub local_addr: SocketAddr,
+    /// The future that runs the server
+    pub server: F,
+}
+
+impl<F: Future> IntoFuture for BoundServer<F> {
+    type Output = F::Output;
+    type IntoFuture = F;
+
+    fn into_future(self) -> Self::IntoFuture {
+        self.server
+    }
+}
 
 /// Configures a server that handles [`EthApi`] related JSON-RPC calls via HTTP and WS.
 ///
-/// The returned future creates a new server, binding it to the given address, which returns another
-/// future that runs it.
+/// The returned future creates a new server, binding it to the given address, which returns a
+/// [BoundServer] that contains the actually bound address and the future that runs the server.
+///
+/// If a [TlsConfig] is provided, all connections, including websocket upgrades, are served over
+/// TLS.
+///
+/// The server runs until the `shutdown` future, see [`signal`](crate::shutdown::signal), resolves.
+/// It then stops accepting new connections and the run future resolves with `Ok(())` once all
+/// in-flight requests have been answered.
 pub async fn serve(
     addr: SocketAddr,
     api: EthApi,
     config: ServerConfig,
-) -> io::Result<impl Future<Output = io::Result<()>>> {
-    tokio::net::TcpListener::bind(addr).await.map(|tcp_listener| {
-        axum::serve(tcp_listener, router(api, config).into_make_service()).into_future()
-    })
+    tls: Option<TlsConfig>,
+    shutdown: Shutdown,
+) -> io::Result<BoundServer<impl Future<Output = io::Result<()>>>> {
+    serve_router(addr, router(api, config), tls, shutdown).await
+}
+
+/// Like [serve], but serves the given [`axum::Router`], see also [chains_router].
+pub async fn serve_router(
+    addr: SocketAddr,
+    router: Router,
+    tls: Option<TlsConfig>,
+    shutdown: Shutdown,
+) -> io::Result<BoundServer<impl Future<Output = io::Result<()>>>> {
+    let tcp_listener = tokio::net::TcpListener::bind(addr).await?;
+    let local_addr = tcp_listener.local_addr()?;
+    let server = match tls {
+        Some(tls) => Either::Left(tls::serve_tls(tcp_listener, router, tls, shutdown)),
+        None => Either::Right(
+            // the client address is required for rate limiting
+            axum::serve(tcp_listener, router.into_make_service_with_connect_info::<SocketAddr>())
+                .with_graceful_shutdown(shutdown)
+                .into_future(),
+        ),
+    };
+    Ok(BoundServer { local_addr, server })
 }
 
 /// Configures an [`axum::Router`] that handles [`EthApi`] related JSON-RPC calls via HTTP and WS.
+///
+/// The readiness of the node is served at `GET /health`. If enabled in the [ServerConfig],
+/// prometheus metrics are served at `GET /metrics`.
+///
+/// Both routes are not subject to the authentication and CORS rules of the RPC endpoint.
 pub fn router(api: EthApi, config: ServerConfig) -> Router {
-    let http = HttpEthRpcHandler::new(api.clone());
-    let ws = PubSubEthRpcHandler::new(api);
-    anvil_server::http_ws_router(config, http, ws)
+    let metrics = config.metrics.then(RpcMetrics::default);
+    // shared by HTTP and WS so the limit applies to all calls
+    let permits = config
+        .max_concurrent_requests
+        .filter(|max| *max > 0)
+        .map(|max| Arc::new(Semaphore::new(max)));
+    let http = HttpEthRpcHandler::new(api.clone())
+        .with_metrics(metrics.clone())
+        .with_permits(permits.clone());
+    let ws =
+        PubSubEthRpcHandler::new(api.clone()).with_metrics(metrics.clone()).with_permits(permits);
+    let mut router = anvil_server::http_ws_router(config, http, ws);
+
+    let health_api = api.clone();
+    router = router.route("/health", get(move || health::handle_health(health_api.clone())));
+
+    if let Some(metrics) = metrics {
+        router = router
+            .route("/metrics", get(move || metrics::handle_metrics(metrics.clone(), api.clone())));
+    }
+    router
+}
+
+/// Configures an [`axum::Router`] like [router], that additionally serves the given chains under
+/// the path of their alias, for example `/optimism`.
+///
+/// Every chain is served by its own [router], so the routes of a chain are not subject to the
+/// rate limits of the other chains.
+///
+/// The aliases must already be validated, see [validate_chain_alias].
+pub fn chains_router(
+    api: EthApi,
+    chains: impl IntoIterator<Item = (String, EthApi)>,
+    config: ServerConfig,
+) -> Router {
+    let mut router = router(api, config.clone());
+    for (alias, api) in chains {
+        router = router.nest(&format!("/{alias}"), self::router(api, config.clone()));
+    }
+    router
+}
+
+/// Returns an error if the alias can't be used as the path of a chain.
+///
+/// Aliases must be non-empty and only consist of ASCII alphanumerics, `-` and `_`. The paths of
+/// the other routes are reserved.
+pub fn validate_chain_alias(alias: &str) -> Result<(), String> {
+    if alias.is_empty() || !alias.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        return Err(format!(
+            "invalid chain alias `{alias}`, only alphanumerics, `-` and `_` are allowed"
+        ))
+    }
+    if ["health", "metrics"].contains(&alias) {
+        return Err(format!("the chain alias `{alias}` is reserved"))
+    }
+    Ok(())
 }
 
 /// Launches an ipc server at the given path in a new task
@@ -47,13 +159,34 @@ pub fn spawn_ipc(api: EthApi, path: String) -> JoinHandle<io::Result<()>> {
 }
 
 /// Launches an ipc server at the given path in a new task.
+///
+/// On Windows the server listens on a named pipe in the `\\.\pipe\` namespace instead of a
+/// Unix socket, see [`endpoint_path`](anvil_server::ipc::endpoint_path).
 pub fn try_spawn_ipc(api: EthApi, path: String) -> io::Result<JoinHandle<io::Result<()>>> {
+    try_spawn_ipc_many(api, vec![path])
+}
+
+/// Launches ipc servers at all the given paths in a single new task.
+///
+/// If any of the endpoints can't be established, the already established ones are closed again
+/// and the first error is returned.
+///
+/// The socket files are removed once the task is dropped, for example when the runtime shuts
+/// down, see [IpcEndpoint::incoming].
+pub fn try_spawn_ipc_many(
+    api: EthApi,
+    paths: Vec<String>,
+) -> io::Result<JoinHandle<io::Result<()>>> {
     let handler = PubSubEthRpcHandler::new(api);
-    let ipc = IpcEndpoint::new(handler, path);
-    let incoming = ipc.incoming()?;
+    let mut endpoints = Vec::with_capacity(paths.len());
+    for path in paths {
+        let ipc = IpcEndpoint::new(handler.clone(), path);
+        // on error, the already established endpoints are dropped
+        endpoints.push(Box::pin(ipc.incoming()?));
+    }
 
     let task = tokio::task::spawn(async move {
-        let mut incoming = pin!(incoming);
+        let mut incoming = futures::stream::select_all(endpoints);
         while let Some(stream) = incoming.next().await {
             trace!(target: "ipc", "new ipc connection");
             tokio::task::spawn(stream);
@@ -0,0 +1,216 @@
+//! TLS termination for the RPC server.
+
+use crate::shutdown::Shutdown;
+use anvil_server::ServerConfig;
+use axum::{extract::ConnectInfo, Extension, Router};
+use hyper_util::{
+    rt::{TokioExecutor, TokioIo},
+    server::conn::auto::Builder,
+    service::TowerToHyperService,
+};
+use std::{fmt, fs::File, io, io::BufReader, path::Path, pin::pin, sync::Arc, time::Duration};
+use tokio::{net::TcpListener, task::JoinSet};
+use tokio_rustls::{
+    rustls::{self, Certificate, PrivateKey},
+    TlsAcceptor,
+};
+use tower::Layer;
+
+/// The time a client has to complete the TLS handshake before the connection is closed
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The TLS configuration used to terminate TLS connections.
+///
+/// This type is cheap to clone.
+#[derive(Clone)]
+pub struct TlsConfig {
+    server_config: Arc<rustls::ServerConfig>,
+}
+
+// === impl TlsConfig ===
+
+impl TlsConfig {
+    /// Creates a new instance from the given [rustls::ServerConfig]
+    pub fn new(server_config: rustls::ServerConfig) -> Self {
+        Self { server_config: Arc::new(server_config) }
+    }
+
+    /// Loads the PEM encoded certificate chain and private key from the given files.
+    pub fn from_pem_files(cert: impl AsRef<Path>, key: impl AsRef<Path>) -> io::Result<Self> {
+        let certs = load_certs(cert.as_ref())?;
+        let key = load_private_key(key.as_ref())?;
+        Self::from_der(certs, key)
+    }
+
+    /// Generates a self-signed certificate for `localhost`.
+    pub fn self_signed() -> io::Result<Self> {
+        let names = vec!["localhost".to_string(), "127.0.0.1".to_string(), "::1".to_string()];
+        let cert = rcgen::generate_simple_self_signed(names)
+            .map_err(|err| io::Error::new(io::ErrorKind::Other, err))?;
+        let der = cert.serialize_der().map_err(|err| io::Error::new(io::ErrorKind::Other, err))?;
+        Self::from_der(vec![Certificate(der)], PrivateKey(cert.serialize_private_key_der()))
+    }
+
+    /// Returns the [TlsConfig] configured by the [ServerConfig], if TLS is enabled.
+    ///
+    /// If TLS is enabled but no certificate was provided this falls back to a self-signed
+    /// certificate.
+    pub fn from_server_config(config: &ServerConfig) -> io::Result<Option<Self>> {
+        if !config.is_tls_enabled() {
+            return Ok(None)
+        }
+        match (&config.tls_cert, &config.tls_key) {
+            (Some(cert), Some(key)) => Self::from_pem_files(cert, key).map(Some),
+            (None, None) => Self::self_signed().map(Some),
+            _ => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "both a TLS certificate and a private key are required",
+            )),
+        }
+    }
+
+    fn from_der(certs: Vec<Certificate>, key: PrivateKey) -> io::Result<Self> {
+        let mut config = rustls::ServerConfig::builder()
+            .with_safe_defaults()
+            .with_no_client_auth()
+            .with_single_cert(certs, key)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidInput, err))?;
+        config.alpn_protocols = vec![b"h2".to_vec(), b"http/1.1".to_vec()];
+        Ok(Self::new(config))
+    }
+
+    /// Returns the acceptor that performs the TLS handshake
+    fn acceptor(&self) -> TlsAcceptor {
+        TlsAcceptor::from(Arc::clone(&self.server_config))
+    }
+}
+
+impl fmt::Debug for TlsConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TlsConfig").finish_non_exhaustive()
+    }
+}
+
+/// Accepts incoming connections on the listener, terminates TLS and serves the router on the
+/// decrypted stream.
+///
+/// Connections are served with upgrades enabled so that websocket connections work over TLS as
+/// well.
+///
+/// Clients that don't complete the TLS handshake within [HANDSHAKE_TIMEOUT] are disconnected.
+///
+/// Once `shutdown` resolves, no new connections are accepted, connections that are still in the
+/// handshake are closed and all other open connections are gracefully shut down.
+pub(crate) async fn serve_tls(
+    tcp_listener: TcpListener,
+    router: Router,
+    tls: TlsConfig,
+    mut shutdown: Shutdown,
+) -> io::Result<()> {
+    let acceptor = tls.acceptor();
+    let mut connections = JoinSet::new();
+    loop {
+        let (stream, remote_addr) = tokio::select! {
+            conn = tcp_listener.accept() => match conn {
+                Ok(conn) => conn,
+                Err(err) => {
+                    // these are usually transient errors, like too many open files, so we back
+                    // off for a bit instead of shutting down the server
+                    warn!(target: "rpc::tls", ?err, "failed to accept connection");
+                    tokio::select! {
+                        _ = tokio::time::sleep(Duration::from_secs(1)) => continue,
+                        _ = &mut shutdown => {
+                            trace!(target: "rpc::tls", "shutting down TLS server");
+                            break
+                        }
+                    }
+                }
+            },
+            // reap finished connections
+            Some(_) = connections.join_next(), if !connections.is_empty() => continue,
+            _ = &mut shutdown => {
+                trace!(target: "rpc::tls", "shutting down TLS server");
+                break
+            }
+        };
+
+        let acceptor = acceptor.clone();
+        // the client address is required for rate limiting
+        let service =
+            TowerToHyperService::new(Extension(ConnectInfo(remote_addr)).layer(router.clone()));
+        let mut shutdown = shutdown.clone();
+        connections.spawn(async move {
+            let handshake = tokio::time::timeout(HANDSHAKE_TIMEOUT, acceptor.accept(stream));
+            let stream = tokio::select! {
+                res = handshake => match res {
+                    Ok(Ok(stream)) => stream,
+                    Ok(Err(err)) => {
+                        trace!(target: "rpc::tls", ?remote_addr, ?err, "TLS handshake failed");
+                        return
+                    }
+                    Err(_) => {
+                        trace!(target: "rpc::tls", ?remote_addr, "TLS handshake timed out");
+                        return
+                    }
+                },
+                _ = &mut shutdown => return,
+            };
+
+            let builder = Builder::new(TokioExecutor::new());
+            let mut conn =
+                pin!(builder.serve_connection_with_upgrades(TokioIo::new(stream), service));
+            let res = tokio::select! {
+                res = conn.as_mut() => res,
+                _ = &mut shutdown => {
+                    conn.as_mut().graceful_shutdown();
+                    conn.await
+                }
+            };
+            if let Err(err) = res {
+                trace!(target: "rpc::tls", ?remote_addr, ?err, "connection closed with error");
+            }
+        });
+    }
+
+    // wait for all in-flight connections to finish
+    while connections.join_next().await.is_some() {}
+
+    Ok(())
+}
+
+/// Opens the file, the error includes the path
+fn open(path: &Path) -> io::Result<File> {
+    File::open(path).map_err(|err| {
+        io::Error::new(err.kind(), format!("failed to open {}: {err}", path.display()))
+    })
+}
+
+fn load_certs(path: &Path) -> io::Result<Vec<Certificate>> {
+    let mut reader = BufReader::new(open(path)?);
+    let certs = rustls_pemfile::certs(&mut reader)?;
+    if certs.is_empty() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("no certificates found in {}", path.display()),
+        ))
+    }
+    Ok(certs.into_iter().map(Certificate).collect())
+}
+
+fn load_private_key(path: &Path) -> io::Result<PrivateKey> {
+    let mut reader = BufReader::new(open(path)?);
+    loop {
+        match rustls_pemfile::read_one(&mut reader)? {
+            Some(rustls_pemfile::Item::RSAKey(key)) |
+            Some(rustls_pemfile::Item::PKCS8Key(key)) |
+            Some(rustls_pemfile::Item::ECKey(key)) => return Ok(PrivateKey(key)),
+            Some(_) => {}
+            None => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("no private key found in {}", path.display()),
+                ))
+            }
+        }
+    }
+}
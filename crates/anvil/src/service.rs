@@ -73,6 +73,7 @@ impl Future for NodeService {
         loop {
             while let Poll::Ready(Some(outcome)) = pin.block_producer.poll_next_unpin(cx) {
                 trace!(target: "node", "mined block {}", outcome.block_number);
+                pin.miner.on_block_mined();
                 // prune the transactions from the pool
                 pin.pool.on_mined_block(outcome);
             }
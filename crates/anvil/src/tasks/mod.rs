@@ -77,6 +77,7 @@ impl TaskManager {
                         .anvil_reset(Some(Forking {
                             json_rpc_url: None,
                             block_number: block.number.map(|b| b.as_u64()),
+                            keep_impersonated_accounts: true,
                         }))
                         .await;
                 }
@@ -128,6 +129,7 @@ impl TaskManager {
                     .anvil_reset(Some(Forking {
                         json_rpc_url: None,
                         block_number: block.number.map(|b| b.as_u64()),
+                        keep_impersonated_accounts: true,
                     }))
                     .await;
             }
@@ -1,8 +1,16 @@
 //! tests for anvil specific logic
 
-use anvil::{spawn, NodeConfig};
-use ethers::{prelude::Middleware, types::Address};
-use foundry_common::types::ToAlloy;
+use anvil::{spawn, try_spawn, NodeConfig};
+use anvil_core::eth::trie::ordered_trie_root;
+use anvil_server::ServerConfig;
+use ethers::{
+    prelude::{Middleware, Signer},
+    providers::RpcError,
+    types::{Address, Bytes, Eip1559TransactionRequest, TransactionRequest},
+    utils::rlp,
+};
+use foundry_common::{provider::ethers::ProviderBuilder, types::ToAlloy};
+use foundry_evm::constants::{DEFAULT_CREATE2_DEPLOYER, DEFAULT_CREATE2_DEPLOYER_RUNTIME_CODE};
 
 #[tokio::test(flavor = "multi_thread")]
 async fn test_can_change_mining_mode() {
@@ -52,6 +60,31 @@ async fn can_set_empty_code() {
     assert!(code.as_ref().is_empty());
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn can_reject_halting_code() {
+    let (api, _handle) = spawn(NodeConfig::test()).await;
+    let addr = Address::random().to_alloy();
+
+    let err = api.anvil_set_code_with_validation(addr, vec![0xfe].into(), true).await.unwrap_err();
+    assert!(err.to_string().contains("INVALID"), "{err}");
+    assert!(api.get_code(addr, None).await.unwrap().is_empty());
+
+    // without validation the code is set anyway
+    api.anvil_set_code_with_validation(addr, vec![0xfe].into(), false).await.unwrap();
+    assert_eq!(api.get_code(addr, None).await.unwrap().as_ref(), &[0xfe]);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn can_preload_create2_deployer() {
+    let (api, _handle) = spawn(NodeConfig::test()).await;
+    let code = api.get_code(DEFAULT_CREATE2_DEPLOYER, None).await.unwrap();
+    assert_eq!(code.as_ref(), DEFAULT_CREATE2_DEPLOYER_RUNTIME_CODE);
+
+    let (api, _handle) =
+        spawn(NodeConfig::test().with_disable_default_create2_deployer(true)).await;
+    assert!(api.get_code(DEFAULT_CREATE2_DEPLOYER, None).await.unwrap().is_empty());
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn test_can_set_genesis_timestamp() {
     let genesis_timestamp = 1000u64;
@@ -69,3 +102,267 @@ async fn test_can_use_default_genesis_timestamp() {
 
     assert_ne!(0u64, provider.get_block(0).await.unwrap().unwrap().timestamp.as_u64());
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_can_bind_to_ephemeral_port() {
+    let (_api, handle) = spawn(NodeConfig::test().with_port(0)).await;
+    assert_ne!(handle.socket_address().port(), 0);
+
+    let provider = handle.ethers_http_provider();
+    assert_eq!(provider.get_block_number().await.unwrap().as_u64(), 0);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_rejects_port_in_use() {
+    let (_api, handle) = spawn(NodeConfig::test().with_port(0)).await;
+    let port = handle.socket_address().port();
+
+    assert!(try_spawn(NodeConfig::test().with_port(port)).await.is_err());
+    // the running node is not affected
+    let provider = handle.ethers_http_provider();
+    assert_eq!(provider.get_block_number().await.unwrap().as_u64(), 0);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_can_shutdown_server_gracefully() {
+    let (_api, mut handle) = spawn(NodeConfig::test()).await;
+    let provider = handle.ethers_http_provider();
+    assert_eq!(provider.get_block_number().await.unwrap().as_u64(), 0);
+
+    let signal = handle.shutdown_signal_mut().take().unwrap();
+    signal.fire().unwrap();
+
+    for server in handle.servers.drain(..) {
+        server.await.unwrap().unwrap();
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_rejects_oversized_requests() {
+    let server_config = ServerConfig::default().with_max_request_size(1024);
+    let (_api, handle) = spawn(NodeConfig::test().with_server_config(server_config)).await;
+    let provider = handle.ethers_http_provider();
+    assert_eq!(provider.get_block_number().await.unwrap().as_u64(), 0);
+
+    let data = Bytes::from(vec![0u8; 2048]);
+    let tx = TransactionRequest::new().to(Address::random()).data(data.clone());
+    assert!(provider.call(&tx.into(), None).await.is_err());
+
+    // admin methods are subject to the larger admin limit
+    provider.request::<_, ()>("anvil_setCode", (Address::random(), data)).await.unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_admin_methods_require_auth_token() {
+    let server_config = ServerConfig::default().with_auth_token(Some("secret".to_string()));
+    let (_api, handle) = spawn(NodeConfig::test().with_server_config(server_config)).await;
+    let provider = handle.ethers_http_provider();
+
+    // standard methods don't require the token
+    assert_eq!(provider.get_block_number().await.unwrap().as_u64(), 0);
+
+    let err = provider.request::<_, ()>("anvil_setAutomine", [true]).await.unwrap_err();
+    assert_eq!(err.as_error_response().unwrap().code, -32001);
+
+    // every call of an unauthorized batch is rejected with its own id
+    let batch = r#"[{"jsonrpc":"2.0","id":7,"method":"eth_blockNumber"},{"jsonrpc":"2.0","id":"a","method":"anvil_setAutomine","params":[true]}]"#;
+    let res = reqwest::Client::new()
+        .post(handle.http_endpoint())
+        .header("content-type", "application/json")
+        .body(batch)
+        .send()
+        .await
+        .unwrap();
+    let res: serde_json::Value = serde_json::from_str(&res.text().await.unwrap()).unwrap();
+    let res = res.as_array().unwrap();
+    assert_eq!(res.len(), 2);
+    assert_eq!(res[0]["id"], 7);
+    assert_eq!(res[1]["id"], "a");
+    assert!(res.iter().all(|res| res["error"]["code"] == -32001));
+
+    let provider = ProviderBuilder::new(&handle.http_endpoint())
+        .headers(vec!["Authorization: Bearer secret".to_string()])
+        .build()
+        .unwrap();
+    provider.request::<_, ()>("anvil_setAutomine", [true]).await.unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_can_serve_metrics() {
+    let server_config = ServerConfig::default().set_metrics(true);
+    let (api, handle) = spawn(NodeConfig::test().with_server_config(server_config)).await;
+    let provider = handle.ethers_http_provider();
+    assert_eq!(provider.get_block_number().await.unwrap().as_u64(), 0);
+
+    let url = format!("{}/metrics", handle.http_endpoint());
+    let metrics = reqwest::get(&url).await.unwrap().text().await.unwrap();
+    assert!(metrics.contains(r#"anvil_rpc_requests_total{method="eth_blockNumber"} 1"#));
+    assert!(metrics.contains("anvil_block_number 0"));
+    assert!(metrics.contains("anvil_blocks_mined_total 0"));
+
+    // blocks mined on request and by the miner are counted
+    api.mine_one().await;
+    let from = handle.dev_wallets().next().unwrap().address();
+    let tx = TransactionRequest::new().from(from).to(Address::random()).value(1u64);
+    provider.send_transaction(tx, None).await.unwrap().await.unwrap().unwrap();
+    let metrics = reqwest::get(&url).await.unwrap().text().await.unwrap();
+    assert!(metrics.contains("anvil_block_number 2"));
+    assert!(metrics.contains("# TYPE anvil_blocks_mined_total counter"));
+    assert!(metrics.contains("anvil_blocks_mined_total 2"));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_can_serve_health() {
+    let (api, handle) = spawn(NodeConfig::test()).await;
+    api.mine_one().await;
+
+    let url = format!("{}/health", handle.http_endpoint());
+    let res = reqwest::get(url).await.unwrap();
+    assert_eq!(res.status(), reqwest::StatusCode::OK);
+    let health: serde_json::Value = serde_json::from_str(&res.text().await.unwrap()).unwrap();
+    assert_eq!(health["blockNumber"], 1);
+    assert_eq!(health["chainId"], api.chain_id());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_rate_limits_by_ip() {
+    let server_config = ServerConfig::default().with_ip_rate_limit(1, Some(2));
+    let (_api, handle) = spawn(NodeConfig::test().with_server_config(server_config)).await;
+
+    let client = reqwest::Client::new();
+    let request = || {
+        client
+            .post(handle.http_endpoint())
+            .header("content-type", "application/json")
+            .body(r#"{"jsonrpc":"2.0","id":1,"method":"eth_blockNumber","params":[]}"#)
+            .send()
+    };
+    assert_eq!(request().await.unwrap().status(), reqwest::StatusCode::OK);
+    assert_eq!(request().await.unwrap().status(), reqwest::StatusCode::OK);
+
+    let res = request().await.unwrap();
+    assert_eq!(res.status(), reqwest::StatusCode::TOO_MANY_REQUESTS);
+    let body: serde_json::Value = serde_json::from_str(&res.text().await.unwrap()).unwrap();
+    assert_eq!(body["error"]["code"], -32005);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_rejects_oversized_batches() {
+    let server_config =
+        ServerConfig::default().with_max_batch_size(Some(2)).with_max_concurrent_requests(Some(1));
+    let (_api, handle) = spawn(NodeConfig::test().with_server_config(server_config)).await;
+
+    let client = reqwest::Client::new();
+    let batch = |len: usize| {
+        let calls = (0..len)
+            .map(|id| format!(r#"{{"jsonrpc":"2.0","id":{id},"method":"eth_blockNumber"}}"#))
+            .collect::<Vec<_>>()
+            .join(",");
+        client
+            .post(handle.http_endpoint())
+            .header("content-type", "application/json")
+            .body(format!("[{calls}]"))
+            .send()
+    };
+
+    let res: serde_json::Value =
+        serde_json::from_str(&batch(2).await.unwrap().text().await.unwrap()).unwrap();
+    assert_eq!(res.as_array().unwrap().len(), 2);
+
+    let res: serde_json::Value =
+        serde_json::from_str(&batch(3).await.unwrap().text().await.unwrap()).unwrap();
+    assert_eq!(res["error"]["code"], -32600);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn can_serve_chains_by_alias() {
+    let chain = NodeConfig::test().with_chain_id(Some(1337u64));
+    let (api, handle) = spawn(NodeConfig::test().with_chain("other", chain)).await;
+    let provider = handle.ethers_http_provider();
+    let other = ProviderBuilder::new(&handle.chain_http_endpoint("other")).build().unwrap();
+
+    assert_eq!(provider.get_chainid().await.unwrap().as_u64(), 31337);
+    assert_eq!(other.get_chainid().await.unwrap().as_u64(), 1337);
+
+    // the chains have independent block heights
+    api.mine_one().await;
+    assert_eq!(provider.get_block_number().await.unwrap().as_u64(), 1);
+    assert_eq!(other.get_block_number().await.unwrap().as_u64(), 0);
+
+    handle.chain_api("other").unwrap().mine_one().await;
+    handle.chain_api("other").unwrap().mine_one().await;
+    assert_eq!(other.get_block_number().await.unwrap().as_u64(), 2);
+    assert_eq!(provider.get_block_number().await.unwrap().as_u64(), 1);
+    assert!(handle.chain_api("unknown").is_none());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_rejects_invalid_chain_alias() {
+    let config = NodeConfig::test().with_chain("metrics", NodeConfig::test());
+    let err = try_spawn(config).await.err().unwrap();
+    assert!(err.to_string().contains("the chain alias `metrics` is reserved"), "{err}");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn block_header_commits_to_state() {
+    let (api, handle) = spawn(NodeConfig::test()).await;
+    let provider = handle.ethers_http_provider();
+
+    // the genesis block commits to the initial state, including the dev accounts
+    let genesis = provider.get_block(0).await.unwrap().unwrap();
+    assert_eq!(genesis.state_root.to_alloy(), api.state_root().await.unwrap());
+
+    let accounts: Vec<_> = handle.dev_wallets().collect();
+    let tx = Eip1559TransactionRequest::new()
+        .to(accounts[1].address())
+        .value(1337u64)
+        .from(accounts[0].address());
+    let receipt = provider.send_transaction(tx, None).await.unwrap().await.unwrap().unwrap();
+
+    let block = provider.get_block(receipt.block_number.unwrap()).await.unwrap().unwrap();
+    assert_ne!(block.state_root, genesis.state_root);
+    assert_eq!(block.state_root.to_alloy(), api.state_root().await.unwrap());
+
+    // typed transactions and receipts are committed to as `type || rlp`
+    let tx = provider.get_transaction(receipt.transaction_hash).await.unwrap().unwrap();
+    assert_eq!(block.transactions_root, ordered_trie_root([tx.rlp()]));
+    let mut encoded_receipt = vec![2];
+    encoded_receipt.extend_from_slice(&rlp::encode(&receipt));
+    assert_eq!(block.receipts_root, ordered_trie_root([encoded_receipt]));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_rejects_missing_tls_key() {
+    let dir = tempfile::tempdir().unwrap();
+    let cert = dir.path().join("cert.pem");
+    let pem = rcgen::generate_simple_self_signed(vec!["localhost".to_string()])
+        .unwrap()
+        .serialize_pem()
+        .unwrap();
+    std::fs::write(&cert, pem).unwrap();
+    let key = dir.path().join("key.pem");
+
+    let server_config = ServerConfig::default().with_tls_cert(cert, key.clone());
+    let err = try_spawn(NodeConfig::test().with_server_config(server_config)).await.err().unwrap();
+    assert!(err.to_string().contains(&format!("failed to open {}", key.display())), "{err}");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_tls_shutdown_is_not_blocked_by_handshakes() {
+    let server_config = ServerConfig { tls: true, ..Default::default() };
+    let (_api, mut handle) = spawn(NodeConfig::test().with_server_config(server_config)).await;
+
+    // a client that connects but never starts the handshake
+    let _stream = tokio::net::TcpStream::connect(handle.socket_address()).await.unwrap();
+    tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+
+    let signal = handle.shutdown_signal_mut().take().unwrap();
+    signal.fire().unwrap();
+    for server in handle.servers.drain(..) {
+        tokio::time::timeout(std::time::Duration::from_secs(5), server)
+            .await
+            .expect("shutdown is blocked by the pending handshake")
+            .unwrap()
+            .unwrap();
+    }
+}
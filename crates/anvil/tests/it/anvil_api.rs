@@ -1,14 +1,17 @@
 //! tests for custom anvil endpoints
 use crate::{abi::*, fork::fork_config};
-use alloy_rpc_types::BlockNumberOrTag;
-use anvil::{eth::api::CLIENT_VERSION, spawn, Hardfork, NodeConfig};
+use alloy_rpc_types::{BlockNumberOrTag, CallRequest};
+use anvil::{eth::api::CLIENT_VERSION, spawn, AccountGenerator, Hardfork, NodeConfig};
 use anvil_core::{
     eth::EthRequest,
-    types::{AnvilMetadata, ForkedNetwork, Forking, NodeEnvironment, NodeForkConfig, NodeInfo},
+    types::{
+        AnvilMetadata, ForkedNetwork, Forking, MiningModeInfo, NodeEnvironment, NodeForkConfig,
+        NodeInfo, StorageBatch,
+    },
 };
 use ethers::{
     abi::{ethereum_types::BigEndianHash, AbiDecode},
-    prelude::{Middleware, SignerMiddleware},
+    prelude::{Middleware, Signer, SignerMiddleware},
     types::{
         transaction::eip2718::TypedTransaction, Address, BlockNumber, Eip1559TransactionRequest,
         TransactionRequest, H256, U256, U64,
@@ -18,6 +21,7 @@ use ethers::{
 use foundry_common::types::{ToAlloy, ToEthers};
 use foundry_evm::revm::primitives::SpecId;
 use std::{
+    collections::BTreeMap,
     str::FromStr,
     sync::Arc,
     time::{Duration, SystemTime},
@@ -46,6 +50,92 @@ async fn can_set_block_gas_limit() {
     assert_eq!(block_gas_limit.to_alloy(), latest_block.header.gas_limit);
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn can_get_node_logs() {
+    let (api, _handle) = spawn(NodeConfig::test()).await;
+    api.block_number().unwrap();
+
+    // the logs are kept even though the test node is silent
+    let logs = api.anvil_get_node_logs(1_000).unwrap();
+    assert!(logs.iter().any(|log| log.message == "eth_blockNumber"));
+    assert!(logs.iter().all(|log| log.level == "INFO" && log.timestamp > 0));
+    assert_eq!(api.anvil_get_node_logs(1).unwrap().len(), 1);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn can_switch_mining_mode() {
+    let (api, handle) = spawn(NodeConfig::test()).await;
+    let provider = handle.ethers_http_provider();
+    let from = handle.dev_accounts().next().unwrap().to_ethers();
+    assert_eq!(api.anvil_get_mining_mode().unwrap(), MiningModeInfo::Auto);
+
+    api.anvil_set_interval_mining(12).unwrap();
+    assert_eq!(api.anvil_get_mining_mode().unwrap(), MiningModeInfo::Interval { block_time: 12 });
+    assert!(!api.anvil_get_auto_mine().unwrap());
+
+    // disabling automine doesn't stop interval mining
+    api.anvil_set_auto_mine(false).await.unwrap();
+    assert_eq!(api.anvil_get_mining_mode().unwrap(), MiningModeInfo::Interval { block_time: 12 });
+
+    api.anvil_set_auto_mine(true).await.unwrap();
+    assert_eq!(api.anvil_get_mining_mode().unwrap(), MiningModeInfo::Auto);
+    assert!(api.anvil_get_auto_mine().unwrap());
+
+    api.anvil_set_auto_mine(false).await.unwrap();
+    assert_eq!(api.anvil_get_mining_mode().unwrap(), MiningModeInfo::Manual);
+    assert!(!api.anvil_get_auto_mine().unwrap());
+
+    // pending transactions stay queued in manual mode
+    let tx = TransactionRequest::new().from(from).to(Address::random()).value(1337u64);
+    let tx = provider.send_transaction(tx, None).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(500)).await;
+    assert!(provider.get_transaction_receipt(tx.tx_hash()).await.unwrap().is_none());
+    assert_eq!(api.block_number().unwrap().to::<u64>(), 0);
+
+    api.anvil_mine(None, None).await.unwrap();
+    assert!(provider.get_transaction_receipt(tx.tx_hash()).await.unwrap().is_some());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn block_gas_limit_splits_pending_transactions() {
+    let (api, handle) = spawn(NodeConfig::test()).await;
+    let provider = handle.ethers_http_provider();
+    let from = handle.dev_accounts().next().unwrap().to_ethers();
+
+    api.anvil_set_auto_mine(false).await.unwrap();
+    // room for two transfers per block
+    api.evm_set_block_gas_limit(U256::from(21_000u64 * 2).to_alloy()).unwrap();
+
+    for nonce in 0..3u64 {
+        let tx = TransactionRequest::new()
+            .from(from)
+            .to(Address::random())
+            .value(1337u64)
+            .gas(21_000u64)
+            .nonce(nonce);
+        provider.send_transaction(tx, None).await.unwrap();
+    }
+
+    api.mine_one().await;
+    let block = provider.get_block(1u64).await.unwrap().unwrap();
+    assert_eq!(block.transactions.len(), 2);
+    api.mine_one().await;
+    let block = provider.get_block(2u64).await.unwrap().unwrap();
+    assert_eq!(block.transactions.len(), 1);
+
+    // a transaction that can never fit into a block is rejected
+    let tx = TransactionRequest::new()
+        .from(from)
+        .to(Address::random())
+        .value(1337u64)
+        .gas(50_000u64)
+        .nonce(3u64);
+    let err = provider.send_transaction(tx, None).await.unwrap_err();
+    assert!(err.to_string().contains("exceeds block gas limit"), "{err}");
+
+    assert!(api.evm_set_block_gas_limit(alloy_primitives::U256::ZERO).is_err());
+}
+
 // Ref <https://github.com/foundry-rs/foundry/issues/2341>
 #[tokio::test(flavor = "multi_thread")]
 async fn can_set_storage() {
@@ -101,6 +191,39 @@ async fn can_impersonate_account() {
     res.unwrap_err();
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn can_impersonate_accounts() {
+    let (api, handle) = spawn(NodeConfig::test()).await;
+    let provider = handle.ethers_http_provider();
+
+    let accounts = vec![Address::random(), Address::random(), Address::random()];
+    let funding = U256::from(1e18 as u64);
+    for account in &accounts {
+        api.anvil_set_balance(account.to_alloy(), funding.to_alloy()).await.unwrap();
+    }
+
+    api.anvil_impersonate_accounts(accounts.iter().map(|acc| acc.to_alloy()).collect())
+        .await
+        .unwrap();
+    for account in &accounts {
+        assert!(api.accounts().unwrap().contains(&account.to_alloy()));
+        let tx = TransactionRequest::new().from(*account).to(Address::random()).value(1337u64);
+        let res = provider.send_transaction(tx, None).await.unwrap().await.unwrap().unwrap();
+        assert_eq!(res.from, *account);
+    }
+
+    api.anvil_stop_impersonating_accounts(vec![accounts[0].to_alloy()]).await.unwrap();
+    assert!(!api.accounts().unwrap().contains(&accounts[0].to_alloy()));
+    assert!(api.accounts().unwrap().contains(&accounts[1].to_alloy()));
+
+    api.anvil_stop_impersonating_all().await.unwrap();
+    for account in &accounts {
+        assert!(!api.accounts().unwrap().contains(&account.to_alloy()));
+        let tx = TransactionRequest::new().from(*account).to(Address::random()).value(1337u64);
+        provider.send_transaction(tx, None).await.unwrap_err();
+    }
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn can_auto_impersonate_account() {
     let (api, handle) = spawn(NodeConfig::test()).await;
@@ -305,6 +428,67 @@ async fn test_set_next_timestamp() {
     assert!(next.timestamp > block.timestamp);
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn test_increase_time_applies_to_following_blocks() {
+    let (api, handle) = spawn(NodeConfig::test()).await;
+    let provider = handle.ethers_http_provider();
+
+    let offset = api.anvil_get_time_offset().unwrap();
+    let next_timestamp = api.evm_increase_time(U256::from(1000).to_alloy()).await.unwrap();
+    assert_eq!(api.anvil_get_time_offset().unwrap(), offset + 1000);
+
+    api.evm_mine(None).await.unwrap();
+    let block = provider.get_block(BlockNumber::Latest).await.unwrap().unwrap();
+    // the clock may have advanced in the meantime
+    assert!(block.timestamp.as_u64() >= next_timestamp);
+    assert!(block.timestamp.as_u64() <= next_timestamp + 1);
+
+    // the offset still applies to the next block
+    api.evm_mine(None).await.unwrap();
+    let next = provider.get_block(BlockNumber::Latest).await.unwrap().unwrap();
+    let now = SystemTime::now().duration_since(SystemTime::UNIX_EPOCH).unwrap().as_secs();
+    assert!(next.timestamp.as_u64() >= now + 999);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_set_next_timestamp_overrides_increased_time() {
+    let (api, handle) = spawn(NodeConfig::test()).await;
+    let provider = handle.ethers_http_provider();
+
+    let now = SystemTime::now().duration_since(SystemTime::UNIX_EPOCH).unwrap();
+    let next_timestamp = now.as_secs() + 60;
+
+    api.evm_increase_time(U256::from(1000).to_alloy()).await.unwrap();
+    api.evm_set_next_block_timestamp(next_timestamp).unwrap();
+    api.evm_mine(None).await.unwrap();
+    let block = provider.get_block(BlockNumber::Latest).await.unwrap().unwrap();
+    assert_eq!(block.timestamp.as_u64(), next_timestamp);
+
+    // the increased time isn't applied again
+    api.evm_mine(None).await.unwrap();
+    let next = provider.get_block(BlockNumber::Latest).await.unwrap().unwrap();
+    assert!(next.timestamp > block.timestamp);
+    assert!(next.timestamp.as_u64() < next_timestamp + 1000);
+
+    // timestamps that aren't greater than the last one are rejected
+    let err = api.evm_set_next_block_timestamp(next.timestamp.as_u64()).unwrap_err();
+    assert!(err.to_string().contains("lower than or equal to previous block's timestamp"));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_allow_non_increasing_timestamps() {
+    let (api, handle) = spawn(NodeConfig::test().with_allow_non_increasing_timestamps(true)).await;
+    let provider = handle.ethers_http_provider();
+
+    api.evm_mine(None).await.unwrap();
+    let block = provider.get_block(BlockNumber::Latest).await.unwrap().unwrap();
+
+    api.evm_set_next_block_timestamp(block.timestamp.as_u64() - 10).unwrap();
+    api.evm_mine(None).await.unwrap();
+    let next = provider.get_block(BlockNumber::Latest).await.unwrap().unwrap();
+    assert_eq!(next.timestamp, block.timestamp - 10);
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn test_evm_set_time() {
     let (api, handle) = spawn(NodeConfig::test()).await;
@@ -329,6 +513,27 @@ async fn test_evm_set_time() {
     assert!(next.timestamp > block.timestamp);
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn test_set_time_resets_offset() {
+    let (api, handle) = spawn(NodeConfig::test()).await;
+    let provider = handle.ethers_http_provider();
+
+    api.evm_increase_time(U256::from(1000).to_alloy()).await.unwrap();
+
+    let now = SystemTime::now().duration_since(SystemTime::UNIX_EPOCH).unwrap().as_secs();
+    let timestamp = now + 365 * 24 * 60 * 60;
+    let delta = api.evm_set_time(timestamp).unwrap();
+    assert!(((timestamp - now) as i64 - 1000 - delta).abs() <= 1);
+
+    // auto mined blocks continue from the new base
+    let from = handle.dev_accounts().next().unwrap();
+    let tx = TransactionRequest::new().from(from).to(Address::random()).value(1u64);
+    provider.send_transaction(tx, None).await.unwrap().await.unwrap().unwrap();
+    let block = provider.get_block(BlockNumber::Latest).await.unwrap().unwrap();
+    assert!(block.timestamp.as_u64() >= timestamp);
+    assert!(block.timestamp.as_u64() <= timestamp + 1);
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn test_evm_set_time_in_past() {
     let (api, handle) = spawn(NodeConfig::test()).await;
@@ -438,11 +643,14 @@ async fn can_get_node_info() {
     let block = provider.get_block(block_number).await.unwrap().unwrap();
 
     let expected_node_info = NodeInfo {
+        client_version: CLIENT_VERSION.to_string(),
         current_block_number: U64([0]).to_alloy(),
         current_block_timestamp: 1,
         current_block_hash: block.hash.unwrap().to_alloy(),
         hard_fork: SpecId::SHANGHAI,
         transaction_order: "fees".to_owned(),
+        mining_mode: MiningModeInfo::Auto,
+        accounts_count: 10,
         environment: NodeEnvironment {
             base_fee: U256::from_str("0x3b9aca00").unwrap().to_alloy(),
             chain_id: 0x7a69,
@@ -484,6 +692,20 @@ async fn can_get_metadata() {
     assert_eq!(metadata, expected_metadata);
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn metadata_identifies_instance() {
+    let (api, handle) = spawn(NodeConfig::test()).await;
+    let (other_api, _other_handle) = spawn(NodeConfig::test()).await;
+    let provider = handle.ethers_http_provider();
+
+    let metadata = api.anvil_metadata().await.unwrap();
+    assert_eq!(api.anvil_metadata().await.unwrap(), metadata);
+    assert_ne!(other_api.anvil_metadata().await.unwrap().instance_id, metadata.instance_id);
+
+    // retrieving the metadata doesn't change the chain
+    assert_eq!(provider.get_block_number().await.unwrap().as_u64(), metadata.latest_block_number);
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn can_get_metadata_on_fork() {
     let (api, handle) =
@@ -521,7 +743,9 @@ async fn metadata_changes_on_reset() {
     let metadata = api.anvil_metadata().await.unwrap();
     let instance_id = metadata.instance_id;
 
-    api.anvil_reset(Some(Forking { json_rpc_url: None, block_number: None })).await.unwrap();
+    api.anvil_reset(Some(Forking { json_rpc_url: None, block_number: None, ..Default::default() }))
+        .await
+        .unwrap();
 
     let new_metadata = api.anvil_metadata().await.unwrap();
     let new_instance_id = new_metadata.instance_id;
@@ -574,6 +798,27 @@ async fn test_set_chain_id() {
     assert_eq!(chain_id, U256::from(1234));
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn test_set_chain_id_removes_transactions_of_previous_chain() {
+    let (api, handle) = spawn(NodeConfig::test()).await;
+    let provider = handle.ethers_http_provider();
+    api.anvil_set_auto_mine(false).await.unwrap();
+
+    let from = handle.dev_accounts().next().unwrap();
+    let tx = TransactionRequest::new().from(from).to(Address::random()).value(1u64);
+    provider.send_transaction(tx.clone(), None).await.unwrap();
+    assert_eq!(provider.txpool_status().await.unwrap().pending, U64::from(1));
+
+    api.anvil_set_chain_id(1234).await.unwrap();
+    assert_eq!(provider.txpool_status().await.unwrap().pending, U64::from(0));
+
+    // newly signed transactions use the new chain id
+    let pending = provider.send_transaction(tx, None).await.unwrap();
+    api.mine_one().await;
+    let tx = provider.get_transaction(*pending).await.unwrap().unwrap();
+    assert_eq!(tx.chain_id, Some(U256::from(1234)));
+}
+
 // <https://github.com/foundry-rs/foundry/issues/6096>
 #[tokio::test(flavor = "multi_thread")]
 async fn test_fork_revert_next_block_timestamp() {
@@ -618,9 +863,10 @@ async fn test_fork_revert_call_latest_block_timestamp() {
         multicall.get_current_block_timestamp().await.unwrap(),
         latest_block.header.timestamp.to_ethers()
     );
+    // after the merge `DIFFICULTY` reads the prevrandao
     assert_eq!(
         multicall.get_current_block_difficulty().await.unwrap(),
-        latest_block.header.difficulty.to_ethers()
+        U256::from_big_endian(latest_block.header.mix_hash.unwrap_or_default().as_slice())
     );
     assert_eq!(
         multicall.get_current_block_gas_limit().await.unwrap(),
@@ -631,3 +877,336 @@ async fn test_fork_revert_call_latest_block_timestamp() {
         latest_block.header.miner.to_ethers()
     );
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn can_profile_gas_per_opcode() {
+    let (api, _handle) = spawn(NodeConfig::test()).await;
+
+    // PUSH1 0x01 PUSH1 0x02 ADD POP
+    let init_code = alloy_primitives::Bytes::from(hex::decode("600160020150").unwrap());
+    let report = api
+        .anvil_gas_profile(CallRequest { input: init_code.into(), ..Default::default() }, None)
+        .await
+        .unwrap();
+
+    let gas = |opcode: &str| {
+        report.iter().find(|op| op.opcode == opcode).map(|op| (op.gas, op.count)).unwrap()
+    };
+    assert_eq!(report[0].opcode, "PUSH1");
+    assert_eq!(gas("PUSH1"), (6, 2));
+    assert_eq!(gas("ADD"), (3, 1));
+    assert_eq!(gas("POP"), (2, 1));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn can_set_storage_batch() {
+    let (api, _handle) = spawn(NodeConfig::test()).await;
+
+    let first = Address::random().to_alloy();
+    let second = Address::random().to_alloy();
+    let value = H256::from_uint(&U256::from(1337u64)).to_alloy();
+    let storage = StorageBatch::from([
+        (
+            first,
+            BTreeMap::from([(U256::zero().to_alloy(), value), (U256::one().to_alloy(), value)]),
+        ),
+        (second, BTreeMap::from([(U256::from(2u64).to_alloy(), value)])),
+    ]);
+
+    assert!(api.anvil_set_storage_batch(storage).await.unwrap());
+    for (address, slot) in [(first, 0u64), (first, 1), (second, 2)] {
+        let storage = api.storage_at(address, U256::from(slot).to_alloy(), None).await.unwrap();
+        assert_eq!(storage, value);
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn can_mine_blocks_with_interval() {
+    let (api, handle) = spawn(NodeConfig::test()).await;
+    let provider = handle.ethers_http_provider();
+
+    api.anvil_mine(Some(5u64.into()), Some(12u64.into())).await.unwrap();
+
+    let mut timestamps = Vec::new();
+    for num in 1..=5u64 {
+        timestamps.push(provider.get_block(num).await.unwrap().unwrap().timestamp.as_u64());
+    }
+    for window in timestamps.windows(2) {
+        assert_eq!(window[1] - window[0], 12);
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn can_reorg_with_transactions() {
+    let (api, handle) = spawn(NodeConfig::test()).await;
+    let provider = handle.ethers_http_provider();
+
+    let wallet = handle.dev_wallets().next().unwrap();
+    let from = wallet.address();
+    let to = Address::random();
+
+    let tx = TransactionRequest::new().from(from).to(to).value(1u64);
+    let receipt = provider.send_transaction(tx, None).await.unwrap().await.unwrap().unwrap();
+    api.anvil_mine(Some(1u64.into()), None).await.unwrap();
+    assert_eq!(provider.get_block_number().await.unwrap(), 2u64.into());
+
+    // replace both blocks, the alternative transaction reuses the nonce of the removed one
+    let alternative: TypedTransaction = TransactionRequest::new()
+        .from(from)
+        .to(to)
+        .value(2u64)
+        .nonce(0u64)
+        .gas(21_000u64)
+        .gas_price(provider.get_gas_price().await.unwrap())
+        .chain_id(provider.get_chainid().await.unwrap().as_u64())
+        .into();
+    let signature = wallet.sign_transaction(&alternative).await.unwrap();
+    let raw = alternative.rlp_signed(&signature);
+    api.anvil_reorg(2u64.into(), vec![alloy_primitives::Bytes::from(raw.to_vec())]).await.unwrap();
+
+    assert_eq!(provider.get_block_number().await.unwrap(), 2u64.into());
+    assert!(provider.get_transaction_receipt(receipt.transaction_hash).await.unwrap().is_none());
+
+    let block = provider.get_block(1u64).await.unwrap().unwrap();
+    assert_ne!(block.hash, receipt.block_hash);
+    assert_eq!(block.transactions, vec![alternative.hash(&signature)]);
+    assert_eq!(provider.get_balance(to, None).await.unwrap(), 2u64.into());
+
+    // can't remove more blocks than there are
+    let err = api.anvil_reorg(3u64.into(), vec![]).await.unwrap_err();
+    assert!(err.to_string().contains("cannot roll back past the genesis block"), "{err}");
+    assert!(api.anvil_reorg(0u64.into(), vec![]).await.is_err());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn can_rollback_blocks() {
+    let (api, handle) = spawn(NodeConfig::test()).await;
+    let provider = handle.ethers_http_provider();
+
+    let from = handle.dev_wallets().next().unwrap().address();
+    let to = Address::random();
+
+    let tx = TransactionRequest::new().from(from).to(to).value(1u64);
+    let receipt = provider.send_transaction(tx, None).await.unwrap().await.unwrap().unwrap();
+    api.anvil_mine(Some(1u64.into()), None).await.unwrap();
+    assert_eq!(provider.get_block_number().await.unwrap(), 2u64.into());
+
+    // keep the transaction in the pool once it's returned
+    api.anvil_set_auto_mine(false).await.unwrap();
+    api.anvil_rollback(2u64.into()).await.unwrap();
+
+    assert_eq!(provider.get_block_number().await.unwrap(), 0u64.into());
+    assert!(provider.get_block(1u64).await.unwrap().is_none());
+    assert!(provider.get_transaction_receipt(receipt.transaction_hash).await.unwrap().is_none());
+    assert_eq!(provider.get_balance(to, None).await.unwrap(), 0u64.into());
+    assert_eq!(provider.get_transaction_count(from, None).await.unwrap(), 0u64.into());
+
+    let pending = provider.get_transaction(receipt.transaction_hash).await.unwrap().unwrap();
+    assert!(pending.block_number.is_none());
+
+    // can't roll back past genesis
+    let err = api.anvil_rollback(1u64.into()).await.unwrap_err();
+    assert!(err.to_string().contains("cannot roll back past the genesis block"), "{err}");
+    assert!(api.anvil_rollback(0u64.into()).await.is_err());
+
+    // the returned transaction is mined again
+    api.mine_one().await;
+    let receipt =
+        provider.get_transaction_receipt(receipt.transaction_hash).await.unwrap().unwrap();
+    assert_eq!(receipt.block_number, Some(1u64.into()));
+    assert_eq!(provider.get_balance(to, None).await.unwrap(), 1u64.into());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn can_revert_named_snapshots() {
+    let (api, _handle) = spawn(NodeConfig::test()).await;
+
+    let setup = api.anvil_snapshot_named("setup".to_string()).await.unwrap();
+    api.mine_one().await;
+    let deployed = api.anvil_snapshot_named("deployed".to_string()).await.unwrap();
+    api.mine_one().await;
+    api.anvil_snapshot_named("late".to_string()).await.unwrap();
+    api.mine_one().await;
+    assert_eq!(api.best_number(), 3);
+
+    let snapshots = api.anvil_list_snapshots().unwrap();
+    assert_eq!(snapshots.len(), 3);
+    assert_eq!(snapshots["setup"], setup);
+    assert_eq!(snapshots["deployed"], deployed);
+
+    // reverting also removes the snapshots that were created later
+    assert!(api.anvil_revert_named("deployed").await.unwrap());
+    assert_eq!(api.best_number(), 1);
+    let snapshots = api.anvil_list_snapshots().unwrap();
+    assert_eq!(snapshots.into_iter().collect::<Vec<_>>(), vec![("setup".to_string(), setup)]);
+    assert!(!api.anvil_revert_named("late").await.unwrap());
+
+    // numeric reverts also invalidate names
+    assert!(api.evm_revert(setup).await.unwrap());
+    assert_eq!(api.best_number(), 0);
+    assert!(api.anvil_list_snapshots().unwrap().is_empty());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn can_add_derived_account() {
+    let gen = AccountGenerator::new(2)
+        .phrase("test test test test test test test test test test test junk")
+        .derivation_path("m/44'/60'/1'/0");
+    let expected = AccountGenerator::new(3)
+        .phrase("test test test test test test test test test test test junk")
+        .derivation_path("m/44'/60'/1'/0")
+        .gen()[2]
+        .address();
+    let (api, handle) = spawn(NodeConfig::test().with_account_generator(gen)).await;
+    let provider = handle.ethers_http_provider();
+
+    let account = api.anvil_add_account().await.unwrap();
+    assert_eq!(account, expected.to_alloy());
+    assert_eq!(provider.get_accounts().await.unwrap().len(), 3);
+    assert_eq!(provider.get_balance(expected, None).await.unwrap(), handle.genesis_balance());
+
+    // the new account can sign transactions
+    let tx = TransactionRequest::new().from(expected).to(Address::random()).value(1337u64);
+    let receipt = provider.send_transaction(tx, None).await.unwrap().await.unwrap().unwrap();
+    assert_eq!(receipt.from, expected);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn can_set_hardfork() {
+    let (api, handle) = spawn(NodeConfig::test().with_hardfork(Some(Hardfork::Berlin))).await;
+    let provider = handle.ethers_http_provider();
+
+    api.mine_one().await;
+    let berlin_block = provider.get_block(BlockNumber::Latest).await.unwrap().unwrap();
+    assert!(berlin_block.base_fee_per_gas.is_none());
+
+    api.anvil_set_hardfork("london".to_string()).await.unwrap();
+    assert_eq!(api.anvil_node_info().await.unwrap().hard_fork, SpecId::LONDON);
+
+    // EIP-1559 transactions are accepted from now on
+    let from = handle.dev_wallets().next().unwrap().address();
+    let tx = Eip1559TransactionRequest::new().to(Address::random()).value(1u64).from(from);
+    let receipt = provider.send_transaction(tx, None).await.unwrap().await.unwrap().unwrap();
+    let london_block = provider.get_block(receipt.block_number.unwrap()).await.unwrap().unwrap();
+    assert!(london_block.base_fee_per_gas.is_some());
+
+    // mined blocks keep their hardfork
+    let block = provider.get_block(berlin_block.number.unwrap()).await.unwrap().unwrap();
+    assert!(block.base_fee_per_gas.is_none());
+
+    assert!(api.anvil_set_hardfork("unknown".to_string()).await.is_err());
+    assert_eq!(api.anvil_node_info().await.unwrap().hard_fork, SpecId::LONDON);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn can_get_and_set_nonce() {
+    let (api, handle) = spawn(NodeConfig::test()).await;
+    let provider = handle.ethers_http_provider();
+    let from = handle.dev_accounts().next().unwrap();
+
+    let tx = TransactionRequest::new().from(from).to(Address::random()).value(1337u64);
+    provider.send_transaction(tx, None).await.unwrap().await.unwrap().unwrap();
+    assert_eq!(api.anvil_get_nonce(from.to_alloy()).await.unwrap().to::<u64>(), 1);
+
+    // the nonce can't be lowered
+    api.anvil_set_nonce(from.to_alloy(), U256::zero().to_alloy()).await.unwrap_err();
+
+    api.anvil_set_nonce(from.to_alloy(), U256::from(5).to_alloy()).await.unwrap();
+    assert_eq!(api.anvil_get_nonce(from.to_alloy()).await.unwrap().to::<u64>(), 5);
+
+    // ready transactions in the pool are accounted for, while a gapped one isn't
+    api.anvil_set_auto_mine(false).await.unwrap();
+    let tx = TransactionRequest::new().from(from).to(Address::random()).value(1337u64).nonce(5);
+    provider.send_transaction(tx, None).await.unwrap();
+    let tx = TransactionRequest::new().from(from).to(Address::random()).value(1337u64).nonce(7);
+    provider.send_transaction(tx, None).await.unwrap();
+    assert_eq!(api.anvil_get_nonce(from.to_alloy()).await.unwrap().to::<u64>(), 6);
+    assert_eq!(provider.get_transaction_count(from, None).await.unwrap().as_u64(), 5);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn can_set_coinbase() {
+    let (api, handle) = spawn(NodeConfig::test()).await;
+    let provider = handle.ethers_http_provider();
+    let wallet = handle.dev_wallets().next().unwrap();
+    let client = Arc::new(SignerMiddleware::new(provider.clone(), wallet));
+    let multicall = MulticallContract::deploy(client.clone(), ()).unwrap().send().await.unwrap();
+
+    let coinbase = Address::random();
+    api.anvil_set_coinbase(coinbase.to_alloy()).await.unwrap();
+
+    let tip = 1_000_000_000u64;
+    let tx = Eip1559TransactionRequest::new()
+        .to(Address::random())
+        .value(1u64)
+        .max_fee_per_gas(100_000_000_000u64)
+        .max_priority_fee_per_gas(tip);
+    let receipt = client.send_transaction(tx, None).await.unwrap().await.unwrap().unwrap();
+    let block = provider.get_block(receipt.block_number.unwrap()).await.unwrap().unwrap();
+    assert_eq!(block.author, Some(coinbase));
+
+    // the priority fees are credited to the coinbase
+    let base_fee = block.base_fee_per_gas.unwrap();
+    assert_eq!(receipt.effective_gas_price.unwrap() - base_fee, tip.into());
+    let balance = provider.get_balance(coinbase, None).await.unwrap();
+    assert_eq!(balance, receipt.gas_used.unwrap() * tip);
+
+    // `block.coinbase` reports the coinbase
+    assert_eq!(multicall.get_current_block_coinbase().call().await.unwrap(), coinbase);
+}
+
+/// Deploys a contract that stores the value of `PREVRANDAO`, `DIFFICULTY` before the merge, in
+/// slot 0 whenever it's called
+async fn deploy_prevrandao_recorder<M: Middleware>(provider: &M, from: Address) -> Address {
+    // runtime code: PREVRANDAO PUSH1 0 SSTORE STOP
+    let code = hex::decode("6005600c60003960056000f34460005500").unwrap();
+    let tx = TransactionRequest::new().from(from).data(code);
+    let receipt = provider.send_transaction(tx, None).await.unwrap().await.unwrap().unwrap();
+    receipt.contract_address.unwrap()
+}
+
+/// Calls the recorder and returns the recorded value and the mix hash of the block of the call
+async fn record_prevrandao<M: Middleware>(
+    provider: &M,
+    from: Address,
+    recorder: Address,
+) -> (H256, H256) {
+    let tx = TransactionRequest::new().from(from).to(recorder);
+    let receipt = provider.send_transaction(tx, None).await.unwrap().await.unwrap().unwrap();
+    let block = provider.get_block(receipt.block_hash.unwrap()).await.unwrap().unwrap();
+    let value = provider.get_storage_at(recorder, H256::zero(), None).await.unwrap();
+    (value, block.mix_hash.unwrap())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn can_set_prevrandao() {
+    let (api, handle) = spawn(NodeConfig::test()).await;
+    let provider = handle.ethers_http_provider();
+    let from = handle.dev_wallets().next().unwrap().address();
+    let recorder = deploy_prevrandao_recorder(&provider, from).await;
+
+    api.anvil_set_prevrandao(42u64.into()).await.unwrap();
+    let pinned = H256::from_low_u64_be(42);
+    assert_eq!(record_prevrandao(&provider, from, recorder).await, (pinned, pinned));
+    assert_eq!(record_prevrandao(&provider, from, recorder).await, (pinned, pinned));
+
+    // the value of the next block only applies to one block
+    api.anvil_set_next_block_prevrandao(7u64.into()).await.unwrap();
+    let next = H256::from_low_u64_be(7);
+    assert_eq!(record_prevrandao(&provider, from, recorder).await, (next, next));
+    assert_eq!(record_prevrandao(&provider, from, recorder).await, (pinned, pinned));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn can_set_difficulty_before_the_merge() {
+    let (api, handle) = spawn(NodeConfig::test().with_hardfork(Some(Hardfork::London))).await;
+    let provider = handle.ethers_http_provider();
+    let from = handle.dev_wallets().next().unwrap().address();
+    let recorder = deploy_prevrandao_recorder(&provider, from).await;
+
+    api.anvil_set_prevrandao(1337u64.into()).await.unwrap();
+    let (value, _) = record_prevrandao(&provider, from, recorder).await;
+    assert_eq!(value, H256::from_low_u64_be(1337));
+    let block = provider.get_block(BlockNumber::Latest).await.unwrap().unwrap();
+    assert_eq!(block.difficulty, 1337u64.into());
+}
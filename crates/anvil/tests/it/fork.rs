@@ -3,11 +3,17 @@
 use crate::{abi::*, utils};
 use alloy_primitives::U256 as rU256;
 use alloy_rpc_types::{BlockNumberOrTag, CallRequest};
-use anvil::{eth::EthApi, spawn, NodeConfig, NodeHandle};
-use anvil_core::types::Forking;
+use anvil::{
+    eth::{backend::db::SerializableState, EthApi},
+    spawn, NodeConfig, NodeHandle,
+};
+use anvil_core::{
+    eth::{state::AccountOverride, transaction::to_alloy_state_override},
+    types::{DumpStateOptions, Forking},
+};
 use ethers::{
     core::rand,
-    prelude::{Bytes, LocalWallet, Middleware, SignerMiddleware},
+    prelude::{Bytes, Filter, LocalWallet, Middleware, SignerMiddleware},
     providers::{Http, Provider},
     signers::Signer,
     types::{
@@ -15,6 +21,7 @@ use ethers::{
         U256,
     },
 };
+use flate2::read::GzDecoder;
 use foundry_common::{
     provider::ethers::get_http_provider,
     rpc,
@@ -23,7 +30,7 @@ use foundry_common::{
 };
 use foundry_config::Config;
 use futures::StreamExt;
-use std::{sync::Arc, time::Duration};
+use std::{collections::HashMap, io::Read, sync::Arc, time::Duration};
 
 const BLOCK_NUMBER: u64 = 14_608_400u64;
 const DEAD_BALANCE_AT_BLOCK_NUMBER: u128 = 12_556_069_338_441_120_059_867u128;
@@ -209,6 +216,7 @@ async fn test_fork_reset() {
     api.anvil_reset(Some(Forking {
         json_rpc_url: None,
         block_number: Some(block_number.as_u64()),
+        ..Default::default()
     }))
     .await
     .unwrap();
@@ -246,6 +254,7 @@ async fn test_fork_reset_setup() {
     api.anvil_reset(Some(Forking {
         json_rpc_url: Some(rpc::next_http_archive_rpc_endpoint()),
         block_number: Some(BLOCK_NUMBER),
+        ..Default::default()
     }))
     .await
     .unwrap();
@@ -257,6 +266,51 @@ async fn test_fork_reset_setup() {
     assert_eq!(remote_balance, DEAD_BALANCE_AT_BLOCK_NUMBER.into());
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn test_fork_reset_to_unreachable_endpoint() {
+    let (api, handle) = spawn(fork_config()).await;
+    let provider = handle.ethers_http_provider();
+
+    let err = api
+        .anvil_reset(Some(Forking {
+            json_rpc_url: Some("http://127.0.0.1:1".to_string()),
+            block_number: Some(BLOCK_NUMBER),
+            ..Default::default()
+        }))
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("Failed to connect to fork endpoint"), "{err}");
+
+    // the current fork is still usable
+    let block_number = provider.get_block_number().await.unwrap();
+    assert_eq!(block_number, BLOCK_NUMBER.into());
+    let dead_addr: Address = "000000000000000000000000000000000000dEaD".parse().unwrap();
+    let balance = provider.get_balance(dead_addr, None).await.unwrap();
+    assert_eq!(balance, DEAD_BALANCE_AT_BLOCK_NUMBER.into());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_fork_reset_keeps_impersonated_accounts() {
+    let (api, _handle) = spawn(fork_config()).await;
+    let impersonated = Address::random().to_alloy();
+
+    api.anvil_impersonate_account(impersonated).await.unwrap();
+    api.anvil_reset(Some(Forking {
+        json_rpc_url: Some(rpc::next_http_archive_rpc_endpoint()),
+        block_number: Some(BLOCK_NUMBER - 1),
+        keep_impersonated_accounts: true,
+    }))
+    .await
+    .unwrap();
+    assert_eq!(api.block_number().unwrap(), rU256::from(BLOCK_NUMBER - 1));
+    assert!(api.accounts().unwrap().contains(&impersonated));
+
+    api.anvil_reset(Some(Forking { block_number: Some(BLOCK_NUMBER), ..Default::default() }))
+        .await
+        .unwrap();
+    assert!(!api.accounts().unwrap().contains(&impersonated));
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn test_fork_snapshotting() {
     let (api, handle) = spawn(fork_config()).await;
@@ -520,9 +574,13 @@ async fn test_fork_timestamp() {
 
     let start = std::time::Instant::now();
     // reset to check timestamp works after resetting
-    api.anvil_reset(Some(Forking { json_rpc_url: None, block_number: Some(BLOCK_NUMBER) }))
-        .await
-        .unwrap();
+    api.anvil_reset(Some(Forking {
+        json_rpc_url: None,
+        block_number: Some(BLOCK_NUMBER),
+        ..Default::default()
+    }))
+    .await
+    .unwrap();
     let block = provider.get_block(BLOCK_NUMBER).await.unwrap().unwrap();
     assert_eq!(block.timestamp.as_u64(), BLOCK_TIMESTAMP);
 
@@ -536,9 +594,13 @@ async fn test_fork_timestamp() {
 
     // ensure that after setting a timestamp manually, then next block time is correct
     let start = std::time::Instant::now();
-    api.anvil_reset(Some(Forking { json_rpc_url: None, block_number: Some(BLOCK_NUMBER) }))
-        .await
-        .unwrap();
+    api.anvil_reset(Some(Forking {
+        json_rpc_url: None,
+        block_number: Some(BLOCK_NUMBER),
+        ..Default::default()
+    }))
+    .await
+    .unwrap();
     api.evm_set_next_block_timestamp(BLOCK_TIMESTAMP + 1).unwrap();
     let tx = TransactionRequest::new().to(Address::random()).value(1337u64).from(from);
     let _tx = provider.send_transaction(tx, None).await.unwrap().await.unwrap().unwrap();
@@ -789,6 +851,31 @@ async fn test_fork_call() {
     assert_eq!(res0, res1.to_ethers());
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn test_fork_call_with_state_override_before_fork() {
+    let (api, _handle) = spawn(fork_config()).await;
+    let target = Address::random();
+
+    // NUMBER PUSH1 0 MSTORE PUSH1 32 PUSH1 0 RETURN
+    let code: Bytes = "0x4360005260206000f3".parse().unwrap();
+    let overrides =
+        HashMap::from([(target, AccountOverride { code: Some(code), ..Default::default() })]);
+
+    let block_number = BLOCK_NUMBER - 1;
+    let res = api
+        .call(
+            CallRequest { to: Some(target.to_alloy()), ..Default::default() },
+            Some(BlockNumberOrTag::Number(block_number).into()),
+            Some(to_alloy_state_override(overrides)),
+        )
+        .await
+        .unwrap();
+
+    // executed against the block of the remote client
+    assert_eq!(rU256::from_be_slice(&res), rU256::from(block_number));
+    assert!(api.get_code(target.to_alloy(), None).await.unwrap().is_empty());
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn test_fork_block_timestamp() {
     let (api, _) = spawn(fork_config()).await;
@@ -1046,6 +1133,7 @@ async fn test_fork_reset_moonbeam() {
     api.anvil_reset(Some(Forking {
         json_rpc_url: Some("https://rpc.api.moonbeam.network".to_string()),
         block_number: None,
+        ..Default::default()
     }))
     .await
     .unwrap();
@@ -1068,9 +1156,13 @@ async fn test_fork_reset_basefee() {
     assert_eq!(latest.base_fee_per_gas.unwrap(), 59455969592u64.into());
 
     // now reset to block 18835000 -1
-    api.anvil_reset(Some(Forking { json_rpc_url: None, block_number: Some(18835000u64 - 1) }))
-        .await
-        .unwrap();
+    api.anvil_reset(Some(Forking {
+        json_rpc_url: None,
+        block_number: Some(18835000u64 - 1),
+        ..Default::default()
+    }))
+    .await
+    .unwrap();
 
     api.mine_one().await;
     let latest = api.block_by_number(BlockNumber::Latest).await.unwrap().unwrap();
@@ -1078,3 +1170,89 @@ async fn test_fork_reset_basefee() {
     // basefee of the forked block: <https://etherscan.io/block/18835000>
     assert_eq!(latest.base_fee_per_gas.unwrap(), 59017001138u64.into());
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn can_dump_and_load_state_diff() {
+    let (origin_api, origin_handle) = spawn(NodeConfig::test()).await;
+    let origin_provider = origin_handle.ethers_http_provider();
+    let account = origin_handle.dev_accounts().next().unwrap();
+    let unchanged = Address::random();
+    let tx = TransactionRequest::new().from(account).to(unchanged).value(1337u64);
+    origin_provider.send_transaction(tx, None).await.unwrap().await.unwrap().unwrap();
+
+    let fork = || NodeConfig::test().with_eth_rpc_url(Some(origin_handle.http_endpoint()));
+    let (fork_api, fork_handle) = spawn(fork()).await;
+    let fork_provider = fork_handle.ethers_http_provider();
+
+    // only fetched from the origin
+    assert_eq!(fork_provider.get_balance(unchanged, None).await.unwrap(), 1337u64.into());
+
+    let to = Address::random();
+    let tx = TransactionRequest::new().from(account).to(to).value(42u64);
+    fork_provider.send_transaction(tx, None).await.unwrap().await.unwrap().unwrap();
+
+    let dump = fork_api.anvil_dump_state(Some(DumpStateOptions { diff: true })).await.unwrap();
+    let mut json = Vec::new();
+    GzDecoder::new(&dump.0[..]).read_to_end(&mut json).unwrap();
+    let state: SerializableState = serde_json::from_slice(&json).unwrap();
+    assert_eq!(state.fork.unwrap().number, 1);
+    assert!(state.accounts.contains_key(&to.to_alloy()));
+    assert!(!state.accounts.contains_key(&unchanged.to_alloy()));
+
+    // a node that's forked off the same block replays the changes
+    let (api, handle) = spawn(fork()).await;
+    assert!(api.anvil_load_state(dump.clone()).await.unwrap());
+    assert_eq!(handle.ethers_http_provider().get_balance(to, None).await.unwrap(), 42u64.into());
+
+    // the origin is not forked
+    assert!(origin_api.anvil_load_state(dump).await.is_err());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn limits_block_range_of_fork_logs() {
+    let (origin_api, origin_handle) = spawn(NodeConfig::test()).await;
+    for _ in 0..10 {
+        origin_api.mine_one().await;
+    }
+
+    let (api, handle) = spawn(
+        NodeConfig::test()
+            .with_eth_rpc_url(Some(origin_handle.http_endpoint()))
+            .with_max_logs_block_range(Some(5)),
+    )
+    .await;
+    let provider = handle.ethers_http_provider();
+    for _ in 0..10 {
+        api.mine_one().await;
+    }
+
+    let err = provider.get_logs(&Filter::new().from_block(0u64)).await.unwrap_err();
+    assert!(err.to_string().contains("exceeds the maximum of 5 blocks"), "{err}");
+    assert!(provider.get_logs(&Filter::new().from_block(8u64).to_block(12u64)).await.is_ok());
+
+    // locally mined blocks are not limited
+    assert!(provider.get_logs(&Filter::new().from_block(11u64)).await.is_ok());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn cannot_rollback_past_fork_block() {
+    let (origin_api, origin_handle) = spawn(NodeConfig::test()).await;
+    origin_api.mine_one().await;
+    origin_api.mine_one().await;
+
+    let (api, _handle) =
+        spawn(NodeConfig::test().with_eth_rpc_url(Some(origin_handle.http_endpoint()))).await;
+    api.mine_one().await;
+    assert_eq!(api.block_number().unwrap(), rU256::from(3));
+
+    let err = api.anvil_rollback(rU256::from(2)).await.unwrap_err();
+    assert!(err.to_string().contains("cannot roll back past the fork block 2"), "{err}");
+    assert_eq!(api.block_number().unwrap(), rU256::from(3));
+    let err = api.anvil_reorg(rU256::from(2), vec![]).await.unwrap_err();
+    assert!(err.to_string().contains("cannot roll back past the fork block 2"), "{err}");
+    assert_eq!(api.block_number().unwrap(), rU256::from(3));
+
+    // the blocks after the fork block can be removed
+    api.anvil_rollback(rU256::from(1)).await.unwrap();
+    assert_eq!(api.block_number().unwrap(), rU256::from(2));
+}
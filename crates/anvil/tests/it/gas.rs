@@ -1,15 +1,24 @@
 //! Gas related tests
 
 use alloy_primitives::U256;
-use anvil::{eth::fees::INITIAL_BASE_FEE, spawn, NodeConfig};
+use alloy_rpc_types::BlockNumberOrTag;
+use anvil::{
+    eth::fees::{GasPriceOracle, INITIAL_BASE_FEE},
+    spawn, NodeConfig,
+};
+use anvil_core::types::BaseFeeParamsOptions;
 use ethers::{
     prelude::Middleware,
     types::{
-        transaction::eip2718::TypedTransaction, Address, BlockNumber, Eip1559TransactionRequest,
-        TransactionRequest,
+        transaction::{
+            eip2718::TypedTransaction,
+            eip2930::{AccessList, Eip2930TransactionRequest},
+        },
+        Address, BlockNumber, Eip1559TransactionRequest, TransactionRequest, U256 as EthersU256,
     },
 };
 use foundry_common::types::ToAlloy;
+use std::time::Duration;
 
 const GAS_TRANSFER: u64 = 21_000u64;
 
@@ -115,3 +124,241 @@ async fn test_tip_above_fee_cap() {
         .to_string()
         .contains("max priority fee per gas higher than max fee per gas"));
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_fee_history_rewards() {
+    let base_fee = INITIAL_BASE_FEE;
+    let (api, handle) = spawn(NodeConfig::test().with_base_fee(Some(base_fee.to_alloy()))).await;
+    let provider = handle.ethers_http_provider();
+    api.anvil_set_auto_mine(false).await.unwrap();
+
+    // two transactions with different tips in the same block, followed by an empty block
+    let accounts: Vec<_> = handle.dev_accounts().collect();
+    for (from, tip) in [(accounts[0], 3 * base_fee), (accounts[1], base_fee)] {
+        let tx = TypedTransaction::Eip1559(
+            Eip1559TransactionRequest::new()
+                .from(from)
+                .to(Address::random())
+                .value(1337u64)
+                .max_fee_per_gas(10 * base_fee)
+                .max_priority_fee_per_gas(tip),
+        );
+        provider.send_transaction(tx, None).await.unwrap();
+    }
+    api.evm_mine(None).await.unwrap();
+    api.evm_mine(None).await.unwrap();
+
+    // the fee history cache is updated in the background
+    let mut history = None;
+    for _ in 0..20 {
+        let fees = api
+            .fee_history(U256::from(2), BlockNumberOrTag::Latest, vec![0.0, 50.0, 100.0])
+            .await
+            .unwrap();
+        if fees.gas_used_ratio.len() == 2 {
+            history = Some(fees);
+            break
+        }
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+    let history = history.unwrap();
+
+    assert_eq!(history.oldest_block, U256::from(1));
+    assert_eq!(history.base_fee_per_gas.len(), 3);
+    assert_eq!(history.base_fee_per_gas[0], U256::from(base_fee));
+    assert!(history.gas_used_ratio[0] > 0.0);
+    assert_eq!(history.gas_used_ratio[1], 0.0);
+
+    let rewards = history.reward.unwrap();
+    assert_eq!(
+        rewards[0],
+        vec![U256::from(base_fee), U256::from(base_fee), U256::from(3 * base_fee)]
+    );
+    assert_eq!(rewards[1], vec![U256::ZERO; 3]);
+
+    // percentiles must be increasing
+    api.fee_history(U256::from(2), BlockNumberOrTag::Latest, vec![50.0, 10.0]).await.unwrap_err();
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_recent_gas_price_oracle() {
+    let base_fee = INITIAL_BASE_FEE;
+    let tip = 5 * base_fee;
+    for pinned in [false, true] {
+        let config = NodeConfig::test()
+            .with_base_fee(Some(base_fee.to_alloy()))
+            .with_gas_price(pinned.then(|| U256::from(base_fee)))
+            .with_gas_price_oracle(GasPriceOracle::Recent);
+        let (api, handle) = spawn(config).await;
+        let provider = handle.ethers_http_provider();
+
+        // without recent transactions the gas price is the fixed one
+        assert!(api.gas_price().unwrap() < U256::from(tip));
+
+        let from = handle.dev_accounts().next().unwrap();
+        let tx = TypedTransaction::Eip1559(
+            Eip1559TransactionRequest::new()
+                .from(from)
+                .to(Address::random())
+                .value(1337u64)
+                .max_fee_per_gas(10 * base_fee)
+                .max_priority_fee_per_gas(tip),
+        );
+        provider.send_transaction(tx, None).await.unwrap().await.unwrap().unwrap();
+
+        // the fee history cache is updated in the background
+        let mut gas_price = U256::ZERO;
+        for _ in 0..20 {
+            gas_price = api.gas_price().unwrap();
+            if gas_price > U256::from(tip) {
+                break
+            }
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+
+        if pinned {
+            // an explicit gas price pins the value
+            assert!(gas_price < U256::from(tip));
+        } else {
+            // the base fee of the next block and the tip of the recent transaction
+            assert!(gas_price > U256::from(tip));
+            assert!(gas_price < U256::from(tip + 2 * base_fee));
+        }
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_base_fee_params() {
+    let base_fee = INITIAL_BASE_FEE;
+    let (api, handle) = spawn(
+        NodeConfig::test()
+            .with_base_fee(Some(base_fee.to_alloy()))
+            .with_gas_target(Some(GAS_TRANSFER / 2)),
+    )
+    .await;
+    let provider = handle.ethers_http_provider();
+    api.anvil_set_base_fee_params(BaseFeeParamsOptions {
+        gas_target: None,
+        max_change_denominator: Some(2),
+    })
+    .await
+    .unwrap();
+
+    // a block that uses twice the target gas increases the base fee by 1/denominator
+    let tx = TransactionRequest::new().to(Address::random()).value(1337u64);
+    provider.send_transaction(tx, None).await.unwrap().await.unwrap().unwrap();
+    api.evm_mine(None).await.unwrap();
+    let block = provider.get_block(2u64).await.unwrap().unwrap();
+    assert_eq!(block.base_fee_per_gas.unwrap(), (base_fee * 3 / 2).into());
+
+    // an empty block decreases it by 1/denominator
+    api.evm_mine(None).await.unwrap();
+    let block = provider.get_block(3u64).await.unwrap().unwrap();
+    assert_eq!(block.base_fee_per_gas.unwrap(), (base_fee * 3 / 4).into());
+
+    api.anvil_set_base_fee_params(BaseFeeParamsOptions {
+        gas_target: Some(0),
+        max_change_denominator: None,
+    })
+    .await
+    .unwrap_err();
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_min_gas_price() {
+    let base_fee = INITIAL_BASE_FEE;
+    let (api, handle) = spawn(NodeConfig::test().with_base_fee(Some(base_fee.to_alloy()))).await;
+    let provider = handle.ethers_http_provider();
+    let min_gas_price = base_fee * 3;
+    api.anvil_set_min_gas_price(U256::from(min_gas_price)).await.unwrap();
+
+    // the suggested gas price doesn't fall below the floor
+    assert!(provider.get_gas_price().await.unwrap() >= min_gas_price.into());
+
+    // effective gas price is `base_fee + tip`
+    let tx = Eip1559TransactionRequest::new()
+        .to(Address::random())
+        .value(1337u64)
+        .max_fee_per_gas(base_fee * 10)
+        .max_priority_fee_per_gas(base_fee);
+    let err = provider.send_transaction(tx, None).await.unwrap_err();
+    assert!(err.to_string().contains("underpriced"), "{err}");
+
+    let tx = Eip1559TransactionRequest::new()
+        .to(Address::random())
+        .value(1337u64)
+        .max_fee_per_gas(base_fee * 10)
+        .max_priority_fee_per_gas(base_fee * 5);
+    provider.send_transaction(tx, None).await.unwrap().await.unwrap().unwrap();
+
+    // legacy transactions are checked against their gas price
+    let tx = TransactionRequest::new().to(Address::random()).value(1337u64).gas_price(base_fee * 2);
+    let err = provider.send_transaction(tx, None).await.unwrap_err();
+    assert!(err.to_string().contains("underpriced"), "{err}");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_receipts_of_mixed_fee_types() {
+    let base_fee = INITIAL_BASE_FEE;
+    let (api, handle) = spawn(NodeConfig::test().with_base_fee(Some(base_fee.to_alloy()))).await;
+    let provider = handle.ethers_http_provider();
+    let accounts = handle.dev_accounts().collect::<Vec<_>>();
+    api.anvil_set_auto_mine(false).await.unwrap();
+
+    let legacy = TransactionRequest::new()
+        .from(accounts[0])
+        .to(Address::random())
+        .value(1337u64)
+        .gas_price(base_fee * 2);
+    let access_list = Eip2930TransactionRequest::new(
+        TransactionRequest::new()
+            .from(accounts[1])
+            .to(Address::random())
+            .value(1337u64)
+            .gas_price(base_fee * 3),
+        AccessList::default(),
+    );
+    // the fee cap is below `base_fee + tip`
+    let dynamic = Eip1559TransactionRequest::new()
+        .from(accounts[2])
+        .to(Address::random())
+        .value(1337u64)
+        .max_fee_per_gas(base_fee)
+        .max_priority_fee_per_gas(base_fee);
+
+    let mut hashes = Vec::new();
+    for tx in [
+        TypedTransaction::Legacy(legacy),
+        TypedTransaction::Eip2930(access_list),
+        TypedTransaction::Eip1559(dynamic),
+    ] {
+        hashes.push(provider.send_transaction(tx, None).await.unwrap().tx_hash());
+    }
+    api.mine_one().await;
+
+    let block = provider.get_block(1u64).await.unwrap().unwrap();
+    assert_eq!(block.transactions.len(), 3);
+    let block_base_fee = block.base_fee_per_gas.unwrap();
+
+    let mut cumulative_gas_used = EthersU256::zero();
+    for hash in block.transactions {
+        let receipt = provider.get_transaction_receipt(hash).await.unwrap().unwrap();
+        cumulative_gas_used += receipt.gas_used.unwrap();
+        assert_eq!(receipt.cumulative_gas_used, cumulative_gas_used);
+
+        let expected = if hash == hashes[0] {
+            EthersU256::from(base_fee * 2)
+        } else if hash == hashes[1] {
+            EthersU256::from(base_fee * 3)
+        } else {
+            // `min(tip, max_fee_per_gas - base_fee)` is capped by the fee cap
+            assert!(block_base_fee + base_fee > base_fee.into());
+            EthersU256::from(base_fee)
+        };
+        assert_eq!(receipt.effective_gas_price.unwrap(), expected);
+
+        let tx = provider.get_transaction(hash).await.unwrap().unwrap();
+        assert_eq!(tx.gas_price.unwrap(), expected);
+    }
+    assert_eq!(cumulative_gas_used, block.gas_used);
+}
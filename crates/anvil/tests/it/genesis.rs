@@ -2,7 +2,7 @@
 
 use std::str::FromStr;
 
-use alloy_primitives::{Address, U256, U64};
+use alloy_primitives::{Address, B256, U256, U64};
 use alloy_providers::provider::TempProvider;
 use anvil::{genesis::Genesis, spawn, NodeConfig};
 
@@ -48,3 +48,41 @@ async fn can_apply_genesis() {
     let expected: U256 = U256::from_str_radix("ffffffffffffffffffffffffff", 16).unwrap();
     assert_eq!(balance, expected);
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn can_apply_genesis_alloc_and_block_params() {
+    let genesis = r#"{
+  "timestamp": "0x6553f100",
+  "gasLimit": "0x1c9c380",
+  "difficulty": "0x0",
+  "baseFeePerGas": "0x3b9aca00",
+  "alloc": {
+    "0x1000000000000000000000000000000000000001": {
+      "balance": "0x1",
+      "nonce": "0x5",
+      "code": "0x602a60005260206000f3",
+      "storage": {
+        "0x0000000000000000000000000000000000000000000000000000000000000001": "0x0000000000000000000000000000000000000000000000000000000000000007"
+      }
+    }
+  }
+}
+"#;
+    let genesis: Genesis = serde_json::from_str(genesis).unwrap();
+    let (api, _handle) = spawn(NodeConfig::test().with_genesis(Some(genesis))).await;
+
+    let addr = Address::from_str("0x1000000000000000000000000000000000000001").unwrap();
+    assert_eq!(api.balance(addr, None).await.unwrap(), U256::from(1));
+    assert_eq!(api.transaction_count(addr, None).await.unwrap(), U256::from(5));
+    assert_eq!(
+        api.get_code(addr, None).await.unwrap().as_ref(),
+        &[0x60, 0x2a, 0x60, 0x00, 0x52, 0x60, 0x20, 0x60, 0x00, 0xf3]
+    );
+    assert_eq!(api.storage_at(addr, U256::from(1), None).await.unwrap(), B256::with_last_byte(7));
+
+    let block = api.block_by_number(alloy_rpc_types::BlockNumberOrTag::Earliest).await.unwrap();
+    let block = block.unwrap();
+    assert_eq!(block.header.timestamp, U256::from(0x6553f100));
+    assert_eq!(block.header.gas_limit, U256::from(30_000_000));
+    assert_eq!(block.header.base_fee_per_gas, Some(U256::from(1_000_000_000)));
+}
@@ -1,8 +1,11 @@
 //! IPC tests
 
 use alloy_primitives::U256;
-use anvil::{spawn, NodeConfig};
-use ethers::{core::rand, prelude::Middleware};
+use anvil::{spawn, try_spawn, NodeConfig};
+use ethers::{
+    core::rand,
+    prelude::{Middleware, Provider},
+};
 use futures::StreamExt;
 
 pub fn rand_ipc_endpoint() -> String {
@@ -47,3 +50,67 @@ async fn test_sub_new_heads_ipc() {
 
     assert_eq!(block_numbers, vec![1, 2, 3]);
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn can_serve_multiple_ipc_endpoints() {
+    let (api, _handle) = spawn(NodeConfig::test()).await;
+
+    let paths = vec![rand_ipc_endpoint(), rand_ipc_endpoint()];
+    let _task = anvil::server::try_spawn_ipc_many(api.clone(), paths.clone()).unwrap();
+
+    for path in paths {
+        let provider = Provider::connect_ipc(path).await.unwrap();
+        let num = provider.get_block_number().await.unwrap();
+        assert_eq!(num.as_u64(), api.block_number().unwrap().to::<u64>());
+    }
+}
+
+#[cfg(unix)]
+#[tokio::test(flavor = "multi_thread")]
+async fn can_replace_stale_ipc_socket() {
+    let (api, _handle) = spawn(NodeConfig::test()).await;
+    let path = rand_ipc_endpoint();
+
+    // a socket file that no server is listening on anymore
+    drop(std::os::unix::net::UnixListener::bind(&path).unwrap());
+    assert!(std::path::Path::new(&path).exists());
+
+    let task = anvil::server::try_spawn_ipc(api.clone(), path.clone()).unwrap();
+    let provider = Provider::connect_ipc(&path).await.unwrap();
+    provider.get_block_number().await.unwrap();
+
+    // the socket is still served
+    let err = anvil::server::try_spawn_ipc(api, path.clone()).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::AddrInUse);
+
+    task.abort();
+    let _ = task.await;
+    assert!(!std::path::Path::new(&path).exists());
+}
+
+#[cfg(unix)]
+#[tokio::test(flavor = "multi_thread")]
+async fn rejects_ipc_path_in_use() {
+    let (_api, handle) = spawn(ipc_config()).await;
+    let path = handle.ipc_path().unwrap();
+
+    let config = NodeConfig::test().with_ipc(Some(Some(path)));
+    assert!(try_spawn(config).await.is_err());
+    // the running node is still served
+    handle.ethers_ipc_provider().unwrap().get_block_number().await.unwrap();
+}
+
+#[cfg(windows)]
+#[test]
+fn maps_ipc_paths_to_named_pipes() {
+    use anvil_server::ipc::endpoint_path;
+
+    assert_eq!(endpoint_path(r"C:\tmp\anvil.ipc"), r"\\.\pipe\anvil.ipc");
+    assert_eq!(endpoint_path("anvil.ipc"), r"\\.\pipe\anvil.ipc");
+    assert_eq!(endpoint_path("/tmp/anvil.ipc"), r"\\.\pipe\anvil.ipc");
+    // paths in the pipe namespace are kept as is
+    assert_eq!(endpoint_path(r"\\.\pipe\anvil-ipc"), r"\\.\pipe\anvil-ipc");
+    assert_eq!(endpoint_path(r"\\.\PIPE\anvil-ipc"), r"\\.\PIPE\anvil-ipc");
+    // paths without a file name use the default pipe
+    assert_eq!(endpoint_path(r"C:\"), r"\\.\pipe\anvil.ipc");
+}
@@ -8,7 +8,7 @@ use ethers::{
     types::Log,
 };
 use futures::StreamExt;
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
 
 #[tokio::test(flavor = "multi_thread")]
 async fn get_past_events() {
@@ -84,6 +84,33 @@ async fn get_all_events() {
     assert_eq!(logs.len(), num_logs);
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn rejects_too_many_logs() {
+    let (_api, handle) = spawn(NodeConfig::test().with_max_logs_returned(Some(2))).await;
+    let provider = handle.ethers_http_provider();
+
+    let wallet = handle.dev_wallets().next().unwrap();
+    let client = Arc::new(SignerMiddleware::new(provider, wallet));
+
+    let contract = SimpleStorage::deploy(Arc::clone(&client), "initial value".to_string())
+        .unwrap()
+        .send()
+        .await
+        .unwrap();
+
+    for value in ["a", "b"] {
+        let func = contract.method::<_, H256>("setValue", value.to_owned()).unwrap();
+        let _receipt = func.send().await.unwrap().await.unwrap();
+    }
+
+    let err = client.get_logs(&Filter::new().from_block(0u64)).await.unwrap_err();
+    assert!(err.to_string().contains("query returned more than 2 results"), "{err}");
+
+    // narrower queries still succeed
+    let logs = client.get_logs(&Filter::new().from_block(2u64)).await.unwrap();
+    assert_eq!(logs.len(), 2);
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn can_install_filter() {
     let (api, handle) = spawn(NodeConfig::test()).await;
@@ -126,6 +153,20 @@ async fn can_install_filter() {
     assert_eq!(all_logs.len(), num_logs + 1);
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn removes_stale_filters() {
+    let (_api, handle) =
+        spawn(NodeConfig::test().with_filter_timeout(Some(Duration::from_secs(1)))).await;
+    let provider = handle.ethers_http_provider();
+
+    let filter = provider.new_filter(FilterKind::NewBlocks).await.unwrap();
+    provider.get_filter_changes::<_, H256>(filter).await.unwrap();
+
+    tokio::time::sleep(Duration::from_secs(2)).await;
+    let err = provider.get_filter_changes::<_, H256>(filter).await.unwrap_err();
+    assert!(err.to_string().contains("filter not found"), "{err}");
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn watch_events() {
     let (_api, handle) = spawn(NodeConfig::test()).await;
@@ -15,6 +15,7 @@ mod pubsub;
 // mod revert; // TODO uncomment <https://github.com/gakonst/ethers-rs/issues/2186>
 mod otterscan;
 mod sign;
+mod state;
 mod traces;
 mod transaction;
 mod txpool;
@@ -9,7 +9,10 @@ use ethers::{
         TransactionRequest, U256,
     },
 };
-use ethers_core::types::{Bytes, H256};
+use ethers_core::{
+    types::{Bytes, H256},
+    utils::keccak256,
+};
 use foundry_common::types::ToAlloy;
 use std::str::FromStr;
 
@@ -129,7 +132,9 @@ async fn test_send_value_raw_deposit_transaction() {
     });
 
     let rlpbytes = deposit_tx.rlp();
-    let pending = provider.send_raw_transaction(rlpbytes).await.unwrap();
+    let pending = provider.send_raw_transaction(rlpbytes.clone()).await.unwrap();
+    // the hash covers the type of the deposit, like the hash of other typed transactions
+    assert_eq!(pending.tx_hash(), H256::from(keccak256(&rlpbytes)));
 
     // mine block
     api.evm_mine(None).await.unwrap();
@@ -1,18 +1,80 @@
 //! tests for `eth_getProof`
 
-use crate::proof::eip1186::verify_proof;
-use alloy_rpc_types::EIP1186AccountProofResponse;
+use alloy_primitives::{Address as rAddress, Bytes, B256, U256 as rU256};
+use alloy_rpc_types::{BlockId, BlockNumberOrTag, EIP1186AccountProofResponse};
 use anvil::{spawn, NodeConfig};
-use anvil_core::eth::{proof::BasicAccount, trie::ExtensionLayout};
+use anvil_core::eth::proof::BasicAccount;
 use ethers::{
     abi::ethereum_types::BigEndianHash,
     types::{Address, H256, U256},
-    utils::{keccak256, rlp},
+    utils::{keccak256, rlp, rlp::Rlp},
 };
 use foundry_common::types::{ToAlloy, ToEthers};
 use foundry_evm::revm::primitives::KECCAK_EMPTY;
 
-mod eip1186;
+/// Verifies the merkle proof of the `key` against the `root` of a secure trie
+///
+/// `value` is `None` if the proof is expected to prove the absence of the key.
+fn verify_proof(root: B256, proof: &[Bytes], key: &[u8], value: Option<&[u8]>) {
+    let path: Vec<u8> = keccak256(key).iter().flat_map(|b| [b >> 4, b & 0x0f]).collect();
+    let mut nodes = proof.iter();
+    // either the hash of the next node or an embedded node
+    let mut next = root.to_vec();
+    let mut offset = 0;
+    loop {
+        let node = if next.len() == 32 {
+            let node = nodes.next().expect("incomplete proof");
+            assert_eq!(keccak256(node).as_slice(), next.as_slice(), "hash mismatch");
+            node.to_vec()
+        } else {
+            next
+        };
+        let node = Rlp::new(&node);
+        let child = match node.item_count().unwrap() {
+            17 => {
+                let child = node.at(path[offset] as usize).unwrap();
+                offset += 1;
+                child
+            }
+            2 => {
+                let encoded: Vec<u8> = node.val_at(0).unwrap();
+                let mut nibbles =
+                    if encoded[0] & 0x10 != 0 { vec![encoded[0] & 0x0f] } else { Vec::new() };
+                nibbles.extend(encoded[1..].iter().flat_map(|b| [b >> 4, b & 0x0f]));
+                if !path[offset..].starts_with(&nibbles) {
+                    assert!(value.is_none(), "key not found");
+                    return
+                }
+                offset += nibbles.len();
+                if encoded[0] & 0x20 != 0 {
+                    // leaf
+                    let leaf: Vec<u8> = node.val_at(1).unwrap();
+                    assert_eq!(Some(leaf.as_slice()), value, "value mismatch");
+                    return
+                }
+                node.at(1).unwrap()
+            }
+            n => panic!("invalid node with {n} items"),
+        };
+        if child.is_empty() {
+            assert!(value.is_none(), "key not found");
+            return
+        }
+        next =
+            if child.is_list() { child.as_raw().to_vec() } else { child.data().unwrap().to_vec() };
+    }
+}
+
+fn verify_account_proof(root: B256, proof: &EIP1186AccountProofResponse) {
+    let account = BasicAccount {
+        nonce: proof.nonce.to::<u64>().into(),
+        balance: proof.balance.to_ethers(),
+        storage_root: proof.storage_hash.to_ethers(),
+        code_hash: proof.code_hash.to_ethers(),
+    };
+    let rlp_account = rlp::encode(&account);
+    verify_proof(root, &proof.account_proof, proof.address.as_slice(), Some(rlp_account.as_ref()));
+}
 
 #[tokio::test(flavor = "multi_thread")]
 async fn can_get_proof() {
@@ -30,53 +92,89 @@ async fn can_get_proof() {
     let proof: EIP1186AccountProofResponse =
         api.get_proof(acc.to_alloy(), vec![H256::from_uint(&key).to_alloy()], None).await.unwrap();
 
-    let account = BasicAccount {
-        nonce: 0.into(),
-        balance: 0.into(),
-        storage_root: proof.storage_hash.to_ethers(),
-        code_hash: KECCAK_EMPTY.to_ethers(),
-    };
-
-    let rlp_account = rlp::encode(&account);
-
-    let root: H256 = api.state_root().await.unwrap().to_ethers();
-    let acc_proof: Vec<Vec<u8>> = proof
-        .account_proof
-        .into_iter()
-        .map(|node| rlp::decode::<Vec<u8>>(&node).unwrap())
-        .collect();
-
-    verify_proof::<ExtensionLayout>(
-        &root.0,
-        &acc_proof,
-        &keccak256(acc.as_bytes())[..],
-        Some(rlp_account.as_ref()),
-    )
-    .unwrap();
+    assert_eq!(proof.code_hash, KECCAK_EMPTY);
+    let root = api.state_root().await.unwrap();
+    verify_account_proof(root, &proof);
 
     assert_eq!(proof.storage_proof.len(), 1);
     let expected_value = rlp::encode(&value);
-    let proof = proof.storage_proof[0].clone();
-    let storage_proof: Vec<Vec<u8>> =
-        proof.proof.into_iter().map(|node| rlp::decode::<Vec<u8>>(&node).unwrap()).collect();
-    let key = H256::from(keccak256(proof.key.0 .0));
-    verify_proof::<ExtensionLayout>(
-        &account.storage_root.0,
-        &storage_proof,
-        key.as_bytes(),
+    let storage_proof = proof.storage_proof[0].clone();
+    assert_eq!(storage_proof.value, value.to_alloy());
+    verify_proof(
+        proof.storage_hash,
+        &storage_proof.proof,
+        storage_proof.key.0.as_slice(),
         Some(expected_value.as_ref()),
-    )
-    .unwrap();
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn can_get_proof_of_mined_block() {
+    let (api, _handle) = spawn(NodeConfig::test()).await;
+
+    let acc = rAddress::repeat_byte(0xaa);
+    let slots = (0..20u64).map(rU256::from).collect::<Vec<_>>();
+    api.anvil_set_balance(acc, rU256::from(1337)).await.unwrap();
+    for slot in &slots {
+        api.anvil_set_storage_at(acc, *slot, B256::from(*slot + rU256::from(1))).await.unwrap();
+    }
+    api.evm_mine(None).await.unwrap();
+    let block = api.block_by_number(BlockNumberOrTag::Latest).await.unwrap().unwrap();
+    let number = block.header.number.unwrap().to::<u64>();
+
+    // modify the state after the block was mined
+    api.anvil_set_storage_at(acc, slots[0], B256::ZERO).await.unwrap();
+    api.evm_mine(None).await.unwrap();
+
+    let keys = slots.iter().map(|slot| B256::from(*slot)).chain([B256::repeat_byte(0xff)]);
+    let proof =
+        api.get_proof(acc, keys.collect(), Some(BlockId::Number(number.into()))).await.unwrap();
+    assert_eq!(proof.balance, rU256::from(1337));
+    verify_account_proof(block.header.state_root, &proof);
+
+    for (slot, storage_proof) in slots.iter().zip(&proof.storage_proof) {
+        let value = *slot + rU256::from(1);
+        assert_eq!(storage_proof.value, value);
+        let expected_value = rlp::encode(&value.to_ethers());
+        verify_proof(
+            proof.storage_hash,
+            &storage_proof.proof,
+            storage_proof.key.0.as_slice(),
+            Some(expected_value.as_ref()),
+        );
+    }
+    // unset slot
+    let storage_proof = proof.storage_proof.last().unwrap();
+    assert_eq!(storage_proof.value, rU256::ZERO);
+    verify_proof(proof.storage_hash, &storage_proof.proof, storage_proof.key.0.as_slice(), None);
+
+    // the cleared slot isn't part of the latest state
+    let block = api.block_by_number(BlockNumberOrTag::Latest).await.unwrap().unwrap();
+    let proof = api.get_proof(acc, vec![B256::from(slots[0])], None).await.unwrap();
+    verify_account_proof(block.header.state_root, &proof);
+    verify_proof(
+        proof.storage_hash,
+        &proof.storage_proof[0].proof,
+        proof.storage_proof[0].key.0.as_slice(),
+        None,
+    );
 }
 
 #[tokio::test(flavor = "multi_thread")]
 async fn can_get_random_account_proofs() {
     let (api, _handle) = spawn(NodeConfig::test()).await;
+    let root = api.state_root().await.unwrap();
 
     for acc in std::iter::repeat_with(Address::random).take(10) {
-        let _ = api
-            .get_proof(acc.to_alloy(), Vec::new(), None)
+        let proof = api
+            .get_proof(acc.to_alloy(), vec![B256::ZERO], None)
             .await
             .unwrap_or_else(|_| panic!("Failed to get proof for {acc:?}"));
+
+        // absent accounts are empty and proven to be absent
+        assert_eq!(proof.balance, rU256::ZERO);
+        assert_eq!(proof.code_hash, KECCAK_EMPTY);
+        verify_proof(root, &proof.account_proof, acc.as_bytes(), None);
+        assert!(proof.storage_proof[0].proof.is_empty());
     }
 }
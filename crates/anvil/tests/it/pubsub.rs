@@ -1,15 +1,18 @@
 //! tests for subscriptions
 
+use alloy_primitives::U256 as rU256;
 use anvil::{spawn, NodeConfig};
 use ethers::{
-    contract::abigen,
+    contract::{abigen, EthEvent},
     middleware::SignerMiddleware,
     prelude::{Middleware, Ws},
     providers::{JsonRpcClient, PubsubClient},
     signers::Signer,
-    types::{Address, Block, Filter, TransactionRequest, TxHash, ValueOrArray, U256},
+    types::{
+        Address, Block, Filter, Transaction, TransactionRequest, TxHash, ValueOrArray, H256, U256,
+    },
 };
-use foundry_common::types::ToAlloy;
+use foundry_common::types::{ToAlloy, ToEthers};
 use futures::StreamExt;
 use std::sync::Arc;
 
@@ -30,6 +33,33 @@ async fn test_sub_new_heads() {
     assert_eq!(block_numbers, vec![1, 2, 3]);
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn test_sub_new_heads_mine_to() {
+    let (api, handle) = spawn(NodeConfig::test()).await;
+    let provider = handle.ethers_ws_provider();
+    let from = handle.dev_accounts().next().unwrap().to_ethers();
+    api.anvil_set_auto_mine(false).await.unwrap();
+
+    let blocks = provider.subscribe_blocks().await.unwrap();
+
+    // the pending transaction is included in the first block
+    let tx = TransactionRequest::new().from(from).to(Address::random()).value(1337u64);
+    let tx = provider.send_transaction(tx, None).await.unwrap();
+    api.anvil_mine_to(rU256::from(3)).await.unwrap();
+
+    let blocks = blocks.take(3).collect::<Vec<_>>().await;
+    let block_numbers = blocks.iter().map(|b| b.number.unwrap().as_u64()).collect::<Vec<_>>();
+    assert_eq!(block_numbers, vec![1, 2, 3]);
+    assert_eq!(blocks[0].transactions, vec![tx.tx_hash()]);
+
+    // mining to the current block is a noop, lower and too distant targets are rejected
+    api.anvil_mine_to(rU256::from(3)).await.unwrap();
+    api.anvil_mine_to(rU256::from(2)).await.unwrap_err();
+    api.anvil_mine_to(rU256::from(10_004)).await.unwrap_err();
+    api.anvil_mine_to(rU256::MAX).await.unwrap_err();
+    assert_eq!(api.block_number().unwrap(), rU256::from(3));
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn test_sub_logs_legacy() {
     abigen!(EmitLogs, "test-data/emit_logs.json");
@@ -107,6 +137,143 @@ async fn test_sub_logs() {
     assert_eq!(receipt.logs[0], log);
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn test_sub_logs_removed_by_reorg() {
+    abigen!(EmitLogs, "test-data/emit_logs.json");
+
+    let (api, handle) = spawn(NodeConfig::test()).await;
+    let provider = handle.ethers_ws_provider();
+
+    let wallet = handle.dev_wallets().next().unwrap();
+    let client = Arc::new(SignerMiddleware::new(provider, wallet));
+
+    let msg = "First Message".to_string();
+    let contract =
+        EmitLogs::deploy(Arc::clone(&client), msg.clone()).unwrap().send().await.unwrap();
+
+    let filter = Filter::new().address(ValueOrArray::Value(contract.address()));
+    let mut logs_sub = client.subscribe_logs(&filter).await.unwrap();
+
+    let receipt = contract
+        .set_value("Next Message".to_string())
+        .send()
+        .await
+        .unwrap()
+        .await
+        .unwrap()
+        .unwrap();
+    let log = logs_sub.next().await.unwrap();
+    assert_eq!(receipt.logs[0], log);
+
+    api.anvil_reorg(rU256::from(1), vec![]).await.unwrap();
+
+    // the log of the removed block is emitted again with `removed: true`
+    let removed = logs_sub.next().await.unwrap();
+    assert_eq!(removed.removed, Some(true));
+    assert_eq!(removed.transaction_hash, Some(receipt.transaction_hash));
+
+    // the chain was re-mined to the same height without the transaction
+    let block_number = client.get_block_number().await.unwrap();
+    assert_eq!(block_number, receipt.block_number.unwrap());
+    let block = client.get_block(block_number).await.unwrap().unwrap();
+    assert_ne!(block.hash, receipt.block_hash);
+    assert!(block.transactions.is_empty());
+    assert!(client.get_transaction_receipt(receipt.transaction_hash).await.unwrap().is_none());
+
+    // the state was reverted
+    let val = contract.get_value().call().await.unwrap();
+    assert_eq!(val, msg);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_sub_logs_removed_by_rollback() {
+    abigen!(EmitLogs, "test-data/emit_logs.json");
+
+    let (api, handle) = spawn(NodeConfig::test()).await;
+    let provider = handle.ethers_ws_provider();
+
+    let wallet = handle.dev_wallets().next().unwrap();
+    let client = Arc::new(SignerMiddleware::new(provider, wallet));
+
+    let msg = "First Message".to_string();
+    let contract =
+        EmitLogs::deploy(Arc::clone(&client), msg.clone()).unwrap().send().await.unwrap();
+
+    let filter = Filter::new().address(ValueOrArray::Value(contract.address()));
+    let mut logs_sub = client.subscribe_logs(&filter).await.unwrap();
+
+    let receipt = contract
+        .set_value("Next Message".to_string())
+        .send()
+        .await
+        .unwrap()
+        .await
+        .unwrap()
+        .unwrap();
+    let log = logs_sub.next().await.unwrap();
+    assert_eq!(receipt.logs[0], log);
+
+    api.anvil_set_auto_mine(false).await.unwrap();
+    api.anvil_rollback(rU256::from(1)).await.unwrap();
+
+    // the log of the removed block is emitted again with `removed: true`
+    let removed = logs_sub.next().await.unwrap();
+    assert_eq!(removed.removed, Some(true));
+    assert_eq!(removed.transaction_hash, Some(receipt.transaction_hash));
+
+    // the block is gone and the state was reverted
+    let block_number = client.get_block_number().await.unwrap();
+    assert_eq!(block_number + 1, receipt.block_number.unwrap());
+    let val = contract.get_value().call().await.unwrap();
+    assert_eq!(val, msg);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_sub_logs_filtered() {
+    abigen!(EmitLogs, "test-data/emit_logs.json");
+
+    let (api, handle) = spawn(NodeConfig::test()).await;
+    let provider = handle.ethers_ws_provider();
+
+    let wallet = handle.dev_wallets().next().unwrap();
+    let from = wallet.address();
+    let client = Arc::new(SignerMiddleware::new(provider, wallet));
+
+    let msg = "First Message".to_string();
+    let first = EmitLogs::deploy(Arc::clone(&client), msg.clone()).unwrap().send().await.unwrap();
+    let second = EmitLogs::deploy(Arc::clone(&client), msg).unwrap().send().await.unwrap();
+
+    // `null` topics match any topic
+    let filter = Filter::new().address(first.address()).topic1(H256::from(from));
+    let mut first_sub = client.subscribe_logs(&filter).await.unwrap();
+    // a topic array matches any of its topics
+    let filter = Filter::new()
+        .address(vec![first.address(), second.address()])
+        .topic0(vec![ValueChangedFilter::signature(), H256::random()]);
+    let mut any_sub = client.subscribe_logs(&filter).await.unwrap();
+    // no log matches the topic
+    let filter = Filter::new().topic0(H256::random());
+    let mut none_sub = client.subscribe_logs(&filter).await.unwrap();
+
+    let second_receipt =
+        second.set_value("Next Message".to_string()).send().await.unwrap().await.unwrap().unwrap();
+    let first_receipt =
+        first.set_value("Next Message".to_string()).send().await.unwrap().await.unwrap().unwrap();
+
+    assert_eq!(first_sub.next().await.unwrap(), first_receipt.logs[0]);
+    assert_eq!(any_sub.next().await.unwrap(), second_receipt.logs[0]);
+    assert_eq!(any_sub.next().await.unwrap(), first_receipt.logs[0]);
+
+    // removed logs are filtered as well
+    api.anvil_reorg(rU256::from(2), vec![]).await.unwrap();
+    let removed = first_sub.next().await.unwrap();
+    assert_eq!(removed.removed, Some(true));
+    assert_eq!(removed.transaction_hash, Some(first_receipt.transaction_hash));
+
+    let none = tokio::time::timeout(std::time::Duration::from_millis(500), none_sub.next()).await;
+    assert!(none.is_err());
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn test_sub_logs_impersonated() {
     abigen!(EmitLogs, "test-data/emit_logs.json");
@@ -250,6 +417,67 @@ async fn test_subscriptions() {
     assert_eq!(blocks, vec![1, 2, 3])
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn test_sub_full_pending_transactions() {
+    let (_api, handle) = spawn(NodeConfig::test()).await;
+    let provider = handle.ethers_http_provider();
+    let from = handle.dev_accounts().next().unwrap().to_ethers();
+    let ws = Ws::connect(handle.ws_endpoint()).await.unwrap();
+
+    // both geth's bool param and the options object are supported
+    let sub_id: U256 = ws.request("eth_subscribe", ("newPendingTransactions", true)).await.unwrap();
+    let mut bool_stream = ws.subscribe(sub_id).unwrap();
+    let sub_id: U256 = ws
+        .request(
+            "eth_subscribe",
+            ("newPendingTransactions", serde_json::json!({ "fullTransactions": true })),
+        )
+        .await
+        .unwrap();
+    let mut options_stream = ws.subscribe(sub_id).unwrap();
+
+    let tx = TransactionRequest::new().from(from).to(Address::random()).value(1337u64);
+    let tx_hash = provider.send_transaction(tx, None).await.unwrap().tx_hash();
+
+    for stream in [&mut bool_stream, &mut options_stream] {
+        let item = stream.next().await.unwrap();
+        let tx: Transaction = serde_json::from_str(item.get()).unwrap();
+        assert_eq!(tx.hash, tx_hash);
+        assert_eq!(tx.from, from);
+        assert_eq!(tx.value, 1337u64.into());
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_sub_full_blocks() {
+    let (_api, handle) = spawn(NodeConfig::test()).await;
+    let provider = handle.ethers_http_provider();
+    let from = handle.dev_accounts().next().unwrap().to_ethers();
+    let ws = Ws::connect(handle.ws_endpoint()).await.unwrap();
+
+    let sub_id: U256 = ws
+        .request("anvil_subscribe", ("blocks", serde_json::json!({ "fullTransactions": true })))
+        .await
+        .unwrap();
+    let mut full_stream = ws.subscribe(sub_id).unwrap();
+    let sub_id: U256 = ws.request("anvil_subscribe", ["blocks"]).await.unwrap();
+    let mut hashes_stream = ws.subscribe(sub_id).unwrap();
+
+    let tx = TransactionRequest::new().from(from).to(Address::random()).value(1337u64);
+    let tx_hash = provider.send_transaction(tx, None).await.unwrap().tx_hash();
+
+    let item = full_stream.next().await.unwrap();
+    let block: Block<Transaction> = serde_json::from_str(item.get()).unwrap();
+    assert_eq!(block.number.unwrap().as_u64(), 1);
+    assert_eq!(block.transactions.len(), 1);
+    assert_eq!(block.transactions[0].hash, tx_hash);
+    assert_eq!(block.transactions[0].value, 1337u64.into());
+
+    let item = hashes_stream.next().await.unwrap();
+    let block: Block<TxHash> = serde_json::from_str(item.get()).unwrap();
+    assert_eq!(block.transactions, vec![tx_hash]);
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn test_sub_new_heads_fast() {
     let (api, handle) = spawn(NodeConfig::test()).await;
@@ -223,3 +223,44 @@ contract Contract {
 
     let _ = resp.unwrap_err();
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_revert_above_max_call_depth() {
+    let prj = TempProject::dapptools().unwrap();
+    prj.add_source(
+        "Contract",
+        r#"
+pragma solidity 0.8.13;
+contract Contract {
+    function recurse(uint256 n) public returns (uint256) {
+        if (n == 0) {
+            return 0;
+        }
+        return this.recurse(n - 1) + 1;
+    }
+}
+"#,
+    )
+    .unwrap();
+
+    let mut compiled = prj.compile().unwrap();
+    assert!(!compiled.has_compiler_errors());
+    let contract = compiled.remove_first("Contract").unwrap();
+    let (abi, bytecode, _) = contract.into_contract_bytecode().into_parts();
+
+    let (_api, handle) = spawn(NodeConfig::test().with_max_call_depth(Some(2))).await;
+    let provider = handle.ws_provider();
+    let wallet = handle.dev_wallets().next().unwrap();
+    let client = Arc::new(SignerMiddleware::new(provider, wallet));
+
+    let factory = ContractFactory::new(abi.unwrap(), bytecode.unwrap(), client);
+    let contract = factory.deploy(()).unwrap().send().await.unwrap();
+
+    // the outermost call has depth 0, so two nested calls are allowed
+    let depth: U256 = contract.method("recurse", U256::from(2)).unwrap().call().await.unwrap();
+    assert_eq!(depth, U256::from(2));
+
+    let err =
+        contract.method::<_, U256>("recurse", U256::from(3)).unwrap().call().await.unwrap_err();
+    assert!(err.to_string().contains("execution reverted"), "{err}");
+}
@@ -0,0 +1,70 @@
+//! tests for dumping and loading the state of the node
+
+use alloy_primitives::U256 as rU256;
+use anvil::{spawn, try_spawn, NodeConfig};
+use ethers::{
+    prelude::{Middleware, Signer},
+    types::{BlockNumber, TransactionRequest},
+};
+
+#[tokio::test(flavor = "multi_thread")]
+async fn can_reload_dumped_chain() {
+    let (api, handle) = spawn(NodeConfig::test()).await;
+    let provider = handle.ethers_http_provider();
+    let accounts = handle.dev_wallets().collect::<Vec<_>>();
+    let from = accounts[0].address();
+    let to = accounts[1].address();
+
+    let tx = TransactionRequest::new().to(to).value(1337u64).from(from);
+    let receipt = provider.send_transaction(tx, None).await.unwrap().await.unwrap().unwrap();
+    api.mine_one().await;
+    api.mine_one().await;
+
+    let latest = provider.get_block(BlockNumber::Latest).await.unwrap().unwrap();
+    let balance = provider.get_balance(to, None).await.unwrap();
+    let state = api.serialized_state().await.unwrap();
+    assert_eq!(state.best_block_number, Some(3));
+
+    let (api, handle) = spawn(NodeConfig::test().with_init_state(Some(state))).await;
+    let provider = handle.ethers_http_provider();
+
+    assert_eq!(api.block_number().unwrap(), rU256::from(3));
+    let reloaded = provider.get_block(BlockNumber::Latest).await.unwrap().unwrap();
+    assert_eq!(reloaded.hash, latest.hash);
+    assert_eq!(reloaded.timestamp, latest.timestamp);
+
+    let reloaded_receipt =
+        provider.get_transaction_receipt(receipt.transaction_hash).await.unwrap().unwrap();
+    assert_eq!(reloaded_receipt.block_hash, receipt.block_hash);
+    assert_eq!(provider.get_balance(to, None).await.unwrap(), balance);
+
+    // new blocks continue the loaded chain
+    api.mine_one().await;
+    let block = provider.get_block(BlockNumber::Latest).await.unwrap().unwrap();
+    assert_eq!(block.number.unwrap().as_u64(), 4);
+    assert_eq!(block.parent_hash, latest.hash.unwrap());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn rejects_state_of_other_chain() {
+    let (api, _handle) = spawn(NodeConfig::test()).await;
+    api.mine_one().await;
+    let dump = api.anvil_dump_state(None).await.unwrap();
+
+    let (api, _handle) = spawn(NodeConfig::test().with_chain_id(Some(1u64))).await;
+    let err = api.anvil_load_state(dump).await.unwrap_err();
+    assert!(err.to_string().contains("chain id"), "{err}");
+    assert_eq!(api.block_number().unwrap(), rU256::ZERO);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn rejects_init_state_of_other_chain() {
+    let (api, _handle) = spawn(NodeConfig::test()).await;
+    api.mine_one().await;
+    let state = api.serialized_state().await.unwrap();
+
+    let config = NodeConfig::test().with_chain_id(Some(1u64)).with_init_state(Some(state));
+    let err = try_spawn(config).await.err().unwrap();
+    assert!(err.to_string().starts_with("failed to load the initial state"), "{err}");
+    assert!(err.to_string().contains("chain id"), "{err}");
+}
@@ -1,17 +1,31 @@
 use crate::fork::fork_config;
-use anvil::{spawn, NodeConfig};
+use alloy_primitives::{B256, U256, U64};
+use alloy_rpc_types::{
+    trace::geth::{
+        GethDebugBuiltInTracerType, GethDebugTracerConfig, GethDebugTracerType,
+        GethDebugTracingOptions, GethTrace as AlloyGethTrace, PreStateFrame, PreStateMode,
+    },
+    BlockNumberOrTag, CallRequest,
+};
+use anvil::{eth::signatures::SignatureDatabase, spawn, NodeConfig};
+use anvil_core::{
+    eth::{state::AccountOverride, transaction::to_alloy_state_override},
+    types::{
+        BlockOverrides, StorageDiff, TraceCallOptions, TraceTransactionOptions, STORAGE_DIFF_TRACER,
+    },
+};
 use ethers::{
     contract::ContractInstance,
     prelude::{
         Action, ContractFactory, GethTrace, GethTraceFrame, Middleware, Signer, SignerMiddleware,
         TransactionRequest,
     },
-    types::{ActionType, Address, GethDebugTracingCallOptions, Trace},
+    types::{ActionType, Address, GethDebugTracingCallOptions, Trace, TraceFilter, H256},
     utils::hex,
 };
 use ethers_solc::{project_util::TempProject, Artifact};
 use foundry_common::types::ToAlloy;
-use std::sync::Arc;
+use std::{collections::HashMap, sync::Arc};
 
 #[tokio::test(flavor = "multi_thread")]
 async fn test_get_transfer_parity_traces() {
@@ -47,6 +61,42 @@ async fn test_get_transfer_parity_traces() {
     assert_eq!(traces, block_traces);
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn test_trace_filter() {
+    let (_api, handle) = spawn(NodeConfig::test()).await;
+    let provider = handle.ethers_http_provider();
+
+    let accounts: Vec<_> = handle.dev_wallets().map(|wallet| wallet.address()).collect();
+    for (from, to) in [(0, 1), (2, 3), (0, 1)] {
+        let tx = TransactionRequest::new().to(accounts[to]).value(1u64).from(accounts[from]);
+        provider.send_transaction(tx, None).await.unwrap().await.unwrap().unwrap();
+    }
+
+    let traces = provider.trace_filter(TraceFilter::default()).await.unwrap();
+    assert_eq!(traces.len(), 3);
+
+    let filter = TraceFilter::default().from_address(vec![accounts[0]]);
+    let traces = provider.trace_filter(filter.clone()).await.unwrap();
+    assert_eq!(traces.len(), 2);
+    assert_eq!(traces[0].block_number, 1);
+    assert_eq!(traces[1].block_number, 3);
+
+    let traces = provider.trace_filter(filter.after(1).count(1)).await.unwrap();
+    assert_eq!(traces.len(), 1);
+    assert_eq!(traces[0].block_number, 3);
+
+    let filter = TraceFilter::default().to_address(vec![accounts[3]]).to_block(2u64.into());
+    let traces = provider.trace_filter(filter).await.unwrap();
+    assert_eq!(traces.len(), 1);
+    match traces[0].action {
+        Action::Call(ref call) => assert_eq!(call.from, accounts[2]),
+        _ => unreachable!("unexpected action"),
+    }
+
+    let filter = TraceFilter::default().from_block(2u64.into()).to_block(1u64.into());
+    assert!(provider.trace_filter(filter).await.is_err());
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn test_parity_suicide_trace() {
     let prj = TempProject::dapptools().unwrap();
@@ -156,6 +206,42 @@ contract Contract {
     }
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn test_debug_trace_call_state_override() {
+    let (api, _handle) = spawn(NodeConfig::test()).await;
+    let target = Address::random();
+
+    // PUSH1 0 SLOAD PUSH1 0 MSTORE PUSH1 32 PUSH1 0 RETURN
+    let code = hex::decode("60005460005260206000f3").unwrap();
+    let overrides = HashMap::from([(
+        target,
+        AccountOverride {
+            code: Some(code.into()),
+            state_diff: Some(HashMap::from([(H256::zero(), H256::from_low_u64_be(42))])),
+            ..Default::default()
+        },
+    )]);
+
+    let trace = api
+        .debug_trace_call(
+            CallRequest { to: Some(target.to_alloy()), ..Default::default() },
+            None,
+            TraceCallOptions {
+                state_overrides: Some(to_alloy_state_override(overrides)),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+    assert!(!trace.failed);
+    assert!(trace.struct_logs.iter().any(|log| log.op == "SLOAD"));
+    assert_eq!(trace.return_value.as_ref(), H256::from_low_u64_be(42).as_bytes());
+
+    // the overrides are discarded after the call
+    assert!(api.get_code(target.to_alloy(), None).await.unwrap().is_empty());
+    assert_eq!(api.storage_at(target.to_alloy(), U256::ZERO, None).await.unwrap(), B256::ZERO);
+}
+
 // <https://github.com/foundry-rs/foundry/issues/2656>
 #[tokio::test(flavor = "multi_thread")]
 async fn test_trace_address_fork() {
@@ -605,3 +691,580 @@ async fn test_trace_address_fork2() {
         }
     })
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_storage_diff_trace() {
+    let (api, handle) = spawn(NodeConfig::test()).await;
+    let provider = handle.ethers_http_provider();
+    let from = handle.dev_wallets().next().unwrap().address();
+
+    // PUSH1 0x2a PUSH1 0x01 SSTORE PUSH1 0x02 SLOAD STOP
+    let init_code = hex::decode("602a600155600254").unwrap();
+    let tx = TransactionRequest::new().data(init_code).from(from);
+    let receipt = provider.send_transaction(tx, None).await.unwrap().await.unwrap().unwrap();
+    let contract = receipt.contract_address.unwrap().to_alloy();
+
+    let opts = GethDebugTracingOptions {
+        tracer: Some(GethDebugTracerType::JsTracer(STORAGE_DIFF_TRACER.to_string())),
+        ..Default::default()
+    };
+    let trace = api.debug_trace_transaction(receipt.transaction_hash.to_alloy(), opts.into()).await;
+    let AlloyGethTrace::JS(diff) = trace.unwrap() else { panic!("expected storage diff") };
+    let diff: StorageDiff = serde_json::from_value(diff).unwrap();
+
+    let slots = &diff[&contract];
+    assert_eq!(slots[&U256::from(1)], (U256::ZERO, U256::from(42)));
+    assert_eq!(slots[&U256::from(2)], (U256::ZERO, U256::ZERO));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_debug_trace_block() {
+    let (api, handle) = spawn(NodeConfig::test()).await;
+    let provider = handle.ethers_http_provider();
+    let accounts: Vec<_> = handle.dev_wallets().map(|wallet| wallet.address()).collect();
+
+    api.anvil_set_auto_mine(false).await.unwrap();
+    let mut hashes = vec![];
+    for to in [accounts[1], accounts[2]] {
+        let tx = TransactionRequest::new().to(to).value(1u64).from(accounts[0]);
+        hashes.push(provider.send_transaction(tx, None).await.unwrap().tx_hash().to_alloy());
+    }
+    api.mine_one().await;
+
+    let opts = GethDebugTracingOptions {
+        tracer: Some(GethDebugTracerType::BuiltInTracer(GethDebugBuiltInTracerType::CallTracer)),
+        ..Default::default()
+    };
+    let traces = api.debug_trace_block_by_number(BlockNumberOrTag::Latest, opts).await.unwrap();
+    assert_eq!(traces.iter().map(|trace| trace.tx_hash).collect::<Vec<_>>(), hashes);
+    for (trace, to) in traces.into_iter().zip([accounts[1], accounts[2]]) {
+        let AlloyGethTrace::CallTracer(frame) = trace.result else { panic!("expected call frame") };
+        assert_eq!(frame.from, accounts[0].to_alloy());
+        assert_eq!(frame.to, Some(to.to_alloy()));
+    }
+
+    let block = api.block_by_number(BlockNumberOrTag::Latest).await.unwrap().unwrap();
+    let traces = api
+        .debug_trace_block_by_hash(block.header.hash.unwrap(), Default::default())
+        .await
+        .unwrap();
+    assert_eq!(traces.len(), 2);
+    assert!(traces
+        .iter()
+        .all(|trace| matches!(&trace.result, AlloyGethTrace::Default(frame) if !frame.failed)));
+
+    assert!(api
+        .debug_trace_block_by_number(BlockNumberOrTag::Number(10), Default::default())
+        .await
+        .is_err());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_steps_tracing_can_be_enabled() {
+    let (api, handle) = spawn(NodeConfig::test().with_steps_tracing_cache_size(1)).await;
+    let provider = handle.ethers_http_provider();
+    let from = handle.dev_wallets().next().unwrap().address();
+
+    // PUSH1 0x2a PUSH1 0x01 SSTORE STOP
+    let tx = TransactionRequest::new().data(hex::decode("602a600155").unwrap()).from(from);
+    let mut hashes = vec![];
+    for enabled in [false, true, true] {
+        api.anvil_set_tracing_enabled(enabled).unwrap();
+        let receipt =
+            provider.send_transaction(tx.clone(), None).await.unwrap().await.unwrap().unwrap();
+        hashes.push(receipt.transaction_hash.to_alloy());
+    }
+
+    let mut struct_logs = vec![];
+    for hash in &hashes {
+        let trace = api.debug_trace_transaction(*hash, Default::default()).await.unwrap();
+        let AlloyGethTrace::Default(frame) = trace else { panic!("expected default frame") };
+        assert!(!frame.failed);
+        struct_logs.push(frame.struct_logs);
+    }
+    // the steps are only traced while enabled and only kept for the most recent transaction
+    assert!(struct_logs[0].is_empty());
+    assert!(struct_logs[1].is_empty());
+    assert!(struct_logs[2].iter().any(|log| log.op == "SSTORE"));
+
+    // the call traces are always kept
+    let opts = GethDebugTracingOptions {
+        tracer: Some(GethDebugTracerType::BuiltInTracer(GethDebugBuiltInTracerType::CallTracer)),
+        ..Default::default()
+    };
+    let trace = api.debug_trace_transaction(hashes[1], opts.into()).await.unwrap();
+    let AlloyGethTrace::CallTracer(frame) = trace else { panic!("expected call frame") };
+    assert_eq!(frame.from, from.to_alloy());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_prestate_trace() {
+    let (api, handle) = spawn(NodeConfig::test()).await;
+    let provider = handle.ethers_http_provider();
+    let from = handle.dev_wallets().next().unwrap().address();
+
+    // PUSH1 0x2a PUSH1 0x01 SSTORE STOP
+    let tx = TransactionRequest::new().data(hex::decode("602a600155").unwrap()).from(from);
+    let receipt = provider.send_transaction(tx, None).await.unwrap().await.unwrap().unwrap();
+    let contract = receipt.contract_address.unwrap().to_alloy();
+
+    let opts = GethDebugTracingOptions {
+        tracer: Some(GethDebugTracerType::BuiltInTracer(
+            GethDebugBuiltInTracerType::PreStateTracer,
+        )),
+        ..Default::default()
+    };
+    let trace = api.debug_trace_transaction(receipt.transaction_hash.to_alloy(), opts.into()).await;
+    let AlloyGethTrace::PreStateTracer(PreStateFrame::Default(PreStateMode(accounts))) =
+        trace.unwrap()
+    else {
+        panic!("expected prestate frame")
+    };
+
+    // the state before the deployment
+    let sender = &accounts[&from.to_alloy()];
+    assert_eq!(sender.balance, Some(handle.genesis_balance().to_alloy()));
+    assert_eq!(sender.nonce, None);
+    let created = &accounts[&contract];
+    assert_eq!(created.code, None);
+    assert_eq!(created.storage[&B256::from(U256::from(1))], B256::ZERO);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_decoded_call_trace() {
+    let config = NodeConfig::test().with_signatures(Some(SignatureDatabase::new(["foo(uint256)"])));
+    let (api, handle) = spawn(config).await;
+    let provider = handle.ethers_http_provider();
+    let accounts: Vec<_> = handle.dev_wallets().map(|wallet| wallet.address()).collect();
+
+    let opts = TraceTransactionOptions {
+        tracing_options: GethDebugTracingOptions {
+            tracer: Some(GethDebugTracerType::BuiltInTracer(
+                GethDebugBuiltInTracerType::CallTracer,
+            )),
+            ..Default::default()
+        },
+        decode: true,
+    };
+
+    let inputs = [
+        // transfer(0x000000000000000000000000000000000000dEaD, 1)
+        "a9059cbb000000000000000000000000000000000000000000000000000000000000dead0000000000000000000000000000000000000000000000000000000000000001",
+        // foo(42)
+        "2fbebd38000000000000000000000000000000000000000000000000000000000000002a",
+        "deadbeef",
+    ];
+    let mut traces = vec![];
+    for input in inputs {
+        let tx = TransactionRequest::new()
+            .to(accounts[1])
+            .data(hex::decode(input).unwrap())
+            .from(accounts[0]);
+        let receipt = provider.send_transaction(tx, None).await.unwrap().await.unwrap().unwrap();
+        let trace = api
+            .debug_trace_transaction(receipt.transaction_hash.to_alloy(), opts.clone())
+            .await
+            .unwrap();
+        let AlloyGethTrace::JS(trace) = trace else { panic!("expected decoded call frame") };
+        traces.push(trace);
+    }
+
+    assert_eq!(traces[0]["decoded"]["signature"], "transfer(address,uint256)");
+    assert_eq!(traces[0]["decoded"]["params"][1], "1");
+    assert_eq!(traces[1]["decoded"]["signature"], "foo(uint256)");
+    assert_eq!(traces[1]["decoded"]["params"][0], "42");
+    // unknown selectors keep the raw input
+    assert!(traces[2].get("decoded").is_none());
+    assert_eq!(traces[2]["input"], "0xdeadbeef");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_inspect_transaction() {
+    let prj = TempProject::dapptools().unwrap();
+    prj.add_source(
+        "Contract",
+        r#"
+pragma solidity 0.8.13;
+contract Contract {
+    uint256 public value;
+    function log(string memory message) internal view {
+        (bool success,) = address(0x000000000000000000636F6e736F6c652e6c6f67).staticcall(
+            abi.encodeWithSignature("log(string)", message)
+        );
+        success;
+    }
+    function set(uint256 newValue) public {
+        value = newValue;
+    }
+    function run() public {
+        log(value == 7 ? "value is 7" : "value is not 7");
+        this.set(8);
+    }
+    function fail() public {
+        log("failing");
+        revert("nope");
+    }
+}
+"#,
+    )
+    .unwrap();
+
+    let mut compiled = prj.compile().unwrap();
+    assert!(!compiled.has_compiler_errors());
+    let contract = compiled.remove_first("Contract").unwrap();
+    let (abi, bytecode, _) = contract.into_contract_bytecode().into_parts();
+
+    let (api, handle) = spawn(NodeConfig::test()).await;
+    let provider = handle.ethers_http_provider();
+    let wallets = handle.dev_wallets().collect::<Vec<_>>();
+    let from = wallets[0].address();
+    let client = Arc::new(SignerMiddleware::new(provider.clone(), wallets[0].clone()));
+
+    let factory = ContractFactory::new(abi.clone().unwrap(), bytecode.unwrap(), client);
+    let contract = factory.deploy(()).unwrap().send().await.unwrap();
+    let contract = ContractInstance::new(contract.address(), abi.unwrap(), provider.clone());
+
+    // the inspected transaction is executed on top of the one before it in the same block
+    api.anvil_set_auto_mine(false).await.unwrap();
+    let mut hashes = vec![];
+    for mut tx in [
+        contract.method::<_, ()>("set", 7u64).unwrap().tx,
+        contract.method::<_, ()>("run", ()).unwrap().tx,
+    ] {
+        tx.set_from(from).set_gas(200_000u64);
+        hashes.push(provider.send_transaction(tx, None).await.unwrap().tx_hash().to_alloy());
+    }
+    api.mine_one().await;
+
+    let report = api.anvil_inspect_transaction(hashes[1]).await.unwrap().unwrap();
+    assert_eq!(report.transaction_hash, hashes[1]);
+    assert!(report.success);
+    assert!(report.gas_used > 21_000);
+    assert_eq!(report.console_logs, vec!["value is 7".to_string()]);
+    assert_eq!(report.revert_reason, None);
+    let set = report.calls.iter().find(|call| call.to == contract.address().to_alloy()).unwrap();
+    assert_eq!(set.from, contract.address().to_alloy());
+    assert!(set.success);
+    assert!(set.gas_used > 0);
+
+    api.anvil_set_auto_mine(true).await.unwrap();
+    let mut tx = contract.method::<_, ()>("fail", ()).unwrap().tx;
+    tx.set_from(from).set_gas(200_000u64);
+    let receipt = provider.send_transaction(tx, None).await.unwrap().await.unwrap().unwrap();
+    assert_eq!(receipt.status, Some(0u64.into()));
+
+    let report =
+        api.anvil_inspect_transaction(receipt.transaction_hash.to_alloy()).await.unwrap().unwrap();
+    assert!(!report.success);
+    assert_eq!(report.console_logs, vec!["failing".to_string()]);
+    assert!(report.revert_reason.unwrap().contains("nope"));
+
+    assert!(api.anvil_inspect_transaction(H256::random().to_alloy()).await.unwrap().is_none());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_simulate_call() {
+    let prj = TempProject::dapptools().unwrap();
+    prj.add_source(
+        "Contract",
+        r#"
+pragma solidity 0.8.13;
+contract Contract {
+    uint256 public value;
+    event Set(uint256 value);
+    function log(string memory message) internal view {
+        (bool success,) = address(0x000000000000000000636F6e736F6c652e6c6f67).staticcall(
+            abi.encodeWithSignature("log(string)", message)
+        );
+        success;
+    }
+    function set(uint256 newValue) public returns (uint256) {
+        log("setting");
+        value = newValue;
+        emit Set(newValue);
+        return newValue + 1;
+    }
+    function run() public returns (uint256) {
+        return this.set(8);
+    }
+    function fail() public {
+        revert("nope");
+    }
+}
+"#,
+    )
+    .unwrap();
+
+    let mut compiled = prj.compile().unwrap();
+    assert!(!compiled.has_compiler_errors());
+    let contract = compiled.remove_first("Contract").unwrap();
+    let (abi, bytecode, _) = contract.into_contract_bytecode().into_parts();
+
+    let (api, handle) = spawn(NodeConfig::test()).await;
+    let provider = handle.ethers_http_provider();
+    let wallets = handle.dev_wallets().collect::<Vec<_>>();
+    let from = wallets[0].address();
+    let client = Arc::new(SignerMiddleware::new(provider.clone(), wallets[0].clone()));
+
+    let factory = ContractFactory::new(abi.clone().unwrap(), bytecode.unwrap(), client);
+    let contract = factory.deploy(()).unwrap().send().await.unwrap();
+    let contract = ContractInstance::new(contract.address(), abi.unwrap(), provider.clone());
+    let call = |method: &str| {
+        let input = contract.method::<_, ()>(method, ()).unwrap().calldata().unwrap();
+        CallRequest {
+            from: Some(from.to_alloy()),
+            to: Some(contract.address().to_alloy()),
+            input: alloy_primitives::Bytes::from(input.0).into(),
+            ..Default::default()
+        }
+    };
+    let block_number = provider.get_block_number().await.unwrap();
+
+    let simulation = api.anvil_simulate(call("run"), None, None).await.unwrap();
+    assert!(simulation.success);
+    assert!(simulation.gas_used > 21_000);
+    assert_eq!(simulation.return_data, B256::from(U256::from(9)).0.to_vec());
+    assert_eq!(simulation.revert_reason, None);
+    assert_eq!(simulation.console_logs, vec!["setting".to_string()]);
+    assert_eq!(simulation.logs.len(), 1);
+    assert_eq!(simulation.logs[0].address, contract.address().to_alloy());
+    assert_eq!(simulation.logs[0].data, B256::from(U256::from(8)).0.to_vec());
+    assert_eq!(simulation.trace.calls.len(), 1);
+    assert_eq!(simulation.trace.calls[0].logs.len(), 1);
+
+    // nothing was committed or mined
+    let value: ethers::types::U256 = contract.method("value", ()).unwrap().call().await.unwrap();
+    assert_eq!(value, 0u64.into());
+    assert_eq!(provider.get_block_number().await.unwrap(), block_number);
+    assert_eq!(api.txpool_status().await.unwrap().pending.to::<u64>(), 0);
+
+    let simulation = api.anvil_simulate(call("fail"), None, None).await.unwrap();
+    assert!(!simulation.success);
+    assert!(simulation.revert_reason.unwrap().contains("nope"));
+    assert!(simulation.logs.is_empty());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_decode_with_registered_abi() {
+    let prj = TempProject::dapptools().unwrap();
+    prj.add_source(
+        "Emitter",
+        r#"
+pragma solidity 0.8.13;
+contract Emitter {
+    event Set(uint256 value);
+    function set(uint256 newValue) public {
+        emit Set(newValue);
+    }
+}
+"#,
+    )
+    .unwrap();
+
+    let mut compiled = prj.compile().unwrap();
+    assert!(!compiled.has_compiler_errors());
+    let contract = compiled.remove_first("Emitter").unwrap();
+    let (abi, bytecode, _) = contract.into_contract_bytecode().into_parts();
+    let abi = abi.unwrap();
+
+    let (api, handle) = spawn(NodeConfig::test()).await;
+    let provider = handle.ethers_http_provider();
+    let wallet = handle.dev_wallets().next().unwrap();
+    let client = Arc::new(SignerMiddleware::new(provider.clone(), wallet.clone()));
+
+    let factory = ContractFactory::new(abi.clone(), bytecode.unwrap(), client.clone());
+    let contract = factory.deploy(()).unwrap().send().await.unwrap();
+    let input =
+        contract.method::<_, ()>("set", ethers::types::U256::from(8)).unwrap().calldata().unwrap();
+
+    let json_abi = serde_json::from_value(serde_json::to_value(&abi).unwrap()).unwrap();
+    api.anvil_register_abi(Some(contract.address().to_alloy()), json_abi).unwrap();
+
+    let tx = TransactionRequest::new().to(contract.address()).data(input.clone());
+    let receipt = client.send_transaction(tx, None).await.unwrap().await.unwrap().unwrap();
+    let opts = TraceTransactionOptions {
+        tracing_options: GethDebugTracingOptions {
+            tracer: Some(GethDebugTracerType::BuiltInTracer(
+                GethDebugBuiltInTracerType::CallTracer,
+            )),
+            tracer_config: GethDebugTracerConfig(serde_json::json!({ "withLog": true })),
+            ..Default::default()
+        },
+        decode: true,
+    };
+    let trace =
+        api.debug_trace_transaction(receipt.transaction_hash.to_alloy(), opts).await.unwrap();
+    let AlloyGethTrace::JS(trace) = trace else { panic!("expected decoded call frame") };
+    assert_eq!(trace["decoded"]["signature"], "set(uint256)");
+    assert_eq!(trace["decoded"]["names"][0], "newValue");
+    assert_eq!(trace["logs"][0]["decoded"]["signature"], "Set(uint256)");
+    assert_eq!(trace["logs"][0]["decoded"]["names"][0], "value");
+    assert_eq!(trace["logs"][0]["decoded"]["params"][0], "8");
+
+    let request = CallRequest {
+        from: Some(wallet.address().to_alloy()),
+        to: Some(contract.address().to_alloy()),
+        input: alloy_primitives::Bytes::from(input.0).into(),
+        ..Default::default()
+    };
+    let simulation = api.anvil_simulate(request, None, None).await.unwrap();
+    let decoded = simulation.logs[0].decoded.as_ref().unwrap();
+    assert_eq!(decoded.signature, "Set(uint256)");
+    assert_eq!(decoded.names, vec!["value".to_string()]);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_trace_gas_summary() {
+    let (api, handle) = spawn(NodeConfig::test()).await;
+    let provider = handle.ethers_http_provider();
+    let accounts: Vec<_> = handle.dev_wallets().map(|wallet| wallet.address()).collect();
+
+    let tx = TransactionRequest::new()
+        .to(accounts[1])
+        .data(hex::decode("a9059cbb").unwrap())
+        .from(accounts[0]);
+    let receipt = provider.send_transaction(tx, None).await.unwrap().await.unwrap().unwrap();
+
+    let summary =
+        api.anvil_trace_gas_summary(receipt.transaction_hash.to_alloy()).unwrap().unwrap();
+    assert_eq!(summary.len(), 1);
+    assert_eq!(summary[0].address, accounts[1].to_alloy());
+    assert_eq!(
+        summary[0].selector,
+        Some(alloy_primitives::Selector::from([0xa9, 0x05, 0x9c, 0xbb]))
+    );
+    assert_eq!(summary[0].self_gas_used, summary[0].gas_used);
+
+    assert_eq!(api.anvil_trace_gas_summary(B256::random()).unwrap(), None);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_simulate_bundle() {
+    let prj = TempProject::dapptools().unwrap();
+    prj.add_source(
+        "Counter",
+        r#"
+pragma solidity 0.8.13;
+contract Counter {
+    uint256 public value;
+    function set(uint256 newValue) public {
+        value = newValue;
+    }
+    function check(uint256 expected) public view returns (uint256) {
+        require(value == expected, "mismatch");
+        return block.number;
+    }
+}
+"#,
+    )
+    .unwrap();
+
+    let mut compiled = prj.compile().unwrap();
+    assert!(!compiled.has_compiler_errors());
+    let contract = compiled.remove_first("Counter").unwrap();
+    let (abi, bytecode, _) = contract.into_contract_bytecode().into_parts();
+
+    let (api, handle) = spawn(NodeConfig::test()).await;
+    let provider = handle.ethers_http_provider();
+    let wallets = handle.dev_wallets().collect::<Vec<_>>();
+    let from = wallets[0].address();
+    let client = Arc::new(SignerMiddleware::new(provider.clone(), wallets[0].clone()));
+
+    let factory = ContractFactory::new(abi.clone().unwrap(), bytecode.unwrap(), client);
+    let contract = factory.deploy(()).unwrap().send().await.unwrap();
+    let contract = ContractInstance::new(contract.address(), abi.unwrap(), provider.clone());
+    let call = |method: &str, value: u64| {
+        let input = contract.method::<_, ()>(method, value).unwrap().calldata().unwrap();
+        CallRequest {
+            from: Some(from.to_alloy()),
+            to: Some(contract.address().to_alloy()),
+            input: alloy_primitives::Bytes::from(input.0).into(),
+            ..Default::default()
+        }
+    };
+    let bundle = vec![call("set", 5), call("check", 5), call("check", 6), call("check", 5)];
+    let overrides = BlockOverrides { number: Some(U64::from(100)), ..Default::default() };
+
+    // every call sees the changes of the calls before it
+    let simulations =
+        api.anvil_simulate_bundle(bundle.clone(), Some(overrides.clone()), None).await.unwrap();
+    let success = simulations.iter().map(|simulation| simulation.success).collect::<Vec<_>>();
+    assert_eq!(success, vec![true, true, false, true]);
+    assert_eq!(simulations[1].return_data, B256::from(U256::from(100)).0.to_vec());
+    assert!(simulations[2].revert_reason.as_ref().unwrap().contains("mismatch"));
+
+    let simulations = api.anvil_simulate_bundle(bundle, Some(overrides), Some(true)).await.unwrap();
+    assert_eq!(simulations.len(), 3);
+    assert!(!simulations[2].success);
+
+    // all changes are discarded
+    let value: ethers::types::U256 = contract.method("value", ()).unwrap().call().await.unwrap();
+    assert_eq!(value, 0u64.into());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_call_with_block_overrides() {
+    let prj = TempProject::dapptools().unwrap();
+    prj.add_source(
+        "BlockEnv",
+        r#"
+pragma solidity 0.8.13;
+contract BlockEnv {
+    function env() public view returns (address, uint256, uint256, uint256, uint256) {
+        return (block.coinbase, block.difficulty, block.basefee, block.number, block.timestamp);
+    }
+}
+"#,
+    )
+    .unwrap();
+
+    let mut compiled = prj.compile().unwrap();
+    assert!(!compiled.has_compiler_errors());
+    let contract = compiled.remove_first("BlockEnv").unwrap();
+    let (abi, bytecode, _) = contract.into_contract_bytecode().into_parts();
+
+    let (api, handle) = spawn(NodeConfig::test()).await;
+    let provider = handle.ethers_http_provider();
+    let wallet = handle.dev_wallets().next().unwrap();
+    let client = Arc::new(SignerMiddleware::new(provider.clone(), wallet));
+
+    let factory = ContractFactory::new(abi.clone().unwrap(), bytecode.unwrap(), client);
+    let contract = factory.deploy(()).unwrap().send().await.unwrap();
+    let input = contract.method::<_, ()>("env", ()).unwrap().calldata().unwrap();
+    let request = CallRequest {
+        to: Some(contract.address().to_alloy()),
+        input: alloy_primitives::Bytes::from(input.0).into(),
+        ..Default::default()
+    };
+
+    let coinbase = Address::random().to_alloy();
+    let prev_randao = B256::random();
+    let overrides = BlockOverrides {
+        number: Some(U64::from(1_000)),
+        time: Some(U64::from(1_700_000_000)),
+        coinbase: Some(coinbase),
+        prev_randao: Some(prev_randao),
+        base_fee: Some(U256::from(7)),
+        gas_limit: None,
+    };
+    let expected = [
+        coinbase.into_word(),
+        prev_randao,
+        B256::from(U256::from(7)),
+        B256::from(U256::from(1_000)),
+        B256::from(U256::from(1_700_000_000)),
+    ]
+    .iter()
+    .flat_map(|word| word.0)
+    .collect::<Vec<_>>();
+
+    let output = api.call(request.clone(), None, None, Some(overrides.clone())).await.unwrap();
+    assert_eq!(output, expected);
+    let simulation = api.anvil_simulate(request.clone(), None, Some(overrides)).await.unwrap();
+    assert_eq!(simulation.return_data, expected);
+
+    // the overrides only apply to the call
+    let output = api.call(request, None, None, None).await.unwrap();
+    assert_ne!(output, expected);
+    assert_eq!(&output[96..128], B256::from(U256::from(1)).as_slice());
+}
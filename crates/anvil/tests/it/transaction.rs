@@ -1,15 +1,26 @@
 use crate::abi::*;
+use alloy_primitives::U256 as rU256;
 use anvil::{spawn, Hardfork, NodeConfig};
+use anvil_core::eth::transaction::{
+    EIP4844Transaction, EIP4844TransactionRequest, EIP7702Transaction, EIP7702TransactionRequest,
+    SignedAuthorization, TransactionKind,
+};
 use ethers::{
     abi::ethereum_types::BigEndianHash,
     prelude::{
         signer::SignerMiddlewareError, BlockId, Middleware, Signer, SignerMiddleware,
         TransactionRequest,
     },
+    providers::RpcError,
     types::{
-        transaction::eip2930::{AccessList, AccessListItem},
-        Address, BlockNumber, Transaction, TransactionReceipt, H256, U256,
+        transaction::{
+            eip2718::TypedTransaction,
+            eip2930::{AccessList, AccessListItem},
+        },
+        Address, BlockNumber, Bytes, Eip1559TransactionRequest, Eip2930TransactionRequest,
+        Transaction, TransactionReceipt, H256, U256,
     },
+    utils::{keccak256, rlp},
 };
 use foundry_common::types::{to_call_request_from_tx_request, ToAlloy, ToEthers};
 use futures::{future::join_all, FutureExt, StreamExt};
@@ -85,6 +96,78 @@ async fn can_order_transactions() {
     assert_eq!(block.transactions, vec![higher_price, lower_price])
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn can_resort_transactions_on_order_change() {
+    let (api, handle) = spawn(NodeConfig::test()).await;
+    let provider = handle.ethers_http_provider();
+
+    api.anvil_set_auto_mine(false).await.unwrap();
+    api.anvil_set_tx_ordering("fifo").unwrap();
+
+    let accounts: Vec<_> = handle.dev_wallets().collect();
+    let from = accounts[0].address();
+    let to = accounts[1].address();
+    let gas_price = provider.get_gas_price().await.unwrap();
+
+    let tx = TransactionRequest::new().to(to).from(from).value(1u64).gas_price(gas_price);
+    let tx_lower = provider.send_transaction(tx, None).await.unwrap();
+    let tx = TransactionRequest::new().to(from).from(to).value(1u64).gas_price(gas_price + 1);
+    let tx_higher = provider.send_transaction(tx, None).await.unwrap();
+
+    // the pending transactions are re-sorted by their fees
+    api.anvil_set_tx_ordering("fees").unwrap();
+    api.mine_one().await;
+
+    let block = provider.get_block(BlockNumber::Latest).await.unwrap().unwrap();
+    let lower_price = tx_lower.await.unwrap().unwrap().transaction_hash;
+    let higher_price = tx_higher.await.unwrap().unwrap().transaction_hash;
+    assert_eq!(block.transactions, vec![higher_price, lower_price]);
+
+    assert!(api.anvil_set_tx_ordering("random").is_err());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn can_pin_next_block_order() {
+    let (api, handle) = spawn(NodeConfig::test()).await;
+    let provider = handle.ethers_http_provider();
+
+    api.anvil_set_auto_mine(false).await.unwrap();
+
+    let accounts: Vec<_> = handle.dev_wallets().map(|wallet| wallet.address()).collect();
+    let gas_price = provider.get_gas_price().await.unwrap();
+    let send = |from: Address, nonce: u64, tip: u64| {
+        let tx = TransactionRequest::new()
+            .to(Address::random())
+            .from(from)
+            .value(1u64)
+            .nonce(nonce)
+            .gas_price(gas_price + tip);
+        let provider = provider.clone();
+        async move { provider.send_transaction(tx, None).await.unwrap().tx_hash() }
+    };
+
+    let first = send(accounts[0], 0, 0).await;
+    let second = send(accounts[0], 1, 0).await;
+    let pinned = send(accounts[1], 0, 1).await;
+    let highest = send(accounts[2], 0, 2).await;
+
+    // unknown transactions are rejected
+    api.anvil_set_next_block_order(vec![H256::random().to_alloy()]).unwrap_err();
+
+    // the lower nonce of the sender is moved ahead of the pinned transaction
+    api.anvil_set_next_block_order(vec![second.to_alloy(), pinned.to_alloy()]).unwrap();
+    api.mine_one().await;
+    let block = provider.get_block(BlockNumber::Latest).await.unwrap().unwrap();
+    assert_eq!(block.transactions, vec![first, second, pinned, highest]);
+
+    // the order only applies to a single block
+    let lower = send(accounts[0], 2, 0).await;
+    let higher = send(accounts[1], 1, 1).await;
+    api.mine_one().await;
+    let block = provider.get_block(BlockNumber::Latest).await.unwrap().unwrap();
+    assert_eq!(block.transactions, vec![higher, lower]);
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn can_respect_nonces() {
     let (api, handle) = spawn(NodeConfig::test()).await;
@@ -143,9 +226,9 @@ async fn can_replace_transaction() {
     let lower_priced_pending_tx =
         provider.send_transaction(tx.clone().gas_price(gas_price), None).await.unwrap();
 
-    // send the same transaction with higher gas price
+    // send the same transaction with a gas price that is higher by the minimum bump of 10%
     let higher_priced_pending_tx =
-        provider.send_transaction(tx.gas_price(gas_price + 1u64), None).await.unwrap();
+        provider.send_transaction(tx.gas_price(gas_price * 11u64 / 10u64), None).await.unwrap();
 
     // mine exactly one block
     api.mine_one().await;
@@ -162,6 +245,42 @@ async fn can_replace_transaction() {
     assert_eq!(vec![higher_priced_receipt.transaction_hash], block.transactions);
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn can_reject_replacement_below_min_fee_bump() {
+    let (api, handle) = spawn(NodeConfig::test().with_min_fee_bump_percent(20)).await;
+
+    // disable auto mining
+    api.anvil_set_auto_mine(false).await.unwrap();
+
+    let provider = handle.ethers_http_provider();
+
+    let accounts: Vec<_> = handle.dev_wallets().collect();
+    let from = accounts[0].address();
+    let to = accounts[1].address();
+
+    let nonce = provider.get_transaction_count(from, None).await.unwrap();
+    let gas_price = provider.get_gas_price().await.unwrap();
+
+    let tx = TransactionRequest::new().to(to).value(1u64).from(from).nonce(nonce);
+    provider.send_transaction(tx.clone().gas_price(gas_price), None).await.unwrap();
+
+    // a bump of 10% is not enough
+    let err = provider
+        .send_transaction(tx.clone().gas_price(gas_price * 11u64 / 10u64), None)
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("replacement transaction underpriced"), "{err}");
+    assert!(err.to_string().contains("20%"), "{err}");
+
+    api.anvil_set_min_fee_bump_percent(rU256::from(10)).unwrap();
+    let replacement =
+        provider.send_transaction(tx.gas_price(gas_price * 11u64 / 10u64), None).await.unwrap();
+
+    api.mine_one().await;
+    let block = provider.get_block(1u64).await.unwrap().unwrap();
+    assert_eq!(vec![replacement.tx_hash()], block.transactions);
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn can_reject_too_high_gas_limits() {
     let (api, handle) = spawn(NodeConfig::test()).await;
@@ -187,7 +306,7 @@ async fn can_reject_too_high_gas_limits() {
 
     assert!(pending.is_err());
     let err = pending.unwrap_err();
-    assert!(err.to_string().contains("gas too high"));
+    assert!(err.to_string().contains("exceeds block gas limit"), "{err}");
 
     api.anvil_set_balance(from.to_alloy(), U256::MAX.to_alloy()).await.unwrap();
 
@@ -195,6 +314,143 @@ async fn can_reject_too_high_gas_limits() {
     pending.unwrap();
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn can_reject_raw_transaction_above_block_gas_limit() {
+    let (api, handle) = spawn(NodeConfig::test()).await;
+    let provider = handle.ethers_http_provider();
+
+    let wallet = handle.dev_wallets().next().unwrap();
+    let tx: TypedTransaction = TransactionRequest::new()
+        .to(Address::random())
+        .value(1u64)
+        .from(wallet.address())
+        .nonce(0u64)
+        .gas(api.gas_limit().to_ethers() + 1u64)
+        .gas_price(api.gas_price().unwrap().to_ethers())
+        .chain_id(wallet.chain_id())
+        .into();
+    let signature = wallet.sign_transaction(&tx).await.unwrap();
+
+    let err = provider.send_raw_transaction(tx.rlp_signed(&signature)).await.unwrap_err();
+    let err = err.as_error_response().unwrap();
+    assert_eq!(err.code, -32000);
+    assert!(err.message.contains("exceeds block gas limit"), "{}", err.message);
+
+    // the transaction never made it into the pool
+    let status = api.txpool_status().await.unwrap();
+    assert_eq!(status.pending.to::<u64>() + status.queued.to::<u64>(), 0);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn can_send_raw_transactions_of_all_types() {
+    let (api, handle) = spawn(NodeConfig::test()).await;
+    let provider = handle.ethers_http_provider();
+
+    let wallet = handle.dev_wallets().next().unwrap();
+    let to = Address::random();
+    let access_list = AccessList(vec![AccessListItem {
+        address: to,
+        storage_keys: vec![H256::from_low_u64_be(1)],
+    }]);
+    let gas_price = api.gas_price().unwrap().to_ethers();
+
+    let legacy = TransactionRequest::new()
+        .to(to)
+        .value(1u64)
+        .from(wallet.address())
+        .gas(50_000u64)
+        .gas_price(gas_price)
+        .chain_id(wallet.chain_id());
+    let eip2930 = Eip2930TransactionRequest::new(legacy.clone(), access_list.clone());
+    let eip1559 = Eip1559TransactionRequest::new()
+        .to(to)
+        .value(1u64)
+        .from(wallet.address())
+        .gas(50_000u64)
+        .max_fee_per_gas(gas_price)
+        .max_priority_fee_per_gas(1u64)
+        .access_list(access_list.clone())
+        .chain_id(wallet.chain_id());
+
+    let txs: [(TypedTransaction, u64); 3] =
+        [(legacy.into(), 0), (eip2930.into(), 1), (eip1559.into(), 2)];
+    for (nonce, (mut tx, ty)) in txs.into_iter().enumerate() {
+        tx.set_nonce(nonce as u64);
+        let signature = wallet.sign_transaction(&tx).await.unwrap();
+        let raw = tx.rlp_signed(&signature);
+
+        let receipt = provider.send_raw_transaction(raw).await.unwrap().await.unwrap().unwrap();
+        assert_eq!(receipt.from, wallet.address());
+        assert_eq!(receipt.transaction_type, Some(ty.into()));
+
+        let mined = provider.get_transaction(receipt.transaction_hash).await.unwrap().unwrap();
+        assert_eq!(mined.hash, tx.hash(&signature));
+        assert_eq!(mined.from, wallet.address());
+        assert_eq!(mined.transaction_type, Some(ty.into()));
+        if ty == 0 {
+            assert!(mined.access_list.map_or(true, |list| list.0.is_empty()));
+        } else {
+            assert_eq!(mined.access_list, Some(access_list.clone()));
+        }
+    }
+
+    // trailing bytes are rejected
+    let tx: TypedTransaction = TransactionRequest::new()
+        .to(to)
+        .from(wallet.address())
+        .nonce(3u64)
+        .gas(50_000u64)
+        .gas_price(gas_price)
+        .chain_id(wallet.chain_id())
+        .into();
+    let signature = wallet.sign_transaction(&tx).await.unwrap();
+    let mut raw = tx.rlp_signed(&signature).to_vec();
+    raw.push(0);
+    provider.send_raw_transaction(raw.into()).await.unwrap_err();
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn raw_transaction_hash_is_keccak_of_raw_bytes() {
+    let (api, handle) = spawn(NodeConfig::test()).await;
+    let provider = handle.ethers_http_provider();
+    api.anvil_set_auto_mine(false).await.unwrap();
+
+    let wallet = handle.dev_wallets().next().unwrap();
+    let gas_price = api.gas_price().unwrap().to_ethers();
+    let legacy = TransactionRequest::new()
+        .to(Address::random())
+        .value(1u64)
+        .from(wallet.address())
+        .gas(50_000u64)
+        .gas_price(gas_price)
+        .chain_id(wallet.chain_id());
+    let eip2930 = Eip2930TransactionRequest::new(legacy.clone(), AccessList::default());
+    let eip1559 = Eip1559TransactionRequest::new()
+        .to(Address::random())
+        .value(1u64)
+        .from(wallet.address())
+        .gas(50_000u64)
+        .max_fee_per_gas(gas_price)
+        .max_priority_fee_per_gas(1u64)
+        .chain_id(wallet.chain_id());
+
+    let txs: [TypedTransaction; 3] = [legacy.into(), eip2930.into(), eip1559.into()];
+    for (nonce, mut tx) in txs.into_iter().enumerate() {
+        tx.set_nonce(nonce as u64);
+        let signature = wallet.sign_transaction(&tx).await.unwrap();
+        let raw = tx.rlp_signed(&signature);
+
+        // the hash is returned before the transaction is mined
+        let pending = provider.send_raw_transaction(raw.clone()).await.unwrap();
+        assert_eq!(pending.tx_hash(), H256::from(keccak256(&raw)));
+        assert_eq!(pending.tx_hash(), tx.hash(&signature));
+    }
+
+    api.mine_one().await;
+    let block = provider.get_block(BlockNumber::Latest).await.unwrap().unwrap();
+    assert_eq!(block.transactions.len(), 3);
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn can_reject_underpriced_replacement() {
     let (api, handle) = spawn(NodeConfig::test()).await;
@@ -900,6 +1156,11 @@ async fn test_tx_access_list() {
         AccessList::from(vec![AccessListItem { address: other_acc, storage_keys: vec![] }]),
     );
 
+    // The sender is warm anyway, so fetching its balance shouldn't add it to the Access List
+    let call_tx = multicall.get_eth_balance(sender).from(sender).tx;
+    let access_list = client.create_access_list(&call_tx, None).await.unwrap();
+    assert_access_list_eq(access_list.access_list, AccessList::from(vec![]));
+
     // With a subcall to another contract, the AccessList should be the same as when calling the
     // subcontract directly (given that the proxy contract doesn't read/write any state)
     let subcall_tx = multicall
@@ -1019,3 +1280,260 @@ async fn test_reject_eip1559_pre_london() {
     let greeting = greeter_contract.greet().call().await.unwrap();
     assert_eq!("Hello World!", greeting);
 }
+
+/// Signs the blob transaction and returns its `eth_sendRawTransaction` encoding
+fn sign_blob_transaction(
+    wallet: &ethers::signers::LocalWallet,
+    mut tx: EIP4844Transaction,
+) -> Bytes {
+    let signature = wallet.sign_hash(EIP4844TransactionRequest::from(tx.clone()).hash()).unwrap();
+    let recid: u8 = signature.recovery_id().unwrap().into();
+    tx.odd_y_parity = recid != 0;
+    tx.r = H256::from_uint(&signature.r);
+    tx.s = H256::from_uint(&signature.s);
+
+    let mut raw = vec![0x03];
+    raw.extend_from_slice(&rlp::encode(&tx));
+    raw.into()
+}
+
+fn blob_transaction(chain_id: u64, to: Address, versioned_hashes: Vec<H256>) -> EIP4844Transaction {
+    EIP4844Transaction {
+        chain_id,
+        nonce: U256::zero(),
+        max_priority_fee_per_gas: U256::from(1_000_000_000u64),
+        max_fee_per_gas: U256::from(10_000_000_000u64),
+        gas_limit: U256::from(21_000u64),
+        kind: TransactionKind::Call(to),
+        value: U256::from(1u64),
+        input: Default::default(),
+        access_list: Default::default(),
+        max_fee_per_blob_gas: U256::from(10u64),
+        blob_versioned_hashes: versioned_hashes,
+        odd_y_parity: false,
+        r: H256::zero(),
+        s: H256::zero(),
+    }
+}
+
+fn versioned_hash() -> H256 {
+    let mut hash = H256::random();
+    hash.0[0] = 0x01;
+    hash
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn can_send_blob_transaction() {
+    let (api, handle) = spawn(NodeConfig::test().with_hardfork(Some(Hardfork::Cancun))).await;
+    let provider = handle.ethers_http_provider();
+    let wallet = handle.dev_wallets().next().unwrap();
+    let to = Address::random();
+
+    assert_eq!(api.blob_base_fee().unwrap(), rU256::from(1));
+
+    let tx = blob_transaction(api.chain_id(), to, vec![versioned_hash(), versioned_hash()]);
+    let pending = provider.send_raw_transaction(sign_blob_transaction(&wallet, tx)).await.unwrap();
+    let receipt = pending.await.unwrap().unwrap();
+    assert_eq!(receipt.transaction_type, Some(3u64.into()));
+    assert_eq!(receipt.status, Some(1u64.into()));
+    assert_eq!(receipt.from, wallet.address());
+
+    let block =
+        api.block_by_number(alloy_rpc_types::BlockNumberOrTag::Latest).await.unwrap().unwrap();
+    assert_eq!(block.header.blob_gas_used.map(|gas| gas.to::<u64>()), Some(2 * 131_072));
+    assert_eq!(block.header.excess_blob_gas.map(|gas| gas.to::<u64>()), Some(0));
+
+    let tx = api.transaction_by_hash(receipt.transaction_hash.to_alloy()).await.unwrap().unwrap();
+    assert_eq!(tx.blob_versioned_hashes.len(), 2);
+    assert_eq!(tx.max_fee_per_blob_gas.map(|fee| fee.to::<u64>()), Some(10));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_reject_invalid_blob_transactions() {
+    let (api, handle) = spawn(NodeConfig::test().with_hardfork(Some(Hardfork::Cancun))).await;
+    let provider = handle.ethers_http_provider();
+    let wallet = handle.dev_wallets().next().unwrap();
+    let to = Address::random();
+    let chain_id = api.chain_id();
+
+    let tx = blob_transaction(chain_id, to, vec![]);
+    let err = provider.send_raw_transaction(sign_blob_transaction(&wallet, tx)).await.unwrap_err();
+    assert!(err.to_string().contains("at least one blob"), "{err}");
+
+    let tx = blob_transaction(chain_id, to, vec![H256::random()]);
+    let err = provider.send_raw_transaction(sign_blob_transaction(&wallet, tx)).await.unwrap_err();
+    assert!(err.to_string().contains("unsupported version"), "{err}");
+
+    let mut tx = blob_transaction(chain_id, to, vec![versioned_hash()]);
+    tx.max_fee_per_blob_gas = U256::zero();
+    let err = provider.send_raw_transaction(sign_blob_transaction(&wallet, tx)).await.unwrap_err();
+    assert!(err.to_string().contains("max_fee_per_blob_gas"), "{err}");
+
+    let (api, handle) = spawn(NodeConfig::test().with_hardfork(Some(Hardfork::Shanghai))).await;
+    let provider = handle.ethers_http_provider();
+    let tx = blob_transaction(api.chain_id(), to, vec![versioned_hash()]);
+    let err = provider.send_raw_transaction(sign_blob_transaction(&wallet, tx)).await.unwrap_err();
+    assert!(err.to_string().contains("not supported by the current hardfork"), "{err}");
+}
+
+/// Signs the set code transaction and returns its `eth_sendRawTransaction` encoding
+fn sign_set_code_transaction(
+    wallet: &ethers::signers::LocalWallet,
+    mut tx: EIP7702Transaction,
+) -> Bytes {
+    let signature = wallet.sign_hash(EIP7702TransactionRequest::from(tx.clone()).hash()).unwrap();
+    let recid: u8 = signature.recovery_id().unwrap().into();
+    tx.odd_y_parity = recid != 0;
+    tx.r = H256::from_uint(&signature.r);
+    tx.s = H256::from_uint(&signature.s);
+
+    let mut raw = vec![0x04];
+    raw.extend_from_slice(&rlp::encode(&tx));
+    raw.into()
+}
+
+fn sign_authorization(
+    wallet: &ethers::signers::LocalWallet,
+    chain_id: u64,
+    address: Address,
+    nonce: u64,
+) -> SignedAuthorization {
+    let mut authorization = SignedAuthorization {
+        chain_id,
+        address,
+        nonce,
+        y_parity: false,
+        r: H256::zero(),
+        s: H256::zero(),
+    };
+    let signature = wallet.sign_hash(authorization.signature_hash()).unwrap();
+    let recid: u8 = signature.recovery_id().unwrap().into();
+    authorization.y_parity = recid != 0;
+    authorization.r = H256::from_uint(&signature.r);
+    authorization.s = H256::from_uint(&signature.s);
+    authorization
+}
+
+fn set_code_transaction(
+    chain_id: u64,
+    nonce: u64,
+    to: Address,
+    authorization_list: Vec<SignedAuthorization>,
+) -> EIP7702Transaction {
+    EIP7702Transaction {
+        chain_id,
+        nonce: nonce.into(),
+        max_priority_fee_per_gas: U256::from(1_000_000_000u64),
+        max_fee_per_gas: U256::from(10_000_000_000u64),
+        gas_limit: U256::from(100_000u64),
+        kind: TransactionKind::Call(to),
+        value: U256::zero(),
+        input: Default::default(),
+        access_list: Default::default(),
+        authorization_list,
+        odd_y_parity: false,
+        r: H256::zero(),
+        s: H256::zero(),
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_reject_set_code_transaction_before_prague() {
+    let (api, handle) = spawn(NodeConfig::test().with_hardfork(Some(Hardfork::Cancun))).await;
+    let provider = handle.ethers_http_provider();
+    let wallets: Vec<_> = handle.dev_wallets().collect();
+    let chain_id = api.chain_id();
+
+    let authorization = sign_authorization(&wallets[1], chain_id, Address::random(), 0);
+    let tx = set_code_transaction(chain_id, 0, wallets[1].address(), vec![authorization]);
+    let err = provider
+        .send_raw_transaction(sign_set_code_transaction(&wallets[0], tx))
+        .await
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("not supported by the current hardfork"), "{err}");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn can_send_set_code_transaction() {
+    let (api, handle) = spawn(NodeConfig::test().with_hardfork(Some(Hardfork::Prague))).await;
+    let provider = handle.ethers_http_provider();
+    let wallets: Vec<_> = handle.dev_wallets().collect();
+    let chain_id = api.chain_id();
+
+    // stores the caller in slot 0: CALLER PUSH0 SSTORE STOP
+    let delegate = Address::random();
+    api.anvil_set_code(delegate.to_alloy(), vec![0x33, 0x5f, 0x55, 0x00].into()).await.unwrap();
+
+    let sender = &wallets[0];
+    let authority = &wallets[1];
+    let authorization = sign_authorization(authority, chain_id, delegate, 0);
+    let tx = set_code_transaction(chain_id, 0, authority.address(), vec![authorization]);
+    let pending =
+        provider.send_raw_transaction(sign_set_code_transaction(sender, tx)).await.unwrap();
+    let receipt = pending.await.unwrap().unwrap();
+    assert_eq!(receipt.transaction_type, Some(4u64.into()));
+    assert_eq!(receipt.status, Some(1u64.into()));
+
+    let mut designator = vec![0xef, 0x01, 0x00];
+    designator.extend_from_slice(delegate.as_bytes());
+    assert_eq!(provider.get_code(authority.address(), None).await.unwrap().to_vec(), designator);
+    assert_eq!(provider.get_transaction_count(authority.address(), None).await.unwrap(), 1.into());
+    // the delegated code ran in the context of the authority
+    let slot = provider.get_storage_at(authority.address(), H256::zero(), None).await.unwrap();
+    assert_eq!(Address::from(slot), sender.address());
+
+    let tx = api.transaction_by_hash(receipt.transaction_hash.to_alloy()).await.unwrap().unwrap();
+    assert_eq!(
+        tx.other.get("authorizationList").and_then(|list| list.as_array()).unwrap().len(),
+        1
+    );
+
+    // the delegation is respected by later transactions and calls
+    let caller = &wallets[2];
+    let tx = TransactionRequest::new().from(caller.address()).to(authority.address()).gas(100_000);
+    provider.send_transaction(tx, None).await.unwrap().await.unwrap().unwrap();
+    let slot = provider.get_storage_at(authority.address(), H256::zero(), None).await.unwrap();
+    assert_eq!(Address::from(slot), caller.address());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn can_authorize_set_code_by_sender() {
+    let (api, handle) = spawn(NodeConfig::test().with_hardfork(Some(Hardfork::Prague))).await;
+    let provider = handle.ethers_http_provider();
+    let wallet = handle.dev_wallets().next().unwrap();
+    let chain_id = api.chain_id();
+
+    let delegate = Address::random();
+    api.anvil_set_code(delegate.to_alloy(), vec![0x33, 0x5f, 0x55, 0x00].into()).await.unwrap();
+
+    // the nonce of the sender is bumped before the authorization is checked
+    let authorization = sign_authorization(&wallet, 0, delegate, 1);
+    let tx = set_code_transaction(chain_id, 0, wallet.address(), vec![authorization]);
+    let pending =
+        provider.send_raw_transaction(sign_set_code_transaction(&wallet, tx)).await.unwrap();
+    let receipt = pending.await.unwrap().unwrap();
+    assert_eq!(receipt.status, Some(1u64.into()));
+    assert_eq!(provider.get_transaction_count(wallet.address(), None).await.unwrap(), 2.into());
+    let slot = provider.get_storage_at(wallet.address(), H256::zero(), None).await.unwrap();
+    assert_eq!(Address::from(slot), wallet.address());
+
+    // authorizations with a stale nonce are skipped
+    let other = Address::random();
+    let authorization = sign_authorization(&wallet, chain_id, other, 0);
+    let tx = set_code_transaction(chain_id, 2, Address::random(), vec![authorization]);
+    let pending =
+        provider.send_raw_transaction(sign_set_code_transaction(&wallet, tx)).await.unwrap();
+    assert_eq!(pending.await.unwrap().unwrap().status, Some(1u64.into()));
+    let code = provider.get_code(wallet.address(), None).await.unwrap();
+    assert_eq!(&code[3..], delegate.as_bytes());
+
+    // create transactions and empty authorization lists are rejected
+    let mut tx = set_code_transaction(chain_id, 3, Address::random(), vec![]);
+    let err = provider.send_raw_transaction(sign_set_code_transaction(&wallet, tx.clone())).await;
+    assert!(err.unwrap_err().to_string().contains("at least one authorization"));
+    tx.kind = TransactionKind::Create;
+    tx.authorization_list = vec![sign_authorization(&wallet, chain_id, other, 3)];
+    let err = provider.send_raw_transaction(sign_set_code_transaction(&wallet, tx)).await;
+    assert!(err.unwrap_err().to_string().contains("can't create contracts"));
+}
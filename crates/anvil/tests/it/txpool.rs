@@ -1,10 +1,13 @@
 //! txpool related tests
 
-use anvil::{spawn, NodeConfig};
+use alloy_primitives::U64;
+use anvil::{eth::pool::transactions::TransactionOrder, spawn, NodeConfig};
 use ethers::{
     prelude::Middleware,
-    types::{TransactionRequest, U256},
+    types::{Address, TransactionRequest, TxpoolContent, U256},
+    utils::parse_units,
 };
+use foundry_common::types::ToAlloy;
 
 #[tokio::test(flavor = "multi_thread")]
 async fn geth_txpool() {
@@ -48,3 +51,225 @@ async fn geth_txpool() {
         assert!(content.contains_key(&nonce.to_string()));
     }
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn can_get_mempool_with_queued_transactions() {
+    let (api, handle) = spawn(NodeConfig::test()).await;
+    let provider = handle.ethers_http_provider();
+    api.anvil_set_auto_mine(false).await.unwrap();
+
+    let account = provider.get_accounts().await.unwrap()[0];
+    let tx = TransactionRequest::new().to(account).from(account).value(42u64);
+
+    provider.send_transaction(tx.clone().nonce(0u64), None).await.unwrap();
+    // leaves a nonce gap, so this transaction can't be mined yet
+    provider.send_transaction(tx.nonce(2u64), None).await.unwrap();
+
+    let content: TxpoolContent = provider.request("anvil_getMempool", ()).await.unwrap();
+    let pending = content.pending.get(&account).unwrap();
+    assert_eq!(pending.len(), 1);
+    assert!(pending.contains_key("0"));
+    let queued = content.queued.get(&account).unwrap();
+    assert_eq!(queued.len(), 1);
+    assert!(queued.contains_key("2"));
+
+    let inspect = provider.txpool_inspect().await.unwrap();
+    assert!(inspect.pending.get(&account).unwrap().contains_key("0"));
+    assert!(inspect.queued.get(&account).unwrap().contains_key("2"));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn can_revert_mempool_with_full_snapshot() {
+    let (api, handle) = spawn(NodeConfig::test()).await;
+    let provider = handle.ethers_http_provider();
+    api.anvil_set_auto_mine(false).await.unwrap();
+
+    let account = provider.get_accounts().await.unwrap()[0];
+    let tx = TransactionRequest::new().to(account).from(account).value(42u64);
+
+    let before = provider.send_transaction(tx.clone().nonce(0u64), None).await.unwrap().tx_hash();
+    let id = api.anvil_snapshot_full().await.unwrap();
+
+    provider.send_transaction(tx.clone().nonce(1u64), None).await.unwrap();
+    provider.send_transaction(tx.nonce(3u64), None).await.unwrap();
+    let status = provider.txpool_status().await.unwrap();
+    assert_eq!(status.pending.as_u64(), 2);
+    assert_eq!(status.queued.as_u64(), 1);
+
+    assert!(api.evm_revert(id).await.unwrap());
+
+    // only the transaction that was sent before the snapshot is left
+    let content = provider.txpool_content().await.unwrap();
+    assert!(content.queued.is_empty());
+    let pending = content.pending.get(&account).unwrap();
+    assert_eq!(pending.len(), 1);
+    assert_eq!(pending.get("0").unwrap().hash, before);
+
+    // regular snapshots don't touch the mempool
+    let id = api.evm_snapshot().await.unwrap();
+    provider
+        .send_transaction(TransactionRequest::new().to(account).from(account).nonce(1u64), None)
+        .await
+        .unwrap();
+    assert!(api.evm_revert(id).await.unwrap());
+    assert_eq!(provider.txpool_status().await.unwrap().pending.as_u64(), 2);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn can_drop_transactions() {
+    let (api, handle) = spawn(NodeConfig::test()).await;
+    let provider = handle.ethers_http_provider();
+    api.anvil_set_auto_mine(false).await.unwrap();
+
+    let account = provider.get_accounts().await.unwrap()[0];
+    let tx = TransactionRequest::new().to(account).from(account).value(42u64);
+    let mut txs = Vec::new();
+    for nonce in 0..3u64 {
+        let tx_hash = provider.send_transaction(tx.clone().nonce(nonce), None).await.unwrap();
+        txs.push(tx_hash.tx_hash());
+    }
+
+    // dropping the first transaction creates a nonce gap, the others are queued
+    assert!(api.anvil_drop_transaction(txs[0].to_alloy()).await.unwrap());
+    assert!(!api.anvil_drop_transaction(txs[0].to_alloy()).await.unwrap());
+    let status = provider.txpool_status().await.unwrap();
+    assert_eq!(status.pending.as_u64(), 0);
+    assert_eq!(status.queued.as_u64(), 2);
+
+    // filling the gap promotes them again
+    provider.send_transaction(tx.clone().nonce(0u64).value(43u64), None).await.unwrap();
+    let status = provider.txpool_status().await.unwrap();
+    assert_eq!(status.pending.as_u64(), 3);
+    assert_eq!(status.queued.as_u64(), 0);
+
+    api.anvil_drop_all_transactions().await.unwrap();
+    let status = provider.txpool_status().await.unwrap();
+    assert_eq!(status.pending.as_u64(), 0);
+    assert_eq!(status.queued.as_u64(), 0);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn drop_transaction_requeues_descendants() {
+    let (api, handle) = spawn(NodeConfig::test()).await;
+    let provider = handle.ethers_http_provider();
+
+    let account = provider.get_accounts().await.unwrap()[0];
+    let tx = TransactionRequest::new().to(account).from(account).value(42u64);
+    // mine the first 5 nonces
+    for _ in 0..5 {
+        provider.send_transaction(tx.clone(), None).await.unwrap().await.unwrap().unwrap();
+    }
+
+    api.anvil_set_auto_mine(false).await.unwrap();
+    let mut txs = Vec::new();
+    for nonce in 5..8u64 {
+        let tx_hash = provider.send_transaction(tx.clone().nonce(nonce), None).await.unwrap();
+        txs.push(tx_hash.tx_hash());
+    }
+
+    // dropping nonce 6 queues nonce 7 until the gap is filled
+    assert!(api.anvil_drop_transaction(txs[1].to_alloy()).await.unwrap());
+    let status = provider.txpool_status().await.unwrap();
+    assert_eq!(status.pending.as_u64(), 1);
+    assert_eq!(status.queued.as_u64(), 1);
+
+    api.mine_one().await;
+    let block = provider.get_block(6u64).await.unwrap().unwrap();
+    assert_eq!(block.transactions, vec![txs[0]]);
+    let status = provider.txpool_status().await.unwrap();
+    assert_eq!(status.pending.as_u64(), 0);
+    assert_eq!(status.queued.as_u64(), 1);
+
+    // a replacement for nonce 6 promotes nonce 7 again
+    let replacement =
+        provider.send_transaction(tx.clone().nonce(6u64).value(43u64), None).await.unwrap();
+    api.mine_one().await;
+    let block = provider.get_block(7u64).await.unwrap().unwrap();
+    assert_eq!(block.transactions, vec![replacement.tx_hash(), txs[2]]);
+    assert_eq!(provider.get_transaction_count(account, None).await.unwrap(), 8u64.into());
+}
+
+/// A transfer to self with the given gas price in gwei
+fn transfer(from: Address, nonce: u64, gas_price: u64) -> TransactionRequest {
+    let gas_price: U256 = parse_units(gas_price, "gwei").unwrap().into();
+    TransactionRequest::new().to(from).from(from).nonce(nonce).gas_price(gas_price)
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn evicts_lowest_priced_transactions_without_stranding_descendants() {
+    let (api, handle) = spawn(NodeConfig::test().with_txpool_max_count(Some(3))).await;
+    let provider = handle.ethers_http_provider();
+    api.anvil_set_auto_mine(false).await.unwrap();
+
+    let accounts = provider.get_accounts().await.unwrap();
+    let cheapest =
+        provider.send_transaction(transfer(accounts[0], 0, 10), None).await.unwrap().tx_hash();
+    let parent =
+        provider.send_transaction(transfer(accounts[1], 0, 20), None).await.unwrap().tx_hash();
+    provider.send_transaction(transfer(accounts[2], 0, 30), None).await.unwrap();
+
+    // the pool is full, the cheapest transaction makes room for the new one
+    let cheap =
+        provider.send_transaction(transfer(accounts[3], 0, 15), None).await.unwrap().tx_hash();
+    assert!(provider.get_transaction(cheapest).await.unwrap().is_none());
+
+    // a transaction that pays less than all others is rejected
+    let err = provider.send_transaction(transfer(accounts[4], 0, 5), None).await.unwrap_err();
+    assert!(err.to_string().contains("underpriced"), "{err}");
+
+    // the parent of the new transaction isn't evicted for it
+    let child =
+        provider.send_transaction(transfer(accounts[1], 1, 40), None).await.unwrap().tx_hash();
+    assert!(provider.get_transaction(cheap).await.unwrap().is_none());
+    assert!(provider.get_transaction(parent).await.unwrap().is_some());
+
+    // the parent pays less, but evicting it would strand its child
+    let err = provider.send_transaction(transfer(accounts[4], 0, 25), None).await.unwrap_err();
+    assert!(err.to_string().contains("underpriced"), "{err}");
+
+    let status = api.txpool_status().await.unwrap();
+    assert_eq!(status.pending, U64::from(3));
+    assert_eq!(status.queued, U64::ZERO);
+    assert_eq!(status.max_count, Some(U64::from(3)));
+    assert_eq!(status.max_bytes, None);
+
+    api.mine_one().await;
+    let block = provider.get_block(1u64).await.unwrap().unwrap();
+    assert_eq!(block.transactions.len(), 3);
+    assert!(block.transactions.contains(&parent) && block.transactions.contains(&child));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn evicts_oldest_transactions_in_fifo_mode() {
+    // fits two transfers
+    let (api, handle) = spawn(
+        NodeConfig::test()
+            .with_transaction_order(TransactionOrder::Fifo)
+            .with_txpool_max_bytes(Some(250)),
+    )
+    .await;
+    let provider = handle.ethers_http_provider();
+    api.anvil_set_auto_mine(false).await.unwrap();
+
+    let accounts = provider.get_accounts().await.unwrap();
+    let mut txs = Vec::new();
+    for (account, gas_price) in accounts.iter().zip([30, 20, 10]) {
+        let tx = transfer(*account, 0, gas_price);
+        txs.push(provider.send_transaction(tx, None).await.unwrap().tx_hash());
+    }
+
+    // the oldest transaction is evicted regardless of its gas price
+    assert!(provider.get_transaction(txs[0]).await.unwrap().is_none());
+    assert!(provider.get_transaction(txs[1]).await.unwrap().is_some());
+    assert!(provider.get_transaction(txs[2]).await.unwrap().is_some());
+
+    let status = api.txpool_status().await.unwrap();
+    assert_eq!(status.pending, U64::from(2));
+    assert!(status.bytes <= U64::from(250) && status.bytes > U64::ZERO);
+    assert_eq!(status.max_bytes, Some(U64::from(250)));
+
+    // a transaction that doesn't fit into the pool at all is rejected
+    let tx = transfer(accounts[3], 0, 10).data(vec![1u8; 250]);
+    let err = provider.send_transaction(tx, None).await.unwrap_err();
+    assert!(err.to_string().contains("exceeds the max size of the txpool"), "{err}");
+}
@@ -119,17 +119,19 @@ impl Service<RequestPacket> for RetryBackoffService<RuntimeTransport> {
                 if should_retry {
                     rate_limit_retry_number += 1;
                     if rate_limit_retry_number > this.max_rate_limit_retries {
-                        return Err(TransportErrorKind::custom_str("Max retries exceeded"))
+                        this.requests_enqueued.fetch_sub(1, Ordering::SeqCst);
+                        return Err(TransportErrorKind::custom_str(&format!(
+                            "Max retries exceeded: {err}"
+                        )))
                     }
 
                     let current_queued_reqs = this.requests_enqueued.load(Ordering::SeqCst) as u64;
 
                     // try to extract the requested backoff from the error or compute the next
                     // backoff based on retry count
-                    let mut next_backoff = this
-                        .policy
-                        .backoff_hint(&err)
-                        .unwrap_or_else(|| std::time::Duration::from_millis(this.initial_backoff));
+                    let mut next_backoff = this.policy.backoff_hint(&err).unwrap_or_else(|| {
+                        exponential_backoff(this.initial_backoff, rate_limit_retry_number)
+                    });
 
                     // requests are usually weighted and can vary from 10 CU to several 100 CU,
                     // cheaper requests are more common some example alchemy
@@ -159,13 +161,22 @@ impl Service<RequestPacket> for RetryBackoffService<RuntimeTransport> {
                     }
 
                     this.requests_enqueued.fetch_sub(1, Ordering::SeqCst);
-                    return Err(TransportErrorKind::custom_str("Max retries exceeded"))
+                    return Err(TransportErrorKind::custom_str(&format!(
+                        "Max retries exceeded: {err}"
+                    )))
                 }
             }
         })
     }
 }
 
+/// Returns the backoff before the given retry, which doubles with every retry starting at
+/// `initial_backoff` milliseconds
+fn exponential_backoff(initial_backoff: u64, retry: u32) -> std::time::Duration {
+    let factor = 1u64 << retry.saturating_sub(1).min(16);
+    std::time::Duration::from_millis(initial_backoff.saturating_mul(factor))
+}
+
 /// Calculates an offset in seconds by taking into account the number of currently queued requests,
 /// number of requests that were ahead in the queue when the request was first issued, the average
 /// cost a weighted request (heuristic), and the number of available compute units per seconds.
@@ -190,3 +201,16 @@ fn compute_unit_offset_in_secs(
         0
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn doubles_backoff() {
+        assert_eq!(exponential_backoff(1000, 1), Duration::from_millis(1000));
+        assert_eq!(exponential_backoff(1000, 2), Duration::from_millis(2000));
+        assert_eq!(exponential_backoff(1000, 4), Duration::from_millis(8000));
+    }
+}
@@ -182,7 +182,13 @@ where
                     // serialize & deserialize back to U256
                     let idx_req = B256::from(idx);
                     let storage = provider.get_storage_at(address, idx_req, block_id).await;
-                    (storage.wrap_err("could not fetch slot {idx} from {address}"), address, idx)
+                    let storage = storage.wrap_err_with(|| {
+                        format!(
+                            "eth_getStorageAt failed for slot {idx} of {address} at block {}",
+                            fmt_block_id(block_id)
+                        )
+                    });
+                    (storage, address, idx)
                 });
                 self.pending_requests.push(ProviderRequest::Storage(fut));
             }
@@ -195,11 +201,18 @@ where
         let provider = self.provider.clone();
         let block_id = self.block_id;
         let fut = Box::pin(async move {
-            let balance = provider.get_balance(address, block_id);
-            let nonce = provider.get_transaction_count(address, block_id);
-            let code =
-                provider.get_code_at(address, block_id.unwrap_or(BlockNumberOrTag::Latest.into()));
-            let resp = tokio::try_join!(balance, nonce, code).map_err(Into::into);
+            let block = fmt_block_id(block_id);
+            let failed = |method: &str| format!("{method} failed for {address} at block {block}");
+            let balance = provider
+                .get_balance(address, block_id)
+                .map(|res| res.wrap_err_with(|| failed("eth_getBalance")));
+            let nonce = provider
+                .get_transaction_count(address, block_id)
+                .map(|res| res.wrap_err_with(|| failed("eth_getTransactionCount")));
+            let code = provider
+                .get_code_at(address, block_id.unwrap_or(BlockNumberOrTag::Latest.into()))
+                .map(|res| res.wrap_err_with(|| failed("eth_getCode")));
+            let resp = tokio::try_join!(balance, nonce, code);
             (resp, address)
         });
         ProviderRequest::Account(fut)
@@ -255,10 +268,10 @@ where
                 entry.insert(vec![listener]);
                 let provider = self.provider.clone();
                 let fut = Box::pin(async move {
-                    let block = provider
-                        .get_block_by_number(number.into(), false)
-                        .await
-                        .wrap_err("failed to get block");
+                    let block =
+                        provider.get_block_by_number(number.into(), false).await.wrap_err_with(
+                            || format!("eth_getBlockByNumber failed for block {number}"),
+                        );
 
                     let block_hash = match block {
                         Ok(Some(block)) => Ok(block
@@ -284,6 +297,15 @@ where
     }
 }
 
+/// Formats the block a request is made for, requests without a block are made for the latest block
+fn fmt_block_id(block_id: Option<BlockId>) -> String {
+    match block_id {
+        Some(BlockId::Number(BlockNumberOrTag::Number(number))) => number.to_string(),
+        Some(block_id) => format!("{block_id:?}"),
+        None => "latest".to_string(),
+    }
+}
+
 impl<P> Future for BackendHandler<P>
 where
     P: TempProvider + Clone + Unpin + 'static,
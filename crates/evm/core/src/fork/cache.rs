@@ -337,7 +337,7 @@ pub struct JsonBlockCacheDB {
     /// Where this cache file is stored.
     ///
     /// If this is a [None] then caching is disabled
-    cache_path: Option<PathBuf>,
+    cache_path: RwLock<Option<PathBuf>>,
     /// Object that's stored in a json file
     data: JsonBlockCacheData,
 }
@@ -345,7 +345,10 @@ pub struct JsonBlockCacheDB {
 impl JsonBlockCacheDB {
     /// Creates a new instance.
     fn new(meta: Arc<RwLock<BlockchainDbMeta>>, cache_path: Option<PathBuf>) -> Self {
-        Self { cache_path, data: JsonBlockCacheData { meta, data: Arc::new(Default::default()) } }
+        Self {
+            cache_path: RwLock::new(cache_path),
+            data: JsonBlockCacheData { meta, data: Arc::new(Default::default()) },
+        }
     }
 
     /// Loads the contents of the diskmap file and returns the read object
@@ -365,7 +368,7 @@ impl JsonBlockCacheDB {
             warn!(target: "cache", ?err, ?path, "Failed to deserialize cache data");
             err
         })?;
-        Ok(Self { cache_path: Some(path), data })
+        Ok(Self { cache_path: RwLock::new(Some(path)), data })
     }
 
     /// Returns the [MemDb] it holds access to
@@ -380,13 +383,21 @@ impl JsonBlockCacheDB {
 
     /// Returns `true` if this is a transient cache and nothing will be flushed
     pub fn is_transient(&self) -> bool {
-        self.cache_path.is_none()
+        self.cache_path.read().is_none()
+    }
+
+    /// Sets the file the cache is flushed to, `None` disables flushing.
+    ///
+    /// This should be used if the cached data no longer belongs to the block of the cache file.
+    pub fn set_cache_path(&self, cache_path: Option<PathBuf>) {
+        *self.cache_path.write() = cache_path;
     }
 
     /// Flushes the DB to disk if caching is enabled.
-    #[instrument(level = "warn", skip_all, fields(path = ?self.cache_path))]
+    #[instrument(level = "warn", skip_all, fields(path = ?self.cache_path.read()))]
     pub fn flush(&self) {
-        let Some(path) = &self.cache_path else { return };
+        let cache_path = self.cache_path.read();
+        let Some(path) = cache_path.as_ref() else { return };
         trace!(target: "cache", "saving json cache");
 
         if let Some(parent) = path.parent() {
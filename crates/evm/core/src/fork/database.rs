@@ -74,6 +74,11 @@ impl ForkedDatabase {
 
         // TODO need to find a way to update generic provider via url
 
+        // persist the data fetched for the current block, anything fetched after the reset belongs
+        // to another block and must not end up in its cache file
+        self.flush_cache();
+        self.db.cache().set_cache_path(None);
+
         // wipe the storage retrieved from remote
         self.inner().db().clear();
         // create a fresh `CacheDB`, effectively wiping modified state